@@ -1,7 +1,12 @@
 use clap::Parser;
-use std::{net::Ipv4Addr, path::PathBuf};
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    path::PathBuf,
+    time::Duration,
+};
 
 use serde::{Deserialize, Serialize};
+use server::{config::ServerConfig, keep_alive::KeepAliveConfig, world::generator::random_seed};
 
 /// General server configuration
 #[derive(Debug, Serialize, Deserialize, Parser)]
@@ -31,10 +36,26 @@ pub struct Config {
     #[arg(short = 'M', long, default_value_t = 20)]
     pub max_players: u8,
 
+    /// Whether the operators may join when the server is full.
+    #[arg(long, default_value_t = false)]
+    pub ops_bypass_player_limit: bool,
+
     /// World level type.
     #[arg(short = 'L', long, default_value = "FLAT")]
     pub level_type: String,
 
+    /// Directory the world is saved in, an existing vanilla world may be served as well.
+    #[arg(short = 'W', long, default_value = "world")]
+    pub world_dir: PathBuf,
+
+    /// Seed the terrain is generated from, random if not given.
+    #[arg(short = 'S', long)]
+    pub seed: Option<i64>,
+
+    /// Number of the columns sent in each direction around the players.
+    #[arg(short = 'V', long, default_value_t = 10, value_parser = clap::value_parser!(u8).range(3..=15))]
+    pub view_distance: u8,
+
     /// Default game mode.
     #[arg(short = 'G', long, default_value_t = 1)]
     pub game_mode: i32,
@@ -46,6 +67,22 @@ pub struct Config {
     /// World difficulty.
     #[arg(short = 'D', long, default_value_t = 0)]
     pub difficulty: i8,
+
+    /// Whether the time of the day advances, the day or night lasts forever otherwise.
+    #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+    pub do_daylight_cycle: bool,
+
+    /// Whether the joining users are verified with the session server.
+    #[arg(short = 'O', long, default_value_t = false)]
+    pub online_mode: bool,
+
+    /// Seconds a client may take to answer a keep-alive before being kicked.
+    #[arg(short = 'K', long, default_value_t = 30)]
+    pub keep_alive_timeout: u64,
+
+    /// Seconds between the saves of the world, `0` to save only as the server stops.
+    #[arg(long, default_value_t = 300)]
+    pub autosave_interval: u64,
 }
 
 impl Default for Config {
@@ -57,10 +94,50 @@ impl Default for Config {
             motd: "A Minecraft Server".to_string(),
             tps: 20,
             max_players: 20,
+            ops_bypass_player_limit: false,
             level_type: "FLAT".to_string(),
+            world_dir: "world".into(),
+            seed: None,
+            view_distance: 10,
             game_mode: 1,
             enable_pvp: true,
             difficulty: 0,
+            do_daylight_cycle: true,
+            online_mode: false,
+            keep_alive_timeout: 30,
+            autosave_interval: 300,
+        }
+    }
+}
+
+impl From<Config> for ServerConfig {
+    fn from(config: Config) -> Self {
+        let keep_alive_timeout = Duration::from_secs(config.keep_alive_timeout);
+
+        ServerConfig {
+            address: SocketAddr::from((config.server_ip, config.server_port)),
+            motd: config.motd,
+            tps: config.tps,
+            max_players: config.max_players,
+            ops_bypass_player_limit: config.ops_bypass_player_limit,
+            level_type: config.level_type,
+            world_path: config.world_dir,
+            seed: config.seed.unwrap_or_else(random_seed),
+            view_distance: config.view_distance,
+            game_mode: config.game_mode,
+            enable_pvp: config.enable_pvp,
+            difficulty: config.difficulty,
+            do_daylight_cycle: config.do_daylight_cycle,
+            online_mode: config.online_mode,
+            keep_alive: KeepAliveConfig {
+                // Send the keep-alives often enough to notice the timeout in time.
+                interval: (keep_alive_timeout / 2)
+                    .clamp(Duration::from_secs(1), KeepAliveConfig::default().interval),
+                timeout: keep_alive_timeout,
+            },
+            autosave_interval: (config.autosave_interval > 0)
+                .then(|| Duration::from_secs(config.autosave_interval)),
+            ..ServerConfig::default()
         }
     }
 }
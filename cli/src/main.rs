@@ -11,5 +11,5 @@ async fn main() {
     let config = Config::parse();
     debug!("Arguments: {:?}", config);
 
-    server::start_server().await;
+    server::start_server(config.into()).await;
 }
@@ -0,0 +1,179 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Field, Fields, LitInt};
+
+/// Derives `protocol::packet::FromBytes` for a struct.
+///
+/// Fields are read one after another in the order of declaration,
+/// each one using its own `FromBytes` implementation.
+///
+/// String fields accept the `#[protocol(max_length = N)]` attribute limiting
+/// their length, optionally with `printable` rejecting the control characters.
+#[proc_macro_derive(FromBytes, attributes(protocol))]
+pub fn derive_from_bytes(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand_from_bytes(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derives `protocol::packet::ToBytes` for a struct.
+///
+/// Fields are written one after another in the order of declaration,
+/// each one using its own `ToBytes` implementation.
+///
+/// Accepts the same field attributes as `FromBytes`.
+#[proc_macro_derive(ToBytes, attributes(protocol))]
+pub fn derive_to_bytes(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand_to_bytes(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_from_bytes(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let body = match struct_fields(input)? {
+        Fields::Named(fields) => {
+            let fields = fields
+                .named
+                .iter()
+                .map(|field| {
+                    let ident = &field.ident;
+                    let read = read_field(field)?;
+                    Ok(quote! { #ident: #read })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! { Self { #(#fields),* } }
+        }
+        Fields::Unnamed(fields) => {
+            let fields = fields
+                .unnamed
+                .iter()
+                .map(read_field)
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! { Self(#(#fields),*) }
+        }
+        Fields::Unit => quote! { Self },
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::protocol::packet::FromBytes for #name #ty_generics #where_clause {
+            fn from_bytes(
+                bytes: &mut ::std::io::Cursor<&[u8]>,
+            ) -> ::std::result::Result<Self, ::protocol::error::ProtocolError> {
+                ::std::result::Result::Ok(#body)
+            }
+        }
+    })
+}
+
+fn expand_to_bytes(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields: Vec<TokenStream2> = match struct_fields(input)? {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|field| {
+                let ident = &field.ident;
+                put_field(field, quote! { self.#ident })
+            })
+            .collect::<syn::Result<_>>()?,
+        Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let index = syn::Index::from(index);
+                put_field(field, quote! { self.#index })
+            })
+            .collect::<syn::Result<_>>()?,
+        Fields::Unit => Vec::new(),
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::protocol::packet::ToBytes for #name #ty_generics #where_clause {
+            fn to_bytes(
+                &self,
+                buffer: &mut ::protocol::bytes::BytesMut,
+            ) -> ::std::result::Result<(), ::protocol::error::ProtocolError> {
+                #(#fields)*
+                ::std::result::Result::Ok(())
+            }
+        }
+    })
+}
+
+/// Returns an expression reading the field.
+fn read_field(field: &Field) -> syn::Result<TokenStream2> {
+    Ok(match string_limit(field)? {
+        Some(limit) => quote! { #limit.read(bytes)? },
+        None => quote! { ::protocol::packet::FromBytes::from_bytes(bytes)? },
+    })
+}
+
+/// Returns a statement writing the field accessed with the expression.
+fn put_field(field: &Field, access: TokenStream2) -> syn::Result<TokenStream2> {
+    Ok(match string_limit(field)? {
+        Some(limit) => quote! { #limit.put(buffer, &#access)?; },
+        None => quote! { ::protocol::packet::ToBytes::to_bytes(&#access, buffer)?; },
+    })
+}
+
+/// Parses the `#[protocol(...)]` attributes of the field into a `StringLimit` expression.
+fn string_limit(field: &Field) -> syn::Result<Option<TokenStream2>> {
+    let mut max_length = None;
+    let mut printable = false;
+
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("protocol"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("max_length") {
+                let value: LitInt = meta.value()?.parse()?;
+                max_length = Some(value.base10_parse::<usize>()?);
+                Ok(())
+            } else if meta.path.is_ident("printable") {
+                printable = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported protocol attribute"))
+            }
+        })?;
+    }
+
+    Ok(match (max_length, printable) {
+        (Some(max_length), true) => {
+            Some(quote! { ::protocol::packet::StringLimit::printable(#max_length) })
+        }
+        (Some(max_length), false) => {
+            Some(quote! { ::protocol::packet::StringLimit::new(#max_length) })
+        }
+        (None, true) => Some(quote! {
+            ::protocol::packet::StringLimit::printable(
+                ::protocol::packet::StringLimit::DEFAULT.max_length,
+            )
+        }),
+        (None, false) => None,
+    })
+}
+
+/// Returns the fields of the struct, the derives don't support enums and unions.
+fn struct_fields(input: &DeriveInput) -> syn::Result<&Fields> {
+    match &input.data {
+        Data::Struct(data) => Ok(&data.fields),
+        _ => Err(Error::new_spanned(
+            &input.ident,
+            "FromBytes and ToBytes can only be derived for structs",
+        )),
+    }
+}
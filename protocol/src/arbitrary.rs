@@ -0,0 +1,192 @@
+use std::{fmt::Debug, io::Cursor};
+
+use bytes::BytesMut;
+use proptest::{prelude::*, test_runner::TestCaseError};
+
+use crate::{
+    chunk::ChunkData,
+    metadata::{Metadata, MetadataEntry, MetadataValue},
+    nbt::{Compound, Nbt, Tag},
+    packet::{FromBytes, StringLimit, ToBytes},
+    slot::{ItemStack, Slot},
+    version::ProtocolVersion,
+};
+
+/// Upper bound of the generated string lengths, keeps the default limit from
+/// producing needlessly large strings.
+const MAX_GENERATED_LENGTH: usize = 256;
+
+/// Generates strings of at most `max_length` UTF-16 code units.
+pub fn string(max_length: usize) -> impl Strategy<Value = String> {
+    limited_string(StringLimit::new(max_length))
+}
+
+/// Generates strings of at most `max_length` UTF-16 code units,
+/// without control characters.
+pub fn printable_string(max_length: usize) -> impl Strategy<Value = String> {
+    limited_string(StringLimit::printable(max_length))
+}
+
+/// Generates strings satisfying the limit.
+///
+/// Characters outside of the Basic Multilingual Plane are included, they take
+/// two UTF-16 code units and are the usual suspects of length prefix bugs.
+pub fn limited_string(limit: StringLimit) -> impl Strategy<Value = String> {
+    let max_length = limit.max_length.min(MAX_GENERATED_LENGTH);
+
+    proptest::collection::vec(any::<char>(), 0..=max_length).prop_map(move |chars| {
+        let mut s = String::new();
+        let mut length = 0;
+
+        for c in chars {
+            if limit.printable && c.is_control() {
+                continue;
+            }
+
+            length += c.len_utf16();
+
+            if length > max_length {
+                break;
+            }
+
+            s.push(c);
+        }
+
+        s
+    })
+}
+
+/// Generates slots, empty or holding an item stack.
+///
+/// Damageable items may carry enchantments, the only NBT data the wire
+/// format keeps, the NBT of the other items would be lost.
+pub fn slot() -> impl Strategy<Value = Slot> {
+    prop_oneof![Just(Slot::EMPTY), item_stack().prop_map(Slot::new)]
+}
+
+/// Generates item stacks with a valid item identifier.
+pub fn item_stack() -> impl Strategy<Value = ItemStack> {
+    (0..=i16::MAX, any::<i8>(), any::<i16>(), enchantments()).prop_map(
+        |(id, count, damage, nbt)| {
+            let mut item = ItemStack::new(id, count, damage);
+
+            if item.is_damageable() {
+                item.nbt = nbt;
+            }
+
+            item
+        },
+    )
+}
+
+/// Generates the enchantments NBT, a list of the enchantment ID and level pairs.
+fn enchantments() -> impl Strategy<Value = Option<Nbt>> {
+    proptest::option::of(proptest::collection::vec(
+        (any::<i16>(), any::<i16>()),
+        0..4,
+    ))
+    .prop_map(|enchantments| {
+        enchantments.map(|enchantments| {
+            let list = enchantments
+                .into_iter()
+                .map(|(id, level)| {
+                    let mut enchantment = Compound::new();
+                    enchantment.insert("id".to_string(), Tag::Short(id));
+                    enchantment.insert("lvl".to_string(), Tag::Short(level));
+                    Tag::Compound(enchantment)
+                })
+                .collect();
+
+            let mut root = Compound::new();
+            root.insert("ench".to_string(), Tag::List(list));
+            Nbt::new("", root)
+        })
+    })
+}
+
+/// Generates chunk data, the data doesn't necessarily match the bitmaps.
+pub fn chunk_data() -> impl Strategy<Value = ChunkData> {
+    (
+        any::<u16>(),
+        any::<u16>(),
+        any::<bool>(),
+        proptest::collection::vec(any::<u8>(), 0..1024),
+    )
+        .prop_map(
+            |(primary_bitmap, add_bitmap, ground_up_continuous, data)| ChunkData {
+                primary_bitmap,
+                add_bitmap,
+                ground_up_continuous,
+                data,
+            },
+        )
+}
+
+/// Generates entity metadata with the values of all the types.
+pub fn metadata() -> impl Strategy<Value = Metadata> {
+    proptest::collection::vec((0..=31u8, metadata_value()), 0..8).prop_map(|entries| {
+        let entries = entries
+            .into_iter()
+            // A float at the last index has the same header as the end of the stream.
+            .filter(|(index, value)| !(*index == 31 && matches!(value, MetadataValue::Float(_))))
+            .map(|(index, value)| MetadataEntry { index, value })
+            .collect();
+
+        Metadata { entries }
+    })
+}
+
+fn metadata_value() -> impl Strategy<Value = MetadataValue> {
+    prop_oneof![
+        any::<i8>().prop_map(MetadataValue::Byte),
+        any::<i16>().prop_map(MetadataValue::Short),
+        any::<i32>().prop_map(MetadataValue::Int),
+        any::<f32>().prop_map(MetadataValue::Float),
+        string(64).prop_map(MetadataValue::String),
+        (any::<i16>(), any::<i8>(), any::<i16>())
+            .prop_map(|(id, count, damage)| MetadataValue::Item { id, count, damage }),
+        (any::<i32>(), any::<i32>(), any::<i32>()).prop_map(|(x, y, z)| MetadataValue::Position {
+            x,
+            y,
+            z
+        }),
+    ]
+}
+
+/// Checks that the value reads back equal after being written,
+/// and that reading consumes exactly the written bytes.
+pub fn assert_roundtrip<T>(value: &T) -> Result<(), TestCaseError>
+where
+    T: FromBytes + ToBytes + PartialEq + Debug,
+{
+    let mut buffer = BytesMut::new();
+    value.to_bytes(&mut buffer).map_err(fail)?;
+
+    let mut cursor = Cursor::new(buffer.as_ref());
+    let decoded = T::from_bytes(&mut cursor).map_err(fail)?;
+
+    prop_assert_eq!(&decoded, value);
+    prop_assert_eq!(cursor.position() as usize, buffer.len());
+    Ok(())
+}
+
+/// Checks that the packet of the protocol version decodes back equal after
+/// being encoded, occupying exactly the encoded bytes.
+pub fn assert_packet_roundtrip<V>(packet: &V::Packet) -> Result<(), TestCaseError>
+where
+    V: ProtocolVersion,
+    V::Packet: PartialEq,
+{
+    let mut buffer = BytesMut::new();
+    V::encode(packet, &mut buffer).map_err(fail)?;
+
+    let (decoded, length) = V::decode(buffer.as_ref()).map_err(fail)?;
+
+    prop_assert_eq!(&decoded, packet);
+    prop_assert_eq!(length, buffer.len());
+    Ok(())
+}
+
+fn fail(error: impl std::fmt::Display) -> TestCaseError {
+    TestCaseError::fail(error.to_string())
+}
@@ -0,0 +1,345 @@
+use std::io::Cursor;
+
+use bytes::{Buf, BufMut, BytesMut};
+
+pub use crate::packet::{
+    ChatMessagePayload, ChunkAllocationPayload, DisconnectKickPayload, EntityEquipmentPayload,
+    HandshakePayload, PlayerLookPayload, PlayerPayload, PlayerPositionAndLookPayload,
+    PlayerPositionPayload, SpawnPositionPayload, TimeUpdatePayload,
+};
+use crate::{
+    error::ProtocolError,
+    packet::{Direction, FromBytes, ToBytes},
+    version::ProtocolVersion,
+};
+
+/// Keep alive packet identifier.
+const KEEP_ALIVE_PACKET_ID: u8 = 0x00;
+/// Login request identifier.
+const LOGIN_REQUEST_PACKET_ID: u8 = 0x01;
+/// Handshake packet identifier.
+const HANDSHAKE_PACKET_ID: u8 = 0x02;
+/// Chat message packet identifier.
+const CHAT_MESSAGE_PACKET_ID: u8 = 0x03;
+/// Time update packet identifier.
+const TIME_UPDATE_PACKET_ID: u8 = 0x04;
+/// Entity equipment packet identifier.
+const ENTITY_EQUIPMENT_PACKET_ID: u8 = 0x05;
+/// Spawn position packet identifier.
+const SPAWN_POSITION_PACKET_ID: u8 = 0x06;
+/// Player packet identifier.
+const PLAYER_PACKET_ID: u8 = 0x0A;
+/// Player position packet identifier.
+const PLAYER_POSITION_PACKET_ID: u8 = 0x0B;
+/// Player look packet identifier.
+const PLAYER_LOOK_PACKET_ID: u8 = 0x0C;
+/// Player position and look packet identifier.
+const PLAYER_POSITION_AND_LOOK_PACKET_ID: u8 = 0x0D;
+/// Pre-chunk packet identifier, the chunk allocation of the later versions.
+const CHUNK_ALLOCATION_PACKET_ID: u8 = 0x32;
+/// Disconnect/Kick packet identifier.
+const DISCONNECT_KICK_PACKET_ID: u8 = 0xFF;
+
+/// Protocol version 14, used by the Beta 1.7.3 clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Beta17;
+
+impl ProtocolVersion for Beta17 {
+    const VERSION: i32 = 14;
+    const GAME_VERSION: &'static str = "Beta 1.7.3";
+
+    type Packet = Packet;
+
+    fn decode(bytes: &[u8]) -> Result<(Self::Packet, usize), ProtocolError> {
+        Packet::decode(bytes)
+    }
+
+    fn encode(packet: &Self::Packet, buffer: &mut BytesMut) -> Result<(), ProtocolError> {
+        packet.encode(buffer)
+    }
+
+    fn direction(id: u8) -> Option<Direction> {
+        match id {
+            KEEP_ALIVE_PACKET_ID
+            | LOGIN_REQUEST_PACKET_ID
+            | HANDSHAKE_PACKET_ID
+            | CHAT_MESSAGE_PACKET_ID
+            | PLAYER_POSITION_AND_LOOK_PACKET_ID
+            | DISCONNECT_KICK_PACKET_ID => Some(Direction::TwoWay),
+            TIME_UPDATE_PACKET_ID
+            | ENTITY_EQUIPMENT_PACKET_ID
+            | SPAWN_POSITION_PACKET_ID
+            | CHUNK_ALLOCATION_PACKET_ID => Some(Direction::ServerToClient),
+            PLAYER_PACKET_ID | PLAYER_POSITION_PACKET_ID | PLAYER_LOOK_PACKET_ID => {
+                Some(Direction::ClientToServer)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Checks whether the handshake was sent by a Beta client.
+///
+/// Both versions start with the same handshake, but the 1.2.5 clients send
+/// the username together with the host, `ezioleq;localhost:25565`,
+/// while the Beta clients send the username alone.
+pub fn is_beta_handshake(handshake: &HandshakePayload) -> bool {
+    !handshake.data.contains(';')
+}
+
+/// Represents a single Beta 1.7.3 packet type and payload contained within it.
+///
+/// Payloads sharing the layout with the 1.2.5 ones are reused.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub enum Packet {
+    /// Two-way, Keep Alive packet.
+    KeepAlive(KeepAlivePayload),
+
+    /// Two-way, Login request packet.
+    LoginRequest(LoginRequestPayload),
+
+    /// Two-way, Handshake packet.
+    Handshake(HandshakePayload),
+
+    /// Two-way, Chat message packet.
+    ChatMessage(ChatMessagePayload),
+
+    /// Server to Client, time update packet.
+    TimeUpdate(TimeUpdatePayload),
+
+    /// Server to Client, entity equipment packet.
+    EntityEquipment(EntityEquipmentPayload),
+
+    /// Server to Client, spawn position packet.
+    SpawnPosition(SpawnPositionPayload),
+
+    /// Client to Server, player packet.
+    Player(PlayerPayload),
+
+    /// Client to Server, player position packet.
+    PlayerPosition(PlayerPositionPayload),
+
+    /// Client to Server, player look packet.
+    PlayerLook(PlayerLookPayload),
+
+    /// Two-way, Player position and look packet.
+    PlayerPositionAndLook(PlayerPositionAndLookPayload),
+
+    /// Server to Client, pre-chunk packet.
+    ChunkAllocation(ChunkAllocationPayload),
+
+    /// Two-way, Disconnect/Kick packet.
+    DisconnectKick(DisconnectKickPayload),
+}
+
+impl Packet {
+    /// Parses a packet from the beginning of the bytes.
+    ///
+    /// Returns the packet together with the number of bytes it occupied.
+    pub fn decode(bytes: &[u8]) -> Result<(Self, usize), ProtocolError> {
+        let mut cursor = Cursor::new(bytes);
+        let packet = Self::read(&mut cursor)?;
+        Ok((packet, cursor.position() as usize))
+    }
+
+    fn read(cursor: &mut Cursor<&[u8]>) -> Result<Self, ProtocolError> {
+        let packet_id = cursor.try_get_u8()?;
+
+        match packet_id {
+            KEEP_ALIVE_PACKET_ID => Ok(Packet::KeepAlive(KeepAlivePayload::from_bytes(cursor)?)),
+            LOGIN_REQUEST_PACKET_ID => Ok(Packet::LoginRequest(LoginRequestPayload::from_bytes(
+                cursor,
+            )?)),
+            HANDSHAKE_PACKET_ID => Ok(Packet::Handshake(HandshakePayload::from_bytes(cursor)?)),
+            CHAT_MESSAGE_PACKET_ID => {
+                Ok(Packet::ChatMessage(ChatMessagePayload::from_bytes(cursor)?))
+            }
+            TIME_UPDATE_PACKET_ID => Ok(Packet::TimeUpdate(TimeUpdatePayload::from_bytes(cursor)?)),
+            ENTITY_EQUIPMENT_PACKET_ID => Ok(Packet::EntityEquipment(
+                EntityEquipmentPayload::from_bytes(cursor)?,
+            )),
+            SPAWN_POSITION_PACKET_ID => Ok(Packet::SpawnPosition(
+                SpawnPositionPayload::from_bytes(cursor)?,
+            )),
+            PLAYER_PACKET_ID => Ok(Packet::Player(PlayerPayload::from_bytes(cursor)?)),
+            PLAYER_POSITION_PACKET_ID => Ok(Packet::PlayerPosition(
+                PlayerPositionPayload::from_bytes(cursor)?,
+            )),
+            PLAYER_LOOK_PACKET_ID => Ok(Packet::PlayerLook(PlayerLookPayload::from_bytes(cursor)?)),
+            PLAYER_POSITION_AND_LOOK_PACKET_ID => Ok(Packet::PlayerPositionAndLook(
+                PlayerPositionAndLookPayload::from_bytes(cursor)?,
+            )),
+            CHUNK_ALLOCATION_PACKET_ID => Ok(Packet::ChunkAllocation(
+                ChunkAllocationPayload::from_bytes(cursor)?,
+            )),
+            DISCONNECT_KICK_PACKET_ID => Ok(Packet::DisconnectKick(
+                DisconnectKickPayload::from_bytes(cursor)?,
+            )),
+            _ => Err(ProtocolError::UnknownPacketId(packet_id)),
+        }
+    }
+
+    /// Returns the packet identifier.
+    pub fn id(&self) -> u8 {
+        match self {
+            Packet::KeepAlive(_) => KEEP_ALIVE_PACKET_ID,
+            Packet::LoginRequest(_) => LOGIN_REQUEST_PACKET_ID,
+            Packet::Handshake(_) => HANDSHAKE_PACKET_ID,
+            Packet::ChatMessage(_) => CHAT_MESSAGE_PACKET_ID,
+            Packet::TimeUpdate(_) => TIME_UPDATE_PACKET_ID,
+            Packet::EntityEquipment(_) => ENTITY_EQUIPMENT_PACKET_ID,
+            Packet::SpawnPosition(_) => SPAWN_POSITION_PACKET_ID,
+            Packet::Player(_) => PLAYER_PACKET_ID,
+            Packet::PlayerPosition(_) => PLAYER_POSITION_PACKET_ID,
+            Packet::PlayerLook(_) => PLAYER_LOOK_PACKET_ID,
+            Packet::PlayerPositionAndLook(_) => PLAYER_POSITION_AND_LOOK_PACKET_ID,
+            Packet::ChunkAllocation(_) => CHUNK_ALLOCATION_PACKET_ID,
+            Packet::DisconnectKick(_) => DISCONNECT_KICK_PACKET_ID,
+        }
+    }
+
+    /// Writes the packet to the end of the buffer.
+    ///
+    /// On failure, part of the packet may have already been written to the buffer.
+    pub fn encode(&self, buffer: &mut BytesMut) -> Result<(), ProtocolError> {
+        buffer.put_u8(self.id());
+
+        match self {
+            Packet::KeepAlive(payload) => payload.to_bytes(buffer),
+            Packet::LoginRequest(payload) => payload.to_bytes(buffer),
+            Packet::Handshake(payload) => payload.to_bytes(buffer),
+            Packet::ChatMessage(payload) => payload.to_bytes(buffer),
+            Packet::TimeUpdate(payload) => payload.to_bytes(buffer),
+            Packet::EntityEquipment(payload) => payload.to_bytes(buffer),
+            Packet::SpawnPosition(payload) => payload.to_bytes(buffer),
+            Packet::Player(payload) => payload.to_bytes(buffer),
+            Packet::PlayerPosition(payload) => payload.to_bytes(buffer),
+            Packet::PlayerLook(payload) => payload.to_bytes(buffer),
+            Packet::PlayerPositionAndLook(payload) => payload.to_bytes(buffer),
+            Packet::ChunkAllocation(payload) => payload.to_bytes(buffer),
+            Packet::DisconnectKick(payload) => payload.to_bytes(buffer),
+        }
+    }
+}
+
+//
+// Keep alive packet
+//
+
+/// Payload for the `Packet::KeepAlive`, the Beta clients send no identifier.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct KeepAlivePayload;
+
+//
+// Login request packet
+//
+
+/// Payload for the `Packet::LoginRequest`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct LoginRequestPayload {
+    /// # Client to Server
+    /// The `id` is the protocol version, for Beta 1.7.3 it should be equal to `14`.
+    ///
+    /// # Server to Client
+    /// The `id` is the player's entity identifier.
+    pub id: i32,
+
+    /// # Client to Server
+    /// Player's username.
+    ///
+    /// # Server to Client
+    /// Not used.
+    #[protocol(max_length = 16, printable)]
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(strategy = "crate::arbitrary::printable_string(16)")
+    )]
+    pub username: String,
+
+    /// # Client to Server
+    /// Not used.
+    ///
+    /// # Server to Client
+    /// World seed, used by the client to compute the biome colors.
+    pub map_seed: i64,
+
+    /// # Client to Server
+    /// Not used.
+    ///
+    /// # Server to Client
+    /// Dimension, `-1` for Nether, `0` for The Overworld.
+    pub dimension: i8,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_login_request_packet() {
+        let data: &[u8] = &[
+            0x01, 0x00, 0x00, 0x00, 0x0E, 0x00, 0x02, 0x00, 0x61, 0x00, 0x62, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+
+        let (packet, length) = Packet::decode(data).unwrap();
+
+        assert_eq!(
+            packet,
+            Packet::LoginRequest(LoginRequestPayload {
+                id: 14,
+                username: "ab".to_string(),
+                map_seed: 0,
+                dimension: 0,
+            })
+        );
+        assert_eq!(length, data.len());
+    }
+
+    #[test]
+    fn encode_login_request_packet() {
+        let packet = Packet::LoginRequest(LoginRequestPayload {
+            id: 1234,
+            username: "".to_string(),
+            map_seed: -1,
+            dimension: -1,
+        });
+
+        let mut buffer = BytesMut::new();
+        packet.encode(&mut buffer).unwrap();
+
+        assert_eq!(
+            buffer.as_ref(),
+            &[
+                0x01, 0x00, 0x00, 0x04, 0xD2, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+                0xFF, 0xFF
+            ]
+        );
+    }
+
+    #[test]
+    fn detect_beta_handshake() {
+        let beta = HandshakePayload {
+            data: "ezioleq".to_string(),
+        };
+        let release = HandshakePayload {
+            data: "ezioleq;localhost:25565".to_string(),
+        };
+
+        assert!(is_beta_handshake(&beta));
+        assert!(!is_beta_handshake(&release));
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn roundtrip_packets(packet: Packet) {
+            crate::arbitrary::assert_packet_roundtrip::<Beta17>(&packet)?;
+        }
+    }
+}
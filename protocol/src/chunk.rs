@@ -0,0 +1,361 @@
+use std::io::{Read, Write};
+
+use flate2::{read::ZlibDecoder, write::ZlibEncoder};
+
+use crate::error::ProtocolError;
+
+/// Width of a chunk column along the X and Z axes.
+pub const CHUNK_WIDTH: usize = 16;
+/// Height of a chunk column.
+pub const CHUNK_HEIGHT: usize = 256;
+/// Height of a single chunk section.
+pub const SECTION_HEIGHT: usize = 16;
+/// Number of sections stacked in a chunk column.
+pub const SECTION_COUNT: usize = CHUNK_HEIGHT / SECTION_HEIGHT;
+
+/// Number of blocks in a chunk section.
+const SECTION_VOLUME: usize = CHUNK_WIDTH * SECTION_HEIGHT * CHUNK_WIDTH;
+/// Number of biomes in a chunk column.
+const BIOME_COUNT: usize = CHUNK_WIDTH * CHUNK_WIDTH;
+/// Light level of the blocks exposed to the sky.
+const FULL_LIGHT: u8 = 15;
+/// Length of the data of a full column, all sections with the add arrays and the biomes.
+const MAX_DATA_LENGTH: usize =
+    SECTION_COUNT * (SECTION_VOLUME + 4 * SECTION_VOLUME / 2) + BIOME_COUNT;
+/// Max length of the compressed data of a column, the zlib bound of a full column,
+/// which doesn't shrink when compressed at worst.
+pub const MAX_COMPRESSED_LENGTH: usize = MAX_DATA_LENGTH
+    + (MAX_DATA_LENGTH >> 12)
+    + (MAX_DATA_LENGTH >> 14)
+    + (MAX_DATA_LENGTH >> 25)
+    + 13;
+
+/// Block data of a chunk column, laid out as the Map Chunk packet carries it.
+///
+/// The data holds the present sections only, and is not compressed yet.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChunkData {
+    /// Bit `n` set means section `n` is present in the data.
+    pub primary_bitmap: u16,
+
+    /// Bit `n` set means section `n` has the add array, the upper 4 bits of the block IDs.
+    pub add_bitmap: u16,
+
+    /// Whether the data contains the biome array, it's sent with whole columns only.
+    pub ground_up_continuous: bool,
+
+    /// Block IDs, metadata, block light, sky light and add arrays of all present
+    /// sections, each kind grouped together, followed by the biome array.
+    pub data: Vec<u8>,
+}
+
+impl ChunkData {
+    /// Compresses the data with zlib, as the Map Chunk packet carries it.
+    pub fn compress(&self) -> Result<Vec<u8>, ProtocolError> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&self.data)?;
+        Ok(encoder.finish()?)
+    }
+
+    /// Decompresses the data of the Map Chunk packet.
+    ///
+    /// Data longer than a full column is rejected,
+    /// so a small packet can't expand into a huge allocation.
+    pub fn decompress(bytes: &[u8]) -> Result<Vec<u8>, ProtocolError> {
+        let mut data = Vec::new();
+
+        ZlibDecoder::new(bytes)
+            .take(MAX_DATA_LENGTH as u64 + 1)
+            .read_to_end(&mut data)?;
+
+        if data.len() > MAX_DATA_LENGTH {
+            return Err(ProtocolError::PayloadTooLarge);
+        }
+
+        Ok(data)
+    }
+}
+
+/// Storage of a single 16×16×16 section while building the chunk data.
+#[derive(Debug, Clone)]
+struct Section {
+    /// Lower 8 bits of the block IDs.
+    blocks: Box<[u8; SECTION_VOLUME]>,
+    /// Upper 4 bits of the block IDs.
+    add: Box<[u8; SECTION_VOLUME / 2]>,
+    metadata: Box<[u8; SECTION_VOLUME / 2]>,
+    block_light: Box<[u8; SECTION_VOLUME / 2]>,
+    sky_light: Box<[u8; SECTION_VOLUME / 2]>,
+}
+
+impl Section {
+    fn new() -> Self {
+        Self {
+            blocks: Box::new([0; SECTION_VOLUME]),
+            add: Box::new([0; SECTION_VOLUME / 2]),
+            metadata: Box::new([0; SECTION_VOLUME / 2]),
+            block_light: Box::new([0; SECTION_VOLUME / 2]),
+            sky_light: Box::new([FULL_LIGHT << 4 | FULL_LIGHT; SECTION_VOLUME / 2]),
+        }
+    }
+
+    fn has_add(&self) -> bool {
+        self.add.iter().any(|&nibbles| nibbles != 0)
+    }
+}
+
+/// Builds the `ChunkData` of a single chunk column.
+///
+/// Coordinates are relative to the column, `x` and `z` in `0..16`, `y` in `0..256`.
+/// Sections without any block set stay out of the data, the client treats them
+/// as air. Sky light defaults to full brightness, block light to darkness.
+#[derive(Debug, Clone)]
+pub struct ChunkDataBuilder {
+    sections: [Option<Section>; SECTION_COUNT],
+    biomes: Option<Box<[u8; BIOME_COUNT]>>,
+}
+
+impl Default for ChunkDataBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChunkDataBuilder {
+    /// Creates a builder of an empty column.
+    pub fn new() -> Self {
+        Self {
+            sections: std::array::from_fn(|_| None),
+            biomes: None,
+        }
+    }
+
+    /// Creates a builder filled with the block IDs, indexed as `blocks[x][y][z]`.
+    pub fn from_blocks(blocks: &[[[u16; CHUNK_WIDTH]; CHUNK_HEIGHT]; CHUNK_WIDTH]) -> Self {
+        let mut builder = Self::new();
+
+        for (x, plane) in blocks.iter().enumerate() {
+            for (y, row) in plane.iter().enumerate() {
+                for (z, &id) in row.iter().enumerate() {
+                    if id != 0 {
+                        builder.set_block(x, y, z, id, 0);
+                    }
+                }
+            }
+        }
+
+        builder
+    }
+
+    /// Sets the block ID, 12 bits at most, and its 4 bits of metadata.
+    pub fn set_block(&mut self, x: usize, y: usize, z: usize, id: u16, metadata: u8) -> &mut Self {
+        let section = self.section_mut(y);
+        let index = block_index(x, y, z);
+
+        section.blocks[index] = id as u8;
+        set_nibble(&mut section.add[..], index, (id >> 8) as u8);
+        set_nibble(&mut section.metadata[..], index, metadata);
+        self
+    }
+
+    /// Sets the light level emitted by the blocks, in range `0..=15`.
+    pub fn set_block_light(&mut self, x: usize, y: usize, z: usize, level: u8) -> &mut Self {
+        let section = self.section_mut(y);
+        set_nibble(&mut section.block_light[..], block_index(x, y, z), level);
+        self
+    }
+
+    /// Sets the light level coming from the sky, in range `0..=15`.
+    pub fn set_sky_light(&mut self, x: usize, y: usize, z: usize, level: u8) -> &mut Self {
+        let section = self.section_mut(y);
+        set_nibble(&mut section.sky_light[..], block_index(x, y, z), level);
+        self
+    }
+
+    /// Sets the biome of the vertical column of blocks.
+    ///
+    /// Once any biome is set, the data is built as ground-up continuous.
+    pub fn set_biome(&mut self, x: usize, z: usize, biome: u8) -> &mut Self {
+        let biomes = self
+            .biomes
+            .get_or_insert_with(|| Box::new([0; BIOME_COUNT]));
+        biomes[z * CHUNK_WIDTH + x] = biome;
+        self
+    }
+
+    /// Lays out the data of the present sections.
+    pub fn build(&self) -> ChunkData {
+        let present: Vec<(usize, &Section)> = self
+            .sections
+            .iter()
+            .enumerate()
+            .filter_map(|(i, section)| section.as_ref().map(|section| (i, section)))
+            .collect();
+
+        let mut primary_bitmap = 0u16;
+        let mut add_bitmap = 0u16;
+        let mut data = Vec::new();
+
+        for (i, section) in &present {
+            primary_bitmap |= 1 << i;
+            data.extend_from_slice(&section.blocks[..]);
+        }
+
+        for (_, section) in &present {
+            data.extend_from_slice(&section.metadata[..]);
+        }
+
+        for (_, section) in &present {
+            data.extend_from_slice(&section.block_light[..]);
+        }
+
+        for (_, section) in &present {
+            data.extend_from_slice(&section.sky_light[..]);
+        }
+
+        for (i, section) in &present {
+            if section.has_add() {
+                add_bitmap |= 1 << i;
+                data.extend_from_slice(&section.add[..]);
+            }
+        }
+
+        if let Some(biomes) = &self.biomes {
+            data.extend_from_slice(&biomes[..]);
+        }
+
+        ChunkData {
+            primary_bitmap,
+            add_bitmap,
+            ground_up_continuous: self.biomes.is_some(),
+            data,
+        }
+    }
+
+    fn section_mut(&mut self, y: usize) -> &mut Section {
+        self.sections[y / SECTION_HEIGHT].get_or_insert_with(Section::new)
+    }
+}
+
+/// Index of the block within its section, ordered by Y, then Z, then X.
+fn block_index(x: usize, y: usize, z: usize) -> usize {
+    (y % SECTION_HEIGHT) << 8 | z << 4 | x
+}
+
+/// Sets the 4-bit value, even indices take the lower half of a byte.
+fn set_nibble(array: &mut [u8], index: usize, value: u8) {
+    let byte = &mut array[index >> 1];
+
+    if index & 1 == 0 {
+        *byte = (*byte & 0xF0) | (value & 0x0F);
+    } else {
+        *byte = (*byte & 0x0F) | (value & 0x0F) << 4;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_empty_chunk() {
+        let data = ChunkDataBuilder::new().build();
+
+        assert_eq!(
+            data,
+            ChunkData {
+                primary_bitmap: 0,
+                add_bitmap: 0,
+                ground_up_continuous: false,
+                data: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn build_single_section_layout() {
+        let data = ChunkDataBuilder::new()
+            .set_block(1, 18, 3, 35, 14)
+            .set_block_light(1, 18, 3, 7)
+            .build();
+
+        let index = 2 << 8 | 3 << 4 | 1;
+
+        assert_eq!(data.primary_bitmap, 0b10);
+        assert_eq!(data.add_bitmap, 0);
+        assert_eq!(data.data.len(), 4096 + 3 * 2048);
+        assert_eq!(data.data[index], 35);
+        assert_eq!(data.data[4096 + index / 2], 14 << 4);
+        assert_eq!(data.data[4096 + 2048 + index / 2], 7 << 4);
+        assert_eq!(data.data[4096 + 2 * 2048 + index / 2], 0xFF);
+    }
+
+    #[test]
+    fn build_groups_arrays_of_multiple_sections() {
+        let data = ChunkDataBuilder::new()
+            .set_block(0, 0, 0, 7, 0)
+            .set_block(0, 255, 0, 1, 0)
+            .build();
+
+        assert_eq!(data.primary_bitmap, 0b1000_0000_0000_0001);
+        assert_eq!(data.data.len(), 2 * (4096 + 3 * 2048));
+        assert_eq!(data.data[0], 7);
+        assert_eq!(data.data[4096 + (15 << 8)], 1);
+    }
+
+    #[test]
+    fn build_add_array() {
+        let data = ChunkDataBuilder::new().set_block(1, 0, 0, 0x1A5, 0).build();
+
+        assert_eq!(data.add_bitmap, 1);
+        assert_eq!(data.data.len(), 4096 + 4 * 2048);
+        assert_eq!(data.data[1], 0xA5);
+        assert_eq!(data.data[4096 + 3 * 2048], 0x10);
+    }
+
+    #[test]
+    fn build_biomes() {
+        let data = ChunkDataBuilder::new().set_biome(2, 1, 4).build();
+
+        assert!(data.ground_up_continuous);
+        assert_eq!(data.primary_bitmap, 0);
+        assert_eq!(data.data.len(), 256);
+        assert_eq!(data.data[16 + 2], 4);
+    }
+
+    #[test]
+    fn build_from_blocks() {
+        let mut blocks = Box::new([[[0u16; 16]; 256]; 16]);
+        blocks[3][64][5] = 2;
+
+        let data = ChunkDataBuilder::from_blocks(&blocks).build();
+
+        assert_eq!(data.primary_bitmap, 1 << 4);
+        assert_eq!(data.data[5 << 4 | 3], 2);
+    }
+
+    #[test]
+    fn compress_decompress_data() {
+        let data = ChunkDataBuilder::new().set_block(0, 64, 0, 2, 0).build();
+
+        let compressed = data.compress().unwrap();
+
+        assert!(compressed.len() < data.data.len());
+        assert_eq!(ChunkData::decompress(&compressed).unwrap(), data.data);
+    }
+
+    #[test]
+    fn decompress_too_large_data() {
+        let data = ChunkData {
+            primary_bitmap: 0,
+            add_bitmap: 0,
+            ground_up_continuous: false,
+            data: vec![0; MAX_DATA_LENGTH + 1],
+        };
+
+        let result = ChunkData::decompress(&data.compress().unwrap());
+
+        assert!(matches!(result, Err(ProtocolError::PayloadTooLarge)));
+    }
+}
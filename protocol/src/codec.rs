@@ -0,0 +1,220 @@
+use std::marker::PhantomData;
+
+use bytes::{Buf, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::{error::ProtocolError, packet::Direction, v29::V29, version::ProtocolVersion};
+
+/// Max length of a frame kept in the read buffer, longer than the largest column
+/// the Map Chunk packet can carry.
+pub const MAX_FRAME_LENGTH: usize = 2 * 1024 * 1024;
+
+/// Splits a byte stream into complete packets and serializes outgoing ones.
+///
+/// The protocol has no length prefix, so a frame ends wherever its payload ends.
+/// Incomplete frames are kept in the read buffer until the rest of the bytes arrive,
+/// up to `MAX_FRAME_LENGTH`.
+///
+/// Uses the packet layouts of the protocol version `V`, 1.2.5 by default.
+#[derive(Debug)]
+pub struct PacketCodec<V: ProtocolVersion = V29> {
+    /// Direction of the packets decoded, the packets travelling the other way
+    /// are rejected by their identifier, `None` accepts both.
+    incoming: Option<Direction>,
+    version: PhantomData<V>,
+}
+
+impl<V: ProtocolVersion> PacketCodec<V> {
+    pub fn new() -> Self {
+        Self {
+            incoming: None,
+            version: PhantomData,
+        }
+    }
+
+    /// Creates the codec decoding only the packets travelling in the direction,
+    /// e.g. the ones the clients may send on the server.
+    pub fn receiving(direction: Direction) -> Self {
+        Self {
+            incoming: Some(direction),
+            version: PhantomData,
+        }
+    }
+}
+
+impl<V: ProtocolVersion> Default for PacketCodec<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V: ProtocolVersion> Decoder for PacketCodec<V> {
+    type Item = V::Packet;
+    type Error = ProtocolError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let Some(&id) = src.first() else {
+            return Ok(None);
+        };
+
+        // Rejected before waiting for the rest of the packet.
+        if let Some(incoming) = self.incoming {
+            match V::direction(id) {
+                None => return Err(ProtocolError::UnknownPacketId(id)),
+                Some(direction) if direction != Direction::TwoWay && direction != incoming => {
+                    return Err(ProtocolError::WrongDirection(id));
+                }
+                Some(_) => {}
+            }
+        }
+
+        match V::decode(src.as_ref()) {
+            Ok((packet, length)) => {
+                src.advance(length);
+                Ok(Some(packet))
+            }
+            Err(ProtocolError::UnexpectedEof { needed }) => {
+                if src.len().saturating_add(needed) > MAX_FRAME_LENGTH {
+                    return Err(ProtocolError::PayloadTooLarge);
+                }
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl<V: ProtocolVersion> Encoder<V::Packet> for PacketCodec<V> {
+    type Error = ProtocolError;
+
+    fn encode(&mut self, item: V::Packet, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let length = dst.len();
+
+        // Don't leave a partially written packet in the buffer.
+        V::encode(&item, dst).inspect_err(|_| dst.truncate(length))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BufMut;
+
+    use super::*;
+    use crate::{
+        beta17::Beta17,
+        packet::{KeepAlivePayload, Packet, ServerListPingPayload},
+    };
+
+    #[test]
+    fn decode_empty_buffer() {
+        let mut buffer = BytesMut::new();
+
+        let packet = PacketCodec::<V29>::new().decode(&mut buffer).unwrap();
+
+        assert_eq!(packet, None);
+    }
+
+    #[test]
+    fn decode_partial_packet() {
+        let mut buffer = BytesMut::from(&[0x00, 0x00, 0x00][..]);
+
+        let packet = PacketCodec::<V29>::new().decode(&mut buffer).unwrap();
+
+        assert_eq!(packet, None);
+        assert_eq!(buffer.len(), 3);
+    }
+
+    #[test]
+    fn decode_packet_split_across_reads() {
+        let mut codec = PacketCodec::<V29>::new();
+        let mut buffer = BytesMut::from(&[0x00, 0x00, 0x00][..]);
+
+        assert_eq!(codec.decode(&mut buffer).unwrap(), None);
+
+        buffer.extend_from_slice(&[0x00, 0x11]);
+
+        assert_eq!(
+            codec.decode(&mut buffer).unwrap(),
+            Some(Packet::KeepAlive(KeepAlivePayload { keep_alive_id: 17 }))
+        );
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn decode_multiple_packets_in_one_read() {
+        let mut codec = PacketCodec::<V29>::new();
+        let mut buffer = BytesMut::from(&[0x00, 0x00, 0x00, 0x00, 0x11, 0xFE][..]);
+
+        assert_eq!(
+            codec.decode(&mut buffer).unwrap(),
+            Some(Packet::KeepAlive(KeepAlivePayload { keep_alive_id: 17 }))
+        );
+        assert_eq!(
+            codec.decode(&mut buffer).unwrap(),
+            Some(Packet::ServerListPing(ServerListPingPayload))
+        );
+        assert_eq!(codec.decode(&mut buffer).unwrap(), None);
+    }
+
+    #[test]
+    fn decode_unknown_packet() {
+        let mut buffer = BytesMut::from(&[0xAB][..]);
+
+        let result = PacketCodec::<V29>::new().decode(&mut buffer);
+
+        assert!(matches!(result, Err(ProtocolError::UnknownPacketId(0xAB))));
+    }
+
+    #[test]
+    fn decode_packet_from_the_wrong_side() {
+        // Time update, sent by the server only.
+        let mut buffer = BytesMut::from(&[0x04, 0x00][..]);
+
+        let result = PacketCodec::<V29>::receiving(Direction::ClientToServer).decode(&mut buffer);
+
+        assert!(matches!(result, Err(ProtocolError::WrongDirection(0x04))));
+    }
+
+    #[test]
+    fn decode_oversized_frame() {
+        // Map chunk announcing more compressed data than a frame may hold.
+        let mut buffer = BytesMut::new();
+        buffer.put_u8(0x33);
+        buffer.put_bytes(0, 13);
+        buffer.put_i32(i32::MAX);
+        buffer.put_i32(0);
+
+        let result = PacketCodec::<V29>::new().decode(&mut buffer);
+
+        assert!(matches!(result, Err(ProtocolError::PayloadTooLarge)));
+    }
+
+    #[test]
+    fn encode_keep_alive_packet() {
+        let mut buffer = BytesMut::new();
+
+        PacketCodec::<V29>::new()
+            .encode(
+                Packet::KeepAlive(KeepAlivePayload { keep_alive_id: 17 }),
+                &mut buffer,
+            )
+            .unwrap();
+
+        assert_eq!(buffer.as_ref(), &[0x00, 0x00, 0x00, 0x00, 0x11]);
+    }
+
+    #[test]
+    fn decode_beta_keep_alive_packet() {
+        let mut buffer = BytesMut::from(&[0x00, 0xFF][..]);
+
+        let packet = PacketCodec::<Beta17>::new().decode(&mut buffer).unwrap();
+
+        assert_eq!(
+            packet,
+            Some(crate::beta17::Packet::KeepAlive(
+                crate::beta17::KeepAlivePayload
+            ))
+        );
+        assert_eq!(buffer.as_ref(), &[0xFF]);
+    }
+}
@@ -0,0 +1,84 @@
+use std::{fmt, io};
+
+use bytes::TryGetError;
+
+/// Errors that can occur while encoding or decoding packets.
+#[derive(Debug)]
+pub enum ProtocolError {
+    /// The packet identifier doesn't match any known packet.
+    UnknownPacketId(u8),
+
+    /// The data ended before the packet did.
+    ///
+    /// The `needed` field is the number of missing bytes required by the read
+    /// that failed, the whole packet may require more of them.
+    UnexpectedEof { needed: usize },
+
+    /// The entity metadata entry type doesn't match any known type.
+    UnknownMetadataType(u8),
+
+    /// The NBT tag type doesn't match any known type.
+    UnknownNbtTag(u8),
+
+    /// The NBT list contains tags of different types.
+    MixedNbtList,
+
+    /// A length prefix is negative.
+    NegativeLength(i32),
+
+    /// A string is not a valid UTF-16 or modified UTF-8 sequence.
+    InvalidString,
+
+    /// A value is too large to fit in its length prefix or field.
+    PayloadTooLarge,
+
+    /// The packet can't be sent by the peer, it travels the other way.
+    WrongDirection(u8),
+
+    /// An underlying I/O operation failed.
+    Io(io::Error),
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtocolError::UnknownPacketId(id) => write!(f, "unknown packet ID 0x{:02X}", id),
+            ProtocolError::UnexpectedEof { needed } => {
+                write!(f, "unexpected end of data, {} more bytes needed", needed)
+            }
+            ProtocolError::UnknownMetadataType(id) => write!(f, "unknown metadata type {}", id),
+            ProtocolError::UnknownNbtTag(id) => write!(f, "unknown NBT tag type {}", id),
+            ProtocolError::MixedNbtList => write!(f, "NBT list elements differ in type"),
+            ProtocolError::NegativeLength(length) => write!(f, "negative length {}", length),
+            ProtocolError::InvalidString => write!(f, "invalid string"),
+            ProtocolError::PayloadTooLarge => write!(f, "payload too large"),
+            ProtocolError::WrongDirection(id) => {
+                write!(f, "packet ID 0x{:02X} can't be sent by the peer", id)
+            }
+            ProtocolError::Io(e) => write!(f, "I/O error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ProtocolError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ProtocolError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ProtocolError {
+    fn from(error: io::Error) -> Self {
+        ProtocolError::Io(error)
+    }
+}
+
+impl From<TryGetError> for ProtocolError {
+    fn from(error: TryGetError) -> Self {
+        ProtocolError::UnexpectedEof {
+            needed: error.requested - error.available,
+        }
+    }
+}
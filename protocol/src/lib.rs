@@ -1,2 +1,32 @@
+// Lets the derive macros refer to this crate as `::protocol` from within.
+extern crate self as protocol;
+
+pub use bytes;
+
+/// Provides the property-based testing generators and roundtrip checks.
+#[cfg(any(test, feature = "arbitrary"))]
+pub mod arbitrary;
+/// Provides the packets of the Beta 1.7.3 protocol.
+pub mod beta17;
+/// Provides the chunk column data layout used by the chunk packets.
+pub mod chunk;
+/// Provides a codec framing a byte stream into packets.
+pub mod codec;
+/// Provides the error type shared by the encoding and decoding routines.
+pub mod error;
+/// Provides the entity metadata wire type.
+pub mod metadata;
+/// Provides the Named Binary Tag format used by the world and item data.
+pub mod nbt;
 /// Provides support for network packages and related functionalities.
 pub mod packet;
+/// Provides the inventory slot and item stack wire types.
+pub mod slot;
+/// Provides the server list status response builder.
+pub mod status;
+/// Provides extension traits reading and writing packets on async streams.
+pub mod stream;
+/// Provides the packets of the 1.2.5 protocol, version 29.
+pub mod v29;
+/// Provides the protocol version and client compatibility checks.
+pub mod version;
@@ -0,0 +1,308 @@
+use std::io::Cursor;
+
+use bytes::{Buf, BufMut, BytesMut};
+
+use crate::{
+    error::ProtocolError,
+    packet::{FromBytes, StringLimit, ToBytes},
+};
+
+/// Byte terminating the metadata stream.
+const METADATA_END: u8 = 0x7F;
+/// Highest index an entry can have, the index is stored in the lower 5 bits.
+const MAX_METADATA_INDEX: u8 = 0x1F;
+/// Limit of the string values.
+const STRING_LIMIT: StringLimit = StringLimit::new(64);
+
+/// Byte metadata type identifier.
+const BYTE_TYPE: u8 = 0;
+/// Short metadata type identifier.
+const SHORT_TYPE: u8 = 1;
+/// Int metadata type identifier.
+const INT_TYPE: u8 = 2;
+/// Float metadata type identifier.
+const FLOAT_TYPE: u8 = 3;
+/// String metadata type identifier.
+const STRING_TYPE: u8 = 4;
+/// Item metadata type identifier.
+const ITEM_TYPE: u8 = 5;
+/// Position metadata type identifier.
+const POSITION_TYPE: u8 = 6;
+
+/// Entity metadata, also known as watchable objects.
+///
+/// A set of typed values addressed by an index, each entity type
+/// assigns its own meaning to the indices.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Metadata {
+    /// Entries in the order they appear on the wire.
+    pub entries: Vec<MetadataEntry>,
+}
+
+/// A single indexed value of the `Metadata`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MetadataEntry {
+    /// Index of the value, in range `0..=31`.
+    pub index: u8,
+
+    /// The value itself.
+    pub value: MetadataValue,
+}
+
+/// Value of a single metadata entry.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MetadataValue {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Float(f32),
+    String(String),
+
+    /// Item stack, used for example by the dropped items.
+    Item {
+        id: i16,
+        count: i8,
+        damage: i16,
+    },
+
+    /// Position in block coordinates.
+    Position {
+        x: i32,
+        y: i32,
+        z: i32,
+    },
+}
+
+impl MetadataValue {
+    /// Returns the wire type identifier of the value.
+    fn type_id(&self) -> u8 {
+        match self {
+            MetadataValue::Byte(_) => BYTE_TYPE,
+            MetadataValue::Short(_) => SHORT_TYPE,
+            MetadataValue::Int(_) => INT_TYPE,
+            MetadataValue::Float(_) => FLOAT_TYPE,
+            MetadataValue::String(_) => STRING_TYPE,
+            MetadataValue::Item { .. } => ITEM_TYPE,
+            MetadataValue::Position { .. } => POSITION_TYPE,
+        }
+    }
+}
+
+impl Metadata {
+    /// Creates an empty metadata.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the value at the index, replacing the previous one if present.
+    pub fn set(&mut self, index: u8, value: MetadataValue) {
+        match self.entries.iter_mut().find(|entry| entry.index == index) {
+            Some(entry) => entry.value = value,
+            None => self.entries.push(MetadataEntry { index, value }),
+        }
+    }
+
+    /// Returns the value at the index.
+    pub fn get(&self, index: u8) -> Option<&MetadataValue> {
+        self.entries
+            .iter()
+            .find(|entry| entry.index == index)
+            .map(|entry| &entry.value)
+    }
+}
+
+impl FromBytes for Metadata {
+    fn from_bytes(bytes: &mut Cursor<&[u8]>) -> Result<Self, ProtocolError> {
+        let mut entries = Vec::new();
+
+        loop {
+            let header = bytes.try_get_u8()?;
+
+            if header == METADATA_END {
+                break;
+            }
+
+            let index = header & MAX_METADATA_INDEX;
+            let value = match header >> 5 {
+                BYTE_TYPE => MetadataValue::Byte(bytes.try_get_i8()?),
+                SHORT_TYPE => MetadataValue::Short(bytes.try_get_i16()?),
+                INT_TYPE => MetadataValue::Int(bytes.try_get_i32()?),
+                FLOAT_TYPE => MetadataValue::Float(bytes.try_get_f32()?),
+                STRING_TYPE => MetadataValue::String(STRING_LIMIT.read(bytes)?),
+                ITEM_TYPE => MetadataValue::Item {
+                    id: bytes.try_get_i16()?,
+                    count: bytes.try_get_i8()?,
+                    damage: bytes.try_get_i16()?,
+                },
+                POSITION_TYPE => MetadataValue::Position {
+                    x: bytes.try_get_i32()?,
+                    y: bytes.try_get_i32()?,
+                    z: bytes.try_get_i32()?,
+                },
+                type_id => return Err(ProtocolError::UnknownMetadataType(type_id)),
+            };
+
+            entries.push(MetadataEntry { index, value });
+        }
+
+        Ok(Self { entries })
+    }
+}
+
+impl ToBytes for Metadata {
+    fn to_bytes(&self, buffer: &mut BytesMut) -> Result<(), ProtocolError> {
+        for entry in &self.entries {
+            let header = entry.value.type_id() << 5 | entry.index;
+
+            // The header would be indistinguishable from the end of the stream.
+            if entry.index > MAX_METADATA_INDEX || header == METADATA_END {
+                return Err(ProtocolError::PayloadTooLarge);
+            }
+
+            buffer.put_u8(header);
+
+            match &entry.value {
+                MetadataValue::Byte(value) => buffer.put_i8(*value),
+                MetadataValue::Short(value) => buffer.put_i16(*value),
+                MetadataValue::Int(value) => buffer.put_i32(*value),
+                MetadataValue::Float(value) => buffer.put_f32(*value),
+                MetadataValue::String(value) => STRING_LIMIT.put(buffer, value)?,
+                MetadataValue::Item { id, count, damage } => {
+                    buffer.put_i16(*id);
+                    buffer.put_i8(*count);
+                    buffer.put_i16(*damage);
+                }
+                MetadataValue::Position { x, y, z } => {
+                    buffer.put_i32(*x);
+                    buffer.put_i32(*y);
+                    buffer.put_i32(*z);
+                }
+            }
+        }
+
+        buffer.put_u8(METADATA_END);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_empty_metadata() {
+        let mut buffer = BytesMut::new();
+
+        Metadata::new().to_bytes(&mut buffer).unwrap();
+
+        assert_eq!(buffer.as_ref(), &[0x7F]);
+    }
+
+    #[test]
+    fn decode_empty_metadata() {
+        let mut cursor = Cursor::new(&[0x7Fu8] as &[u8]);
+
+        let metadata = Metadata::from_bytes(&mut cursor).unwrap();
+
+        assert_eq!(metadata, Metadata::new());
+    }
+
+    #[test]
+    fn encode_metadata() {
+        let mut metadata = Metadata::new();
+        metadata.set(0, MetadataValue::Byte(0x02));
+        metadata.set(1, MetadataValue::Short(300));
+        metadata.set(
+            10,
+            MetadataValue::Item {
+                id: 1,
+                count: 64,
+                damage: 0,
+            },
+        );
+
+        let mut buffer = BytesMut::new();
+        metadata.to_bytes(&mut buffer).unwrap();
+
+        assert_eq!(
+            buffer.as_ref(),
+            &[0x00, 0x02, 0x21, 0x01, 0x2C, 0xAA, 0x00, 0x01, 0x40, 0x00, 0x00, 0x7F]
+        );
+    }
+
+    #[test]
+    fn encode_decode_all_types() {
+        let mut metadata = Metadata::new();
+        metadata.set(0, MetadataValue::Byte(-1));
+        metadata.set(1, MetadataValue::Short(-300));
+        metadata.set(2, MetadataValue::Int(70000));
+        metadata.set(3, MetadataValue::Float(0.5));
+        metadata.set(4, MetadataValue::String("Notch".to_string()));
+        metadata.set(
+            5,
+            MetadataValue::Item {
+                id: 276,
+                count: 1,
+                damage: 12,
+            },
+        );
+        metadata.set(6, MetadataValue::Position { x: -8, y: 64, z: 8 });
+
+        let mut buffer = BytesMut::new();
+        metadata.to_bytes(&mut buffer).unwrap();
+        let decoded = Metadata::from_bytes(&mut Cursor::new(buffer.as_ref())).unwrap();
+
+        assert_eq!(decoded, metadata);
+    }
+
+    #[test]
+    fn set_replaces_existing_entry() {
+        let mut metadata = Metadata::new();
+        metadata.set(0, MetadataValue::Byte(0));
+        metadata.set(0, MetadataValue::Byte(1));
+
+        assert_eq!(metadata.entries.len(), 1);
+        assert_eq!(metadata.get(0), Some(&MetadataValue::Byte(1)));
+    }
+
+    #[test]
+    fn encode_index_out_of_range() {
+        let mut metadata = Metadata::new();
+        metadata.set(32, MetadataValue::Byte(0));
+
+        let result = metadata.to_bytes(&mut BytesMut::new());
+
+        assert!(matches!(result, Err(ProtocolError::PayloadTooLarge)));
+    }
+
+    #[test]
+    fn decode_unknown_type() {
+        let mut cursor = Cursor::new(&[0xE0u8, 0x00, 0x7F] as &[u8]);
+
+        let result = Metadata::from_bytes(&mut cursor);
+
+        assert!(matches!(result, Err(ProtocolError::UnknownMetadataType(7))));
+    }
+
+    #[test]
+    fn decode_missing_terminator() {
+        let mut cursor = Cursor::new(&[0x00u8, 0x01] as &[u8]);
+
+        let result = Metadata::from_bytes(&mut cursor);
+
+        assert!(matches!(
+            result,
+            Err(ProtocolError::UnexpectedEof { needed: 1 })
+        ));
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn roundtrip_metadata(metadata in crate::arbitrary::metadata()) {
+            crate::arbitrary::assert_roundtrip(&metadata)?;
+        }
+    }
+}
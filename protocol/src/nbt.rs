@@ -0,0 +1,614 @@
+use std::{
+    collections::BTreeMap,
+    io::{Cursor, Read, Write},
+};
+
+use bytes::{Buf, BufMut, BytesMut};
+use flate2::{
+    read::{GzDecoder, ZlibDecoder},
+    write::{GzEncoder, ZlibEncoder},
+};
+
+use crate::error::ProtocolError;
+
+/// End tag identifier, closes a compound.
+const END_TAG_ID: u8 = 0;
+/// Byte tag identifier.
+const BYTE_TAG_ID: u8 = 1;
+/// Short tag identifier.
+const SHORT_TAG_ID: u8 = 2;
+/// Int tag identifier.
+const INT_TAG_ID: u8 = 3;
+/// Long tag identifier.
+const LONG_TAG_ID: u8 = 4;
+/// Float tag identifier.
+const FLOAT_TAG_ID: u8 = 5;
+/// Double tag identifier.
+const DOUBLE_TAG_ID: u8 = 6;
+/// Byte array tag identifier.
+const BYTE_ARRAY_TAG_ID: u8 = 7;
+/// String tag identifier.
+const STRING_TAG_ID: u8 = 8;
+/// List tag identifier.
+const LIST_TAG_ID: u8 = 9;
+/// Compound tag identifier.
+const COMPOUND_TAG_ID: u8 = 10;
+/// Int array tag identifier.
+const INT_ARRAY_TAG_ID: u8 = 11;
+
+/// Maximum nesting of lists and compounds accepted by the reader.
+const MAX_DEPTH: usize = 512;
+
+/// Named tags contained within a compound.
+pub type Compound = BTreeMap<String, Tag>;
+
+/// A single NBT tag value.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Tag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<u8>),
+    String(String),
+
+    /// Tags of the same type, the type of an empty list is written as the end tag.
+    List(Vec<Tag>),
+
+    Compound(Compound),
+    IntArray(Vec<i32>),
+}
+
+impl Tag {
+    /// Returns the tag type identifier.
+    fn id(&self) -> u8 {
+        match self {
+            Tag::Byte(_) => BYTE_TAG_ID,
+            Tag::Short(_) => SHORT_TAG_ID,
+            Tag::Int(_) => INT_TAG_ID,
+            Tag::Long(_) => LONG_TAG_ID,
+            Tag::Float(_) => FLOAT_TAG_ID,
+            Tag::Double(_) => DOUBLE_TAG_ID,
+            Tag::ByteArray(_) => BYTE_ARRAY_TAG_ID,
+            Tag::String(_) => STRING_TAG_ID,
+            Tag::List(_) => LIST_TAG_ID,
+            Tag::Compound(_) => COMPOUND_TAG_ID,
+            Tag::IntArray(_) => INT_ARRAY_TAG_ID,
+        }
+    }
+
+    pub fn as_byte(&self) -> Option<i8> {
+        match self {
+            Tag::Byte(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_short(&self) -> Option<i16> {
+        match self {
+            Tag::Short(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i32> {
+        match self {
+            Tag::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_long(&self) -> Option<i64> {
+        match self {
+            Tag::Long(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_float(&self) -> Option<f32> {
+        match self {
+            Tag::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_double(&self) -> Option<f64> {
+        match self {
+            Tag::Double(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_byte_array(&self) -> Option<&[u8]> {
+        match self {
+            Tag::ByteArray(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Tag::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Tag]> {
+        match self {
+            Tag::List(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_compound(&self) -> Option<&Compound> {
+        match self {
+            Tag::Compound(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_int_array(&self) -> Option<&[i32]> {
+        match self {
+            Tag::IntArray(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// Compression wrapping the binary NBT data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// Plain, uncompressed data.
+    None,
+
+    /// Gzip, used by `level.dat` and the player files.
+    Gzip,
+
+    /// Zlib, used by the chunks stored in region files.
+    Zlib,
+}
+
+/// An NBT document, a compound with a name at the top level.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Nbt {
+    /// Name of the root compound, usually empty.
+    pub name: String,
+
+    /// Tags of the root compound.
+    pub root: Compound,
+}
+
+impl Nbt {
+    /// Creates a document with the given root compound name and tags.
+    pub fn new(name: impl Into<String>, root: Compound) -> Self {
+        Self {
+            name: name.into(),
+            root,
+        }
+    }
+
+    /// Parses a document from the bytes wrapped with the given compression.
+    pub fn read(bytes: &[u8], compression: Compression) -> Result<Self, ProtocolError> {
+        let data = decompress(bytes, compression)?;
+        let mut cursor = Cursor::new(data.as_ref());
+
+        let id = cursor.try_get_u8()?;
+        if id != COMPOUND_TAG_ID {
+            return Err(ProtocolError::UnknownNbtTag(id));
+        }
+
+        let name = read_nbt_string(&mut cursor)?;
+        let root = read_compound(&mut cursor, 0)?;
+
+        Ok(Self { name, root })
+    }
+
+    /// Writes the document to bytes wrapped with the given compression.
+    pub fn write(&self, compression: Compression) -> Result<Vec<u8>, ProtocolError> {
+        let mut buffer = BytesMut::new();
+
+        buffer.put_u8(COMPOUND_TAG_ID);
+        put_nbt_string(&mut buffer, &self.name)?;
+        put_compound(&mut buffer, &self.root)?;
+
+        compress(&buffer, compression)
+    }
+}
+
+/// Removes the compression wrapping the data.
+fn decompress(bytes: &[u8], compression: Compression) -> Result<Vec<u8>, ProtocolError> {
+    let mut data = Vec::new();
+
+    match compression {
+        Compression::None => data.extend_from_slice(bytes),
+        Compression::Gzip => {
+            GzDecoder::new(bytes).read_to_end(&mut data)?;
+        }
+        Compression::Zlib => {
+            ZlibDecoder::new(bytes).read_to_end(&mut data)?;
+        }
+    }
+
+    Ok(data)
+}
+
+/// Wraps the data with the compression.
+fn compress(bytes: &[u8], compression: Compression) -> Result<Vec<u8>, ProtocolError> {
+    match compression {
+        Compression::None => Ok(bytes.to_vec()),
+        Compression::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(bytes)?;
+            Ok(encoder.finish()?)
+        }
+        Compression::Zlib => {
+            let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(bytes)?;
+            Ok(encoder.finish()?)
+        }
+    }
+}
+
+/// Reads the tags of a compound up to and including its end tag.
+fn read_compound(bytes: &mut Cursor<&[u8]>, depth: usize) -> Result<Compound, ProtocolError> {
+    let mut compound = Compound::new();
+
+    loop {
+        let id = bytes.try_get_u8()?;
+
+        if id == END_TAG_ID {
+            return Ok(compound);
+        }
+
+        let name = read_nbt_string(bytes)?;
+        let tag = read_payload(bytes, id, depth + 1)?;
+        compound.insert(name, tag);
+    }
+}
+
+/// Reads the payload of a tag with the given type identifier.
+fn read_payload(bytes: &mut Cursor<&[u8]>, id: u8, depth: usize) -> Result<Tag, ProtocolError> {
+    if depth > MAX_DEPTH {
+        return Err(ProtocolError::PayloadTooLarge);
+    }
+
+    let tag = match id {
+        BYTE_TAG_ID => Tag::Byte(bytes.try_get_i8()?),
+        SHORT_TAG_ID => Tag::Short(bytes.try_get_i16()?),
+        INT_TAG_ID => Tag::Int(bytes.try_get_i32()?),
+        LONG_TAG_ID => Tag::Long(bytes.try_get_i64()?),
+        FLOAT_TAG_ID => Tag::Float(bytes.try_get_f32()?),
+        DOUBLE_TAG_ID => Tag::Double(bytes.try_get_f64()?),
+        BYTE_ARRAY_TAG_ID => {
+            let length = read_length(bytes, 1)?;
+            let mut data = vec![0u8; length];
+            bytes.copy_to_slice(&mut data);
+            Tag::ByteArray(data)
+        }
+        STRING_TAG_ID => Tag::String(read_nbt_string(bytes)?),
+        LIST_TAG_ID => {
+            let element_id = bytes.try_get_u8()?;
+            let length = read_length(bytes, 0)?;
+            let mut list = Vec::new();
+
+            for _ in 0..length {
+                list.push(read_payload(bytes, element_id, depth + 1)?);
+            }
+
+            Tag::List(list)
+        }
+        COMPOUND_TAG_ID => Tag::Compound(read_compound(bytes, depth)?),
+        INT_ARRAY_TAG_ID => {
+            let length = read_length(bytes, 4)?;
+            let mut data = Vec::with_capacity(length);
+
+            for _ in 0..length {
+                data.push(bytes.get_i32());
+            }
+
+            Tag::IntArray(data)
+        }
+        _ => return Err(ProtocolError::UnknownNbtTag(id)),
+    };
+
+    Ok(tag)
+}
+
+/// Reads an `i32` length prefix of an array or a list.
+///
+/// Verifies that the remaining data can hold that many elements of
+/// `element_size` bytes, so a bogus length can't trigger a huge allocation.
+fn read_length(bytes: &mut Cursor<&[u8]>, element_size: usize) -> Result<usize, ProtocolError> {
+    let length = bytes.try_get_i32()?;
+    let length = usize::try_from(length).map_err(|_| ProtocolError::NegativeLength(length))?;
+
+    let required = length * element_size;
+    if bytes.remaining() < required {
+        return Err(ProtocolError::UnexpectedEof {
+            needed: required - bytes.remaining(),
+        });
+    }
+
+    Ok(length)
+}
+
+/// Writes the tags of a compound followed by the end tag.
+fn put_compound(buffer: &mut BytesMut, compound: &Compound) -> Result<(), ProtocolError> {
+    for (name, tag) in compound {
+        buffer.put_u8(tag.id());
+        put_nbt_string(buffer, name)?;
+        put_payload(buffer, tag)?;
+    }
+
+    buffer.put_u8(END_TAG_ID);
+    Ok(())
+}
+
+/// Writes the payload of a tag without its type identifier and name.
+fn put_payload(buffer: &mut BytesMut, tag: &Tag) -> Result<(), ProtocolError> {
+    match tag {
+        Tag::Byte(value) => buffer.put_i8(*value),
+        Tag::Short(value) => buffer.put_i16(*value),
+        Tag::Int(value) => buffer.put_i32(*value),
+        Tag::Long(value) => buffer.put_i64(*value),
+        Tag::Float(value) => buffer.put_f32(*value),
+        Tag::Double(value) => buffer.put_f64(*value),
+        Tag::ByteArray(data) => {
+            put_length(buffer, data.len())?;
+            buffer.put_slice(data);
+        }
+        Tag::String(value) => put_nbt_string(buffer, value)?,
+        Tag::List(list) => {
+            let element_id = list.first().map_or(END_TAG_ID, Tag::id);
+
+            if list.iter().any(|tag| tag.id() != element_id) {
+                return Err(ProtocolError::MixedNbtList);
+            }
+
+            buffer.put_u8(element_id);
+            put_length(buffer, list.len())?;
+
+            for tag in list {
+                put_payload(buffer, tag)?;
+            }
+        }
+        Tag::Compound(compound) => put_compound(buffer, compound)?,
+        Tag::IntArray(data) => {
+            put_length(buffer, data.len())?;
+
+            for value in data {
+                buffer.put_i32(*value);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes an `i32` length prefix of an array or a list.
+fn put_length(buffer: &mut BytesMut, length: usize) -> Result<(), ProtocolError> {
+    let length = i32::try_from(length).map_err(|_| ProtocolError::PayloadTooLarge)?;
+    buffer.put_i32(length);
+    Ok(())
+}
+
+/// Reads a string encoded in Java's modified UTF-8.
+///
+/// Reads a `u16` length prefix at first, followed by that many bytes.
+/// Unlike the packet strings, the length refers to the number of bytes.
+fn read_nbt_string(bytes: &mut Cursor<&[u8]>) -> Result<String, ProtocolError> {
+    let length = bytes.try_get_u16()? as usize;
+
+    if bytes.remaining() < length {
+        return Err(ProtocolError::UnexpectedEof {
+            needed: length - bytes.remaining(),
+        });
+    }
+
+    let mut data = vec![0u8; length];
+    bytes.copy_to_slice(&mut data);
+
+    let mut utf16_data = Vec::with_capacity(length);
+    let mut i = 0;
+
+    while i < data.len() {
+        let byte = data[i] as u16;
+
+        let (unit, size) = if byte & 0x80 == 0 {
+            (byte, 1)
+        } else if byte & 0xE0 == 0xC0 {
+            let second = *data.get(i + 1).ok_or(ProtocolError::InvalidString)? as u16;
+            ((byte & 0x1F) << 6 | (second & 0x3F), 2)
+        } else if byte & 0xF0 == 0xE0 {
+            let second = *data.get(i + 1).ok_or(ProtocolError::InvalidString)? as u16;
+            let third = *data.get(i + 2).ok_or(ProtocolError::InvalidString)? as u16;
+            (
+                (byte & 0x0F) << 12 | (second & 0x3F) << 6 | (third & 0x3F),
+                3,
+            )
+        } else {
+            return Err(ProtocolError::InvalidString);
+        };
+
+        utf16_data.push(unit);
+        i += size;
+    }
+
+    String::from_utf16(&utf16_data).map_err(|_| ProtocolError::InvalidString)
+}
+
+/// Puts a string encoded in Java's modified UTF-8.
+///
+/// The null character is written as two bytes and characters outside
+/// of the Basic Multilingual Plane as two three-byte surrogates.
+fn put_nbt_string(buffer: &mut BytesMut, s: &str) -> Result<(), ProtocolError> {
+    let mut data = Vec::with_capacity(s.len());
+
+    for unit in s.encode_utf16() {
+        match unit {
+            0x0001..=0x007F => data.push(unit as u8),
+            0x0000 | 0x0080..=0x07FF => {
+                data.push(0xC0 | (unit >> 6) as u8);
+                data.push(0x80 | (unit & 0x3F) as u8);
+            }
+            _ => {
+                data.push(0xE0 | (unit >> 12) as u8);
+                data.push(0x80 | (unit >> 6 & 0x3F) as u8);
+                data.push(0x80 | (unit & 0x3F) as u8);
+            }
+        }
+    }
+
+    let length = u16::try_from(data.len()).map_err(|_| ProtocolError::PayloadTooLarge)?;
+    buffer.put_u16(length);
+    buffer.put_slice(&data);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The `hello_world.nbt` example from the NBT specification.
+    const HELLO_WORLD: &[u8] = &[
+        0x0A, 0x00, 0x0B, b'h', b'e', b'l', b'l', b'o', b' ', b'w', b'o', b'r', b'l', b'd', 0x08,
+        0x00, 0x04, b'n', b'a', b'm', b'e', 0x00, 0x09, b'B', b'a', b'n', b'a', b'n', b'r', b'a',
+        b'm', b'a', 0x00,
+    ];
+
+    fn hello_world() -> Nbt {
+        let mut root = Compound::new();
+        root.insert("name".to_string(), Tag::String("Bananrama".to_string()));
+
+        Nbt::new("hello world", root)
+    }
+
+    fn all_tags() -> Nbt {
+        let mut nested = Compound::new();
+        nested.insert("id".to_string(), Tag::Short(276));
+
+        let mut root = Compound::new();
+        root.insert("byte".to_string(), Tag::Byte(-1));
+        root.insert("short".to_string(), Tag::Short(-300));
+        root.insert("int".to_string(), Tag::Int(70000));
+        root.insert("long".to_string(), Tag::Long(-1 << 40));
+        root.insert("float".to_string(), Tag::Float(0.5));
+        root.insert("double".to_string(), Tag::Double(-0.25));
+        root.insert("bytes".to_string(), Tag::ByteArray(vec![1, 2, 3]));
+        root.insert("string".to_string(), Tag::String("\0ąę😀".to_string()));
+        root.insert(
+            "list".to_string(),
+            Tag::List(vec![Tag::Compound(nested.clone()), Tag::Compound(nested)]),
+        );
+        root.insert("empty".to_string(), Tag::List(vec![]));
+        root.insert("ints".to_string(), Tag::IntArray(vec![-1, 0, 1]));
+
+        Nbt::new("", root)
+    }
+
+    #[test]
+    fn decode_hello_world() {
+        let nbt = Nbt::read(HELLO_WORLD, Compression::None).unwrap();
+
+        assert_eq!(nbt, hello_world());
+    }
+
+    #[test]
+    fn encode_hello_world() {
+        let data = hello_world().write(Compression::None).unwrap();
+
+        assert_eq!(data, HELLO_WORLD);
+    }
+
+    #[test]
+    fn encode_decode_all_tags() {
+        let nbt = all_tags();
+
+        let data = nbt.write(Compression::None).unwrap();
+        let decoded = Nbt::read(&data, Compression::None).unwrap();
+
+        assert_eq!(decoded, nbt);
+    }
+
+    #[test]
+    fn encode_decode_gzip() {
+        let nbt = all_tags();
+
+        let data = nbt.write(Compression::Gzip).unwrap();
+        let decoded = Nbt::read(&data, Compression::Gzip).unwrap();
+
+        assert_eq!(&data[..2], &[0x1F, 0x8B]);
+        assert_eq!(decoded, nbt);
+    }
+
+    #[test]
+    fn encode_decode_zlib() {
+        let nbt = all_tags();
+
+        let data = nbt.write(Compression::Zlib).unwrap();
+        let decoded = Nbt::read(&data, Compression::Zlib).unwrap();
+
+        assert_eq!(data[0], 0x78);
+        assert_eq!(decoded, nbt);
+    }
+
+    #[test]
+    fn put_nbt_string_modified_utf8() {
+        let mut buffer = BytesMut::new();
+        put_nbt_string(&mut buffer, "\0😀").unwrap();
+
+        assert_eq!(
+            buffer.as_ref(),
+            &[0x00, 0x08, 0xC0, 0x80, 0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80]
+        );
+    }
+
+    #[test]
+    fn encode_mixed_list() {
+        let mut root = Compound::new();
+        root.insert(
+            "list".to_string(),
+            Tag::List(vec![Tag::Byte(0), Tag::Int(0)]),
+        );
+
+        let result = Nbt::new("", root).write(Compression::None);
+
+        assert!(matches!(result, Err(ProtocolError::MixedNbtList)));
+    }
+
+    #[test]
+    fn decode_unknown_tag() {
+        let data: &[u8] = &[0x0A, 0x00, 0x00, 0x0C, 0x00, 0x00];
+
+        let result = Nbt::read(data, Compression::None);
+
+        assert!(matches!(result, Err(ProtocolError::UnknownNbtTag(12))));
+    }
+
+    #[test]
+    fn decode_negative_length() {
+        let data: &[u8] = &[0x0A, 0x00, 0x00, 0x07, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF];
+
+        let result = Nbt::read(data, Compression::None);
+
+        assert!(matches!(result, Err(ProtocolError::NegativeLength(-1))));
+    }
+
+    #[test]
+    fn decode_truncated_array() {
+        let data: &[u8] = &[
+            0x0A, 0x00, 0x00, 0x0B, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00,
+        ];
+
+        let result = Nbt::read(data, Compression::None);
+
+        assert!(matches!(
+            result,
+            Err(ProtocolError::UnexpectedEof { needed: 7 })
+        ));
+    }
+}
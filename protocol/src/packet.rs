@@ -1,6 +1,14 @@
-use std::io::{self, Cursor};
+use std::io::Cursor;
 
 use bytes::{Buf, BufMut, BytesMut};
+pub use protocol_derive::{FromBytes, ToBytes};
+
+use crate::{
+    chunk::{ChunkData, MAX_COMPRESSED_LENGTH},
+    error::ProtocolError,
+    metadata::Metadata,
+    slot::Slot,
+};
 
 /// Keep alive packet identifier.
 const KEEP_ALIVE_PACKET_ID: u8 = 0x00;
@@ -16,19 +24,456 @@ const TIME_UPDATE_PACKET_ID: u8 = 0x04;
 const ENTITY_EQUIPMENT_PACKET_ID: u8 = 0x05;
 /// Spawn position packet identifier.
 const SPAWN_POSITION_PACKET_ID: u8 = 0x06;
+/// Use entity packet identifier.
+const USE_ENTITY_PACKET_ID: u8 = 0x07;
+/// Update health packet identifier.
+const UPDATE_HEALTH_PACKET_ID: u8 = 0x08;
+/// Respawn packet identifier.
+const RESPAWN_PACKET_ID: u8 = 0x09;
+/// Player packet identifier.
+const PLAYER_PACKET_ID: u8 = 0x0A;
 /// Player position packet identifier.
 const PLAYER_POSITION_PACKET_ID: u8 = 0x0B;
+/// Player look packet identifier.
+const PLAYER_LOOK_PACKET_ID: u8 = 0x0C;
 /// Player position and look packet identifier.
 const PLAYER_POSITION_AND_LOOK_PACKET_ID: u8 = 0x0D;
+/// Player digging packet identifier.
+const PLAYER_DIGGING_PACKET_ID: u8 = 0x0E;
+/// Player block placement packet identifier.
+const PLAYER_BLOCK_PLACEMENT_PACKET_ID: u8 = 0x0F;
+/// Holding change packet identifier.
+const HOLDING_CHANGE_PACKET_ID: u8 = 0x10;
+/// Use bed packet identifier.
+const USE_BED_PACKET_ID: u8 = 0x11;
+/// Animation packet identifier.
+const ANIMATION_PACKET_ID: u8 = 0x12;
+/// Entity action packet identifier.
+const ENTITY_ACTION_PACKET_ID: u8 = 0x13;
+/// Named entity spawn packet identifier.
+const NAMED_ENTITY_SPAWN_PACKET_ID: u8 = 0x14;
+/// Pickup spawn packet identifier.
+const PICKUP_SPAWN_PACKET_ID: u8 = 0x15;
+/// Collect item packet identifier.
+const COLLECT_ITEM_PACKET_ID: u8 = 0x16;
+/// Add object/vehicle packet identifier.
+const ADD_OBJECT_PACKET_ID: u8 = 0x17;
+/// Mob spawn packet identifier.
+const MOB_SPAWN_PACKET_ID: u8 = 0x18;
+/// Painting packet identifier.
+const PAINTING_PACKET_ID: u8 = 0x19;
+/// Experience orb packet identifier.
+const EXPERIENCE_ORB_PACKET_ID: u8 = 0x1A;
+/// Entity velocity packet identifier.
+const ENTITY_VELOCITY_PACKET_ID: u8 = 0x1C;
+/// Destroy entity packet identifier.
+const DESTROY_ENTITY_PACKET_ID: u8 = 0x1D;
+/// Entity packet identifier.
+const ENTITY_PACKET_ID: u8 = 0x1E;
+/// Entity relative move packet identifier.
+const ENTITY_RELATIVE_MOVE_PACKET_ID: u8 = 0x1F;
+/// Entity look packet identifier.
+const ENTITY_LOOK_PACKET_ID: u8 = 0x20;
+/// Entity look and relative move packet identifier.
+const ENTITY_LOOK_AND_RELATIVE_MOVE_PACKET_ID: u8 = 0x21;
+/// Entity teleport packet identifier.
+const ENTITY_TELEPORT_PACKET_ID: u8 = 0x22;
+/// Entity head look packet identifier.
+const ENTITY_HEAD_LOOK_PACKET_ID: u8 = 0x23;
+/// Entity status packet identifier.
+const ENTITY_STATUS_PACKET_ID: u8 = 0x26;
+/// Attach entity packet identifier.
+const ATTACH_ENTITY_PACKET_ID: u8 = 0x27;
+/// Entity metadata packet identifier.
+const ENTITY_METADATA_PACKET_ID: u8 = 0x28;
+/// Entity effect packet identifier.
+const ENTITY_EFFECT_PACKET_ID: u8 = 0x29;
+/// Remove entity effect packet identifier.
+const REMOVE_ENTITY_EFFECT_PACKET_ID: u8 = 0x2A;
+/// Set experience packet identifier.
+const SET_EXPERIENCE_PACKET_ID: u8 = 0x2B;
 /// Chunk allocation packet identifier.
 const CHUNK_ALLOCATION_PACKET_ID: u8 = 0x32;
+/// Map chunk packet identifier.
+const MAP_CHUNK_PACKET_ID: u8 = 0x33;
+/// Multi block change packet identifier.
+const MULTI_BLOCK_CHANGE_PACKET_ID: u8 = 0x34;
+/// Block change packet identifier.
+const BLOCK_CHANGE_PACKET_ID: u8 = 0x35;
+/// Block action packet identifier.
+const BLOCK_ACTION_PACKET_ID: u8 = 0x36;
+/// Sound/particle effect packet identifier.
+const SOUND_PARTICLE_EFFECT_PACKET_ID: u8 = 0x3D;
+/// Change game state packet identifier.
+const CHANGE_GAME_STATE_PACKET_ID: u8 = 0x46;
+/// Thunderbolt packet identifier.
+const THUNDERBOLT_PACKET_ID: u8 = 0x47;
+/// Open window packet identifier.
+const OPEN_WINDOW_PACKET_ID: u8 = 0x64;
+/// Close window packet identifier.
+const CLOSE_WINDOW_PACKET_ID: u8 = 0x65;
+/// Window click packet identifier.
+const WINDOW_CLICK_PACKET_ID: u8 = 0x66;
+/// Set slot packet identifier.
+const SET_SLOT_PACKET_ID: u8 = 0x67;
+/// Window items packet identifier.
+const WINDOW_ITEMS_PACKET_ID: u8 = 0x68;
+/// Update window property packet identifier.
+const UPDATE_WINDOW_PROPERTY_PACKET_ID: u8 = 0x69;
+/// Transaction packet identifier.
+const TRANSACTION_PACKET_ID: u8 = 0x6A;
+/// Creative inventory action packet identifier.
+const CREATIVE_INVENTORY_ACTION_PACKET_ID: u8 = 0x6B;
+/// Update sign packet identifier.
+const UPDATE_SIGN_PACKET_ID: u8 = 0x82;
+/// Item data packet identifier.
+const ITEM_DATA_PACKET_ID: u8 = 0x83;
 /// Server list ping packet identifier.
 const SERVER_LIST_PING_PACKET_ID: u8 = 0xFE;
 /// Disconnect/Kick packet identifier.
 const DISCONNECT_KICK_PACKET_ID: u8 = 0xFF;
 
+/// Direction in which a packet travels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    ClientToServer,
+    ServerToClient,
+    TwoWay,
+}
+
+/// Static information about a packet type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PacketInfo {
+    /// Packet identifier, the first byte of the packet.
+    pub id: u8,
+
+    /// Human-readable name of the packet.
+    pub name: &'static str,
+
+    /// Direction in which the packet travels.
+    pub direction: Direction,
+}
+
+impl PacketInfo {
+    /// Looks up the packet information by the packet identifier.
+    pub fn by_id(id: u8) -> Option<&'static PacketInfo> {
+        PACKETS.iter().find(|info| info.id == id)
+    }
+}
+
+/// Registry of all the supported packets, sorted by the identifier.
+pub const PACKETS: &[PacketInfo] = &[
+    PacketInfo {
+        id: KEEP_ALIVE_PACKET_ID,
+        name: "KeepAlive",
+        direction: Direction::TwoWay,
+    },
+    PacketInfo {
+        id: LOGIN_REQUEST_PACKET_ID,
+        name: "LoginRequest",
+        direction: Direction::TwoWay,
+    },
+    PacketInfo {
+        id: HANDSHAKE_PACKET_ID,
+        name: "Handshake",
+        direction: Direction::TwoWay,
+    },
+    PacketInfo {
+        id: CHAT_MESSAGE_PACKET_ID,
+        name: "ChatMessage",
+        direction: Direction::TwoWay,
+    },
+    PacketInfo {
+        id: TIME_UPDATE_PACKET_ID,
+        name: "TimeUpdate",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: ENTITY_EQUIPMENT_PACKET_ID,
+        name: "EntityEquipment",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: SPAWN_POSITION_PACKET_ID,
+        name: "SpawnPosition",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: USE_ENTITY_PACKET_ID,
+        name: "UseEntity",
+        direction: Direction::ClientToServer,
+    },
+    PacketInfo {
+        id: UPDATE_HEALTH_PACKET_ID,
+        name: "UpdateHealth",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: RESPAWN_PACKET_ID,
+        name: "Respawn",
+        direction: Direction::TwoWay,
+    },
+    PacketInfo {
+        id: PLAYER_PACKET_ID,
+        name: "Player",
+        direction: Direction::ClientToServer,
+    },
+    PacketInfo {
+        id: PLAYER_POSITION_PACKET_ID,
+        name: "PlayerPosition",
+        direction: Direction::ClientToServer,
+    },
+    PacketInfo {
+        id: PLAYER_LOOK_PACKET_ID,
+        name: "PlayerLook",
+        direction: Direction::ClientToServer,
+    },
+    PacketInfo {
+        id: PLAYER_POSITION_AND_LOOK_PACKET_ID,
+        name: "PlayerPositionAndLook",
+        direction: Direction::TwoWay,
+    },
+    PacketInfo {
+        id: PLAYER_DIGGING_PACKET_ID,
+        name: "PlayerDigging",
+        direction: Direction::ClientToServer,
+    },
+    PacketInfo {
+        id: PLAYER_BLOCK_PLACEMENT_PACKET_ID,
+        name: "PlayerBlockPlacement",
+        direction: Direction::ClientToServer,
+    },
+    PacketInfo {
+        id: HOLDING_CHANGE_PACKET_ID,
+        name: "HoldingChange",
+        direction: Direction::ClientToServer,
+    },
+    PacketInfo {
+        id: USE_BED_PACKET_ID,
+        name: "UseBed",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: ANIMATION_PACKET_ID,
+        name: "Animation",
+        direction: Direction::TwoWay,
+    },
+    PacketInfo {
+        id: ENTITY_ACTION_PACKET_ID,
+        name: "EntityAction",
+        direction: Direction::ClientToServer,
+    },
+    PacketInfo {
+        id: NAMED_ENTITY_SPAWN_PACKET_ID,
+        name: "NamedEntitySpawn",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: PICKUP_SPAWN_PACKET_ID,
+        name: "PickupSpawn",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: COLLECT_ITEM_PACKET_ID,
+        name: "CollectItem",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: ADD_OBJECT_PACKET_ID,
+        name: "AddObject",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: MOB_SPAWN_PACKET_ID,
+        name: "MobSpawn",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: PAINTING_PACKET_ID,
+        name: "Painting",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: EXPERIENCE_ORB_PACKET_ID,
+        name: "ExperienceOrb",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: ENTITY_VELOCITY_PACKET_ID,
+        name: "EntityVelocity",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: DESTROY_ENTITY_PACKET_ID,
+        name: "DestroyEntity",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: ENTITY_PACKET_ID,
+        name: "Entity",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: ENTITY_RELATIVE_MOVE_PACKET_ID,
+        name: "EntityRelativeMove",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: ENTITY_LOOK_PACKET_ID,
+        name: "EntityLook",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: ENTITY_LOOK_AND_RELATIVE_MOVE_PACKET_ID,
+        name: "EntityLookAndRelativeMove",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: ENTITY_TELEPORT_PACKET_ID,
+        name: "EntityTeleport",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: ENTITY_HEAD_LOOK_PACKET_ID,
+        name: "EntityHeadLook",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: ENTITY_STATUS_PACKET_ID,
+        name: "EntityStatus",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: ATTACH_ENTITY_PACKET_ID,
+        name: "AttachEntity",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: ENTITY_METADATA_PACKET_ID,
+        name: "EntityMetadata",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: ENTITY_EFFECT_PACKET_ID,
+        name: "EntityEffect",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: REMOVE_ENTITY_EFFECT_PACKET_ID,
+        name: "RemoveEntityEffect",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: SET_EXPERIENCE_PACKET_ID,
+        name: "SetExperience",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: CHUNK_ALLOCATION_PACKET_ID,
+        name: "ChunkAllocation",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: MAP_CHUNK_PACKET_ID,
+        name: "MapChunk",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: MULTI_BLOCK_CHANGE_PACKET_ID,
+        name: "MultiBlockChange",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: BLOCK_CHANGE_PACKET_ID,
+        name: "BlockChange",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: BLOCK_ACTION_PACKET_ID,
+        name: "BlockAction",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: SOUND_PARTICLE_EFFECT_PACKET_ID,
+        name: "SoundParticleEffect",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: CHANGE_GAME_STATE_PACKET_ID,
+        name: "ChangeGameState",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: THUNDERBOLT_PACKET_ID,
+        name: "Thunderbolt",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: OPEN_WINDOW_PACKET_ID,
+        name: "OpenWindow",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: CLOSE_WINDOW_PACKET_ID,
+        name: "CloseWindow",
+        direction: Direction::TwoWay,
+    },
+    PacketInfo {
+        id: WINDOW_CLICK_PACKET_ID,
+        name: "WindowClick",
+        direction: Direction::ClientToServer,
+    },
+    PacketInfo {
+        id: SET_SLOT_PACKET_ID,
+        name: "SetSlot",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: WINDOW_ITEMS_PACKET_ID,
+        name: "WindowItems",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: UPDATE_WINDOW_PROPERTY_PACKET_ID,
+        name: "UpdateWindowProperty",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: TRANSACTION_PACKET_ID,
+        name: "Transaction",
+        direction: Direction::TwoWay,
+    },
+    PacketInfo {
+        id: CREATIVE_INVENTORY_ACTION_PACKET_ID,
+        name: "CreativeInventoryAction",
+        direction: Direction::TwoWay,
+    },
+    PacketInfo {
+        id: UPDATE_SIGN_PACKET_ID,
+        name: "UpdateSign",
+        direction: Direction::TwoWay,
+    },
+    PacketInfo {
+        id: ITEM_DATA_PACKET_ID,
+        name: "ItemData",
+        direction: Direction::ServerToClient,
+    },
+    PacketInfo {
+        id: SERVER_LIST_PING_PACKET_ID,
+        name: "ServerListPing",
+        direction: Direction::ClientToServer,
+    },
+    PacketInfo {
+        id: DISCONNECT_KICK_PACKET_ID,
+        name: "DisconnectKick",
+        direction: Direction::TwoWay,
+    },
+];
+
 /// Represents a single packet type and payload contained within it.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
 pub enum Packet {
     /// Two-way, Keep Alive packet.
     KeepAlive(KeepAlivePayload),
@@ -51,15 +496,162 @@ pub enum Packet {
     /// Server to Client, spawn position packet.
     SpawnPosition(SpawnPositionPayload),
 
+    /// Client to Server, use entity packet.
+    UseEntity(UseEntityPayload),
+
+    /// Server to Client, update health packet.
+    UpdateHealth(UpdateHealthPayload),
+
+    /// Two-way, Respawn packet.
+    Respawn(RespawnPayload),
+
+    /// Client to Server, player packet.
+    Player(PlayerPayload),
+
     /// Client to Server, player position packet.
     PlayerPosition(PlayerPositionPayload),
 
+    /// Client to Server, player look packet.
+    PlayerLook(PlayerLookPayload),
+
     /// Two-way, Player position and look packet.
     PlayerPositionAndLook(PlayerPositionAndLookPayload),
 
+    /// Client to Server, player digging packet.
+    PlayerDigging(PlayerDiggingPayload),
+
+    /// Client to Server, player block placement packet.
+    PlayerBlockPlacement(PlayerBlockPlacementPayload),
+
+    /// Client to Server, holding change packet.
+    HoldingChange(HoldingChangePayload),
+
+    /// Server to Client, use bed packet.
+    UseBed(UseBedPayload),
+
+    /// Two-way, Animation packet.
+    Animation(AnimationPayload),
+
+    /// Client to Server, entity action packet.
+    EntityAction(EntityActionPayload),
+
+    /// Server to Client, named entity spawn packet.
+    NamedEntitySpawn(NamedEntitySpawnPayload),
+
+    /// Server to Client, pickup spawn packet.
+    PickupSpawn(PickupSpawnPayload),
+
+    /// Server to Client, collect item packet.
+    CollectItem(CollectItemPayload),
+
+    /// Server to Client, add object/vehicle packet.
+    AddObject(AddObjectPayload),
+
+    /// Server to Client, mob spawn packet.
+    MobSpawn(MobSpawnPayload),
+
+    /// Server to Client, painting packet.
+    Painting(PaintingPayload),
+
+    /// Server to Client, experience orb packet.
+    ExperienceOrb(ExperienceOrbPayload),
+
+    /// Server to Client, entity velocity packet.
+    EntityVelocity(EntityVelocityPayload),
+
+    /// Server to Client, destroy entity packet.
+    DestroyEntity(DestroyEntityPayload),
+
+    /// Server to Client, entity packet.
+    Entity(EntityPayload),
+
+    /// Server to Client, entity relative move packet.
+    EntityRelativeMove(EntityRelativeMovePayload),
+
+    /// Server to Client, entity look packet.
+    EntityLook(EntityLookPayload),
+
+    /// Server to Client, entity look and relative move packet.
+    EntityLookAndRelativeMove(EntityLookAndRelativeMovePayload),
+
+    /// Server to Client, entity teleport packet.
+    EntityTeleport(EntityTeleportPayload),
+
+    /// Server to Client, entity head look packet.
+    EntityHeadLook(EntityHeadLookPayload),
+
+    /// Server to Client, entity status packet.
+    EntityStatus(EntityStatusPayload),
+
+    /// Server to Client, attach entity packet.
+    AttachEntity(AttachEntityPayload),
+
+    /// Server to Client, entity metadata packet.
+    EntityMetadata(EntityMetadataPayload),
+
+    /// Server to Client, entity effect packet.
+    EntityEffect(EntityEffectPayload),
+
+    /// Server to Client, remove entity effect packet.
+    RemoveEntityEffect(RemoveEntityEffectPayload),
+
+    /// Server to Client, set experience packet.
+    SetExperience(SetExperiencePayload),
+
     /// Server to Client, chunk allocation packet.
     ChunkAllocation(ChunkAllocationPayload),
 
+    /// Server to Client, map chunk packet.
+    MapChunk(MapChunkPayload),
+
+    /// Server to Client, multi block change packet.
+    MultiBlockChange(MultiBlockChangePayload),
+
+    /// Server to Client, block change packet.
+    BlockChange(BlockChangePayload),
+
+    /// Server to Client, block action packet.
+    BlockAction(BlockActionPayload),
+
+    /// Server to Client, sound/particle effect packet.
+    SoundParticleEffect(SoundParticleEffectPayload),
+
+    /// Server to Client, change game state packet.
+    ChangeGameState(ChangeGameStatePayload),
+
+    /// Server to Client, thunderbolt packet.
+    Thunderbolt(ThunderboltPayload),
+
+    /// Server to Client, open window packet.
+    OpenWindow(OpenWindowPayload),
+
+    /// Two-way, Close window packet.
+    CloseWindow(CloseWindowPayload),
+
+    /// Client to Server, window click packet.
+    WindowClick(WindowClickPayload),
+
+    /// Server to Client, set slot packet.
+    SetSlot(SetSlotPayload),
+
+    /// Server to Client, window items packet.
+    WindowItems(WindowItemsPayload),
+
+    /// Server to Client, update window property packet.
+    UpdateWindowProperty(UpdateWindowPropertyPayload),
+
+    /// Two-way, Transaction packet.
+    Transaction(TransactionPayload),
+
+    /// Two-way, Creative inventory action packet.
+    CreativeInventoryAction(CreativeInventoryActionPayload),
+
+    /// Two-way, Update sign packet.
+    UpdateSign(UpdateSignPayload),
+
+    /// Server to Client, item data packet.
+    ItemData(ItemDataPayload),
+
     /// Client to Server, Server List Ping packet.
     ServerListPing(ServerListPingPayload),
 
@@ -68,139 +660,557 @@ pub enum Packet {
 }
 
 impl Packet {
-    pub fn from_bytes(bytes: &[u8]) -> Result<Self, io::Error> {
+    /// Parses a packet from the beginning of the bytes, ignoring any trailing data.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProtocolError> {
+        let mut cursor = Cursor::new(bytes);
+        Self::read(&mut cursor)
+    }
+
+    /// Parses a packet from the beginning of the bytes.
+    ///
+    /// Returns the packet together with the number of bytes it occupied,
+    /// so the remaining bytes can be decoded as the following packets.
+    pub fn decode(bytes: &[u8]) -> Result<(Self, usize), ProtocolError> {
         let mut cursor = Cursor::new(bytes);
-        let packet_id = cursor.get_u8();
+        let packet = Self::read(&mut cursor)?;
+        Ok((packet, cursor.position() as usize))
+    }
+
+    /// Reads a single packet from the cursor, advancing it past the packet.
+    ///
+    /// When the cursor ends before the packet does,
+    /// `ProtocolError::UnexpectedEof` is returned.
+    fn read(cursor: &mut Cursor<&[u8]>) -> Result<Self, ProtocolError> {
+        let packet_id = cursor.try_get_u8()?;
 
         match packet_id {
             KEEP_ALIVE_PACKET_ID => {
-                let payload = KeepAlivePayload::from_bytes(&mut cursor)?;
+                let payload = KeepAlivePayload::from_bytes(cursor)?;
                 Ok(Packet::KeepAlive(payload))
             }
             LOGIN_REQUEST_PACKET_ID => {
-                let payload = LoginRequestPayload::from_bytes(&mut cursor)?;
+                let payload = LoginRequestPayload::from_bytes(cursor)?;
                 Ok(Packet::LoginRequest(payload))
             }
             HANDSHAKE_PACKET_ID => {
-                let payload = HandshakePayload::from_bytes(&mut cursor)?;
+                let payload = HandshakePayload::from_bytes(cursor)?;
                 Ok(Packet::Handshake(payload))
             }
             CHAT_MESSAGE_PACKET_ID => {
-                let payload = ChatMessagePayload::from_bytes(&mut cursor)?;
+                let payload = ChatMessagePayload::from_bytes(cursor)?;
                 Ok(Packet::ChatMessage(payload))
             }
             TIME_UPDATE_PACKET_ID => {
-                let payload = TimeUpdatePayload::from_bytes(&mut cursor)?;
+                let payload = TimeUpdatePayload::from_bytes(cursor)?;
                 Ok(Packet::TimeUpdate(payload))
             }
             ENTITY_EQUIPMENT_PACKET_ID => {
-                let payload = EntityEquipmentPayload::from_bytes(&mut cursor)?;
+                let payload = EntityEquipmentPayload::from_bytes(cursor)?;
                 Ok(Packet::EntityEquipment(payload))
             }
             SPAWN_POSITION_PACKET_ID => {
-                let payload = SpawnPositionPayload::from_bytes(&mut cursor)?;
+                let payload = SpawnPositionPayload::from_bytes(cursor)?;
                 Ok(Packet::SpawnPosition(payload))
             }
+            USE_ENTITY_PACKET_ID => {
+                let payload = UseEntityPayload::from_bytes(cursor)?;
+                Ok(Packet::UseEntity(payload))
+            }
+            UPDATE_HEALTH_PACKET_ID => {
+                let payload = UpdateHealthPayload::from_bytes(cursor)?;
+                Ok(Packet::UpdateHealth(payload))
+            }
+            RESPAWN_PACKET_ID => {
+                let payload = RespawnPayload::from_bytes(cursor)?;
+                Ok(Packet::Respawn(payload))
+            }
+            PLAYER_PACKET_ID => {
+                let payload = PlayerPayload::from_bytes(cursor)?;
+                Ok(Packet::Player(payload))
+            }
             PLAYER_POSITION_PACKET_ID => {
-                let payload = PlayerPositionPayload::from_bytes(&mut cursor)?;
+                let payload = PlayerPositionPayload::from_bytes(cursor)?;
                 Ok(Packet::PlayerPosition(payload))
             }
+            PLAYER_LOOK_PACKET_ID => {
+                let payload = PlayerLookPayload::from_bytes(cursor)?;
+                Ok(Packet::PlayerLook(payload))
+            }
             PLAYER_POSITION_AND_LOOK_PACKET_ID => {
-                let payload = PlayerPositionAndLookPayload::from_bytes(&mut cursor)?;
+                let payload = PlayerPositionAndLookPayload::from_bytes(cursor)?;
                 Ok(Packet::PlayerPositionAndLook(payload))
             }
+            PLAYER_DIGGING_PACKET_ID => {
+                let payload = PlayerDiggingPayload::from_bytes(cursor)?;
+                Ok(Packet::PlayerDigging(payload))
+            }
+            PLAYER_BLOCK_PLACEMENT_PACKET_ID => {
+                let payload = PlayerBlockPlacementPayload::from_bytes(cursor)?;
+                Ok(Packet::PlayerBlockPlacement(payload))
+            }
+            HOLDING_CHANGE_PACKET_ID => {
+                let payload = HoldingChangePayload::from_bytes(cursor)?;
+                Ok(Packet::HoldingChange(payload))
+            }
+            USE_BED_PACKET_ID => {
+                let payload = UseBedPayload::from_bytes(cursor)?;
+                Ok(Packet::UseBed(payload))
+            }
+            ANIMATION_PACKET_ID => {
+                let payload = AnimationPayload::from_bytes(cursor)?;
+                Ok(Packet::Animation(payload))
+            }
+            ENTITY_ACTION_PACKET_ID => {
+                let payload = EntityActionPayload::from_bytes(cursor)?;
+                Ok(Packet::EntityAction(payload))
+            }
+            NAMED_ENTITY_SPAWN_PACKET_ID => {
+                let payload = NamedEntitySpawnPayload::from_bytes(cursor)?;
+                Ok(Packet::NamedEntitySpawn(payload))
+            }
+            PICKUP_SPAWN_PACKET_ID => {
+                let payload = PickupSpawnPayload::from_bytes(cursor)?;
+                Ok(Packet::PickupSpawn(payload))
+            }
+            COLLECT_ITEM_PACKET_ID => {
+                let payload = CollectItemPayload::from_bytes(cursor)?;
+                Ok(Packet::CollectItem(payload))
+            }
+            ADD_OBJECT_PACKET_ID => {
+                let payload = AddObjectPayload::from_bytes(cursor)?;
+                Ok(Packet::AddObject(payload))
+            }
+            MOB_SPAWN_PACKET_ID => {
+                let payload = MobSpawnPayload::from_bytes(cursor)?;
+                Ok(Packet::MobSpawn(payload))
+            }
+            PAINTING_PACKET_ID => {
+                let payload = PaintingPayload::from_bytes(cursor)?;
+                Ok(Packet::Painting(payload))
+            }
+            EXPERIENCE_ORB_PACKET_ID => {
+                let payload = ExperienceOrbPayload::from_bytes(cursor)?;
+                Ok(Packet::ExperienceOrb(payload))
+            }
+            ENTITY_VELOCITY_PACKET_ID => {
+                let payload = EntityVelocityPayload::from_bytes(cursor)?;
+                Ok(Packet::EntityVelocity(payload))
+            }
+            DESTROY_ENTITY_PACKET_ID => {
+                let payload = DestroyEntityPayload::from_bytes(cursor)?;
+                Ok(Packet::DestroyEntity(payload))
+            }
+            ENTITY_PACKET_ID => {
+                let payload = EntityPayload::from_bytes(cursor)?;
+                Ok(Packet::Entity(payload))
+            }
+            ENTITY_RELATIVE_MOVE_PACKET_ID => {
+                let payload = EntityRelativeMovePayload::from_bytes(cursor)?;
+                Ok(Packet::EntityRelativeMove(payload))
+            }
+            ENTITY_LOOK_PACKET_ID => {
+                let payload = EntityLookPayload::from_bytes(cursor)?;
+                Ok(Packet::EntityLook(payload))
+            }
+            ENTITY_LOOK_AND_RELATIVE_MOVE_PACKET_ID => {
+                let payload = EntityLookAndRelativeMovePayload::from_bytes(cursor)?;
+                Ok(Packet::EntityLookAndRelativeMove(payload))
+            }
+            ENTITY_TELEPORT_PACKET_ID => {
+                let payload = EntityTeleportPayload::from_bytes(cursor)?;
+                Ok(Packet::EntityTeleport(payload))
+            }
+            ENTITY_HEAD_LOOK_PACKET_ID => {
+                let payload = EntityHeadLookPayload::from_bytes(cursor)?;
+                Ok(Packet::EntityHeadLook(payload))
+            }
+            ENTITY_STATUS_PACKET_ID => {
+                let payload = EntityStatusPayload::from_bytes(cursor)?;
+                Ok(Packet::EntityStatus(payload))
+            }
+            ATTACH_ENTITY_PACKET_ID => {
+                let payload = AttachEntityPayload::from_bytes(cursor)?;
+                Ok(Packet::AttachEntity(payload))
+            }
+            ENTITY_METADATA_PACKET_ID => {
+                let payload = EntityMetadataPayload::from_bytes(cursor)?;
+                Ok(Packet::EntityMetadata(payload))
+            }
+            ENTITY_EFFECT_PACKET_ID => {
+                let payload = EntityEffectPayload::from_bytes(cursor)?;
+                Ok(Packet::EntityEffect(payload))
+            }
+            REMOVE_ENTITY_EFFECT_PACKET_ID => {
+                let payload = RemoveEntityEffectPayload::from_bytes(cursor)?;
+                Ok(Packet::RemoveEntityEffect(payload))
+            }
+            SET_EXPERIENCE_PACKET_ID => {
+                let payload = SetExperiencePayload::from_bytes(cursor)?;
+                Ok(Packet::SetExperience(payload))
+            }
             CHUNK_ALLOCATION_PACKET_ID => {
-                let payload = ChunkAllocationPayload::from_bytes(&mut cursor)?;
+                let payload = ChunkAllocationPayload::from_bytes(cursor)?;
                 Ok(Packet::ChunkAllocation(payload))
             }
-            SERVER_LIST_PING_PACKET_ID => {
-                let payload = ServerListPingPayload::from_bytes(&mut cursor)?;
-                Ok(Packet::ServerListPing(payload))
+            MAP_CHUNK_PACKET_ID => {
+                let payload = MapChunkPayload::from_bytes(cursor)?;
+                Ok(Packet::MapChunk(payload))
             }
-            DISCONNECT_KICK_PACKET_ID => {
-                let payload = DisconnectKickPayload::from_bytes(&mut cursor)?;
-                Ok(Packet::DisconnectKick(payload))
+            MULTI_BLOCK_CHANGE_PACKET_ID => {
+                let payload = MultiBlockChangePayload::from_bytes(cursor)?;
+                Ok(Packet::MultiBlockChange(payload))
             }
-            _ => Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "Unknown packet ID",
-            )),
-        }
-    }
-
-    pub fn to_bytes(&self) -> Result<Vec<u8>, io::Error> {
-        let mut buffer = BytesMut::new();
-
-        match self {
-            Packet::KeepAlive(payload) => {
-                buffer.put_u8(KEEP_ALIVE_PACKET_ID);
-                payload.to_bytes(&mut buffer)?;
+            BLOCK_CHANGE_PACKET_ID => {
+                let payload = BlockChangePayload::from_bytes(cursor)?;
+                Ok(Packet::BlockChange(payload))
+            }
+            BLOCK_ACTION_PACKET_ID => {
+                let payload = BlockActionPayload::from_bytes(cursor)?;
+                Ok(Packet::BlockAction(payload))
+            }
+            SOUND_PARTICLE_EFFECT_PACKET_ID => {
+                let payload = SoundParticleEffectPayload::from_bytes(cursor)?;
+                Ok(Packet::SoundParticleEffect(payload))
+            }
+            CHANGE_GAME_STATE_PACKET_ID => {
+                let payload = ChangeGameStatePayload::from_bytes(cursor)?;
+                Ok(Packet::ChangeGameState(payload))
             }
-            Packet::LoginRequest(payload) => {
-                buffer.put_u8(LOGIN_REQUEST_PACKET_ID);
-                payload.to_bytes(&mut buffer)?;
+            THUNDERBOLT_PACKET_ID => {
+                let payload = ThunderboltPayload::from_bytes(cursor)?;
+                Ok(Packet::Thunderbolt(payload))
             }
-            Packet::Handshake(payload) => {
-                buffer.put_u8(HANDSHAKE_PACKET_ID);
-                payload.to_bytes(&mut buffer)?;
+            OPEN_WINDOW_PACKET_ID => {
+                let payload = OpenWindowPayload::from_bytes(cursor)?;
+                Ok(Packet::OpenWindow(payload))
             }
-            Packet::ChatMessage(payload) => {
-                buffer.put_u8(CHAT_MESSAGE_PACKET_ID);
-                payload.to_bytes(&mut buffer)?;
+            CLOSE_WINDOW_PACKET_ID => {
+                let payload = CloseWindowPayload::from_bytes(cursor)?;
+                Ok(Packet::CloseWindow(payload))
             }
-            Packet::TimeUpdate(payload) => {
-                buffer.put_u8(TIME_UPDATE_PACKET_ID);
-                payload.to_bytes(&mut buffer)?;
+            WINDOW_CLICK_PACKET_ID => {
+                let payload = WindowClickPayload::from_bytes(cursor)?;
+                Ok(Packet::WindowClick(payload))
             }
-            Packet::EntityEquipment(payload) => {
-                buffer.put_u8(ENTITY_EQUIPMENT_PACKET_ID);
-                payload.to_bytes(&mut buffer)?;
+            SET_SLOT_PACKET_ID => {
+                let payload = SetSlotPayload::from_bytes(cursor)?;
+                Ok(Packet::SetSlot(payload))
             }
-            Packet::SpawnPosition(payload) => {
-                buffer.put_u8(SPAWN_POSITION_PACKET_ID);
-                payload.to_bytes(&mut buffer)?;
+            WINDOW_ITEMS_PACKET_ID => {
+                let payload = WindowItemsPayload::from_bytes(cursor)?;
+                Ok(Packet::WindowItems(payload))
             }
-            Packet::PlayerPosition(payload) => {
-                buffer.put_u8(PLAYER_POSITION_PACKET_ID);
-                payload.to_bytes(&mut buffer)?;
+            UPDATE_WINDOW_PROPERTY_PACKET_ID => {
+                let payload = UpdateWindowPropertyPayload::from_bytes(cursor)?;
+                Ok(Packet::UpdateWindowProperty(payload))
             }
-            Packet::PlayerPositionAndLook(payload) => {
-                buffer.put_u8(PLAYER_POSITION_AND_LOOK_PACKET_ID);
-                payload.to_bytes(&mut buffer)?;
+            TRANSACTION_PACKET_ID => {
+                let payload = TransactionPayload::from_bytes(cursor)?;
+                Ok(Packet::Transaction(payload))
             }
-            Packet::ChunkAllocation(payload) => {
-                buffer.put_u8(CHUNK_ALLOCATION_PACKET_ID);
-                payload.to_bytes(&mut buffer)?;
+            CREATIVE_INVENTORY_ACTION_PACKET_ID => {
+                let payload = CreativeInventoryActionPayload::from_bytes(cursor)?;
+                Ok(Packet::CreativeInventoryAction(payload))
             }
-            Packet::ServerListPing(_) => {
-                buffer.put_u8(SERVER_LIST_PING_PACKET_ID);
+            UPDATE_SIGN_PACKET_ID => {
+                let payload = UpdateSignPayload::from_bytes(cursor)?;
+                Ok(Packet::UpdateSign(payload))
             }
-            Packet::DisconnectKick(payload) => {
-                buffer.put_u8(DISCONNECT_KICK_PACKET_ID);
-                payload.to_bytes(&mut buffer)?;
+            ITEM_DATA_PACKET_ID => {
+                let payload = ItemDataPayload::from_bytes(cursor)?;
+                Ok(Packet::ItemData(payload))
             }
+            SERVER_LIST_PING_PACKET_ID => {
+                let payload = ServerListPingPayload::from_bytes(cursor)?;
+                Ok(Packet::ServerListPing(payload))
+            }
+            DISCONNECT_KICK_PACKET_ID => {
+                let payload = DisconnectKickPayload::from_bytes(cursor)?;
+                Ok(Packet::DisconnectKick(payload))
+            }
+            _ => Err(ProtocolError::UnknownPacketId(packet_id)),
+        }
+    }
+
+    /// Converts the packet to bytes, allocating a new buffer.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ProtocolError> {
+        let mut buffer = BytesMut::new();
+        self.encode(&mut buffer)?;
+        Ok(buffer.into())
+    }
+
+    /// Returns the packet identifier.
+    pub fn id(&self) -> u8 {
+        match self {
+            Packet::KeepAlive(_) => KEEP_ALIVE_PACKET_ID,
+            Packet::LoginRequest(_) => LOGIN_REQUEST_PACKET_ID,
+            Packet::Handshake(_) => HANDSHAKE_PACKET_ID,
+            Packet::ChatMessage(_) => CHAT_MESSAGE_PACKET_ID,
+            Packet::TimeUpdate(_) => TIME_UPDATE_PACKET_ID,
+            Packet::EntityEquipment(_) => ENTITY_EQUIPMENT_PACKET_ID,
+            Packet::SpawnPosition(_) => SPAWN_POSITION_PACKET_ID,
+            Packet::UseEntity(_) => USE_ENTITY_PACKET_ID,
+            Packet::UpdateHealth(_) => UPDATE_HEALTH_PACKET_ID,
+            Packet::Respawn(_) => RESPAWN_PACKET_ID,
+            Packet::Player(_) => PLAYER_PACKET_ID,
+            Packet::PlayerPosition(_) => PLAYER_POSITION_PACKET_ID,
+            Packet::PlayerLook(_) => PLAYER_LOOK_PACKET_ID,
+            Packet::PlayerPositionAndLook(_) => PLAYER_POSITION_AND_LOOK_PACKET_ID,
+            Packet::PlayerDigging(_) => PLAYER_DIGGING_PACKET_ID,
+            Packet::PlayerBlockPlacement(_) => PLAYER_BLOCK_PLACEMENT_PACKET_ID,
+            Packet::HoldingChange(_) => HOLDING_CHANGE_PACKET_ID,
+            Packet::UseBed(_) => USE_BED_PACKET_ID,
+            Packet::Animation(_) => ANIMATION_PACKET_ID,
+            Packet::EntityAction(_) => ENTITY_ACTION_PACKET_ID,
+            Packet::NamedEntitySpawn(_) => NAMED_ENTITY_SPAWN_PACKET_ID,
+            Packet::PickupSpawn(_) => PICKUP_SPAWN_PACKET_ID,
+            Packet::CollectItem(_) => COLLECT_ITEM_PACKET_ID,
+            Packet::AddObject(_) => ADD_OBJECT_PACKET_ID,
+            Packet::MobSpawn(_) => MOB_SPAWN_PACKET_ID,
+            Packet::Painting(_) => PAINTING_PACKET_ID,
+            Packet::ExperienceOrb(_) => EXPERIENCE_ORB_PACKET_ID,
+            Packet::EntityVelocity(_) => ENTITY_VELOCITY_PACKET_ID,
+            Packet::DestroyEntity(_) => DESTROY_ENTITY_PACKET_ID,
+            Packet::Entity(_) => ENTITY_PACKET_ID,
+            Packet::EntityRelativeMove(_) => ENTITY_RELATIVE_MOVE_PACKET_ID,
+            Packet::EntityLook(_) => ENTITY_LOOK_PACKET_ID,
+            Packet::EntityLookAndRelativeMove(_) => ENTITY_LOOK_AND_RELATIVE_MOVE_PACKET_ID,
+            Packet::EntityTeleport(_) => ENTITY_TELEPORT_PACKET_ID,
+            Packet::EntityHeadLook(_) => ENTITY_HEAD_LOOK_PACKET_ID,
+            Packet::EntityStatus(_) => ENTITY_STATUS_PACKET_ID,
+            Packet::AttachEntity(_) => ATTACH_ENTITY_PACKET_ID,
+            Packet::EntityMetadata(_) => ENTITY_METADATA_PACKET_ID,
+            Packet::EntityEffect(_) => ENTITY_EFFECT_PACKET_ID,
+            Packet::RemoveEntityEffect(_) => REMOVE_ENTITY_EFFECT_PACKET_ID,
+            Packet::SetExperience(_) => SET_EXPERIENCE_PACKET_ID,
+            Packet::ChunkAllocation(_) => CHUNK_ALLOCATION_PACKET_ID,
+            Packet::MapChunk(_) => MAP_CHUNK_PACKET_ID,
+            Packet::MultiBlockChange(_) => MULTI_BLOCK_CHANGE_PACKET_ID,
+            Packet::BlockChange(_) => BLOCK_CHANGE_PACKET_ID,
+            Packet::BlockAction(_) => BLOCK_ACTION_PACKET_ID,
+            Packet::SoundParticleEffect(_) => SOUND_PARTICLE_EFFECT_PACKET_ID,
+            Packet::ChangeGameState(_) => CHANGE_GAME_STATE_PACKET_ID,
+            Packet::Thunderbolt(_) => THUNDERBOLT_PACKET_ID,
+            Packet::OpenWindow(_) => OPEN_WINDOW_PACKET_ID,
+            Packet::CloseWindow(_) => CLOSE_WINDOW_PACKET_ID,
+            Packet::WindowClick(_) => WINDOW_CLICK_PACKET_ID,
+            Packet::SetSlot(_) => SET_SLOT_PACKET_ID,
+            Packet::WindowItems(_) => WINDOW_ITEMS_PACKET_ID,
+            Packet::UpdateWindowProperty(_) => UPDATE_WINDOW_PROPERTY_PACKET_ID,
+            Packet::Transaction(_) => TRANSACTION_PACKET_ID,
+            Packet::CreativeInventoryAction(_) => CREATIVE_INVENTORY_ACTION_PACKET_ID,
+            Packet::UpdateSign(_) => UPDATE_SIGN_PACKET_ID,
+            Packet::ItemData(_) => ITEM_DATA_PACKET_ID,
+            Packet::ServerListPing(_) => SERVER_LIST_PING_PACKET_ID,
+            Packet::DisconnectKick(_) => DISCONNECT_KICK_PACKET_ID,
+        }
+    }
+
+    /// Returns the human-readable packet name.
+    pub fn name(&self) -> &'static str {
+        self.info().name
+    }
+
+    /// Returns the static information about the packet type.
+    pub fn info(&self) -> &'static PacketInfo {
+        PacketInfo::by_id(self.id()).expect("Every packet type is registered")
+    }
+
+    /// Writes the packet to the end of the buffer.
+    ///
+    /// On failure, part of the packet may have already been written to the buffer.
+    pub fn encode(&self, buffer: &mut BytesMut) -> Result<(), ProtocolError> {
+        buffer.put_u8(self.id());
+
+        match self {
+            Packet::KeepAlive(payload) => payload.to_bytes(buffer)?,
+            Packet::LoginRequest(payload) => payload.to_bytes(buffer)?,
+            Packet::Handshake(payload) => payload.to_bytes(buffer)?,
+            Packet::ChatMessage(payload) => payload.to_bytes(buffer)?,
+            Packet::TimeUpdate(payload) => payload.to_bytes(buffer)?,
+            Packet::EntityEquipment(payload) => payload.to_bytes(buffer)?,
+            Packet::SpawnPosition(payload) => payload.to_bytes(buffer)?,
+            Packet::UseEntity(payload) => payload.to_bytes(buffer)?,
+            Packet::UpdateHealth(payload) => payload.to_bytes(buffer)?,
+            Packet::Respawn(payload) => payload.to_bytes(buffer)?,
+            Packet::Player(payload) => payload.to_bytes(buffer)?,
+            Packet::PlayerPosition(payload) => payload.to_bytes(buffer)?,
+            Packet::PlayerLook(payload) => payload.to_bytes(buffer)?,
+            Packet::PlayerPositionAndLook(payload) => payload.to_bytes(buffer)?,
+            Packet::PlayerDigging(payload) => payload.to_bytes(buffer)?,
+            Packet::PlayerBlockPlacement(payload) => payload.to_bytes(buffer)?,
+            Packet::HoldingChange(payload) => payload.to_bytes(buffer)?,
+            Packet::UseBed(payload) => payload.to_bytes(buffer)?,
+            Packet::Animation(payload) => payload.to_bytes(buffer)?,
+            Packet::EntityAction(payload) => payload.to_bytes(buffer)?,
+            Packet::NamedEntitySpawn(payload) => payload.to_bytes(buffer)?,
+            Packet::PickupSpawn(payload) => payload.to_bytes(buffer)?,
+            Packet::CollectItem(payload) => payload.to_bytes(buffer)?,
+            Packet::AddObject(payload) => payload.to_bytes(buffer)?,
+            Packet::MobSpawn(payload) => payload.to_bytes(buffer)?,
+            Packet::Painting(payload) => payload.to_bytes(buffer)?,
+            Packet::ExperienceOrb(payload) => payload.to_bytes(buffer)?,
+            Packet::EntityVelocity(payload) => payload.to_bytes(buffer)?,
+            Packet::DestroyEntity(payload) => payload.to_bytes(buffer)?,
+            Packet::Entity(payload) => payload.to_bytes(buffer)?,
+            Packet::EntityRelativeMove(payload) => payload.to_bytes(buffer)?,
+            Packet::EntityLook(payload) => payload.to_bytes(buffer)?,
+            Packet::EntityLookAndRelativeMove(payload) => payload.to_bytes(buffer)?,
+            Packet::EntityTeleport(payload) => payload.to_bytes(buffer)?,
+            Packet::EntityHeadLook(payload) => payload.to_bytes(buffer)?,
+            Packet::EntityStatus(payload) => payload.to_bytes(buffer)?,
+            Packet::AttachEntity(payload) => payload.to_bytes(buffer)?,
+            Packet::EntityMetadata(payload) => payload.to_bytes(buffer)?,
+            Packet::EntityEffect(payload) => payload.to_bytes(buffer)?,
+            Packet::RemoveEntityEffect(payload) => payload.to_bytes(buffer)?,
+            Packet::SetExperience(payload) => payload.to_bytes(buffer)?,
+            Packet::ChunkAllocation(payload) => payload.to_bytes(buffer)?,
+            Packet::MapChunk(payload) => payload.to_bytes(buffer)?,
+            Packet::MultiBlockChange(payload) => payload.to_bytes(buffer)?,
+            Packet::BlockChange(payload) => payload.to_bytes(buffer)?,
+            Packet::BlockAction(payload) => payload.to_bytes(buffer)?,
+            Packet::SoundParticleEffect(payload) => payload.to_bytes(buffer)?,
+            Packet::ChangeGameState(payload) => payload.to_bytes(buffer)?,
+            Packet::Thunderbolt(payload) => payload.to_bytes(buffer)?,
+            Packet::OpenWindow(payload) => payload.to_bytes(buffer)?,
+            Packet::CloseWindow(payload) => payload.to_bytes(buffer)?,
+            Packet::WindowClick(payload) => payload.to_bytes(buffer)?,
+            Packet::SetSlot(payload) => payload.to_bytes(buffer)?,
+            Packet::WindowItems(payload) => payload.to_bytes(buffer)?,
+            Packet::UpdateWindowProperty(payload) => payload.to_bytes(buffer)?,
+            Packet::Transaction(payload) => payload.to_bytes(buffer)?,
+            Packet::CreativeInventoryAction(payload) => payload.to_bytes(buffer)?,
+            Packet::UpdateSign(payload) => payload.to_bytes(buffer)?,
+            Packet::ItemData(payload) => payload.to_bytes(buffer)?,
+            Packet::ServerListPing(_) => {}
+            Packet::DisconnectKick(payload) => payload.to_bytes(buffer)?,
         }
 
-        Ok(buffer.to_vec())
+        Ok(())
     }
 }
 
 /// Parse a packet payload from a byte stream.
-trait FromBytes: Sized {
+///
+/// Implemented for the payloads, the wire types they are composed of,
+/// and the fixed-width numbers. Can be derived for structs whose fields
+/// all implement it, the fields are then read in the order of declaration.
+pub trait FromBytes: Sized {
     /// Parses bytes to return a value of this payload.
     ///
     /// If parsing succeeds, return the value inside Ok,
-    /// otherwise when the data bytes are invalid return an `io::Error`.
-    fn from_bytes(bytes: &mut Cursor<&[u8]>) -> io::Result<Self>;
+    /// otherwise when the data bytes are invalid or incomplete return a `ProtocolError`.
+    ///
+    /// On success the cursor must be advanced exactly past the parsed value,
+    /// so the following value can be read right after it. When the data is
+    /// incomplete `ProtocolError::UnexpectedEof` must be returned, so the caller
+    /// can retry once more data is available. The cursor position after
+    /// a failure is unspecified.
+    fn from_bytes(bytes: &mut Cursor<&[u8]>) -> Result<Self, ProtocolError>;
 }
 
 /// Converts a packet payload to a byte buffer.
-trait ToBytes {
+///
+/// The counterpart of `FromBytes`, also derivable for structs.
+pub trait ToBytes {
     /// Converts a value to return a bytes representation of this payload.
     ///
     /// If converting succeeds, return the value inside Ok,
-    /// otherwise when there is no more space left in the buffer return an `io::Error`.
-    fn to_bytes(&self, buffer: &mut BytesMut) -> io::Result<()>;
+    /// otherwise when a value doesn't fit the wire format return a `ProtocolError`.
+    ///
+    /// The bytes must be appended to the end of the buffer, keeping its previous
+    /// contents intact, and must be readable back with `FromBytes`. After
+    /// a failure part of the value may have already been written to the buffer.
+    fn to_bytes(&self, buffer: &mut BytesMut) -> Result<(), ProtocolError>;
+}
+
+/// Constraints of a string field, checked both when reading and writing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StringLimit {
+    /// Maximum number of UTF-16 code units.
+    pub max_length: usize,
+
+    /// Whether control characters, such as line breaks, are rejected.
+    pub printable: bool,
+}
+
+impl StringLimit {
+    /// Limit of the strings without a field-specific one,
+    /// the largest length a client accepts.
+    pub const DEFAULT: StringLimit = StringLimit::new(i16::MAX as usize);
+
+    /// Creates a limit of the given length, allowing control characters.
+    pub const fn new(max_length: usize) -> Self {
+        Self {
+            max_length,
+            printable: false,
+        }
+    }
+
+    /// Creates a limit of the given length, rejecting control characters.
+    pub const fn printable(max_length: usize) -> Self {
+        Self {
+            max_length,
+            printable: true,
+        }
+    }
+
+    /// Reads a string, failing if it exceeds the limit.
+    ///
+    /// The length is verified before the string is read, so a hostile
+    /// length prefix can't make the reader allocate or wait for more data.
+    pub fn read(&self, bytes: &mut Cursor<&[u8]>) -> Result<String, ProtocolError> {
+        let length = bytes.try_get_u16()? as usize;
+
+        if length > self.max_length {
+            return Err(ProtocolError::PayloadTooLarge);
+        }
+
+        if bytes.remaining() < length * 2 {
+            return Err(ProtocolError::UnexpectedEof {
+                needed: length * 2 - bytes.remaining(),
+            });
+        }
+
+        let mut utf16_data = Vec::with_capacity(length);
+
+        for _ in 0..length {
+            utf16_data.push(bytes.try_get_u16()?);
+        }
+
+        let s = String::from_utf16(&utf16_data).map_err(|_| ProtocolError::InvalidString)?;
+        self.check_characters(&s)?;
+
+        Ok(s)
+    }
+
+    /// Puts a string, failing if it exceeds the limit.
+    pub fn put(&self, buffer: &mut BytesMut, s: &str) -> Result<(), ProtocolError> {
+        let utf16_data: Vec<u16> = s.encode_utf16().collect();
+
+        if utf16_data.len() > self.max_length {
+            return Err(ProtocolError::PayloadTooLarge);
+        }
+
+        self.check_characters(s)?;
+
+        let length = u16::try_from(utf16_data.len()).map_err(|_| ProtocolError::PayloadTooLarge)?;
+        buffer.put_u16(length);
+
+        for utf16_char in utf16_data {
+            buffer.put_u16(utf16_char);
+        }
+
+        Ok(())
+    }
+
+    fn check_characters(&self, s: &str) -> Result<(), ProtocolError> {
+        if self.printable && s.chars().any(char::is_control) {
+            return Err(ProtocolError::InvalidString);
+        }
+
+        Ok(())
+    }
 }
 
 /// Reads a UTF-16 encoded string from a byte stream.
@@ -208,17 +1218,10 @@ trait ToBytes {
 /// Reads a `u16` length prefix at first, followed by that many `u16`
 /// elements, then converts them to a `String`.
 ///
-/// The number of elements refers to the number of characters, not the number of bytes.
-fn read_string(bytes: &mut Cursor<&[u8]>) -> io::Result<String> {
-    let length = bytes.get_u16() as usize;
-    let mut utf16_data = Vec::with_capacity(length);
-
-    for _ in 0..length {
-        utf16_data.push(bytes.get_u16());
-    }
-
-    String::from_utf16(&utf16_data)
-        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Invalid UTF-16 data"))
+/// The number of elements refers to the number of UTF-16 code units, not the number of bytes.
+/// Strings longer than `StringLimit::DEFAULT` are rejected.
+pub fn read_string(bytes: &mut Cursor<&[u8]>) -> Result<String, ProtocolError> {
+    StringLimit::DEFAULT.read(bytes)
 }
 
 /// Puts a UTF-16 encoded string to a byte buffer.
@@ -226,16 +1229,54 @@ fn read_string(bytes: &mut Cursor<&[u8]>) -> io::Result<String> {
 /// Puts a `u16` length prefix at the beginning, followed by that many `u16`
 /// encoded characters.
 ///
-/// The length refers to the number of characters, not the number of bytes.
-fn put_string(buffer: &mut BytesMut, s: &str) -> io::Result<()> {
-    let utf16_data: Vec<u16> = s.encode_utf16().collect();
-    buffer.put_u16(s.chars().count() as u16);
+/// The length refers to the number of UTF-16 code units, not the number of bytes,
+/// so characters outside of the Basic Multilingual Plane count twice.
+/// Strings longer than `StringLimit::DEFAULT` are rejected.
+pub fn put_string(buffer: &mut BytesMut, s: &str) -> Result<(), ProtocolError> {
+    StringLimit::DEFAULT.put(buffer, s)
+}
+
+/// Implements `FromBytes` and `ToBytes` for big-endian, fixed-width numbers.
+macro_rules! impl_number_bytes {
+    ($($ty:ty => $get:ident, $put:ident;)*) => {
+        $(
+            impl FromBytes for $ty {
+                fn from_bytes(bytes: &mut Cursor<&[u8]>) -> Result<Self, ProtocolError> {
+                    Ok(bytes.$get()?)
+                }
+            }
 
-    for utf16_char in utf16_data {
-        buffer.put_u16(utf16_char);
+            impl ToBytes for $ty {
+                fn to_bytes(&self, buffer: &mut BytesMut) -> Result<(), ProtocolError> {
+                    buffer.$put(*self);
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_number_bytes! {
+    u8 => try_get_u8, put_u8;
+    i8 => try_get_i8, put_i8;
+    u16 => try_get_u16, put_u16;
+    i16 => try_get_i16, put_i16;
+    i32 => try_get_i32, put_i32;
+    i64 => try_get_i64, put_i64;
+    f32 => try_get_f32, put_f32;
+    f64 => try_get_f64, put_f64;
+}
+
+impl FromBytes for String {
+    fn from_bytes(bytes: &mut Cursor<&[u8]>) -> Result<Self, ProtocolError> {
+        read_string(bytes)
     }
+}
 
-    Ok(())
+impl ToBytes for String {
+    fn to_bytes(&self, buffer: &mut BytesMut) -> Result<(), ProtocolError> {
+        put_string(buffer, self)
+    }
 }
 
 //
@@ -243,33 +1284,22 @@ fn put_string(buffer: &mut BytesMut, s: &str) -> io::Result<()> {
 //
 
 /// Payload for the `Packet::KeepAlive`.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
 pub struct KeepAlivePayload {
     /// Server-generated random identifier.
     pub keep_alive_id: i32,
 }
 
-impl FromBytes for KeepAlivePayload {
-    fn from_bytes(bytes: &mut Cursor<&[u8]>) -> io::Result<Self> {
-        Ok(Self {
-            keep_alive_id: bytes.get_i32(),
-        })
-    }
-}
-
-impl ToBytes for KeepAlivePayload {
-    fn to_bytes(&self, buffer: &mut BytesMut) -> io::Result<()> {
-        buffer.put_i32(self.keep_alive_id);
-        Ok(())
-    }
-}
-
 //
 // Login request packet
 //
 
 /// Payload for the `Packet::LoginRequest`.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
 pub struct LoginRequestPayload {
     /// # Client to Server
     /// The `id` is the protocol version, for 1.2.5 it should be equal to `29`.
@@ -283,6 +1313,11 @@ pub struct LoginRequestPayload {
     ///
     /// # Server to Client
     /// Not used.
+    #[protocol(max_length = 16, printable)]
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(strategy = "crate::arbitrary::printable_string(16)")
+    )]
     pub username: String,
 
     /// # Client to Server
@@ -290,6 +1325,11 @@ pub struct LoginRequestPayload {
     ///
     /// # Server to Client
     /// Level type defined in server properties, `default` or `FLAT`.
+    #[protocol(max_length = 16, printable)]
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(strategy = "crate::arbitrary::printable_string(16)")
+    )]
     pub level_type: String,
 
     /// # Client to Server
@@ -324,125 +1364,69 @@ pub struct LoginRequestPayload {
     pub max_players: u8,
 }
 
-impl FromBytes for LoginRequestPayload {
-    fn from_bytes(bytes: &mut Cursor<&[u8]>) -> io::Result<Self> {
-        Ok(Self {
-            id: bytes.get_i32(),
-            username: read_string(bytes)?,
-            level_type: read_string(bytes)?,
-            server_mode: bytes.get_i32(),
-            dimension: bytes.get_i32(),
-            difficulty: bytes.get_i8(),
-            unused_0: 0,
-            max_players: bytes.get_u8(),
-        })
-    }
-}
-
-impl ToBytes for LoginRequestPayload {
-    fn to_bytes(&self, buffer: &mut BytesMut) -> io::Result<()> {
-        buffer.put_i32(self.id);
-        put_string(buffer, &self.username)?;
-        put_string(buffer, &self.level_type)?;
-        buffer.put_i32(self.server_mode);
-        buffer.put_i32(self.dimension);
-        buffer.put_i8(self.difficulty);
-        buffer.put_u8(self.unused_0);
-        buffer.put_u8(self.max_players);
-        Ok(())
-    }
-}
-
 //
 // Handshake packet
 //
 
 /// Payload for the `Packet::Handshake`.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
 pub struct HandshakePayload {
     /// # Client to Server
     /// The `data` is username and host, for example `ezioleq;localhost:25565`.
     ///
     /// # Server to Client
     /// The `data` is a connection hash, for example `2e69f1dc002ab5f7`.
+    #[protocol(max_length = 64, printable)]
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(strategy = "crate::arbitrary::printable_string(64)")
+    )]
     pub data: String,
 }
 
-impl FromBytes for HandshakePayload {
-    fn from_bytes(bytes: &mut Cursor<&[u8]>) -> io::Result<Self> {
-        Ok(Self {
-            data: read_string(bytes)?,
-        })
-    }
-}
-
-impl ToBytes for HandshakePayload {
-    fn to_bytes(&self, buffer: &mut BytesMut) -> io::Result<()> {
-        put_string(buffer, &self.data)?;
-        Ok(())
-    }
-}
-
 //
 // Chat message
 //
 
 /// Payload for the `Packet::ChatMessage`.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
 pub struct ChatMessagePayload {
     /// Content of the message.
     ///
     /// User input must be sanitized server-side.
+    #[protocol(max_length = 119, printable)]
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(strategy = "crate::arbitrary::printable_string(119)")
+    )]
     pub message: String,
 }
 
-impl FromBytes for ChatMessagePayload {
-    fn from_bytes(bytes: &mut Cursor<&[u8]>) -> io::Result<Self> {
-        Ok(Self {
-            message: read_string(bytes)?,
-        })
-    }
-}
-
-impl ToBytes for ChatMessagePayload {
-    fn to_bytes(&self, buffer: &mut BytesMut) -> io::Result<()> {
-        put_string(buffer, &self.message)?;
-        Ok(())
-    }
-}
-
 //
 // Time update
 //
 
 /// Payload for the `Packet::TimeUpdate`.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
 pub struct TimeUpdatePayload {
     /// The world (or region) time in ticks.
     pub time: i64,
 }
 
-impl FromBytes for TimeUpdatePayload {
-    fn from_bytes(bytes: &mut Cursor<&[u8]>) -> io::Result<Self> {
-        Ok(Self {
-            time: bytes.get_i64(),
-        })
-    }
-}
-
-impl ToBytes for TimeUpdatePayload {
-    fn to_bytes(&self, buffer: &mut BytesMut) -> io::Result<()> {
-        buffer.put_i64(self.time);
-        Ok(())
-    }
-}
-
 //
 // Entity equipment
 //
 
 /// Payload for the `Packet::EntityEquipment`.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
 pub struct EntityEquipmentPayload {
     /// Named entity identifier.
     entity_id: i32,
@@ -457,33 +1441,14 @@ pub struct EntityEquipmentPayload {
     damage: i16,
 }
 
-impl FromBytes for EntityEquipmentPayload {
-    fn from_bytes(bytes: &mut Cursor<&[u8]>) -> io::Result<Self> {
-        Ok(Self {
-            entity_id: bytes.get_i32(),
-            slot: bytes.get_i16(),
-            item_id: bytes.get_i16(),
-            damage: bytes.get_i16(),
-        })
-    }
-}
-
-impl ToBytes for EntityEquipmentPayload {
-    fn to_bytes(&self, buffer: &mut BytesMut) -> io::Result<()> {
-        buffer.put_i32(self.entity_id);
-        buffer.put_i16(self.slot);
-        buffer.put_i16(self.item_id);
-        buffer.put_i16(self.damage);
-        Ok(())
-    }
-}
-
 //
 // Spawn position packet
 //
 
 /// Payload for the `Packet::SpawnPosition`.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
 pub struct SpawnPositionPayload {
     /// Spawn X in block coordinates.
     pub x: i32,
@@ -493,23 +1458,95 @@ pub struct SpawnPositionPayload {
     pub z: i32,
 }
 
-impl FromBytes for SpawnPositionPayload {
-    fn from_bytes(bytes: &mut Cursor<&[u8]>) -> io::Result<Self> {
-        Ok(Self {
-            x: bytes.get_i32(),
-            y: bytes.get_i32(),
-            z: bytes.get_i32(),
-        })
-    }
+//
+// Use entity packet
+//
+
+/// Payload for the `Packet::UseEntity`.
+///
+/// Sent when the player attacks or right-clicks an entity.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct UseEntityPayload {
+    /// Identifier of the player entity, ignored by the server.
+    pub user: i32,
+
+    /// Identifier of the clicked entity.
+    pub target: i32,
+
+    /// `1` if the entity has been left-clicked, i.e. attacked, `0` for the right click.
+    pub left_click: u8,
 }
 
-impl ToBytes for SpawnPositionPayload {
-    fn to_bytes(&self, buffer: &mut BytesMut) -> io::Result<()> {
-        buffer.put_i32(self.x);
-        buffer.put_i32(self.y);
-        buffer.put_i32(self.z);
-        Ok(())
-    }
+//
+// Update health packet
+//
+
+/// Payload for the `Packet::UpdateHealth`.
+///
+/// Sent to the player whenever its health or food changes.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct UpdateHealthPayload {
+    /// Health of the player, from `0` when dead to `20` for the full health,
+    /// in half-hearts.
+    pub health: i16,
+
+    /// Food level, from `0` to `20`, in half-shanks.
+    pub food: i16,
+
+    /// Food saturation, from `0.0` up to the food level, drained before the food level.
+    pub food_saturation: f32,
+}
+
+//
+// Respawn packet
+//
+
+/// Payload for the `Packet::Respawn`.
+///
+/// Sent by the client clicking the respawn button after dying,
+/// answered by the server with the world to respawn in, which the client reloads.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct RespawnPayload {
+    /// Dimension, `-1` for the Nether, `0` for the Overworld and `1` for the End.
+    pub dimension: i32,
+
+    /// World difficulty, from `0` for peaceful to `3` for hard.
+    pub difficulty: i8,
+
+    /// Game mode, `0` for survival, `1` for creative.
+    pub creative_mode: i8,
+
+    /// Height of the world, always `256`.
+    pub world_height: i16,
+
+    /// Level type, `default` or `FLAT`.
+    #[protocol(max_length = 16, printable)]
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(strategy = "crate::arbitrary::printable_string(16)")
+    )]
+    pub level_type: String,
+}
+
+//
+// Player packet
+//
+
+/// Payload for the `Packet::Player`.
+///
+/// Sent every tick while the player neither moves nor looks around.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct PlayerPayload {
+    /// Whether the client is on the ground.
+    pub on_ground: u8,
 }
 
 //
@@ -517,7 +1554,9 @@ impl ToBytes for SpawnPositionPayload {
 //
 
 /// Payload for the `Packet::PlayerPosition`.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
 pub struct PlayerPositionPayload {
     /// Absolute X position.
     pub x: f64,
@@ -535,27 +1574,23 @@ pub struct PlayerPositionPayload {
     pub on_ground: u8,
 }
 
-impl FromBytes for PlayerPositionPayload {
-    fn from_bytes(bytes: &mut Cursor<&[u8]>) -> io::Result<Self> {
-        Ok(Self {
-            x: bytes.get_f64(),
-            y: bytes.get_f64(),
-            stance: bytes.get_f64(),
-            z: bytes.get_f64(),
-            on_ground: bytes.get_u8(),
-        })
-    }
-}
+//
+// Player look packet
+//
 
-impl ToBytes for PlayerPositionPayload {
-    fn to_bytes(&self, buffer: &mut BytesMut) -> io::Result<()> {
-        buffer.put_f64(self.x);
-        buffer.put_f64(self.y);
-        buffer.put_f64(self.stance);
-        buffer.put_f64(self.z);
-        buffer.put_u8(self.on_ground);
-        Ok(())
-    }
+/// Payload for the `Packet::PlayerLook`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct PlayerLookPayload {
+    /// Absolute rotation on the X axis.
+    pub yaw: f32,
+
+    /// Absolute rotation on the Y axis.
+    pub pitch: f32,
+
+    /// Whether the client is on the ground.
+    pub on_ground: u8,
 }
 
 //
@@ -563,7 +1598,9 @@ impl ToBytes for PlayerPositionPayload {
 //
 
 /// Payload for the `Packet::PlayerPositionAndLook`.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
 pub struct PlayerPositionAndLookPayload {
     /// Absolute X position.
     pub x: f64,
@@ -595,218 +1632,2684 @@ pub struct PlayerPositionAndLookPayload {
     pub on_ground: u8,
 }
 
-impl FromBytes for PlayerPositionAndLookPayload {
-    fn from_bytes(bytes: &mut Cursor<&[u8]>) -> io::Result<Self> {
-        Ok(Self {
-            x: bytes.get_f64(),
-            stance_y_0: bytes.get_f64(),
-            stance_y_1: bytes.get_f64(),
-            z: bytes.get_f64(),
-            yaw: bytes.get_f32(),
-            pitch: bytes.get_f32(),
-            on_ground: bytes.get_u8(),
-        })
-    }
+//
+// Player digging packet
+//
+
+/// Payload for the `Packet::PlayerDigging`.
+///
+/// Sent when the player starts or finishes digging a block.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct PlayerDiggingPayload {
+    /// Action, `0` for started digging, `2` for finished digging, `4` for dropped
+    /// the held item, `5` for shot an arrow or finished eating.
+    pub status: i8,
+
+    /// Block X coordinate.
+    pub x: i32,
+
+    /// Block Y coordinate.
+    pub y: u8,
+
+    /// Block Z coordinate.
+    pub z: i32,
+
+    /// Face of the block being dug, `0` for -Y, `1` for +Y, `2` for -Z, `3` for +Z,
+    /// `4` for -X, `5` for +X.
+    pub face: i8,
 }
 
-impl ToBytes for PlayerPositionAndLookPayload {
-    fn to_bytes(&self, buffer: &mut BytesMut) -> io::Result<()> {
-        buffer.put_f64(self.x);
-        buffer.put_f64(self.stance_y_0);
-        buffer.put_f64(self.stance_y_1);
-        buffer.put_f64(self.z);
-        buffer.put_f32(self.yaw);
-        buffer.put_f32(self.pitch);
-        buffer.put_u8(self.on_ground);
-        Ok(())
+//
+// Player block placement packet
+//
+
+/// Payload for the `Packet::PlayerBlockPlacement`.
+///
+/// When the player uses the held item without targeting a block, such as eating
+/// or drawing a bow, the coordinates are `-1`, `-1` (as `255`), `-1` and the direction is `-1`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct PlayerBlockPlacementPayload {
+    /// Block X coordinate.
+    pub x: i32,
+
+    /// Block Y coordinate.
+    pub y: u8,
+
+    /// Block Z coordinate.
+    pub z: i32,
+
+    /// Face of the block the player clicked, `0` for -Y, `1` for +Y, `2` for -Z,
+    /// `3` for +Z, `4` for -X, `5` for +X.
+    pub direction: i8,
+
+    /// Item held by the player.
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(strategy = "crate::arbitrary::slot()")
+    )]
+    pub held_item: Slot,
+}
+
+impl PlayerBlockPlacementPayload {
+    /// Whether the player used the held item instead of placing it against a block.
+    pub fn is_use_item(&self) -> bool {
+        self.x == -1 && self.y == u8::MAX && self.z == -1 && self.direction == -1
     }
 }
 
 //
-// Chunk allocation packet.
+// Holding change packet
 //
 
-/// Payload for the `Packet::ChunkAllocation`.
-#[derive(Debug, PartialEq)]
-pub struct ChunkAllocationPayload {
-    /// Chunk X coordinate.
+/// Payload for the `Packet::HoldingChange`.
+///
+/// Sent when the player selects another hotbar slot.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct HoldingChangePayload {
+    /// Selected hotbar slot, in range `0..=8`.
+    pub slot_id: i16,
+}
+
+//
+// Use bed packet
+//
+
+/// Payload for the `Packet::UseBed`.
+///
+/// Makes the player appear lying in the bed to the nearby players.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct UseBedPayload {
+    /// Identifier of the player entity going to sleep.
+    pub entity_id: i32,
+
+    /// Unknown, always `0`.
+    pub in_bed: i8,
+
+    /// Bed X in block coordinates.
     pub x: i32,
 
-    /// Chunk Z coordinate.
+    /// Bed Y in block coordinates.
+    pub y: i8,
+
+    /// Bed Z in block coordinates.
     pub z: i32,
+}
 
-    /// Mode, 0 for unloading and 1 for initializing a chunk.
-    pub mode: u8,
+//
+// Animation packet
+//
+
+/// Payload for the `Packet::Animation`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct AnimationPayload {
+    /// Identifier of the animated player entity.
+    pub entity_id: i32,
+
+    /// Animation, `0` for none, `1` for swinging the arm, `2` for taking damage,
+    /// `3` for leaving the bed, `5` for eating, `104` for crouching, `105` for uncrouching.
+    pub animation: i8,
+}
+
+//
+// Entity action packet
+//
+
+/// Payload for the `Packet::EntityAction`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct EntityActionPayload {
+    /// Identifier of the player entity.
+    pub entity_id: i32,
+
+    /// Action, `1` for crouching, `2` for uncrouching, `3` for leaving the bed,
+    /// `4` for starting and `5` for stopping sprinting.
+    pub action: i8,
+}
+
+//
+// Named entity spawn packet
+//
+
+/// Payload for the `Packet::NamedEntitySpawn`.
+///
+/// Spawns another player, sent when they come into view.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct NamedEntitySpawnPayload {
+    /// Identifier of the player entity.
+    pub entity_id: i32,
+
+    /// Name of the player, displayed above the entity.
+    #[protocol(max_length = 16, printable)]
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(strategy = "crate::arbitrary::printable_string(16)")
+    )]
+    pub player_name: String,
+
+    /// X position as a fixed-point number, the absolute position multiplied by 32.
+    pub x: i32,
+
+    /// Y position as a fixed-point number, the absolute position multiplied by 32.
+    pub y: i32,
+
+    /// Z position as a fixed-point number, the absolute position multiplied by 32.
+    pub z: i32,
+
+    /// Rotation on the X axis, in steps of 1/256 of a full turn.
+    pub rotation: i8,
+
+    /// Rotation on the Y axis, in steps of 1/256 of a full turn.
+    pub pitch: i8,
+
+    /// Identifier of the held item, `0` for none.
+    pub current_item: i16,
+}
+
+//
+// Pickup spawn packet
+//
+
+/// Payload for the `Packet::PickupSpawn`.
+///
+/// Spawns a dropped item entity.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct PickupSpawnPayload {
+    /// Identifier of the dropped item entity.
+    pub entity_id: i32,
+
+    /// Item or block identifier.
+    pub item: i16,
+
+    /// Number of items in the stack.
+    pub count: i8,
+
+    /// Damage of a tool or armor, variant of other items.
+    pub damage: i16,
+
+    /// X position as a fixed-point number, the absolute position multiplied by 32.
+    pub x: i32,
+
+    /// Y position as a fixed-point number, the absolute position multiplied by 32.
+    pub y: i32,
+
+    /// Z position as a fixed-point number, the absolute position multiplied by 32.
+    pub z: i32,
+
+    /// Rotation on the X axis, in steps of 1/256 of a full turn.
+    pub rotation: i8,
+
+    /// Rotation on the Y axis, in steps of 1/256 of a full turn.
+    pub pitch: i8,
+
+    /// Rotation on the Z axis, in steps of 1/256 of a full turn.
+    pub roll: i8,
+}
+
+//
+// Collect item packet
+//
+
+/// Payload for the `Packet::CollectItem`.
+///
+/// Plays the animation of the item flying into the collector, the item entity
+/// itself has to be destroyed separately.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct CollectItemPayload {
+    /// Identifier of the collected item entity.
+    pub collected_entity_id: i32,
+
+    /// Identifier of the entity collecting the item.
+    pub collector_entity_id: i32,
+}
+
+//
+// Add object/vehicle packet
+//
+
+/// Payload for the `Packet::AddObject`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct AddObjectPayload {
+    /// Identifier of the object entity.
+    pub entity_id: i32,
+
+    /// Object type, e.g. `1` for a boat, `10` for a minecart, `50` for a primed TNT,
+    /// `60` for an arrow, `61` for a snowball, `63` for a fireball.
+    pub object_type: i8,
+
+    /// X position as a fixed-point number, the absolute position multiplied by 32.
+    pub x: i32,
+
+    /// Y position as a fixed-point number, the absolute position multiplied by 32.
+    pub y: i32,
+
+    /// Z position as a fixed-point number, the absolute position multiplied by 32.
+    pub z: i32,
+
+    /// Entity which threw or shot the object, only present for the projectiles.
+    pub thrower: Option<ObjectThrower>,
+}
+
+/// Thrower of a projectile spawned by the `Packet::AddObject`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct ObjectThrower {
+    /// Identifier of the throwing entity.
+    ///
+    /// Must not be `0`, on the wire it means there's no thrower at all.
+    #[cfg_attr(any(test, feature = "arbitrary"), proptest(filter = "|id| *id != 0"))]
+    pub entity_id: i32,
+
+    /// Velocity on the X axis, in 1/8000 of a block per tick.
+    pub velocity_x: i16,
+
+    /// Velocity on the Y axis, in 1/8000 of a block per tick.
+    pub velocity_y: i16,
+
+    /// Velocity on the Z axis, in 1/8000 of a block per tick.
+    pub velocity_z: i16,
 }
 
-impl FromBytes for ChunkAllocationPayload {
-    fn from_bytes(bytes: &mut Cursor<&[u8]>) -> io::Result<Self> {
+impl FromBytes for AddObjectPayload {
+    fn from_bytes(bytes: &mut Cursor<&[u8]>) -> Result<Self, ProtocolError> {
+        let entity_id = bytes.try_get_i32()?;
+        let object_type = bytes.try_get_i8()?;
+        let x = bytes.try_get_i32()?;
+        let y = bytes.try_get_i32()?;
+        let z = bytes.try_get_i32()?;
+
+        // The velocity follows only when there's a thrower.
+        let thrower = match bytes.try_get_i32()? {
+            0 => None,
+            entity_id => Some(ObjectThrower {
+                entity_id,
+                velocity_x: bytes.try_get_i16()?,
+                velocity_y: bytes.try_get_i16()?,
+                velocity_z: bytes.try_get_i16()?,
+            }),
+        };
+
         Ok(Self {
-            x: bytes.get_i32(),
-            z: bytes.get_i32(),
-            mode: bytes.get_u8(),
+            entity_id,
+            object_type,
+            x,
+            y,
+            z,
+            thrower,
         })
     }
 }
 
-impl ToBytes for ChunkAllocationPayload {
-    fn to_bytes(&self, buffer: &mut BytesMut) -> io::Result<()> {
+impl ToBytes for AddObjectPayload {
+    fn to_bytes(&self, buffer: &mut BytesMut) -> Result<(), ProtocolError> {
+        buffer.put_i32(self.entity_id);
+        buffer.put_i8(self.object_type);
         buffer.put_i32(self.x);
+        buffer.put_i32(self.y);
         buffer.put_i32(self.z);
-        buffer.put_u8(self.mode);
+
+        match &self.thrower {
+            Some(thrower) => {
+                buffer.put_i32(thrower.entity_id);
+                buffer.put_i16(thrower.velocity_x);
+                buffer.put_i16(thrower.velocity_y);
+                buffer.put_i16(thrower.velocity_z);
+            }
+            None => buffer.put_i32(0),
+        }
+
         Ok(())
     }
 }
 
 //
-// Server list ping packet
+// Mob spawn packet
 //
 
-/// Payload for the `Packet::ServerListPing`.
-#[derive(Debug, PartialEq)]
-pub struct ServerListPingPayload;
+/// Payload for the `Packet::MobSpawn`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct MobSpawnPayload {
+    /// Identifier of the mob entity.
+    pub entity_id: i32,
 
-impl FromBytes for ServerListPingPayload {
-    fn from_bytes(_: &mut Cursor<&[u8]>) -> io::Result<Self> {
-        Ok(Self)
-    }
+    /// Mob type, e.g. `50` for a creeper, `54` for a zombie, `90` for a pig.
+    pub mob_type: i8,
+
+    /// X position as a fixed-point number, the absolute position multiplied by 32.
+    pub x: i32,
+
+    /// Y position as a fixed-point number, the absolute position multiplied by 32.
+    pub y: i32,
+
+    /// Z position as a fixed-point number, the absolute position multiplied by 32.
+    pub z: i32,
+
+    /// Rotation on the X axis, in steps of 1/256 of a full turn.
+    pub yaw: i8,
+
+    /// Rotation on the Y axis, in steps of 1/256 of a full turn.
+    pub pitch: i8,
+
+    /// Rotation of the head on the X axis, in steps of 1/256 of a full turn.
+    pub head_yaw: i8,
+
+    /// Initial state of the mob.
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(strategy = "crate::arbitrary::metadata()")
+    )]
+    pub metadata: Metadata,
 }
 
 //
-// Disconnect/Kick packet
+// Painting packet
 //
 
-/// Payload for the `Packet::DisconnectKick`.
-#[derive(Debug, PartialEq)]
-pub struct DisconnectKickPayload {
-    /// Reason displayed to the client when the connection terminates.
-    pub reason: String,
+/// Payload for the `Packet::Painting`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct PaintingPayload {
+    /// Identifier of the painting entity.
+    pub entity_id: i32,
+
+    /// Name of the painting, e.g. `Kebab` or `DonkeyKong`.
+    #[protocol(max_length = 13, printable)]
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(strategy = "crate::arbitrary::printable_string(13)")
+    )]
+    pub title: String,
+
+    /// Center X in block coordinates.
+    pub x: i32,
+
+    /// Center Y in block coordinates.
+    pub y: i32,
+
+    /// Center Z in block coordinates.
+    pub z: i32,
+
+    /// Direction the painting faces, `0` for -Z, `1` for -X, `2` for +Z, `3` for +X.
+    pub direction: i32,
 }
 
-impl FromBytes for DisconnectKickPayload {
-    fn from_bytes(bytes: &mut Cursor<&[u8]>) -> io::Result<Self> {
-        let reason = read_string(bytes)?;
-        Ok(Self { reason })
-    }
+//
+// Experience orb packet
+//
+
+/// Payload for the `Packet::ExperienceOrb`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct ExperienceOrbPayload {
+    /// Identifier of the orb entity.
+    pub entity_id: i32,
+
+    /// X position as a fixed-point number, the absolute position multiplied by 32.
+    pub x: i32,
+
+    /// Y position as a fixed-point number, the absolute position multiplied by 32.
+    pub y: i32,
+
+    /// Z position as a fixed-point number, the absolute position multiplied by 32.
+    pub z: i32,
+
+    /// Amount of experience the orb gives when collected.
+    pub count: i16,
 }
 
-impl ToBytes for DisconnectKickPayload {
-    fn to_bytes(&self, buffer: &mut BytesMut) -> io::Result<()> {
-        put_string(buffer, &self.reason)?;
-        Ok(())
-    }
+//
+// Entity velocity packet
+//
+
+/// Payload for the `Packet::EntityVelocity`.
+///
+/// The client clamps each of the velocities to `-28800..=28800`, 3.6 blocks per tick.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct EntityVelocityPayload {
+    /// Identifier of the moving entity.
+    pub entity_id: i32,
+
+    /// Velocity on the X axis, in 1/8000 of a block per tick.
+    pub velocity_x: i16,
+
+    /// Velocity on the Y axis, in 1/8000 of a block per tick.
+    pub velocity_y: i16,
+
+    /// Velocity on the Z axis, in 1/8000 of a block per tick.
+    pub velocity_z: i16,
 }
 
-// I don't know if it's a good way of unit testing, but so far it works.
-#[cfg(test)]
-mod tests {
-    use super::*;
+//
+// Destroy entity packet
+//
 
-    #[test]
-    fn put_string_empty() {
-        let mut buffer = BytesMut::with_capacity(2);
-        put_string(&mut buffer, "").unwrap();
+/// Payload for the `Packet::DestroyEntity`.
+///
+/// Removes the entity from the client, e.g. when it dies or leaves the range.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct DestroyEntityPayload {
+    /// Identifier of the destroyed entity.
+    pub entity_id: i32,
+}
 
-        assert_eq!(buffer.as_ref(), &[0x00, 0x00]);
-    }
+//
+// Entity packet
+//
 
-    #[test]
-    fn put_string_test() {
-        let mut buffer = BytesMut::with_capacity(10);
-        put_string(&mut buffer, "test").unwrap();
+/// Payload for the `Packet::Entity`.
+///
+/// Doesn't change anything, the vanilla server sends it for the entities
+/// standing still.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct EntityPayload {
+    /// Identifier of the entity.
+    pub entity_id: i32,
+}
 
-        assert_eq!(
-            buffer.as_ref(),
-            &[0x00, 0x04, 0x00, 0x74, 0x00, 0x65, 0x00, 0x73, 0x00, 0x74]
-        );
-    }
+/// Converts the absolute position to the fixed-point number used by the entity packets.
+pub fn to_fixed_point(position: f64) -> i32 {
+    (position * 32.0).floor() as i32
+}
 
-    #[test]
-    fn read_string_test() {
-        let mut cursor =
-            Cursor::new(&[0x00u8, 0x04, 0x00, 0x74, 0x00, 0x65, 0x00, 0x73, 0x00, 0x74] as &[u8]);
-        let s = read_string(&mut cursor).unwrap();
+/// Difference between the fixed-point positions, if it fits the relative move packets.
+///
+/// When it doesn't, the entity has moved more than 4 blocks and has to be
+/// teleported instead.
+pub fn fixed_point_delta(from: i32, to: i32) -> Option<i8> {
+    to.checked_sub(from)
+        .and_then(|delta| i8::try_from(delta).ok())
+}
+
+/// Converts the angle in degrees to the steps of 1/256 of a full turn.
+pub fn to_angle_steps(degrees: f32) -> i8 {
+    ((degrees * 256.0 / 360.0).floor() as i32) as i8
+}
+
+//
+// Entity relative move packet
+//
+
+/// Payload for the `Packet::EntityRelativeMove`.
+///
+/// Moves the entity by up to 4 blocks along each axis.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct EntityRelativeMovePayload {
+    /// Identifier of the entity.
+    pub entity_id: i32,
+
+    /// Change of the X position as a fixed-point number, in 1/32 of a block.
+    pub dx: i8,
+
+    /// Change of the Y position as a fixed-point number, in 1/32 of a block.
+    pub dy: i8,
+
+    /// Change of the Z position as a fixed-point number, in 1/32 of a block.
+    pub dz: i8,
+}
+
+//
+// Entity look packet
+//
+
+/// Payload for the `Packet::EntityLook`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct EntityLookPayload {
+    /// Identifier of the entity.
+    pub entity_id: i32,
+
+    /// Absolute rotation on the X axis, in steps of 1/256 of a full turn.
+    pub yaw: i8,
+
+    /// Absolute rotation on the Y axis, in steps of 1/256 of a full turn.
+    pub pitch: i8,
+}
+
+//
+// Entity look and relative move packet
+//
+
+/// Payload for the `Packet::EntityLookAndRelativeMove`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct EntityLookAndRelativeMovePayload {
+    /// Identifier of the entity.
+    pub entity_id: i32,
+
+    /// Change of the X position as a fixed-point number, in 1/32 of a block.
+    pub dx: i8,
+
+    /// Change of the Y position as a fixed-point number, in 1/32 of a block.
+    pub dy: i8,
+
+    /// Change of the Z position as a fixed-point number, in 1/32 of a block.
+    pub dz: i8,
+
+    /// Absolute rotation on the X axis, in steps of 1/256 of a full turn.
+    pub yaw: i8,
+
+    /// Absolute rotation on the Y axis, in steps of 1/256 of a full turn.
+    pub pitch: i8,
+}
+
+//
+// Entity teleport packet
+//
+
+/// Payload for the `Packet::EntityTeleport`.
+///
+/// Used instead of the relative moves when the entity moved more than 4 blocks.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct EntityTeleportPayload {
+    /// Identifier of the entity.
+    pub entity_id: i32,
+
+    /// X position as a fixed-point number, the absolute position multiplied by 32.
+    pub x: i32,
+
+    /// Y position as a fixed-point number, the absolute position multiplied by 32.
+    pub y: i32,
+
+    /// Z position as a fixed-point number, the absolute position multiplied by 32.
+    pub z: i32,
+
+    /// Absolute rotation on the X axis, in steps of 1/256 of a full turn.
+    pub yaw: i8,
+
+    /// Absolute rotation on the Y axis, in steps of 1/256 of a full turn.
+    pub pitch: i8,
+}
+
+//
+// Entity head look packet
+//
+
+/// Payload for the `Packet::EntityHeadLook`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct EntityHeadLookPayload {
+    /// Identifier of the entity.
+    pub entity_id: i32,
+
+    /// Absolute rotation of the head on the X axis, in steps of 1/256 of a full turn.
+    pub head_yaw: i8,
+}
+
+//
+// Entity status packet
+//
+
+/// Payload for the `Packet::EntityStatus`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct EntityStatusPayload {
+    /// Identifier of the entity.
+    pub entity_id: i32,
+
+    /// Status, `2` for getting hurt, `3` for dying, `6` for a failed and `7` for a successful
+    /// wolf taming, `8` for a wolf shaking off water, `9` for the eating being accepted.
+    pub status: i8,
+}
+
+//
+// Attach entity packet
+//
+
+/// Payload for the `Packet::AttachEntity`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct AttachEntityPayload {
+    /// Identifier of the riding entity.
+    pub entity_id: i32,
+
+    /// Identifier of the vehicle, `-1` for dismounting.
+    pub vehicle_id: i32,
+}
+
+//
+// Entity metadata packet
+//
+
+/// Payload for the `Packet::EntityMetadata`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct EntityMetadataPayload {
+    /// Identifier of the entity.
+    pub entity_id: i32,
+
+    /// Changed values of the entity state.
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(strategy = "crate::arbitrary::metadata()")
+    )]
+    pub metadata: Metadata,
+}
+
+//
+// Entity effect packet
+//
+
+/// Payload for the `Packet::EntityEffect`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct EntityEffectPayload {
+    /// Identifier of the affected entity.
+    pub entity_id: i32,
+
+    /// Effect, e.g. `1` for speed, `10` for regeneration, `19` for poison.
+    pub effect_id: i8,
+
+    /// Level of the effect, `0` for level I.
+    pub amplifier: i8,
+
+    /// Remaining duration in ticks.
+    pub duration: i16,
+}
+
+//
+// Remove entity effect packet
+//
+
+/// Payload for the `Packet::RemoveEntityEffect`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct RemoveEntityEffectPayload {
+    /// Identifier of the affected entity.
+    pub entity_id: i32,
+
+    /// Effect which has expired.
+    pub effect_id: i8,
+}
+
+//
+// Set experience packet
+//
+
+/// Payload for the `Packet::SetExperience`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct SetExperiencePayload {
+    /// Fill of the experience bar, in range `0.0..=1.0`.
+    pub experience_bar: f32,
+
+    /// Experience level.
+    pub level: i16,
+
+    /// Experience collected since the last death.
+    pub total_experience: i16,
+}
+
+//
+// Chunk allocation packet.
+//
+
+/// Payload for the `Packet::ChunkAllocation`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct ChunkAllocationPayload {
+    /// Chunk X coordinate.
+    pub x: i32,
+
+    /// Chunk Z coordinate.
+    pub z: i32,
+
+    /// Mode, 0 for unloading and 1 for initializing a chunk.
+    pub mode: u8,
+}
+
+//
+// Map chunk packet
+//
+
+/// Payload for the `Packet::MapChunk`.
+///
+/// The chunk data is kept uncompressed, it's compressed with zlib on the wire.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct MapChunkPayload {
+    /// Chunk X coordinate.
+    pub x: i32,
+
+    /// Chunk Z coordinate.
+    pub z: i32,
+
+    /// Sections of the column, built with the `ChunkDataBuilder`.
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(strategy = "crate::arbitrary::chunk_data()")
+    )]
+    pub chunk: ChunkData,
+}
+
+impl FromBytes for MapChunkPayload {
+    fn from_bytes(bytes: &mut Cursor<&[u8]>) -> Result<Self, ProtocolError> {
+        let x = bytes.try_get_i32()?;
+        let z = bytes.try_get_i32()?;
+        let ground_up_continuous = bytes.try_get_u8()? != 0;
+        let primary_bitmap = bytes.try_get_u16()?;
+        let add_bitmap = bytes.try_get_u16()?;
+        let compressed_size = bytes.try_get_i32()?;
+        let _unused = bytes.try_get_i32()?;
+
+        let compressed_size = usize::try_from(compressed_size)
+            .map_err(|_| ProtocolError::NegativeLength(compressed_size))?;
+
+        // Rejected before the data is waited for, no column takes more.
+        if compressed_size > MAX_COMPRESSED_LENGTH {
+            return Err(ProtocolError::PayloadTooLarge);
+        }
+
+        if bytes.remaining() < compressed_size {
+            return Err(ProtocolError::UnexpectedEof {
+                needed: compressed_size - bytes.remaining(),
+            });
+        }
+
+        let position = bytes.position() as usize;
+        let compressed = &bytes.get_ref()[position..position + compressed_size];
+        let data = ChunkData::decompress(compressed)?;
+        bytes.advance(compressed_size);
+
+        Ok(Self {
+            x,
+            z,
+            chunk: ChunkData {
+                primary_bitmap,
+                add_bitmap,
+                ground_up_continuous,
+                data,
+            },
+        })
+    }
+}
+
+impl ToBytes for MapChunkPayload {
+    fn to_bytes(&self, buffer: &mut BytesMut) -> Result<(), ProtocolError> {
+        let compressed = self.chunk.compress()?;
+        let compressed_size =
+            i32::try_from(compressed.len()).map_err(|_| ProtocolError::PayloadTooLarge)?;
+
+        buffer.put_i32(self.x);
+        buffer.put_i32(self.z);
+        buffer.put_u8(self.chunk.ground_up_continuous.into());
+        buffer.put_u16(self.chunk.primary_bitmap);
+        buffer.put_u16(self.chunk.add_bitmap);
+        buffer.put_i32(compressed_size);
+        // Unused, previously the size of the uncompressed data.
+        buffer.put_i32(0);
+        buffer.put_slice(&compressed);
+
+        Ok(())
+    }
+}
+
+//
+// Multi block change packet
+//
+
+/// Max number of the blocks changed by a single `Packet::MultiBlockChange`,
+/// a section's worth, more of them are sent in several packets.
+pub const MAX_BLOCK_CHANGE_RECORDS: usize = 4096;
+
+/// Payload for the `Packet::MultiBlockChange`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct MultiBlockChangePayload {
+    /// Chunk X coordinate.
+    pub chunk_x: i32,
+
+    /// Chunk Z coordinate.
+    pub chunk_z: i32,
+
+    /// Changed blocks of the chunk column.
+    pub records: Vec<BlockChangeRecord>,
+}
+
+/// A single block changed by the `Packet::MultiBlockChange`.
+///
+/// Packed into 32 bits on the wire, the values outside of the ranges are truncated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct BlockChangeRecord {
+    /// X coordinate within the chunk, in range `0..16`.
+    #[cfg_attr(any(test, feature = "arbitrary"), proptest(strategy = "0..16u8"))]
+    pub x: u8,
+
+    /// Y coordinate.
+    pub y: u8,
+
+    /// Z coordinate within the chunk, in range `0..16`.
+    #[cfg_attr(any(test, feature = "arbitrary"), proptest(strategy = "0..16u8"))]
+    pub z: u8,
+
+    /// Block identifier, in range `0..4096`.
+    #[cfg_attr(any(test, feature = "arbitrary"), proptest(strategy = "0..4096u16"))]
+    pub block_id: u16,
+
+    /// Block metadata, in range `0..16`.
+    #[cfg_attr(any(test, feature = "arbitrary"), proptest(strategy = "0..16u8"))]
+    pub metadata: u8,
+}
+
+impl BlockChangeRecord {
+    /// Packs the record as `xxxx zzzz yyyyyyyy bbbbbbbbbbbb mmmm`, from the highest bit.
+    fn pack(&self) -> u32 {
+        (self.x as u32 & 0x0F) << 28
+            | (self.z as u32 & 0x0F) << 24
+            | (self.y as u32) << 16
+            | (self.block_id as u32 & 0x0FFF) << 4
+            | (self.metadata as u32 & 0x0F)
+    }
+
+    fn unpack(record: u32) -> Self {
+        Self {
+            x: (record >> 28) as u8,
+            z: (record >> 24 & 0x0F) as u8,
+            y: (record >> 16) as u8,
+            block_id: (record >> 4 & 0x0FFF) as u16,
+            metadata: (record & 0x0F) as u8,
+        }
+    }
+}
+
+impl FromBytes for MultiBlockChangePayload {
+    fn from_bytes(bytes: &mut Cursor<&[u8]>) -> Result<Self, ProtocolError> {
+        let chunk_x = bytes.try_get_i32()?;
+        let chunk_z = bytes.try_get_i32()?;
+        let record_count = bytes.try_get_i16()?;
+        let data_size = bytes.try_get_i32()?;
+
+        let record_count = usize::try_from(record_count)
+            .map_err(|_| ProtocolError::NegativeLength(record_count.into()))?;
+        let data_size =
+            usize::try_from(data_size).map_err(|_| ProtocolError::NegativeLength(data_size))?;
+
+        // Every record takes 4 bytes of the data, with nothing else in it.
+        if record_count > MAX_BLOCK_CHANGE_RECORDS || record_count * 4 != data_size {
+            return Err(ProtocolError::PayloadTooLarge);
+        }
+
+        if bytes.remaining() < data_size {
+            return Err(ProtocolError::UnexpectedEof {
+                needed: data_size - bytes.remaining(),
+            });
+        }
+
+        let mut records = Vec::with_capacity(record_count);
+
+        for _ in 0..record_count {
+            records.push(BlockChangeRecord::unpack(bytes.try_get_u32()?));
+        }
+
+        Ok(Self {
+            chunk_x,
+            chunk_z,
+            records,
+        })
+    }
+}
+
+impl ToBytes for MultiBlockChangePayload {
+    fn to_bytes(&self, buffer: &mut BytesMut) -> Result<(), ProtocolError> {
+        if self.records.len() > MAX_BLOCK_CHANGE_RECORDS {
+            return Err(ProtocolError::PayloadTooLarge);
+        }
+        let record_count = self.records.len() as i16;
+
+        buffer.put_i32(self.chunk_x);
+        buffer.put_i32(self.chunk_z);
+        buffer.put_i16(record_count);
+        buffer.put_i32(i32::from(record_count) * 4);
+
+        for record in &self.records {
+            buffer.put_u32(record.pack());
+        }
+
+        Ok(())
+    }
+}
+
+//
+// Block change packet
+//
+
+/// Payload for the `Packet::BlockChange`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct BlockChangePayload {
+    /// Block X coordinate.
+    pub x: i32,
+
+    /// Block Y coordinate.
+    pub y: u8,
+
+    /// Block Z coordinate.
+    pub z: i32,
+
+    /// New block identifier.
+    pub block_id: u8,
+
+    /// New block metadata.
+    pub metadata: u8,
+}
+
+//
+// Block action packet
+//
+
+/// Payload for the `Packet::BlockAction`.
+///
+/// Plays the block animations, such as the chest lid or the note block note.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct BlockActionPayload {
+    /// Block X coordinate.
+    pub x: i32,
+
+    /// Block Y coordinate.
+    pub y: i16,
+
+    /// Block Z coordinate.
+    pub z: i32,
+
+    /// Note block instrument, piston push or pull, or `1` for a chest.
+    pub data_0: i8,
+
+    /// Note block pitch, piston direction, or the number of the players
+    /// with the chest open.
+    pub data_1: i8,
+}
+
+//
+// Sound/particle effect packet
+//
+
+/// Payload for the `Packet::SoundParticleEffect`.
+///
+/// Plays a sound or shows particles at the block.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct SoundParticleEffectPayload {
+    /// Identifier of the effect, e.g. `2001` for a block break.
+    pub effect_id: i32,
+
+    /// Block X coordinate.
+    pub x: i32,
+
+    /// Block Y coordinate.
+    pub y: u8,
+
+    /// Block Z coordinate.
+    pub z: i32,
+
+    /// Extra data of the effect, e.g. the identifier of the broken block.
+    pub data: i32,
+}
+
+//
+// Change game state packet
+//
+
+/// Payload for the `Packet::ChangeGameState`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct ChangeGameStatePayload {
+    /// Reason, `0` for the invalid bed message, `1` for beginning and `2` for ending
+    /// the rain, `3` for changing the game mode.
+    pub reason: i8,
+
+    /// Game mode, `0` for survival, `1` for creative, used by the reason `3` only.
+    pub game_mode: i8,
+}
+
+//
+// Thunderbolt packet
+//
+
+/// Payload for the `Packet::Thunderbolt`.
+///
+/// Spawns a global entity, the lightning bolt is the only one.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct ThunderboltPayload {
+    /// Identifier of the lightning bolt entity.
+    pub entity_id: i32,
+
+    /// Unknown, always `1`.
+    pub unknown: u8,
+
+    /// X position as a fixed-point number, the absolute position multiplied by 32.
+    pub x: i32,
+
+    /// Y position as a fixed-point number, the absolute position multiplied by 32.
+    pub y: i32,
+
+    /// Z position as a fixed-point number, the absolute position multiplied by 32.
+    pub z: i32,
+}
+
+//
+// Open window packet
+//
+
+/// Payload for the `Packet::OpenWindow`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct OpenWindowPayload {
+    /// Identifier of the window, assigned by the server.
+    pub window_id: i8,
+
+    /// Inventory type, `0` for a chest, `1` for a workbench, `2` for a furnace,
+    /// `3` for a dispenser, `4` for an enchantment table, `5` for a brewing stand.
+    pub inventory_type: i8,
+
+    /// Title displayed above the window.
+    #[protocol(max_length = 32, printable)]
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(strategy = "crate::arbitrary::printable_string(32)")
+    )]
+    pub window_title: String,
+
+    /// Number of the slots, excluding the player's inventory.
+    pub number_of_slots: i8,
+}
+
+//
+// Close window packet
+//
+
+/// Payload for the `Packet::CloseWindow`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct CloseWindowPayload {
+    /// Identifier of the closed window, `0` for the player's inventory.
+    pub window_id: i8,
+}
+
+//
+// Window click packet
+//
+
+/// Payload for the `Packet::WindowClick`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct WindowClickPayload {
+    /// Identifier of the window, `0` for the player's inventory.
+    pub window_id: i8,
+
+    /// Clicked slot, `-999` for clicking outside of the window.
+    pub slot: i16,
+
+    /// Whether the right mouse button was used.
+    pub right_click: u8,
+
+    /// Number of the action, the server confirms or rejects it with the `Packet::Transaction`.
+    pub action_number: i16,
+
+    /// Whether the shift key was held.
+    pub shift: u8,
+
+    /// Item in the clicked slot, as the client sees it.
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(strategy = "crate::arbitrary::slot()")
+    )]
+    pub clicked_item: Slot,
+}
+
+//
+// Set slot packet
+//
+
+/// Payload for the `Packet::SetSlot`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct SetSlotPayload {
+    /// Identifier of the window, `0` for the player's inventory, `-1` for the item
+    /// held by the cursor.
+    pub window_id: i8,
+
+    /// Updated slot.
+    pub slot: i16,
+
+    /// New contents of the slot.
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(strategy = "crate::arbitrary::slot()")
+    )]
+    pub item: Slot,
+}
+
+//
+// Window items packet
+//
+
+/// Payload for the `Packet::WindowItems`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct WindowItemsPayload {
+    /// Identifier of the window, `0` for the player's inventory.
+    pub window_id: i8,
+
+    /// Contents of all the slots of the window, ordered by the slot number.
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(strategy = "proptest::collection::vec(crate::arbitrary::slot(), 0..64)")
+    )]
+    pub slots: Vec<Slot>,
+}
+
+impl FromBytes for WindowItemsPayload {
+    fn from_bytes(bytes: &mut Cursor<&[u8]>) -> Result<Self, ProtocolError> {
+        let window_id = bytes.try_get_i8()?;
+        let count = bytes.try_get_i16()?;
+        let count =
+            usize::try_from(count).map_err(|_| ProtocolError::NegativeLength(count.into()))?;
+
+        // Every slot takes at least 2 bytes, don't allocate for the slots which can't be there.
+        let mut slots = Vec::with_capacity(count.min(bytes.remaining() / 2));
+
+        for _ in 0..count {
+            slots.push(Slot::from_bytes(bytes)?);
+        }
+
+        Ok(Self { window_id, slots })
+    }
+}
+
+impl ToBytes for WindowItemsPayload {
+    fn to_bytes(&self, buffer: &mut BytesMut) -> Result<(), ProtocolError> {
+        let count = i16::try_from(self.slots.len()).map_err(|_| ProtocolError::PayloadTooLarge)?;
+
+        buffer.put_i8(self.window_id);
+        buffer.put_i16(count);
+
+        for slot in &self.slots {
+            slot.to_bytes(buffer)?;
+        }
+
+        Ok(())
+    }
+}
+
+//
+// Update window property packet
+//
+
+/// Payload for the `Packet::UpdateWindowProperty`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct UpdateWindowPropertyPayload {
+    /// Identifier of the window.
+    pub window_id: i8,
+
+    /// Property, for a furnace `0` for the progress arrow and `1` for the fire icon.
+    pub property: i16,
+
+    /// New value of the property.
+    pub value: i16,
+}
+
+//
+// Transaction packet
+//
+
+/// Payload for the `Packet::Transaction`.
+///
+/// Confirms or rejects the click, the client sends it back after a rejection
+/// to have its inventory resynchronized.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct TransactionPayload {
+    /// Identifier of the window.
+    pub window_id: i8,
+
+    /// Number of the action sent with the `Packet::WindowClick`.
+    pub action_number: i16,
+
+    /// Whether the action was accepted, `1` or `0`.
+    pub accepted: u8,
+}
+
+//
+// Creative inventory action packet
+//
+
+/// Payload for the `Packet::CreativeInventoryAction`.
+///
+/// Sets the slot directly, the creative players take items out of nothing.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct CreativeInventoryActionPayload {
+    /// Slot of the player's inventory, `-1` for dropping the item.
+    pub slot: i16,
+
+    /// New contents of the slot.
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(strategy = "crate::arbitrary::slot()")
+    )]
+    pub clicked_item: Slot,
+}
+
+//
+// Update sign packet
+//
+
+/// Payload for the `Packet::UpdateSign`.
+///
+/// Sent by the client once the text of a placed sign is edited,
+/// and by the server to show the text of the signs in view.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct UpdateSignPayload {
+    /// Block X coordinate of the sign.
+    pub x: i32,
+
+    /// Block Y coordinate of the sign.
+    pub y: i16,
+
+    /// Block Z coordinate of the sign.
+    pub z: i32,
+
+    /// First line of the text.
+    #[protocol(max_length = 15, printable)]
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(strategy = "crate::arbitrary::printable_string(15)")
+    )]
+    pub text1: String,
+
+    /// Second line of the text.
+    #[protocol(max_length = 15, printable)]
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(strategy = "crate::arbitrary::printable_string(15)")
+    )]
+    pub text2: String,
+
+    /// Third line of the text.
+    #[protocol(max_length = 15, printable)]
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(strategy = "crate::arbitrary::printable_string(15)")
+    )]
+    pub text3: String,
+
+    /// Fourth line of the text.
+    #[protocol(max_length = 15, printable)]
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(strategy = "crate::arbitrary::printable_string(15)")
+    )]
+    pub text4: String,
+}
+
+//
+// Item data packet
+//
+
+/// Payload for the `Packet::ItemData`.
+///
+/// Carries the pixels of a map item, a column of the map at a time.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct ItemDataPayload {
+    /// Item type, `358` for a map.
+    pub item_type: i16,
+
+    /// Identifier of the item, the damage of the map item.
+    pub item_id: i16,
+
+    /// Item specific data, at most 255 bytes.
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(
+            strategy = "proptest::collection::vec(proptest::arbitrary::any::<u8>(), 0..=255)"
+        )
+    )]
+    pub data: Vec<u8>,
+}
+
+impl FromBytes for ItemDataPayload {
+    fn from_bytes(bytes: &mut Cursor<&[u8]>) -> Result<Self, ProtocolError> {
+        let item_type = bytes.try_get_i16()?;
+        let item_id = bytes.try_get_i16()?;
+        let length = bytes.try_get_u8()? as usize;
+
+        if bytes.remaining() < length {
+            return Err(ProtocolError::UnexpectedEof {
+                needed: length - bytes.remaining(),
+            });
+        }
+
+        let mut data = vec![0; length];
+        bytes.copy_to_slice(&mut data);
+
+        Ok(Self {
+            item_type,
+            item_id,
+            data,
+        })
+    }
+}
+
+impl ToBytes for ItemDataPayload {
+    fn to_bytes(&self, buffer: &mut BytesMut) -> Result<(), ProtocolError> {
+        let length = u8::try_from(self.data.len()).map_err(|_| ProtocolError::PayloadTooLarge)?;
+
+        buffer.put_i16(self.item_type);
+        buffer.put_i16(self.item_id);
+        buffer.put_u8(length);
+        buffer.put_slice(&self.data);
+
+        Ok(())
+    }
+}
+
+//
+// Server list ping packet
+//
+
+/// Payload for the `Packet::ServerListPing`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct ServerListPingPayload;
+
+//
+// Disconnect/Kick packet
+//
+
+/// Payload for the `Packet::DisconnectKick`.
+#[derive(Debug, Clone, PartialEq, FromBytes, ToBytes)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(proptest_derive::Arbitrary))]
+pub struct DisconnectKickPayload {
+    /// Reason displayed to the client when the connection terminates.
+    #[protocol(max_length = 100)]
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(strategy = "crate::arbitrary::string(100)")
+    )]
+    pub reason: String,
+}
+
+// I don't know if it's a good way of unit testing, but so far it works.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{chunk::ChunkDataBuilder, metadata::MetadataValue, slot::ItemStack};
+
+    #[test]
+    fn put_string_empty() {
+        let mut buffer = BytesMut::with_capacity(2);
+        put_string(&mut buffer, "").unwrap();
+
+        assert_eq!(buffer.as_ref(), &[0x00, 0x00]);
+    }
+
+    #[test]
+    fn put_string_test() {
+        let mut buffer = BytesMut::with_capacity(10);
+        put_string(&mut buffer, "test").unwrap();
+
+        assert_eq!(
+            buffer.as_ref(),
+            &[0x00, 0x04, 0x00, 0x74, 0x00, 0x65, 0x00, 0x73, 0x00, 0x74]
+        );
+    }
+
+    #[test]
+    fn put_string_surrogate_pair() {
+        let mut buffer = BytesMut::new();
+        put_string(&mut buffer, "😀").unwrap();
+
+        assert_eq!(buffer.as_ref(), &[0x00, 0x02, 0xD8, 0x3D, 0xDE, 0x00]);
+    }
+
+    #[test]
+    fn read_string_surrogate_pair() {
+        let mut cursor = Cursor::new(&[0x00u8, 0x02, 0xD8, 0x3D, 0xDE, 0x00] as &[u8]);
+        let s = read_string(&mut cursor).unwrap();
+
+        assert_eq!(s, "😀");
+    }
+
+    #[test]
+    fn read_string_over_limit() {
+        // Only the length prefix is present, the limit must be checked before the data.
+        let mut cursor = Cursor::new(&[0x00u8, 0x11] as &[u8]);
+
+        let result = StringLimit::new(16).read(&mut cursor);
+
+        assert!(matches!(result, Err(ProtocolError::PayloadTooLarge)));
+    }
+
+    #[test]
+    fn put_string_over_limit() {
+        let mut buffer = BytesMut::new();
+
+        let result = StringLimit::new(3).put(&mut buffer, "test");
+
+        assert!(matches!(result, Err(ProtocolError::PayloadTooLarge)));
+    }
+
+    #[test]
+    fn read_string_control_character() {
+        let mut cursor = Cursor::new(&[0x00u8, 0x02, 0x00, b'a', 0x00, b'\n'] as &[u8]);
+
+        let result = StringLimit::printable(16).read(&mut cursor);
+
+        assert!(matches!(result, Err(ProtocolError::InvalidString)));
+    }
+
+    #[test]
+    fn decode_too_long_username() {
+        let mut data = vec![0x01, 0x00, 0x00, 0x00, 0x1D, 0x00, 0x11];
+        data.extend(std::iter::repeat_n([0x00, b'a'], 17).flatten());
+
+        let result = Packet::from_bytes(&data);
+
+        assert!(matches!(result, Err(ProtocolError::PayloadTooLarge)));
+    }
+
+    #[test]
+    fn read_string_test() {
+        let mut cursor =
+            Cursor::new(&[0x00u8, 0x04, 0x00, 0x74, 0x00, 0x65, 0x00, 0x73, 0x00, 0x74] as &[u8]);
+        let s = read_string(&mut cursor).unwrap();
+
+        assert_eq!(s, "test");
+    }
+
+    #[test]
+    fn put_string_too_long() {
+        let mut buffer = BytesMut::new();
+        let s = "a".repeat(u16::MAX as usize + 1);
+
+        let result = put_string(&mut buffer, &s);
+
+        assert!(matches!(result, Err(ProtocolError::PayloadTooLarge)));
+    }
+
+    #[test]
+    fn read_string_invalid() {
+        let mut cursor = Cursor::new(&[0x00u8, 0x01, 0xD8, 0x00] as &[u8]);
+
+        let result = read_string(&mut cursor);
+
+        assert!(matches!(result, Err(ProtocolError::InvalidString)));
+    }
+
+    #[test]
+    fn decode_unknown_packet_id() {
+        let data: &[u8] = &[0xAB];
+
+        let result = Packet::from_bytes(data);
+
+        assert!(matches!(result, Err(ProtocolError::UnknownPacketId(0xAB))));
+    }
+
+    #[test]
+    fn decode_incomplete_packet() {
+        let data: &[u8] = &[0x00, 0x00, 0x00];
+
+        let result = Packet::from_bytes(data);
+
+        assert!(matches!(
+            result,
+            Err(ProtocolError::UnexpectedEof { needed: 2 })
+        ));
+    }
+
+    #[test]
+    fn decode_trailing_zeroes_without_payload() {
+        let data: &[u8] = &[0xFE, 0x00, 0x00, 0x00];
+
+        let packet = Packet::from_bytes(data).unwrap();
+
+        assert_eq!(packet, Packet::ServerListPing(ServerListPingPayload {}));
+    }
+
+    #[test]
+    fn decode_trailing_zeroes_with_payload() {
+        let data: &[u8] = &[0x00, 0x00, 0x00, 0x00, 0x11, 0x00, 0x00, 0x00, 0x00];
+
+        let packet = Packet::from_bytes(data).unwrap();
+
+        assert_eq!(
+            packet,
+            Packet::KeepAlive(KeepAlivePayload { keep_alive_id: 17 })
+        );
+    }
+
+    #[test]
+    fn decode_reports_consumed_length() {
+        let data: &[u8] = &[0x00, 0x00, 0x00, 0x00, 0x11, 0xFE];
+
+        let (packet, length) = Packet::decode(data).unwrap();
+
+        assert_eq!(
+            packet,
+            Packet::KeepAlive(KeepAlivePayload { keep_alive_id: 17 })
+        );
+        assert_eq!(length, 5);
+
+        let (packet, length) = Packet::decode(&data[length..]).unwrap();
+
+        assert_eq!(packet, Packet::ServerListPing(ServerListPingPayload));
+        assert_eq!(length, 1);
+    }
+
+    #[test]
+    fn encode_appends_to_buffer() {
+        let mut buffer = BytesMut::from(&[0xFE][..]);
+
+        Packet::KeepAlive(KeepAlivePayload { keep_alive_id: 17 })
+            .encode(&mut buffer)
+            .unwrap();
+
+        assert_eq!(buffer.as_ref(), &[0xFE, 0x00, 0x00, 0x00, 0x00, 0x11]);
+    }
+
+    #[test]
+    fn packet_id_and_name() {
+        let packet = Packet::KeepAlive(KeepAlivePayload { keep_alive_id: 17 });
+
+        assert_eq!(packet.id(), 0x00);
+        assert_eq!(packet.name(), "KeepAlive");
+        assert_eq!(packet.info().direction, Direction::TwoWay);
+    }
+
+    #[test]
+    fn registry_is_sorted_and_unique() {
+        assert!(PACKETS.windows(2).all(|pair| pair[0].id < pair[1].id));
+    }
+
+    #[test]
+    fn registry_lookup() {
+        assert_eq!(PacketInfo::by_id(0xFE).unwrap().name, "ServerListPing");
+        assert_eq!(PacketInfo::by_id(0xAB), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_packet_to_json() {
+        let packet = Packet::ChatMessage(ChatMessagePayload {
+            message: "hi".to_string(),
+        });
+
+        let json = serde_json::to_string(&packet).unwrap();
+        let deserialized: Packet = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(json, r#"{"ChatMessage":{"message":"hi"}}"#);
+        assert_eq!(deserialized, packet);
+    }
+
+    #[test]
+    fn decode_keep_alive_packet() {
+        let data: &[u8] = &[0x00, 0x00, 0x00, 0x00, 0x11];
+
+        let packet = Packet::from_bytes(data).unwrap();
+
+        assert_eq!(
+            packet,
+            Packet::KeepAlive(KeepAlivePayload { keep_alive_id: 17 })
+        );
+    }
+
+    #[test]
+    fn encode_keep_alive_packet() {
+        let packet = Packet::KeepAlive(KeepAlivePayload { keep_alive_id: 17 });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(data, &[0x00, 0x00, 0x00, 0x00, 0x11]);
+    }
+
+    #[test]
+    fn decode_login_request_packet() {
+        let data: &[u8] = &[
+            0x01, 0x00, 0x00, 0x00, 0x1D, 0x00, 0x01, 0x00, 0x65, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+
+        let packet = Packet::from_bytes(data).unwrap();
+
+        assert_eq!(
+            packet,
+            Packet::LoginRequest(LoginRequestPayload {
+                id: 29,
+                username: "e".to_string(),
+                level_type: "".to_string(),
+                server_mode: 0,
+                dimension: 0,
+                difficulty: 0,
+                unused_0: 0,
+                max_players: 0
+            })
+        )
+    }
+
+    #[test]
+    fn encode_login_request_packet() {
+        let packet = Packet::LoginRequest(LoginRequestPayload {
+            id: 1234,
+            username: "".to_string(),
+            level_type: "FLAT".to_string(),
+            server_mode: 1,
+            dimension: 0,
+            difficulty: 0,
+            unused_0: 0,
+            max_players: 5,
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(
+            data,
+            &[
+                0x01, 0x00, 0x00, 0x04, 0xD2, 0x00, 0x00, 0x00, 0x04, 0x00, 0x46, 0x00, 0x4C, 0x00,
+                0x41, 0x00, 0x54, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05,
+            ]
+        )
+    }
+
+    #[test]
+    fn decode_handshake_packet() {
+        let data: &[u8] = &[0x02, 0x00, 0x03, 0x00, 0x65, 0x00, 0x3B, 0x00, 0x31];
+
+        let packet = Packet::from_bytes(data).unwrap();
+
+        assert_eq!(
+            packet,
+            Packet::Handshake(HandshakePayload {
+                data: "e;1".to_string()
+            })
+        )
+    }
+
+    #[test]
+    fn encode_handshake_packet() {
+        let packet = Packet::Handshake(HandshakePayload {
+            data: "e;1".to_string(),
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(
+            data,
+            &[0x02, 0x00, 0x03, 0x00, 0x65, 0x00, 0x3B, 0x00, 0x31]
+        );
+    }
+
+    #[test]
+    fn decode_chat_message_packet() {
+        let data: &[u8] = &[0x03, 0x00, 0x02, 0x00, b'h', 0x00, b'i'];
+
+        let packet = Packet::from_bytes(data).unwrap();
+
+        assert_eq!(
+            packet,
+            Packet::ChatMessage(ChatMessagePayload {
+                message: "hi".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn encode_chat_message_packet() {
+        let packet = Packet::ChatMessage(ChatMessagePayload {
+            message: "hi".to_string(),
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(data, &[0x03, 0x00, 0x02, 0x00, b'h', 0x00, b'i']);
+    }
+
+    #[test]
+    fn decode_time_update_packet() {
+        let data: &[u8] = &[0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10];
+
+        let packet = Packet::from_bytes(data).unwrap();
+
+        assert_eq!(packet, Packet::TimeUpdate(TimeUpdatePayload { time: 16 }));
+    }
+
+    #[test]
+    fn encode_time_update_packet() {
+        let packet = Packet::TimeUpdate(TimeUpdatePayload { time: 16 });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(
+            data,
+            &[0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10]
+        );
+    }
+
+    #[test]
+    fn decode_entity_equipment_packet() {
+        let data: &[u8] = &[
+            0x05, 0x00, 0x00, 0x00, 0x20, 0x00, 0x04, 0x00, 0x40, 0x00, 0x00,
+        ];
+
+        let packet = Packet::from_bytes(data).unwrap();
+
+        assert_eq!(
+            packet,
+            Packet::EntityEquipment(EntityEquipmentPayload {
+                entity_id: 32,
+                slot: 4,
+                item_id: 64,
+                damage: 0
+            })
+        );
+    }
+
+    #[test]
+    fn encode_entity_equipment_packet() {
+        let packet = Packet::EntityEquipment(EntityEquipmentPayload {
+            entity_id: 32,
+            slot: 4,
+            item_id: 64,
+            damage: 0,
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(
+            data,
+            &[0x05, 0x00, 0x00, 0x00, 0x20, 0x00, 0x04, 0x00, 0x40, 0x00, 0x00]
+        )
+    }
+
+    #[test]
+    fn decode_spawn_position_packet() {
+        let data: &[u8] = &[
+            0x06, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x20, 0x00, 0x00, 0x00, 0x30,
+        ];
+
+        let packet = Packet::from_bytes(data).unwrap();
+
+        assert_eq!(
+            packet,
+            Packet::SpawnPosition(SpawnPositionPayload {
+                x: 16,
+                y: 32,
+                z: 48,
+            })
+        );
+    }
+
+    #[test]
+    fn encode_spawn_position_packet() {
+        let packet = Packet::SpawnPosition(SpawnPositionPayload {
+            x: 16,
+            y: 32,
+            z: 48,
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(
+            data,
+            &[0x06, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x20, 0x00, 0x00, 0x00, 0x30]
+        )
+    }
+
+    #[test]
+    fn decode_player_position_packet() {
+        let packet = Packet::PlayerPosition(PlayerPositionPayload {
+            x: 8.5,
+            y: 65.0,
+            stance: 66.62000000476837,
+            z: 8.5,
+            on_ground: 0,
+        });
+
+        let bytes = packet.to_bytes().unwrap();
+
+        assert_eq!(
+            bytes,
+            &[
+                0x0B, 0x40, 0x21, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x50, 0x40, 0x00, 0x00,
+                0x00, 0x00, 0x00, 0x40, 0x50, 0xA7, 0xAE, 0x14, 0x80, 0x00, 0x00, 0x40, 0x21, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_player_position_and_look_packet() {
+        let data: &[u8] = &[
+            0x0D, 0x40, 0x21, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x50, 0x40, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x40, 0x50, 0xA7, 0xAE, 0x14, 0x80, 0x00, 0x00, 0x40, 0x21, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0xC3, 0x34, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+
+        let packet = Packet::from_bytes(data).unwrap();
+
+        assert_eq!(
+            packet,
+            Packet::PlayerPositionAndLook(PlayerPositionAndLookPayload {
+                x: 8.5,
+                stance_y_0: 65.0,
+                stance_y_1: 66.62000000476837,
+                z: 8.5,
+                yaw: -180.0,
+                pitch: 0.0,
+                on_ground: 0
+            })
+        );
+    }
+
+    #[test]
+    fn encode_player_position_and_look_packet() {
+        let packet = Packet::PlayerPositionAndLook(PlayerPositionAndLookPayload {
+            x: 8.5,
+            stance_y_0: 65.0,
+            stance_y_1: 66.62000000476837,
+            z: 8.5,
+            yaw: -180.0,
+            pitch: 0.0,
+            on_ground: 0,
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(
+            data,
+            &[
+                0x0D, 0x40, 0x21, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x50, 0x40, 0x00, 0x00,
+                0x00, 0x00, 0x00, 0x40, 0x50, 0xA7, 0xAE, 0x14, 0x80, 0x00, 0x00, 0x40, 0x21, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0xC3, 0x34, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            ]
+        );
+    }
+
+    #[test]
+    fn encode_decode_chunk_allocation_packet() {
+        let packet = Packet::ChunkAllocation(ChunkAllocationPayload {
+            x: -9,
+            z: 12,
+            mode: 1,
+        });
+
+        let data = packet.to_bytes().unwrap();
+        let decoded = Packet::from_bytes(&data).unwrap();
+
+        assert_eq!(packet, decoded);
+        assert_eq!(
+            data,
+            &[0x32, 0xFF, 0xFF, 0xFF, 0xF7, 0x00, 0x00, 0x00, 0x0C, 0x01]
+        )
+    }
+
+    #[test]
+    fn decode_server_list_ping_packet() {
+        let data: &[u8] = &[0xFE];
+
+        let packet = Packet::from_bytes(data).unwrap();
+
+        assert_eq!(packet, Packet::ServerListPing(ServerListPingPayload {}));
+    }
+
+    #[test]
+    fn decode_disconnect_kick_packet() {
+        let data: &[u8] = &[0xFF, 0x00, 0x01, 0x00, b'A'];
+
+        let packet = Packet::from_bytes(data).unwrap();
+
+        assert_eq!(
+            packet,
+            Packet::DisconnectKick(DisconnectKickPayload {
+                reason: "A".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn encode_disconnect_kick_packet() {
+        let packet = Packet::DisconnectKick(DisconnectKickPayload {
+            reason: "A".to_string(),
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(data, &[0xFF, 0x00, 0x01, 0x00, b'A'])
+    }
+
+    #[test]
+    fn encode_disconnect_server_status_packet() {
+        let expected_data = &[
+            0xFF, 0x00, 0x08, 0x00, 0x45, 0x00, 0x5A, 0x00, 0x49, 0x00, 0x4F, 0x00, 0xA7, 0x00,
+            0x34, 0x00, 0xA7, 0x00, 0x34,
+        ];
+
+        let packet = Packet::DisconnectKick(DisconnectKickPayload {
+            reason: "EZIO§4§4".to_string(),
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(data, expected_data)
+    }
+
+    #[test]
+    fn decode_player_packet() {
+        let data: &[u8] = &[0x0A, 0x01];
+
+        let packet = Packet::from_bytes(data).unwrap();
+
+        assert_eq!(packet, Packet::Player(PlayerPayload { on_ground: 1 }));
+    }
+
+    #[test]
+    fn decode_player_look_packet() {
+        let data: &[u8] = &[0x0C, 0xC3, 0x34, 0x00, 0x00, 0x41, 0xF0, 0x00, 0x00, 0x00];
+
+        let packet = Packet::from_bytes(data).unwrap();
+
+        assert_eq!(
+            packet,
+            Packet::PlayerLook(PlayerLookPayload {
+                yaw: -180.0,
+                pitch: 30.0,
+                on_ground: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn decode_player_block_placement_packet() {
+        let data: &[u8] = &[
+            0x0F, 0x00, 0x00, 0x00, 0x08, 0x40, 0xFF, 0xFF, 0xFF, 0xF8, 0x01, 0x00, 0x01, 0x40,
+            0x00, 0x00,
+        ];
+
+        let packet = Packet::from_bytes(data).unwrap();
+
+        assert_eq!(
+            packet,
+            Packet::PlayerBlockPlacement(PlayerBlockPlacementPayload {
+                x: 8,
+                y: 64,
+                z: -8,
+                direction: 1,
+                held_item: Slot::new(ItemStack::new(1, 64, 0)),
+            })
+        );
+    }
+
+    #[test]
+    fn decode_player_block_placement_use_item_packet() {
+        let data: &[u8] = &[
+            0x0F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01, 0x04, 0x01,
+            0x00, 0x00,
+        ];
+
+        let Packet::PlayerBlockPlacement(payload) = Packet::from_bytes(data).unwrap() else {
+            panic!("Expected the player block placement packet");
+        };
+
+        assert!(payload.is_use_item());
+        assert_eq!(payload.held_item, Slot::new(ItemStack::new(260, 1, 0)));
+    }
+
+    #[test]
+    fn encode_use_bed_packet() {
+        let packet = Packet::UseBed(UseBedPayload {
+            entity_id: 1234,
+            in_bed: 0,
+            x: -16,
+            y: 64,
+            z: 32,
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(
+            data,
+            &[
+                0x11, 0x00, 0x00, 0x04, 0xD2, 0x00, 0xFF, 0xFF, 0xFF, 0xF0, 0x40, 0x00, 0x00, 0x00,
+                0x20
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_animation_packet() {
+        let data: &[u8] = &[0x12, 0x00, 0x00, 0x04, 0xD2, 0x01];
+
+        let packet = Packet::from_bytes(data).unwrap();
+
+        assert_eq!(
+            packet,
+            Packet::Animation(AnimationPayload {
+                entity_id: 1234,
+                animation: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn decode_entity_action_packet() {
+        let data: &[u8] = &[0x13, 0x00, 0x00, 0x04, 0xD2, 0x04];
+
+        let packet = Packet::from_bytes(data).unwrap();
+
+        assert_eq!(
+            packet,
+            Packet::EntityAction(EntityActionPayload {
+                entity_id: 1234,
+                action: 4,
+            })
+        );
+    }
+
+    #[test]
+    fn encode_named_entity_spawn_packet() {
+        let packet = Packet::NamedEntitySpawn(NamedEntitySpawnPayload {
+            entity_id: 1234,
+            player_name: "Notch".to_string(),
+            x: 272,
+            y: 2080,
+            z: -272,
+            rotation: -128,
+            pitch: 0,
+            current_item: 0,
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(
+            data,
+            &[
+                0x14, 0x00, 0x00, 0x04, 0xD2, 0x00, 0x05, 0x00, 0x4E, 0x00, 0x6F, 0x00, 0x74, 0x00,
+                0x63, 0x00, 0x68, 0x00, 0x00, 0x01, 0x10, 0x00, 0x00, 0x08, 0x20, 0xFF, 0xFF, 0xFE,
+                0xF0, 0x80, 0x00, 0x00, 0x00
+            ]
+        );
+    }
+
+    #[test]
+    fn encode_pickup_spawn_packet() {
+        let packet = Packet::PickupSpawn(PickupSpawnPayload {
+            entity_id: 1234,
+            item: 35,
+            count: 3,
+            damage: 14,
+            x: 272,
+            y: 2080,
+            z: -272,
+            rotation: 0,
+            pitch: 0,
+            roll: 0,
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(
+            data,
+            &[
+                0x15, 0x00, 0x00, 0x04, 0xD2, 0x00, 0x23, 0x03, 0x00, 0x0E, 0x00, 0x00, 0x01, 0x10,
+                0x00, 0x00, 0x08, 0x20, 0xFF, 0xFF, 0xFE, 0xF0, 0x00, 0x00, 0x00
+            ]
+        );
+    }
+
+    #[test]
+    fn encode_collect_item_packet() {
+        let packet = Packet::CollectItem(CollectItemPayload {
+            collected_entity_id: 1235,
+            collector_entity_id: 1234,
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(
+            data,
+            &[0x16, 0x00, 0x00, 0x04, 0xD3, 0x00, 0x00, 0x04, 0xD2]
+        );
+    }
+
+    #[test]
+    fn encode_add_object_packet() {
+        let packet = Packet::AddObject(AddObjectPayload {
+            entity_id: 1234,
+            object_type: 1,
+            x: 272,
+            y: 2080,
+            z: -272,
+            thrower: None,
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(
+            data,
+            &[
+                0x17, 0x00, 0x00, 0x04, 0xD2, 0x01, 0x00, 0x00, 0x01, 0x10, 0x00, 0x00, 0x08, 0x20,
+                0xFF, 0xFF, 0xFE, 0xF0, 0x00, 0x00, 0x00, 0x00
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_add_object_with_thrower_packet() {
+        let data: &[u8] = &[
+            0x17, 0x00, 0x00, 0x04, 0xD3, 0x3C, 0x00, 0x00, 0x01, 0x10, 0x00, 0x00, 0x08, 0x20,
+            0xFF, 0xFF, 0xFE, 0xF0, 0x00, 0x00, 0x04, 0xD2, 0x03, 0xE8, 0x00, 0x00, 0xFC, 0x18,
+        ];
+
+        let (packet, length) = Packet::decode(data).unwrap();
+
+        assert_eq!(
+            packet,
+            Packet::AddObject(AddObjectPayload {
+                entity_id: 1235,
+                object_type: 60,
+                x: 272,
+                y: 2080,
+                z: -272,
+                thrower: Some(ObjectThrower {
+                    entity_id: 1234,
+                    velocity_x: 1000,
+                    velocity_y: 0,
+                    velocity_z: -1000,
+                }),
+            })
+        );
+        assert_eq!(length, data.len());
+    }
+
+    #[test]
+    fn encode_mob_spawn_packet() {
+        let mut metadata = Metadata::new();
+        metadata.set(0, MetadataValue::Byte(0));
+
+        let packet = Packet::MobSpawn(MobSpawnPayload {
+            entity_id: 1234,
+            mob_type: 90,
+            x: 272,
+            y: 2080,
+            z: -272,
+            yaw: 64,
+            pitch: 0,
+            head_yaw: 64,
+            metadata,
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(
+            data,
+            &[
+                0x18, 0x00, 0x00, 0x04, 0xD2, 0x5A, 0x00, 0x00, 0x01, 0x10, 0x00, 0x00, 0x08, 0x20,
+                0xFF, 0xFF, 0xFE, 0xF0, 0x40, 0x00, 0x40, 0x00, 0x00, 0x7F
+            ]
+        );
+    }
+
+    #[test]
+    fn encode_painting_packet() {
+        let packet = Packet::Painting(PaintingPayload {
+            entity_id: 1234,
+            title: "Kebab".to_string(),
+            x: 8,
+            y: 65,
+            z: -8,
+            direction: 2,
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(
+            data,
+            &[
+                0x19, 0x00, 0x00, 0x04, 0xD2, 0x00, 0x05, 0x00, 0x4B, 0x00, 0x65, 0x00, 0x62, 0x00,
+                0x61, 0x00, 0x62, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x41, 0xFF, 0xFF, 0xFF,
+                0xF8, 0x00, 0x00, 0x00, 0x02
+            ]
+        );
+    }
+
+    #[test]
+    fn encode_experience_orb_packet() {
+        let packet = Packet::ExperienceOrb(ExperienceOrbPayload {
+            entity_id: 1234,
+            x: 272,
+            y: 2080,
+            z: -272,
+            count: 7,
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(
+            data,
+            &[
+                0x1A, 0x00, 0x00, 0x04, 0xD2, 0x00, 0x00, 0x01, 0x10, 0x00, 0x00, 0x08, 0x20, 0xFF,
+                0xFF, 0xFE, 0xF0, 0x00, 0x07
+            ]
+        );
+    }
+
+    #[test]
+    fn encode_entity_velocity_packet() {
+        let packet = Packet::EntityVelocity(EntityVelocityPayload {
+            entity_id: 1234,
+            velocity_x: 1000,
+            velocity_y: 3200,
+            velocity_z: -1000,
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(
+            data,
+            &[0x1C, 0x00, 0x00, 0x04, 0xD2, 0x03, 0xE8, 0x0C, 0x80, 0xFC, 0x18]
+        );
+    }
+
+    #[test]
+    fn encode_entity_look_and_relative_move_packet() {
+        let packet = Packet::EntityLookAndRelativeMove(EntityLookAndRelativeMovePayload {
+            entity_id: 1234,
+            dx: 16,
+            dy: 0,
+            dz: -32,
+            yaw: -128,
+            pitch: 32,
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(
+            data,
+            &[0x21, 0x00, 0x00, 0x04, 0xD2, 0x10, 0x00, 0xE0, 0x80, 0x20]
+        );
+    }
+
+    #[test]
+    fn encode_entity_packet() {
+        let packet = Packet::Entity(EntityPayload { entity_id: 1234 });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(data, &[0x1E, 0x00, 0x00, 0x04, 0xD2]);
+    }
+
+    #[test]
+    fn fixed_point_conversions() {
+        assert_eq!(to_fixed_point(8.5), 272);
+        assert_eq!(to_fixed_point(-0.01), -1);
+        assert_eq!(fixed_point_delta(272, 399), Some(127));
+        assert_eq!(fixed_point_delta(272, 400), None);
+        assert_eq!(fixed_point_delta(272, 144), Some(-128));
+        assert_eq!(to_angle_steps(-180.0), -128);
+        assert_eq!(to_angle_steps(90.0), 64);
+    }
+
+    #[test]
+    fn encode_entity_teleport_packet() {
+        let packet = Packet::EntityTeleport(EntityTeleportPayload {
+            entity_id: 1234,
+            x: 272,
+            y: 2080,
+            z: -272,
+            yaw: 64,
+            pitch: 0,
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(
+            data,
+            &[
+                0x22, 0x00, 0x00, 0x04, 0xD2, 0x00, 0x00, 0x01, 0x10, 0x00, 0x00, 0x08, 0x20, 0xFF,
+                0xFF, 0xFE, 0xF0, 0x40, 0x00
+            ]
+        );
+    }
+
+    #[test]
+    fn encode_entity_head_look_packet() {
+        let packet = Packet::EntityHeadLook(EntityHeadLookPayload {
+            entity_id: 1234,
+            head_yaw: -64,
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(data, &[0x23, 0x00, 0x00, 0x04, 0xD2, 0xC0]);
+    }
+
+    #[test]
+    fn encode_entity_status_packet() {
+        let packet = Packet::EntityStatus(EntityStatusPayload {
+            entity_id: 1234,
+            status: 2,
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(data, &[0x26, 0x00, 0x00, 0x04, 0xD2, 0x02]);
+    }
+
+    #[test]
+    fn encode_attach_entity_packet() {
+        let packet = Packet::AttachEntity(AttachEntityPayload {
+            entity_id: 1234,
+            vehicle_id: -1,
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(
+            data,
+            &[0x27, 0x00, 0x00, 0x04, 0xD2, 0xFF, 0xFF, 0xFF, 0xFF]
+        );
+    }
+
+    #[test]
+    fn encode_entity_metadata_packet() {
+        let mut metadata = Metadata::new();
+        // Crouching.
+        metadata.set(0, MetadataValue::Byte(0x02));
+
+        let packet = Packet::EntityMetadata(EntityMetadataPayload {
+            entity_id: 1234,
+            metadata,
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(data, &[0x28, 0x00, 0x00, 0x04, 0xD2, 0x00, 0x02, 0x7F]);
+    }
+
+    #[test]
+    fn encode_entity_effect_packet() {
+        let packet = Packet::EntityEffect(EntityEffectPayload {
+            entity_id: 1234,
+            effect_id: 1,
+            amplifier: 1,
+            duration: 600,
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(
+            data,
+            &[0x29, 0x00, 0x00, 0x04, 0xD2, 0x01, 0x01, 0x02, 0x58]
+        );
+    }
+
+    #[test]
+    fn encode_remove_entity_effect_packet() {
+        let packet = Packet::RemoveEntityEffect(RemoveEntityEffectPayload {
+            entity_id: 1234,
+            effect_id: 19,
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(data, &[0x2A, 0x00, 0x00, 0x04, 0xD2, 0x13]);
+    }
+
+    #[test]
+    fn encode_set_experience_packet() {
+        let packet = Packet::SetExperience(SetExperiencePayload {
+            experience_bar: 0.5,
+            level: 3,
+            total_experience: 30,
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(
+            data,
+            &[0x2B, 0x3F, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x1E]
+        );
+    }
+
+    #[test]
+    fn encode_decode_map_chunk_packet() {
+        let packet = Packet::MapChunk(MapChunkPayload {
+            x: -1,
+            z: 2,
+            chunk: ChunkDataBuilder::new()
+                .set_block(0, 0, 0, 7, 0)
+                .set_biome(0, 0, 1)
+                .build(),
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(
+            &data[..13],
+            &[0x33, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x02, 0x01, 0x00, 0x01, 0x00]
+        );
+        assert_eq!(Packet::decode(&data).unwrap(), (packet, data.len()));
+    }
+
+    #[test]
+    fn decode_incomplete_map_chunk_packet() {
+        let data: &[u8] = &[
+            0x33, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x78, 0x9C,
+        ];
+
+        let result = Packet::from_bytes(data);
+
+        assert!(matches!(
+            result,
+            Err(ProtocolError::UnexpectedEof { needed: 14 })
+        ));
+    }
+
+    #[test]
+    fn decode_map_chunk_packet_larger_than_a_column() {
+        let data: &[u8] = &[
+            0x33, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00,
+            0x7F, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00,
+        ];
+
+        let result = Packet::from_bytes(data);
+
+        assert!(matches!(result, Err(ProtocolError::PayloadTooLarge)));
+    }
+
+    #[test]
+    fn encode_multi_block_change_packet() {
+        let packet = Packet::MultiBlockChange(MultiBlockChangePayload {
+            chunk_x: 1,
+            chunk_z: -1,
+            records: vec![BlockChangeRecord {
+                x: 3,
+                y: 64,
+                z: 15,
+                block_id: 35,
+                metadata: 14,
+            }],
+        });
+
+        let data = packet.to_bytes().unwrap();
+
+        assert_eq!(
+            data,
+            &[
+                0x34, 0x00, 0x00, 0x00, 0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x01, 0x00, 0x00, 0x00,
+                0x04, 0x3F, 0x40, 0x02, 0x3E
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_multi_block_change_too_many_records() {
+        let data: &[u8] = &[
+            0x34, 0x00, 0x00, 0x00, 0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x02, 0x00, 0x00, 0x00,
+            0x04, 0x3F, 0x40, 0x02, 0x3E,
+        ];
 
-        assert_eq!(s, "test");
+        let result = Packet::from_bytes(data);
+
+        assert!(matches!(result, Err(ProtocolError::PayloadTooLarge)));
     }
 
     #[test]
-    fn decode_trailing_zeroes_without_payload() {
-        let data: &[u8] = &[0xFE, 0x00, 0x00, 0x00];
+    fn decode_multi_block_change_with_trailing_data() {
+        let data: &[u8] = &[
+            0x34, 0x00, 0x00, 0x00, 0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x01, 0x7F, 0xFF, 0xFF,
+            0xFF, 0x3F, 0x40, 0x02, 0x3E,
+        ];
 
-        let packet = Packet::from_bytes(data).unwrap();
+        let result = Packet::from_bytes(data);
 
-        assert_eq!(packet, Packet::ServerListPing(ServerListPingPayload {}));
+        assert!(matches!(result, Err(ProtocolError::PayloadTooLarge)));
     }
 
     #[test]
-    fn decode_trailing_zeroes_with_payload() {
-        let data: &[u8] = &[0x00, 0x00, 0x00, 0x00, 0x11, 0x00, 0x00, 0x00, 0x00];
+    fn encode_block_change_packet() {
+        let packet = Packet::BlockChange(BlockChangePayload {
+            x: -8,
+            y: 64,
+            z: 8,
+            block_id: 35,
+            metadata: 14,
+        });
 
-        let packet = Packet::from_bytes(data).unwrap();
+        let data = packet.to_bytes().unwrap();
 
         assert_eq!(
-            packet,
-            Packet::KeepAlive(KeepAlivePayload { keep_alive_id: 17 })
+            data,
+            &[0x35, 0xFF, 0xFF, 0xFF, 0xF8, 0x40, 0x00, 0x00, 0x00, 0x08, 0x23, 0x0E]
         );
     }
 
     #[test]
-    fn decode_keep_alive_packet() {
-        let data: &[u8] = &[0x00, 0x00, 0x00, 0x00, 0x11];
+    fn encode_block_action_packet() {
+        let packet = Packet::BlockAction(BlockActionPayload {
+            x: -8,
+            y: 64,
+            z: 8,
+            data_0: 1,
+            data_1: 1,
+        });
 
-        let packet = Packet::from_bytes(data).unwrap();
+        let data = packet.to_bytes().unwrap();
 
         assert_eq!(
-            packet,
-            Packet::KeepAlive(KeepAlivePayload { keep_alive_id: 17 })
+            data,
+            &[0x36, 0xFF, 0xFF, 0xFF, 0xF8, 0x00, 0x40, 0x00, 0x00, 0x00, 0x08, 0x01, 0x01]
         );
     }
 
     #[test]
-    fn encode_keep_alive_packet() {
-        let packet = Packet::KeepAlive(KeepAlivePayload { keep_alive_id: 17 });
+    fn encode_change_game_state_packet() {
+        let packet = Packet::ChangeGameState(ChangeGameStatePayload {
+            reason: 3,
+            game_mode: 1,
+        });
 
         let data = packet.to_bytes().unwrap();
 
-        assert_eq!(data, &[0x00, 0x00, 0x00, 0x00, 0x11]);
+        assert_eq!(data, &[0x46, 0x03, 0x01]);
     }
 
     #[test]
-    fn decode_login_request_packet() {
-        let data: &[u8] = &[
-            0x01, 0x00, 0x00, 0x00, 0x1D, 0x00, 0x01, 0x00, 0x65, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-        ];
+    fn encode_thunderbolt_packet() {
+        let packet = Packet::Thunderbolt(ThunderboltPayload {
+            entity_id: 1234,
+            unknown: 1,
+            x: 272,
+            y: 2080,
+            z: -272,
+        });
 
-        let packet = Packet::from_bytes(data).unwrap();
+        let data = packet.to_bytes().unwrap();
 
         assert_eq!(
-            packet,
-            Packet::LoginRequest(LoginRequestPayload {
-                id: 29,
-                username: "e".to_string(),
-                level_type: "".to_string(),
-                server_mode: 0,
-                dimension: 0,
-                difficulty: 0,
-                unused_0: 0,
-                max_players: 0
-            })
-        )
+            data,
+            &[
+                0x47, 0x00, 0x00, 0x04, 0xD2, 0x01, 0x00, 0x00, 0x01, 0x10, 0x00, 0x00, 0x08, 0x20,
+                0xFF, 0xFF, 0xFE, 0xF0
+            ]
+        );
     }
 
     #[test]
-    fn encode_login_request_packet() {
-        let packet = Packet::LoginRequest(LoginRequestPayload {
-            id: 1234,
-            username: "".to_string(),
-            level_type: "FLAT".to_string(),
-            server_mode: 1,
-            dimension: 0,
-            difficulty: 0,
-            unused_0: 0,
-            max_players: 5,
+    fn encode_open_window_packet() {
+        let packet = Packet::OpenWindow(OpenWindowPayload {
+            window_id: 1,
+            inventory_type: 0,
+            window_title: "Chest".to_string(),
+            number_of_slots: 27,
         });
 
         let data = packet.to_bytes().unwrap();
@@ -814,291 +4317,317 @@ mod tests {
         assert_eq!(
             data,
             &[
-                0x01, 0x00, 0x00, 0x04, 0xD2, 0x00, 0x00, 0x00, 0x04, 0x00, 0x46, 0x00, 0x4C, 0x00,
-                0x41, 0x00, 0x54, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05,
+                0x64, 0x01, 0x00, 0x00, 0x05, 0x00, 0x43, 0x00, 0x68, 0x00, 0x65, 0x00, 0x73, 0x00,
+                0x74, 0x1B
             ]
-        )
+        );
     }
 
     #[test]
-    fn decode_handshake_packet() {
-        let data: &[u8] = &[0x02, 0x00, 0x03, 0x00, 0x65, 0x00, 0x3B, 0x00, 0x31];
+    fn decode_close_window_packet() {
+        let data: &[u8] = &[0x65, 0x01];
 
         let packet = Packet::from_bytes(data).unwrap();
 
         assert_eq!(
             packet,
-            Packet::Handshake(HandshakePayload {
-                data: "e;1".to_string()
+            Packet::CloseWindow(CloseWindowPayload { window_id: 1 })
+        );
+    }
+
+    #[test]
+    fn decode_window_click_packet() {
+        let data: &[u8] = &[
+            0x66, 0x00, 0x00, 0x24, 0x00, 0x00, 0x05, 0x01, 0x00, 0x01, 0x40, 0x00, 0x00,
+        ];
+
+        let packet = Packet::from_bytes(data).unwrap();
+
+        assert_eq!(
+            packet,
+            Packet::WindowClick(WindowClickPayload {
+                window_id: 0,
+                slot: 36,
+                right_click: 0,
+                action_number: 5,
+                shift: 1,
+                clicked_item: Slot::new(ItemStack::new(1, 64, 0)),
             })
-        )
+        );
     }
 
     #[test]
-    fn encode_handshake_packet() {
-        let packet = Packet::Handshake(HandshakePayload {
-            data: "e;1".to_string(),
+    fn encode_set_slot_packet() {
+        let packet = Packet::SetSlot(SetSlotPayload {
+            window_id: 0,
+            slot: 36,
+            item: Slot::new(ItemStack::new(1, 64, 0)),
         });
 
         let data = packet.to_bytes().unwrap();
 
         assert_eq!(
             data,
-            &[0x02, 0x00, 0x03, 0x00, 0x65, 0x00, 0x3B, 0x00, 0x31]
+            &[0x67, 0x00, 0x00, 0x24, 0x00, 0x01, 0x40, 0x00, 0x00]
         );
     }
 
     #[test]
-    fn decode_chat_message_packet() {
-        let data: &[u8] = &[0x03, 0x00, 0x02, 0x00, b'h', 0x00, b'i'];
+    fn encode_window_items_packet() {
+        let packet = Packet::WindowItems(WindowItemsPayload {
+            window_id: 1,
+            slots: vec![Slot::EMPTY, Slot::new(ItemStack::new(4, 1, 0))],
+        });
 
-        let packet = Packet::from_bytes(data).unwrap();
+        let data = packet.to_bytes().unwrap();
 
         assert_eq!(
-            packet,
-            Packet::ChatMessage(ChatMessagePayload {
-                message: "hi".to_string()
-            })
+            data,
+            &[0x68, 0x01, 0x00, 0x02, 0xFF, 0xFF, 0x00, 0x04, 0x01, 0x00, 0x00]
         );
     }
 
     #[test]
-    fn encode_chat_message_packet() {
-        let packet = Packet::ChatMessage(ChatMessagePayload {
-            message: "hi".to_string(),
+    fn decode_window_items_negative_count() {
+        let data: &[u8] = &[0x68, 0x01, 0xFF, 0xFF];
+
+        let result = Packet::from_bytes(data);
+
+        assert!(matches!(result, Err(ProtocolError::NegativeLength(-1))));
+    }
+
+    #[test]
+    fn encode_update_window_property_packet() {
+        let packet = Packet::UpdateWindowProperty(UpdateWindowPropertyPayload {
+            window_id: 2,
+            property: 0,
+            value: 100,
         });
 
         let data = packet.to_bytes().unwrap();
 
-        assert_eq!(data, &[0x03, 0x00, 0x02, 0x00, b'h', 0x00, b'i']);
+        assert_eq!(data, &[0x69, 0x02, 0x00, 0x00, 0x00, 0x64]);
     }
 
     #[test]
-    fn decode_time_update_packet() {
-        let data: &[u8] = &[0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10];
+    fn encode_transaction_packet() {
+        let packet = Packet::Transaction(TransactionPayload {
+            window_id: 0,
+            action_number: 5,
+            accepted: 0,
+        });
 
-        let packet = Packet::from_bytes(data).unwrap();
+        let data = packet.to_bytes().unwrap();
 
-        assert_eq!(packet, Packet::TimeUpdate(TimeUpdatePayload { time: 16 }));
+        assert_eq!(data, &[0x6A, 0x00, 0x00, 0x05, 0x00]);
     }
 
     #[test]
-    fn encode_time_update_packet() {
-        let packet = Packet::TimeUpdate(TimeUpdatePayload { time: 16 });
+    fn decode_creative_inventory_action_packet() {
+        let data: &[u8] = &[0x6B, 0x00, 0x24, 0x00, 0x2D, 0x01, 0x00, 0x00];
 
-        let data = packet.to_bytes().unwrap();
+        let packet = Packet::from_bytes(data).unwrap();
 
         assert_eq!(
-            data,
-            &[0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10]
+            packet,
+            Packet::CreativeInventoryAction(CreativeInventoryActionPayload {
+                slot: 36,
+                clicked_item: Slot::new(ItemStack::new(45, 1, 0)),
+            })
         );
     }
 
     #[test]
-    fn decode_entity_equipment_packet() {
+    fn decode_update_sign_packet() {
         let data: &[u8] = &[
-            0x05, 0x00, 0x00, 0x00, 0x20, 0x00, 0x04, 0x00, 0x40, 0x00, 0x00,
+            0x82, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x40, 0xFF, 0xFF, 0xFF, 0xFE, 0x00, 0x02, 0x00,
+            0x48, 0x00, 0x69, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x21,
         ];
 
         let packet = Packet::from_bytes(data).unwrap();
 
         assert_eq!(
             packet,
-            Packet::EntityEquipment(EntityEquipmentPayload {
-                entity_id: 32,
-                slot: 4,
-                item_id: 64,
-                damage: 0
+            Packet::UpdateSign(UpdateSignPayload {
+                x: 10,
+                y: 64,
+                z: -2,
+                text1: "Hi".to_string(),
+                text2: String::new(),
+                text3: String::new(),
+                text4: "!".to_string(),
             })
         );
     }
 
     #[test]
-    fn encode_entity_equipment_packet() {
-        let packet = Packet::EntityEquipment(EntityEquipmentPayload {
-            entity_id: 32,
-            slot: 4,
-            item_id: 64,
-            damage: 0,
-        });
+    fn decode_update_sign_line_too_long() {
+        let mut data = vec![0x82, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x00, 0x10];
+        data.extend(std::iter::repeat_n([0x00, b'a'], 16).flatten());
+        data.extend([0; 6]);
 
-        let data = packet.to_bytes().unwrap();
+        let result = Packet::from_bytes(&data);
 
-        assert_eq!(
-            data,
-            &[0x05, 0x00, 0x00, 0x00, 0x20, 0x00, 0x04, 0x00, 0x40, 0x00, 0x00]
-        )
+        assert!(matches!(result, Err(ProtocolError::PayloadTooLarge)));
     }
 
     #[test]
-    fn decode_spawn_position_packet() {
-        let data: &[u8] = &[
-            0x06, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x20, 0x00, 0x00, 0x00, 0x30,
-        ];
+    fn encode_item_data_packet() {
+        let packet = Packet::ItemData(ItemDataPayload {
+            item_type: 358,
+            item_id: 0,
+            data: vec![0x00, 0x05, 0x10, 0x22],
+        });
 
-        let packet = Packet::from_bytes(data).unwrap();
+        let data = packet.to_bytes().unwrap();
 
         assert_eq!(
-            packet,
-            Packet::SpawnPosition(SpawnPositionPayload {
-                x: 16,
-                y: 32,
-                z: 48,
-            })
+            data,
+            &[0x83, 0x01, 0x66, 0x00, 0x00, 0x04, 0x00, 0x05, 0x10, 0x22]
         );
     }
 
     #[test]
-    fn encode_spawn_position_packet() {
-        let packet = Packet::SpawnPosition(SpawnPositionPayload {
-            x: 16,
-            y: 32,
-            z: 48,
+    fn encode_item_data_too_large() {
+        let packet = Packet::ItemData(ItemDataPayload {
+            item_type: 358,
+            item_id: 0,
+            data: vec![0; 256],
         });
 
-        let data = packet.to_bytes().unwrap();
+        let result = packet.to_bytes();
 
-        assert_eq!(
-            data,
-            &[0x06, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x20, 0x00, 0x00, 0x00, 0x30]
-        )
+        assert!(matches!(result, Err(ProtocolError::PayloadTooLarge)));
     }
 
     #[test]
-    fn decode_player_position_packet() {
-        let packet = Packet::PlayerPosition(PlayerPositionPayload {
-            x: 8.5,
-            y: 65.0,
-            stance: 66.62000000476837,
-            z: 8.5,
-            on_ground: 0,
-        });
+    fn decode_holding_change_packet() {
+        let data: &[u8] = &[0x10, 0x00, 0x04];
 
-        let bytes = packet.to_bytes().unwrap();
+        let packet = Packet::from_bytes(data).unwrap();
 
         assert_eq!(
-            bytes,
-            &[
-                0x0B, 0x40, 0x21, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x50, 0x40, 0x00, 0x00,
-                0x00, 0x00, 0x00, 0x40, 0x50, 0xA7, 0xAE, 0x14, 0x80, 0x00, 0x00, 0x40, 0x21, 0x00,
-                0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            ]
+            packet,
+            Packet::HoldingChange(HoldingChangePayload { slot_id: 4 })
         );
     }
 
     #[test]
-    fn decode_player_position_and_look_packet() {
+    fn decode_player_digging_packet() {
         let data: &[u8] = &[
-            0x0D, 0x40, 0x21, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x50, 0x40, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x40, 0x50, 0xA7, 0xAE, 0x14, 0x80, 0x00, 0x00, 0x40, 0x21, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0xC3, 0x34, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x0E, 0x02, 0xFF, 0xFF, 0xFF, 0xF8, 0x40, 0x00, 0x00, 0x00, 0x08, 0x01,
         ];
 
         let packet = Packet::from_bytes(data).unwrap();
 
         assert_eq!(
             packet,
-            Packet::PlayerPositionAndLook(PlayerPositionAndLookPayload {
-                x: 8.5,
-                stance_y_0: 65.0,
-                stance_y_1: 66.62000000476837,
-                z: 8.5,
-                yaw: -180.0,
-                pitch: 0.0,
-                on_ground: 0
+            Packet::PlayerDigging(PlayerDiggingPayload {
+                status: 2,
+                x: -8,
+                y: 64,
+                z: 8,
+                face: 1,
             })
         );
     }
 
     #[test]
-    fn encode_player_position_and_look_packet() {
-        let packet = Packet::PlayerPositionAndLook(PlayerPositionAndLookPayload {
-            x: 8.5,
-            stance_y_0: 65.0,
-            stance_y_1: 66.62000000476837,
-            z: 8.5,
-            yaw: -180.0,
-            pitch: 0.0,
-            on_ground: 0,
+    fn encode_sound_particle_effect_packet() {
+        let packet = Packet::SoundParticleEffect(SoundParticleEffectPayload {
+            effect_id: 2001,
+            x: -8,
+            y: 64,
+            z: 8,
+            data: 1,
         });
-
         let data = packet.to_bytes().unwrap();
 
         assert_eq!(
             data,
             &[
-                0x0D, 0x40, 0x21, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x50, 0x40, 0x00, 0x00,
-                0x00, 0x00, 0x00, 0x40, 0x50, 0xA7, 0xAE, 0x14, 0x80, 0x00, 0x00, 0x40, 0x21, 0x00,
-                0x00, 0x00, 0x00, 0x00, 0x00, 0xC3, 0x34, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x3D, 0x00, 0x00, 0x07, 0xD1, 0xFF, 0xFF, 0xFF, 0xF8, 0x40, 0x00, 0x00, 0x00, 0x08,
+                0x00, 0x00, 0x00, 0x01
             ]
         );
     }
 
     #[test]
-    fn encode_decode_chunk_allocation_packet() {
-        let packet = Packet::ChunkAllocation(ChunkAllocationPayload {
-            x: -9,
-            z: 12,
-            mode: 1,
-        });
+    fn encode_destroy_entity_packet() {
+        let packet = Packet::DestroyEntity(DestroyEntityPayload { entity_id: 1234 });
 
         let data = packet.to_bytes().unwrap();
-        let decoded = Packet::from_bytes(&data).unwrap();
 
-        assert_eq!(packet, decoded);
-        assert_eq!(
-            data,
-            &[0x32, 0xFF, 0xFF, 0xFF, 0xF7, 0x00, 0x00, 0x00, 0x0C, 0x01]
-        )
+        assert_eq!(data, &[0x1D, 0x00, 0x00, 0x04, 0xD2]);
     }
 
     #[test]
-    fn decode_server_list_ping_packet() {
-        let data: &[u8] = &[0xFE];
+    fn encode_update_health_packet() {
+        let packet = Packet::UpdateHealth(UpdateHealthPayload {
+            health: 20,
+            food: 18,
+            food_saturation: 5.0,
+        });
 
-        let packet = Packet::from_bytes(data).unwrap();
+        let data = packet.to_bytes().unwrap();
 
-        assert_eq!(packet, Packet::ServerListPing(ServerListPingPayload {}));
+        assert_eq!(
+            data,
+            &[0x08, 0x00, 0x14, 0x00, 0x12, 0x40, 0xA0, 0x00, 0x00]
+        );
     }
 
     #[test]
-    fn decode_disconnect_kick_packet() {
-        let data: &[u8] = &[0xFF, 0x00, 0x01, 0x00, b'A'];
+    fn decode_use_entity_packet() {
+        let data: &[u8] = &[0x07, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x04, 0xD2, 0x01];
 
         let packet = Packet::from_bytes(data).unwrap();
 
         assert_eq!(
             packet,
-            Packet::DisconnectKick(DisconnectKickPayload {
-                reason: "A".to_string(),
+            Packet::UseEntity(UseEntityPayload {
+                user: 1,
+                target: 1234,
+                left_click: 1,
             })
         );
     }
 
     #[test]
-    fn encode_disconnect_kick_packet() {
-        let packet = Packet::DisconnectKick(DisconnectKickPayload {
-            reason: "A".to_string(),
+    fn encode_respawn_packet() {
+        let packet = Packet::Respawn(RespawnPayload {
+            dimension: 0,
+            difficulty: 1,
+            creative_mode: 0,
+            world_height: 256,
+            level_type: "FLAT".to_string(),
         });
 
         let data = packet.to_bytes().unwrap();
 
-        assert_eq!(data, &[0xFF, 0x00, 0x01, 0x00, b'A'])
+        assert_eq!(
+            data,
+            &[
+                0x09, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x04, 0x00, 0x46, 0x00,
+                0x4C, 0x00, 0x41, 0x00, 0x54
+            ]
+        );
     }
 
-    #[test]
-    fn encode_disconnect_server_status_packet() {
-        let expected_data = &[
-            0xFF, 0x00, 0x08, 0x00, 0x45, 0x00, 0x5A, 0x00, 0x49, 0x00, 0x4F, 0x00, 0xA7, 0x00,
-            0x34, 0x00, 0xA7, 0x00, 0x34,
-        ];
-
-        let packet = Packet::DisconnectKick(DisconnectKickPayload {
-            reason: "EZIO§4§4".to_string(),
-        });
+    proptest::proptest! {
+        #[test]
+        fn roundtrip_packets(packet: Packet) {
+            crate::arbitrary::assert_packet_roundtrip::<crate::v29::V29>(&packet)?;
+        }
 
-        let data = packet.to_bytes().unwrap();
+        #[test]
+        fn roundtrip_login_request_payload(payload: LoginRequestPayload) {
+            crate::arbitrary::assert_roundtrip(&payload)?;
+        }
 
-        assert_eq!(data, expected_data)
+        #[test]
+        fn roundtrip_chat_message_payload(payload: ChatMessagePayload) {
+            crate::arbitrary::assert_roundtrip(&payload)?;
+        }
     }
 }
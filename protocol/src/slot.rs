@@ -0,0 +1,287 @@
+use std::io::Cursor;
+
+use bytes::{Buf, BufMut, BytesMut};
+
+use crate::{
+    error::ProtocolError,
+    nbt::{Compression, Nbt},
+    packet::{FromBytes, ToBytes},
+};
+
+/// Item identifier marking an empty slot.
+const EMPTY_SLOT_ID: i16 = -1;
+/// NBT length marking an item stack without the NBT data.
+const NO_NBT_LENGTH: i16 = -1;
+
+/// Item stack held by a slot.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ItemStack {
+    /// Item or block identifier.
+    pub id: i16,
+
+    /// Number of items in the stack.
+    pub count: i8,
+
+    /// Damage of a tool or armor, variant of other items, e.g. wool color.
+    pub damage: i16,
+
+    /// Extra item data such as enchantments, only sent for damageable items.
+    pub nbt: Option<Nbt>,
+}
+
+impl ItemStack {
+    /// Creates an item stack without the NBT data.
+    pub fn new(id: i16, count: i8, damage: i16) -> Self {
+        Self {
+            id,
+            count,
+            damage,
+            nbt: None,
+        }
+    }
+
+    /// Whether the item can take damage and carries NBT data on the wire.
+    ///
+    /// These are the tools, weapons and armor pieces, the only items
+    /// that can be enchanted.
+    pub fn is_damageable(&self) -> bool {
+        is_damageable(self.id)
+    }
+}
+
+/// Whether the item identifier belongs to an item that can take damage.
+fn is_damageable(id: i16) -> bool {
+    matches!(
+        id,
+        // Iron tools and flint and steel.
+        256..=259
+        // Bow.
+        | 261
+        // Iron sword, wooden, stone and diamond tools.
+        | 267..=279
+        // Golden tools.
+        | 283..=286
+        // Hoes.
+        | 290..=294
+        // Armor.
+        | 298..=317
+        // Fishing rod.
+        | 346
+        // Shears.
+        | 359
+    )
+}
+
+/// An inventory slot, empty or holding an item stack.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Slot(pub Option<ItemStack>);
+
+impl Slot {
+    /// Slot without any item.
+    pub const EMPTY: Slot = Slot(None);
+
+    /// Creates a slot holding the item stack.
+    pub fn new(item: ItemStack) -> Self {
+        Self(Some(item))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+
+    pub fn item(&self) -> Option<&ItemStack> {
+        self.0.as_ref()
+    }
+}
+
+impl From<Option<ItemStack>> for Slot {
+    fn from(item: Option<ItemStack>) -> Self {
+        Self(item)
+    }
+}
+
+impl FromBytes for Slot {
+    fn from_bytes(bytes: &mut Cursor<&[u8]>) -> Result<Self, ProtocolError> {
+        let id = bytes.try_get_i16()?;
+
+        if id == EMPTY_SLOT_ID {
+            return Ok(Self::EMPTY);
+        }
+
+        let count = bytes.try_get_i8()?;
+        let damage = bytes.try_get_i16()?;
+
+        let nbt = if is_damageable(id) {
+            read_nbt(bytes)?
+        } else {
+            None
+        };
+
+        Ok(Self::new(ItemStack {
+            id,
+            count,
+            damage,
+            nbt,
+        }))
+    }
+}
+
+impl ToBytes for Slot {
+    fn to_bytes(&self, buffer: &mut BytesMut) -> Result<(), ProtocolError> {
+        let Some(item) = &self.0 else {
+            buffer.put_i16(EMPTY_SLOT_ID);
+            return Ok(());
+        };
+
+        buffer.put_i16(item.id);
+        buffer.put_i8(item.count);
+        buffer.put_i16(item.damage);
+
+        if item.is_damageable() {
+            put_nbt(buffer, item.nbt.as_ref())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the gzipped item NBT data prefixed with its `i16` length in bytes.
+fn read_nbt(bytes: &mut Cursor<&[u8]>) -> Result<Option<Nbt>, ProtocolError> {
+    let length = bytes.try_get_i16()?;
+
+    if length == NO_NBT_LENGTH {
+        return Ok(None);
+    }
+
+    let length =
+        usize::try_from(length).map_err(|_| ProtocolError::NegativeLength(length as i32))?;
+
+    if bytes.remaining() < length {
+        return Err(ProtocolError::UnexpectedEof {
+            needed: length - bytes.remaining(),
+        });
+    }
+
+    let mut data = vec![0u8; length];
+    bytes.copy_to_slice(&mut data);
+
+    Ok(Some(Nbt::read(&data, Compression::Gzip)?))
+}
+
+/// Puts the gzipped item NBT data prefixed with its `i16` length in bytes.
+fn put_nbt(buffer: &mut BytesMut, nbt: Option<&Nbt>) -> Result<(), ProtocolError> {
+    let Some(nbt) = nbt else {
+        buffer.put_i16(NO_NBT_LENGTH);
+        return Ok(());
+    };
+
+    let data = nbt.write(Compression::Gzip)?;
+    let length = i16::try_from(data.len()).map_err(|_| ProtocolError::PayloadTooLarge)?;
+
+    buffer.put_i16(length);
+    buffer.put_slice(&data);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt::{Compound, Tag};
+
+    #[test]
+    fn encode_empty_slot() {
+        let mut buffer = BytesMut::new();
+
+        Slot::EMPTY.to_bytes(&mut buffer).unwrap();
+
+        assert_eq!(buffer.as_ref(), &[0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn decode_empty_slot() {
+        let mut cursor = Cursor::new(&[0xFFu8, 0xFF] as &[u8]);
+
+        let slot = Slot::from_bytes(&mut cursor).unwrap();
+
+        assert_eq!(slot, Slot::EMPTY);
+        assert_eq!(cursor.position(), 2);
+    }
+
+    #[test]
+    fn encode_block_slot() {
+        let mut buffer = BytesMut::new();
+
+        Slot::new(ItemStack::new(35, 64, 14))
+            .to_bytes(&mut buffer)
+            .unwrap();
+
+        assert_eq!(buffer.as_ref(), &[0x00, 0x23, 0x40, 0x00, 0x0E]);
+    }
+
+    #[test]
+    fn decode_block_slot() {
+        let mut cursor = Cursor::new(&[0x00u8, 0x23, 0x40, 0x00, 0x0E] as &[u8]);
+
+        let slot = Slot::from_bytes(&mut cursor).unwrap();
+
+        assert_eq!(slot, Slot::new(ItemStack::new(35, 64, 14)));
+    }
+
+    #[test]
+    fn encode_damageable_slot_without_nbt() {
+        let mut buffer = BytesMut::new();
+
+        Slot::new(ItemStack::new(276, 1, 5))
+            .to_bytes(&mut buffer)
+            .unwrap();
+
+        assert_eq!(buffer.as_ref(), &[0x01, 0x14, 0x01, 0x00, 0x05, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn encode_decode_enchanted_slot() {
+        let mut enchantment = Compound::new();
+        enchantment.insert("id".to_string(), Tag::Short(16));
+        enchantment.insert("lvl".to_string(), Tag::Short(5));
+
+        let mut root = Compound::new();
+        root.insert(
+            "ench".to_string(),
+            Tag::List(vec![Tag::Compound(enchantment)]),
+        );
+
+        let slot = Slot::new(ItemStack {
+            id: 276,
+            count: 1,
+            damage: 0,
+            nbt: Some(Nbt::new("tag", root)),
+        });
+
+        let mut buffer = BytesMut::new();
+        slot.to_bytes(&mut buffer).unwrap();
+        let decoded = Slot::from_bytes(&mut Cursor::new(buffer.as_ref())).unwrap();
+
+        assert_eq!(decoded, slot);
+    }
+
+    #[test]
+    fn decode_truncated_nbt() {
+        let mut cursor = Cursor::new(&[0x01u8, 0x14, 0x01, 0x00, 0x00, 0x00, 0x04, 0x1F] as &[u8]);
+
+        let result = Slot::from_bytes(&mut cursor);
+
+        assert!(matches!(
+            result,
+            Err(ProtocolError::UnexpectedEof { needed: 3 })
+        ));
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn roundtrip_slot(slot in crate::arbitrary::slot()) {
+            crate::arbitrary::assert_roundtrip(&slot)?;
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use crate::packet::{DisconnectKickPayload, Packet};
+
+/// Character separating the fields of the status response.
+const SEPARATOR: char = '§';
+/// Maximum length of the kick reason carrying the status response.
+const MAX_REASON_LENGTH: usize = 100;
+
+/// Server status shown in the multiplayer server list.
+///
+/// The client pings the server with `Packet::ServerListPing`, and the server
+/// replies by kicking it with the fields joined by `§` as the reason.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusResponse {
+    /// Message of the day.
+    pub motd: String,
+
+    /// Number of the players currently online.
+    pub online: usize,
+
+    /// Maximum number of the players.
+    pub max: usize,
+}
+
+impl StatusResponse {
+    pub fn new(motd: impl Into<String>, online: usize, max: usize) -> Self {
+        Self {
+            motd: motd.into(),
+            online,
+            max,
+        }
+    }
+
+    /// Builds the `motd§online§max` kick reason.
+    ///
+    /// The separator is stripped from the MOTD so it can't shift the other fields,
+    /// and the MOTD is shortened when the reason wouldn't fit the packet.
+    pub fn to_reason(&self) -> String {
+        let counts = format!("{}{}{}{}", SEPARATOR, self.online, SEPARATOR, self.max);
+        let available = MAX_REASON_LENGTH.saturating_sub(counts.encode_utf16().count());
+
+        let mut reason = String::new();
+        let mut length = 0;
+
+        for c in self.motd.chars().filter(|&c| c != SEPARATOR) {
+            length += c.len_utf16();
+
+            if length > available {
+                break;
+            }
+
+            reason.push(c);
+        }
+
+        reason.push_str(&counts);
+        reason
+    }
+
+    /// Builds the packet sent in response to the server list ping.
+    pub fn to_packet(&self) -> Packet {
+        Packet::DisconnectKick(DisconnectKickPayload {
+            reason: self.to_reason(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_reason() {
+        let status = StatusResponse::new("A Minecraft Server", 3, 20);
+
+        assert_eq!(status.to_reason(), "A Minecraft Server§3§20");
+    }
+
+    #[test]
+    fn status_reason_strips_separator() {
+        let status = StatusResponse::new("§4Red §§server", 0, 20);
+
+        assert_eq!(status.to_reason(), "4Red server§0§20");
+    }
+
+    #[test]
+    fn status_reason_truncates_motd() {
+        let status = StatusResponse::new("a".repeat(150), 10, 100);
+
+        let reason = status.to_reason();
+
+        assert_eq!(reason.encode_utf16().count(), MAX_REASON_LENGTH);
+        assert!(reason.ends_with("§10§100"));
+    }
+
+    #[test]
+    fn status_packet_encodes() {
+        let status = StatusResponse::new("😀".repeat(60), 1, 2);
+
+        let packet = status.to_packet();
+
+        assert!(packet.to_bytes().is_ok());
+    }
+}
@@ -0,0 +1,103 @@
+use std::future::Future;
+
+use bytes::BytesMut;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::{error::ProtocolError, packet::Packet};
+
+/// Reads packets from an asynchronous byte stream.
+pub trait PacketRead {
+    /// Reads a single packet from the stream.
+    ///
+    /// Reads only as many bytes as the packet occupies, so the stream is left
+    /// positioned at the beginning of the next packet.
+    fn read_packet(&mut self) -> impl Future<Output = Result<Packet, ProtocolError>> + Send;
+}
+
+/// Writes packets to an asynchronous byte stream.
+pub trait PacketWrite {
+    /// Writes a single packet to the stream.
+    fn write_packet(
+        &mut self,
+        packet: &Packet,
+    ) -> impl Future<Output = Result<(), ProtocolError>> + Send;
+}
+
+impl<R: AsyncRead + Unpin + Send> PacketRead for R {
+    async fn read_packet(&mut self) -> Result<Packet, ProtocolError> {
+        let mut data = vec![self.read_u8().await?];
+
+        loop {
+            match Packet::decode(&data) {
+                Ok((packet, _)) => return Ok(packet),
+                Err(ProtocolError::UnexpectedEof { needed }) => {
+                    let length = data.len();
+                    data.resize(length + needed, 0);
+                    self.read_exact(&mut data[length..]).await?;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl<W: AsyncWrite + Unpin + Send> PacketWrite for W {
+    async fn write_packet(&mut self, packet: &Packet) -> Result<(), ProtocolError> {
+        let mut buffer = BytesMut::new();
+        packet.encode(&mut buffer)?;
+
+        self.write_all(&buffer).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packet::{ChatMessagePayload, KeepAlivePayload, ServerListPingPayload};
+
+    #[tokio::test]
+    async fn write_and_read_packets() {
+        let (mut client, mut server) = tokio::io::duplex(64);
+
+        let packets = [
+            Packet::KeepAlive(KeepAlivePayload { keep_alive_id: 17 }),
+            Packet::ChatMessage(ChatMessagePayload {
+                message: "hello".to_string(),
+            }),
+            Packet::ServerListPing(ServerListPingPayload),
+        ];
+
+        for packet in &packets {
+            client.write_packet(packet).await.unwrap();
+        }
+
+        for packet in packets {
+            assert_eq!(server.read_packet().await.unwrap(), packet);
+        }
+    }
+
+    #[tokio::test]
+    async fn read_leaves_following_bytes() {
+        let data: &[u8] = &[0x00, 0x00, 0x00, 0x00, 0x11, 0xAB];
+        let mut reader = data;
+
+        let packet = reader.read_packet().await.unwrap();
+
+        assert_eq!(
+            packet,
+            Packet::KeepAlive(KeepAlivePayload { keep_alive_id: 17 })
+        );
+        assert_eq!(reader, &[0xAB]);
+    }
+
+    #[tokio::test]
+    async fn read_closed_stream() {
+        let data: &[u8] = &[0x00, 0x00];
+        let mut reader = data;
+
+        let result = reader.read_packet().await;
+
+        assert!(matches!(result, Err(ProtocolError::Io(_))));
+    }
+}
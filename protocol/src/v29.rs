@@ -0,0 +1,30 @@
+use bytes::BytesMut;
+
+pub use crate::packet::*;
+use crate::{
+    error::ProtocolError,
+    version::{self, ProtocolVersion},
+};
+
+/// Protocol version 29, used by the 1.2.5 clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct V29;
+
+impl ProtocolVersion for V29 {
+    const VERSION: i32 = version::PROTOCOL_VERSION;
+    const GAME_VERSION: &'static str = version::GAME_VERSION;
+
+    type Packet = Packet;
+
+    fn decode(bytes: &[u8]) -> Result<(Self::Packet, usize), ProtocolError> {
+        Packet::decode(bytes)
+    }
+
+    fn encode(packet: &Self::Packet, buffer: &mut BytesMut) -> Result<(), ProtocolError> {
+        packet.encode(buffer)
+    }
+
+    fn direction(id: u8) -> Option<Direction> {
+        PacketInfo::by_id(id).map(|info| info.direction)
+    }
+}
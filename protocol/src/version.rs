@@ -0,0 +1,113 @@
+use bytes::BytesMut;
+
+use crate::{
+    error::ProtocolError,
+    packet::{Direction, LoginRequestPayload},
+};
+
+/// Protocol version implemented by this crate.
+pub const PROTOCOL_VERSION: i32 = 29;
+/// Game version matching the protocol version.
+pub const GAME_VERSION: &str = "1.2.5";
+
+/// Packet layouts of a single protocol version.
+///
+/// Implemented by marker types, such as `v29::V29`, so the codec and the server
+/// can be written once for all the supported versions.
+pub trait ProtocolVersion {
+    /// Protocol version sent by the client in the login request.
+    const VERSION: i32;
+
+    /// Game version using the protocol version.
+    const GAME_VERSION: &'static str;
+
+    /// Packets of the protocol version.
+    type Packet: std::fmt::Debug;
+
+    /// Parses a packet from the beginning of the bytes.
+    ///
+    /// Returns the packet together with the number of bytes it occupied.
+    fn decode(bytes: &[u8]) -> Result<(Self::Packet, usize), ProtocolError>;
+
+    /// Writes the packet to the end of the buffer.
+    fn encode(packet: &Self::Packet, buffer: &mut BytesMut) -> Result<(), ProtocolError>;
+
+    /// Direction the packet with the identifier travels in, `None` for the unknown ones.
+    fn direction(id: u8) -> Option<Direction>;
+}
+
+/// Compatibility of the client's protocol version with the server's one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionCompatibility {
+    Compatible,
+    ClientTooOld,
+    ClientTooNew,
+}
+
+impl VersionCompatibility {
+    /// Classifies the protocol version sent by the client.
+    pub fn of_version(version: i32) -> Self {
+        match version.cmp(&PROTOCOL_VERSION) {
+            std::cmp::Ordering::Less => VersionCompatibility::ClientTooOld,
+            std::cmp::Ordering::Equal => VersionCompatibility::Compatible,
+            std::cmp::Ordering::Greater => VersionCompatibility::ClientTooNew,
+        }
+    }
+
+    /// Classifies the client sending the login request.
+    ///
+    /// In the client to server direction, the `id` is the protocol version.
+    pub fn of_login(login: &LoginRequestPayload) -> Self {
+        Self::of_version(login.id)
+    }
+
+    /// Returns the kick reason the vanilla server sends to incompatible clients.
+    pub fn kick_reason(&self) -> Option<&'static str> {
+        match self {
+            VersionCompatibility::Compatible => None,
+            VersionCompatibility::ClientTooOld => Some("Outdated client!"),
+            VersionCompatibility::ClientTooNew => Some("Outdated server!"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compatibility_of_version() {
+        assert_eq!(
+            VersionCompatibility::of_version(28),
+            VersionCompatibility::ClientTooOld
+        );
+        assert_eq!(
+            VersionCompatibility::of_version(29),
+            VersionCompatibility::Compatible
+        );
+        assert_eq!(
+            VersionCompatibility::of_version(39),
+            VersionCompatibility::ClientTooNew
+        );
+    }
+
+    #[test]
+    fn compatibility_of_login() {
+        let login = LoginRequestPayload {
+            id: PROTOCOL_VERSION,
+            username: "ezioleq".to_string(),
+            level_type: "".to_string(),
+            server_mode: 0,
+            dimension: 0,
+            difficulty: 0,
+            unused_0: 0,
+            max_players: 0,
+        };
+
+        assert_eq!(
+            VersionCompatibility::of_login(&login),
+            VersionCompatibility::Compatible
+        );
+        assert_eq!(VersionCompatibility::Compatible.kick_reason(), None);
+    }
+}
@@ -0,0 +1,388 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+};
+
+use crate::{
+    entity::Position,
+    game_mode::SURVIVAL,
+    mob::Mob,
+    player::{Player, EYE_HEIGHT},
+    world::{block::Block, BlockPos, World},
+};
+
+/// Farthest the mobs find the paths to, in blocks along each axis.
+const MAX_PATH_DISTANCE: i32 = 16;
+
+/// Most blocks searched for a single path.
+const MAX_PATH_NODES: usize = 256;
+
+/// Farthest the mobs walk down from a ledge.
+const MAX_DROP: i32 = 3;
+
+/// Distance the hostile mobs notice the players within.
+const FOLLOW_RANGE: f64 = 16.0;
+
+/// Distance the mobs wander off to, horizontally.
+const WANDER_DISTANCE: i32 = 10;
+
+/// Distance the panicking mobs run off to, horizontally.
+const FLEE_DISTANCE: i32 = 5;
+
+/// Chance of the idle mob starting to wander each tick, 1 in 120.
+const WANDER_CHANCE: f64 = 1.0 / 120.0;
+
+/// Number of ticks between the paths to the chased player being found again.
+const REPATH_INTERVAL: u64 = 10;
+
+/// How far around the mob's bounding box it reaches the players, horizontally.
+const ATTACK_REACH: f64 = 0.2;
+
+/// Number of ticks between the attacks of a mob.
+const ATTACK_COOLDOWN: u32 = 20;
+
+/// Speed the mobs walk with, in blocks per tick.
+const WALK_SPEED: f64 = 0.1;
+
+/// Speed the panicking mobs run with, in blocks per tick.
+const FLEE_SPEED: f64 = 0.2;
+
+/// Speed the hostile mobs chase the players with, in blocks per tick.
+const CHASE_SPEED: f64 = 0.13;
+
+/// Speed the mobs jump up a block with.
+const JUMP_SPEED: f64 = 0.42;
+
+/// Horizontal distance from the waypoint the mob has reached it within.
+const WAYPOINT_REACH: f64 = 0.3;
+
+/// Step of the line of sight checks, in blocks.
+const SIGHT_STEP: f64 = 0.25;
+
+/// An attack of the mob on the player it touches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Attack {
+    pub target: i32,
+    pub damage: i16,
+}
+
+/// Advances the behavior of the mob by a single tick, the hostile mobs chase
+/// and attack the players, the panicking ones run away and the others wander around.
+///
+/// Returns the attack on the player the mob has reached.
+pub fn tick(mob: &mut Mob, world: &World, players: &[Player]) -> Option<Attack> {
+    if mob.is_dead() {
+        mob.path.clear();
+        return None;
+    }
+
+    mob.attack_cooldown = mob.attack_cooldown.saturating_sub(1);
+    let from = block_of(&mob.base.position);
+
+    if mob.kind.is_hostile() {
+        let target = find_target(mob, world, players);
+        mob.target = target.map(|player| player.base.entity_id);
+
+        if let Some(player) = target {
+            if mob.base.touches(&player.base, ATTACK_REACH) && mob.attack_cooldown == 0 {
+                mob.attack_cooldown = ATTACK_COOLDOWN;
+                return Some(Attack {
+                    target: player.base.entity_id,
+                    damage: mob.kind.attack_damage(),
+                });
+            }
+
+            if mob.path.is_empty() || mob.base.age.is_multiple_of(REPATH_INTERVAL) {
+                mob.path =
+                    find_path(world, from, block_of(&player.base.position)).unwrap_or_default();
+            }
+            follow_path(mob, CHASE_SPEED);
+            return None;
+        }
+    }
+
+    let speed = if mob.panic_ticks > 0 {
+        mob.panic_ticks -= 1;
+        if mob.path.is_empty() {
+            mob.path =
+                find_path(world, from, random_destination(from, FLEE_DISTANCE)).unwrap_or_default();
+        }
+        FLEE_SPEED
+    } else {
+        if mob.path.is_empty() && rand::random::<f64>() < WANDER_CHANCE {
+            mob.path = find_path(world, from, random_destination(from, WANDER_DISTANCE))
+                .unwrap_or_default();
+        }
+        WALK_SPEED
+    };
+
+    follow_path(mob, speed);
+    None
+}
+
+/// Nearest player the hostile mob sees within the range, skipping the dead ones
+/// and the ones in creative.
+fn find_target<'a>(mob: &Mob, world: &World, players: &'a [Player]) -> Option<&'a Player> {
+    let position = &mob.base.position;
+    let eyes = mob.base.bounding_box.height * 0.85;
+
+    players
+        .iter()
+        .filter(|player| player.game_mode == SURVIVAL && !player.health.is_dead())
+        .map(|player| {
+            let to = &player.base.position;
+            (player, position.distance_squared(to.x, to.y, to.z))
+        })
+        .filter(|(_, distance)| *distance <= FOLLOW_RANGE * FOLLOW_RANGE)
+        .filter(|(player, _)| {
+            let to = &player.base.position;
+            can_see(
+                world,
+                (position.x, position.y + eyes, position.z),
+                (to.x, to.y + EYE_HEIGHT, to.z),
+            )
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(player, _)| player)
+}
+
+/// Whether no solid block stands between the points.
+pub fn can_see(world: &World, from: (f64, f64, f64), to: (f64, f64, f64)) -> bool {
+    let (dx, dy, dz) = (to.0 - from.0, to.1 - from.1, to.2 - from.2);
+    let distance = (dx * dx + dy * dy + dz * dz).sqrt();
+    let steps = (distance / SIGHT_STEP).ceil() as u32;
+
+    (0..=steps).all(|step| {
+        let t = if steps == 0 {
+            0.0
+        } else {
+            f64::from(step) / f64::from(steps)
+        };
+        let (x, y, z) = (from.0 + dx * t, from.1 + dy * t, from.2 + dz * t);
+
+        world
+            .get_block(x.floor() as i32, y.floor() as i32, z.floor() as i32)
+            .is_none_or(|block| block.is_replaceable())
+    })
+}
+
+/// Walks the mob towards the next waypoint of its path, jumping up the blocks.
+fn follow_path(mob: &mut Mob, speed: f64) {
+    let position = mob.base.position;
+
+    while let Some(next) = mob.path.last() {
+        let (dx, dz) = (
+            next.x as f64 + 0.5 - position.x,
+            next.z as f64 + 0.5 - position.z,
+        );
+        if dx.hypot(dz) > WAYPOINT_REACH {
+            break;
+        }
+        mob.path.pop();
+    }
+
+    let Some(&next) = mob.path.last() else {
+        return;
+    };
+
+    let (dx, dz) = (
+        next.x as f64 + 0.5 - position.x,
+        next.z as f64 + 0.5 - position.z,
+    );
+    let distance = dx.hypot(dz);
+    let velocity = &mut mob.base.velocity;
+    velocity.0 = dx / distance * speed;
+    velocity.2 = dz / distance * speed;
+
+    if next.y > position.y.floor() as i32 && position.on_ground {
+        velocity.1 = JUMP_SPEED;
+    }
+
+    mob.base.position.yaw = (-dx).atan2(dz).to_degrees() as f32;
+}
+
+/// Random spot within the horizontal distance around the block.
+fn random_destination(from: BlockPos, distance: i32) -> BlockPos {
+    from.offset(
+        rand::random_range(-distance..=distance),
+        rand::random_range(-1..=1),
+        rand::random_range(-distance..=distance),
+    )
+}
+
+fn block_of(position: &Position) -> BlockPos {
+    BlockPos::new(
+        position.x.floor() as i32,
+        position.y.floor() as i32,
+        position.z.floor() as i32,
+    )
+}
+
+/// Whether the mobs walk through the block.
+fn is_passable(block: Block) -> bool {
+    block.is_replaceable() && !block.is_lava() && block.id != Block::FIRE.id
+}
+
+/// Whether the mob can stand with the feet in the block, on a solid one with room above.
+fn is_standable(world: &World, pos: BlockPos) -> bool {
+    let passable = |y| world.get_block(pos.x, y, pos.z).is_some_and(is_passable);
+    let floor = world.get_block(pos.x, pos.y - 1, pos.z);
+
+    floor.is_some_and(|floor| !floor.is_replaceable()) && passable(pos.y) && passable(pos.y + 1)
+}
+
+/// Blocks the mob standing in the block walks to, stepping up a block or dropping down.
+fn neighbours(world: &World, pos: BlockPos) -> Vec<BlockPos> {
+    let passable = |pos: BlockPos| {
+        world
+            .get_block(pos.x, pos.y, pos.z)
+            .is_some_and(is_passable)
+    };
+    let headroom = passable(pos.offset(0, 2, 0));
+
+    [(1, 0), (-1, 0), (0, 1), (0, -1)]
+        .into_iter()
+        .filter_map(|(dx, dz)| {
+            let next = pos.offset(dx, 0, dz);
+
+            if is_standable(world, next) {
+                return Some(next);
+            }
+            if headroom && is_standable(world, next.offset(0, 1, 0)) {
+                return Some(next.offset(0, 1, 0));
+            }
+            if !passable(next) || !passable(next.offset(0, 1, 0)) {
+                return None;
+            }
+
+            (1..=MAX_DROP)
+                .map(|drop| next.offset(0, -drop, 0))
+                .take_while(|&below| passable(below))
+                .find(|&below| is_standable(world, below))
+        })
+        .collect()
+}
+
+fn manhattan(a: BlockPos, b: BlockPos) -> i32 {
+    (a.x - b.x).abs() + (a.y - b.y).abs() + (a.z - b.z).abs()
+}
+
+/// Finds the path of the blocks walked through from the block to the goal,
+/// or to the block closest to it if it can't be reached, avoiding the obstacles.
+///
+/// The path is returned in reverse, the next waypoint last, without the start.
+/// `None` if the goal is too far away or no block closer to it is reachable.
+pub fn find_path(world: &World, from: BlockPos, goal: BlockPos) -> Option<Vec<BlockPos>> {
+    let too_far = (goal.x - from.x).abs() > MAX_PATH_DISTANCE
+        || (goal.y - from.y).abs() > MAX_PATH_DISTANCE
+        || (goal.z - from.z).abs() > MAX_PATH_DISTANCE;
+    if too_far || from == goal {
+        return None;
+    }
+
+    // Costs of reaching the blocks and the blocks they were reached from.
+    let mut visited: HashMap<BlockPos, (i32, Option<BlockPos>)> = HashMap::new();
+    let mut open = BinaryHeap::new();
+    let mut closest = from;
+
+    visited.insert(from, (0, None));
+    open.push(Reverse((manhattan(from, goal), 0, from.x, from.y, from.z)));
+
+    while let Some(Reverse((_, cost, x, y, z))) = open.pop() {
+        let pos = BlockPos::new(x, y, z);
+        if manhattan(pos, goal) < manhattan(closest, goal) {
+            closest = pos;
+        }
+        if pos == goal || visited.len() >= MAX_PATH_NODES {
+            break;
+        }
+
+        for next in neighbours(world, pos) {
+            let next_cost = cost + 1;
+            if visited
+                .get(&next)
+                .is_some_and(|&(known, _)| known <= next_cost)
+            {
+                continue;
+            }
+
+            visited.insert(next, (next_cost, Some(pos)));
+            let estimate = next_cost + manhattan(next, goal);
+            open.push(Reverse((estimate, next_cost, next.x, next.y, next.z)));
+        }
+    }
+
+    if closest == from {
+        return None;
+    }
+
+    let mut path = vec![closest];
+    while let Some(&(_, Some(parent))) = visited.get(path.last()?) {
+        if parent == from {
+            break;
+        }
+        path.push(parent);
+    }
+
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::world::chunk::{ChunkColumn, ChunkPos};
+
+    use super::*;
+
+    /// Flat stone floor at the Y of 63 with a wall along the X of 4, open at the Z of 6.
+    fn walled_world() -> World {
+        let mut world = World::new();
+        world.insert_chunk(ChunkColumn::new(ChunkPos::new(0, 0)));
+
+        for x in 0..16 {
+            for z in 0..16 {
+                world.set_block(x, 63, z, Block::STONE);
+            }
+        }
+        for z in 0..6 {
+            world.set_block(4, 64, z, Block::STONE);
+            world.set_block(4, 65, z, Block::STONE);
+        }
+
+        world
+    }
+
+    #[test]
+    fn path_around_the_wall() {
+        let world = walled_world();
+        let (from, goal) = (BlockPos::new(2, 64, 2), BlockPos::new(6, 64, 2));
+
+        let mut path = find_path(&world, from, goal).unwrap();
+        path.reverse();
+
+        assert_eq!(path.last(), Some(&goal));
+        assert!(path.iter().all(|pos| pos.x != 4 || pos.z >= 6));
+        assert!(path.windows(2).all(|step| manhattan(step[0], step[1]) == 1));
+        assert_eq!(path.len(), 12);
+    }
+
+    #[test]
+    fn step_up_and_drop_down() {
+        let mut world = walled_world();
+        world.set_block(8, 64, 8, Block::STONE);
+
+        assert!(neighbours(&world, BlockPos::new(7, 64, 8)).contains(&BlockPos::new(8, 65, 8)));
+        assert!(neighbours(&world, BlockPos::new(8, 65, 8)).contains(&BlockPos::new(9, 64, 8)));
+        // The wall is two blocks high.
+        assert!(!neighbours(&world, BlockPos::new(3, 64, 2))
+            .iter()
+            .any(|pos| pos.x == 4));
+    }
+
+    #[test]
+    fn walls_block_the_sight() {
+        let world = walled_world();
+
+        assert!(can_see(&world, (2.5, 65.0, 7.5), (6.5, 65.0, 7.5)));
+        assert!(!can_see(&world, (2.5, 65.0, 2.5), (6.5, 65.0, 2.5)));
+    }
+}
@@ -0,0 +1,160 @@
+use std::{future::Future, io, pin::Pin, time::Duration};
+
+use log::{error, info};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+/// Host of the legacy session server.
+const SESSION_HOST: &str = "session.minecraft.net";
+
+/// How long the session server may take to answer.
+const SESSION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Reason the client gets kicked with when the verification fails.
+pub const VERIFICATION_FAILED_REASON: &str = "Failed to verify username!";
+
+pub type VerifyFuture<'a> = Pin<Box<dyn Future<Output = io::Result<bool>> + Send + 'a>>;
+
+/// Checks whether the user has joined the server through the session server.
+pub trait SessionVerifier: Send + Sync {
+    /// Returns `true` if the user has joined the server with the hash.
+    fn verify<'a>(&'a self, username: &'a str, server_hash: &'a str) -> VerifyFuture<'a>;
+}
+
+/// Generates the hash the client joins the server with, sent in the handshake.
+pub fn generate_server_hash() -> String {
+    format!("{:x}", rand::random::<u64>())
+}
+
+/// Verifies the user, returns the kick reason if it isn't allowed to log in.
+pub async fn authenticate(
+    verifier: &dyn SessionVerifier,
+    username: &str,
+    server_hash: &str,
+) -> Result<(), &'static str> {
+    match verifier.verify(username, server_hash).await {
+        Ok(true) => Ok(()),
+        Ok(false) => {
+            info!("{} failed the session verification", username);
+            Err(VERIFICATION_FAILED_REASON)
+        }
+        Err(e) => {
+            error!(
+                "Failed to verify {} with the session server: {}",
+                username, e
+            );
+            Err(VERIFICATION_FAILED_REASON)
+        }
+    }
+}
+
+/// Verifies the users with the legacy `checkserver.jsp` endpoint.
+#[derive(Debug, Default)]
+pub struct LegacySessionServer;
+
+impl LegacySessionServer {
+    async fn check_server(&self, username: &str, server_hash: &str) -> io::Result<bool> {
+        let request = format!(
+            "GET /game/checkserver.jsp?user={}&serverId={} HTTP/1.0\r\nHost: {}\r\n\r\n",
+            percent_encode(username),
+            percent_encode(server_hash),
+            SESSION_HOST
+        );
+
+        let mut stream = TcpStream::connect((SESSION_HOST, 80)).await?;
+        stream.write_all(request.as_bytes()).await?;
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await?;
+
+        parse_check_response(&String::from_utf8_lossy(&response))
+    }
+}
+
+impl SessionVerifier for LegacySessionServer {
+    fn verify<'a>(&'a self, username: &'a str, server_hash: &'a str) -> VerifyFuture<'a> {
+        Box::pin(async move {
+            tokio::time::timeout(SESSION_TIMEOUT, self.check_server(username, server_hash))
+                .await
+                .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "session server timed out"))?
+        })
+    }
+}
+
+/// Parses the HTTP response of the session server, `YES` means the user is verified.
+fn parse_check_response(response: &str) -> io::Result<bool> {
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed HTTP response"))?;
+
+    let status = head.lines().next().unwrap_or_default();
+    if status.split_whitespace().nth(1) != Some("200") {
+        return Err(io::Error::other(format!("unexpected status: {}", status)));
+    }
+
+    Ok(body.trim() == "YES")
+}
+
+/// Encodes the query parameter, leaving only the unreserved characters as they are.
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifier answering with the stored result.
+    struct MockVerifier(Option<bool>);
+
+    impl SessionVerifier for MockVerifier {
+        fn verify<'a>(&'a self, _username: &'a str, _server_hash: &'a str) -> VerifyFuture<'a> {
+            let result = self
+                .0
+                .ok_or_else(|| io::Error::new(io::ErrorKind::ConnectionRefused, "offline"));
+            Box::pin(async move { result })
+        }
+    }
+
+    #[tokio::test]
+    async fn authenticate_with_verifier() {
+        let hash = generate_server_hash();
+
+        assert_eq!(
+            authenticate(&MockVerifier(Some(true)), "ezioleq", &hash).await,
+            Ok(())
+        );
+        assert_eq!(
+            authenticate(&MockVerifier(Some(false)), "ezioleq", &hash).await,
+            Err(VERIFICATION_FAILED_REASON)
+        );
+        assert_eq!(
+            authenticate(&MockVerifier(None), "ezioleq", &hash).await,
+            Err(VERIFICATION_FAILED_REASON)
+        );
+    }
+
+    #[test]
+    fn parse_session_response() {
+        assert!(parse_check_response("HTTP/1.1 200 OK\r\nServer: x\r\n\r\nYES\n").unwrap());
+        assert!(!parse_check_response("HTTP/1.1 200 OK\r\n\r\nNO").unwrap());
+        assert!(parse_check_response("HTTP/1.1 503 Service Unavailable\r\n\r\n").is_err());
+        assert!(parse_check_response("garbage").is_err());
+    }
+
+    #[test]
+    fn encode_query_parameter() {
+        assert_eq!(percent_encode("ezio_leq-1"), "ezio_leq-1");
+        assert_eq!(percent_encode("a b&c"), "a%20b%26c");
+    }
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+use crate::persist::{NameList, NamedEntry};
+
+/// Reason stored when none is given.
+pub const DEFAULT_BAN_REASON: &str = "Banned by an operator.";
+
+/// A banned player, as stored in the ban list file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BanEntry {
+    /// Name of the banned player.
+    pub name: String,
+
+    /// Who issued the ban.
+    pub source: String,
+
+    pub reason: String,
+}
+
+impl NamedEntry for BanEntry {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Players refused at login, persisted in a JSON file.
+pub type BanList = NameList<BanEntry>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn persist_bans() {
+        let path = std::env::temp_dir().join(format!("banned-players-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let bans = BanList::load(&path).unwrap();
+        assert_eq!(bans.get("ezioleq"), None);
+
+        let entry = BanEntry {
+            name: "Ezioleq".to_string(),
+            source: "Server".to_string(),
+            reason: "Griefing".to_string(),
+        };
+        bans.add(entry.clone()).await.unwrap();
+
+        let bans = BanList::load(&path).unwrap();
+        assert_eq!(bans.get("ezioleq"), Some(entry));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
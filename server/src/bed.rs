@@ -0,0 +1,249 @@
+use protocol::packet::{AnimationPayload, Packet, UseBedPayload};
+
+use crate::{
+    player::{Player, Players},
+    tracker::PLAYER_TRACKING_RANGE,
+    world::{block::Block, BlockPos, World},
+};
+
+/// Metadata bit set on both halves of an occupied bed.
+const OCCUPIED: u8 = 0x4;
+
+/// Metadata bit of the head half of a bed, the lower two bits are its direction.
+const HEAD: u8 = 0x8;
+
+/// Animation of the player leaving the bed.
+const LEAVE_BED_ANIMATION: i8 = 3;
+
+/// Distance from the bed the players may lie down within, horizontally.
+const MAX_HORIZONTAL_DISTANCE: f64 = 3.0;
+
+/// Distance from the bed the players may lie down within, vertically.
+const MAX_VERTICAL_DISTANCE: f64 = 2.0;
+
+/// Number of ticks all the players have to be asleep for the night to be skipped.
+pub const SLEEP_TICKS: u64 = 100;
+
+/// Reason the player can't lie down in the bed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BedError {
+    /// It's the day, the players may sleep only at night.
+    NotNight,
+
+    /// Another player sleeps in the bed.
+    Occupied,
+
+    /// There's a solid block above the bed.
+    Obstructed,
+
+    /// The player is too far away from the bed.
+    TooFarAway,
+
+    /// The clicked block isn't a complete bed.
+    NotBed,
+
+    /// The dimension has no night to sleep through.
+    WrongDimension,
+}
+
+impl BedError {
+    /// Message shown to the player.
+    pub fn message(&self) -> &'static str {
+        match self {
+            BedError::NotNight => "You can only sleep at night",
+            BedError::Occupied => "This bed is occupied",
+            BedError::Obstructed => "This bed is obstructed",
+            BedError::TooFarAway => "You are too far away from the bed",
+            BedError::NotBed => "This bed is broken",
+            BedError::WrongDimension => "You can only sleep in the overworld",
+        }
+    }
+}
+
+/// Offset from the foot of the bed to its head, by the direction bits.
+fn head_offset(metadata: u8) -> (i32, i32) {
+    match metadata & 0x3 {
+        0 => (0, 1),
+        1 => (-1, 0),
+        2 => (0, -1),
+        _ => (1, 0),
+    }
+}
+
+/// Returns the positions of the head and the foot of the bed the block is a half of.
+pub fn halves(world: &World, pos: BlockPos, block: Block) -> Option<(BlockPos, BlockPos)> {
+    let (dx, dz) = head_offset(block.metadata);
+    let (head, foot) = if block.metadata & HEAD != 0 {
+        (pos, pos.offset(-dx, 0, -dz))
+    } else {
+        (pos.offset(dx, 0, dz), pos)
+    };
+
+    let other = if head == pos { foot } else { head };
+    world
+        .get_block(other.x, other.y, other.z)
+        .filter(|other| other.id == Block::BED.id)?;
+
+    Some((head, foot))
+}
+
+/// Marks both halves of the bed as occupied or free.
+pub fn set_occupied(world: &mut World, head: BlockPos, occupied: bool) {
+    let Some(block) = world.get_block(head.x, head.y, head.z) else {
+        return;
+    };
+    let Some((head, foot)) = halves(world, head, block) else {
+        return;
+    };
+
+    for pos in [head, foot] {
+        if let Some(mut block) = world.get_block(pos.x, pos.y, pos.z) {
+            block.metadata = if occupied {
+                block.metadata | OCCUPIED
+            } else {
+                block.metadata & !OCCUPIED
+            };
+            world.set_block(pos.x, pos.y, pos.z, block);
+        }
+    }
+}
+
+/// Lays the player down in the clicked bed, returns the position of its head.
+pub fn lie_down(
+    world: &mut World,
+    player: &Player,
+    pos: BlockPos,
+    block: Block,
+) -> Result<BlockPos, BedError> {
+    let (head, foot) = halves(world, pos, block).ok_or(BedError::NotBed)?;
+
+    if !world.dimension().has_sky() {
+        return Err(BedError::WrongDimension);
+    }
+
+    if !world.is_night() {
+        return Err(BedError::NotNight);
+    }
+
+    if block.metadata & OCCUPIED != 0 {
+        return Err(BedError::Occupied);
+    }
+
+    let obstructed = [head, foot].iter().any(|pos| {
+        world
+            .get_block(pos.x, pos.y + 1, pos.z)
+            .is_none_or(|block| !block.is_replaceable())
+    });
+    if obstructed {
+        return Err(BedError::Obstructed);
+    }
+
+    let position = &player.base.position;
+    if (position.x - head.x as f64 - 0.5).abs() > MAX_HORIZONTAL_DISTANCE
+        || (position.y - head.y as f64).abs() > MAX_VERTICAL_DISTANCE
+        || (position.z - head.z as f64 - 0.5).abs() > MAX_HORIZONTAL_DISTANCE
+    {
+        return Err(BedError::TooFarAway);
+    }
+
+    set_occupied(world, head, true);
+    Ok(head)
+}
+
+/// Finds the spot next to the bed the player respawns at,
+/// `None` if the bed is missing or surrounded by the blocks.
+pub fn respawn_spot(world: &World, head: BlockPos) -> Option<BlockPos> {
+    let block = world
+        .get_block(head.x, head.y, head.z)
+        .filter(|block| block.id == Block::BED.id)?;
+    let (head, foot) = halves(world, head, block)?;
+
+    let fits = |pos: &BlockPos| {
+        let block_at = |dy| world.get_block(pos.x, pos.y + dy, pos.z);
+
+        block_at(-1).is_some_and(|block| !block.is_replaceable())
+            && block_at(0).is_some_and(|block| block.is_replaceable())
+            && block_at(1).is_some_and(|block| block.is_replaceable())
+    };
+
+    [head, foot]
+        .into_iter()
+        .flat_map(|half| {
+            (-1..=1).flat_map(move |dx| (-1..=1).map(move |dz| half.offset(dx, 0, dz)))
+        })
+        .find(fits)
+}
+
+/// Builds the packet showing the player lying in the bed.
+pub fn use_bed_packet(entity_id: i32, head: BlockPos) -> Packet {
+    Packet::UseBed(UseBedPayload {
+        entity_id,
+        in_bed: 0,
+        x: head.x,
+        y: head.y as i8,
+        z: head.z,
+    })
+}
+
+/// Gets the player out of the bed, if it's sleeping, freeing the bed.
+pub fn wake_up(players: &Players, world: &mut World, entity_id: i32) {
+    let Some((bed, position)) = players
+        .update(entity_id, |player| {
+            player.bed.take().map(|bed| (bed, player.base.position))
+        })
+        .flatten()
+    else {
+        return;
+    };
+
+    set_occupied(world, bed, false);
+    players.broadcast_near(
+        world.dimension(),
+        position.x,
+        position.y,
+        position.z,
+        PLAYER_TRACKING_RANGE,
+        Packet::Animation(AnimationPayload {
+            entity_id,
+            animation: LEAVE_BED_ANIMATION,
+        }),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::world::{
+        chunk::{ChunkColumn, ChunkPos},
+        NIGHT_START,
+    };
+
+    use super::*;
+
+    #[test]
+    fn occupy_both_halves() {
+        let mut world = World::new();
+        world.insert_chunk(ChunkColumn::new(ChunkPos::new(0, 0)));
+        // Facing east, the head is on the positive X.
+        world.set_block(1, 64, 1, Block::with_metadata(Block::BED.id, 0x3));
+        world.set_block(2, 64, 1, Block::with_metadata(Block::BED.id, 0x3 | HEAD));
+
+        let foot = BlockPos::new(1, 64, 1);
+        let head = BlockPos::new(2, 64, 1);
+        let block = world.get_block(1, 64, 1).unwrap();
+        assert_eq!(halves(&world, foot, block), Some((head, foot)));
+
+        set_occupied(&mut world, head, true);
+        assert_eq!(world.get_block(1, 64, 1).unwrap().metadata, 0x3 | OCCUPIED);
+        assert_eq!(
+            world.get_block(2, 64, 1).unwrap().metadata,
+            0x3 | HEAD | OCCUPIED
+        );
+
+        set_occupied(&mut world, head, false);
+        assert_eq!(world.get_block(1, 64, 1).unwrap().metadata, 0x3);
+
+        world.set_block(2, 64, 1, Block::AIR);
+        assert_eq!(halves(&world, foot, block), None);
+        assert!(world.time_of_day() < NIGHT_START && !world.is_night());
+    }
+}
@@ -0,0 +1,93 @@
+use protocol::packet::{ChatMessagePayload, DisconnectKickPayload, Packet};
+
+/// Max length of the chat message the client accepts, in UTF-16 code units.
+pub const MAX_MESSAGE_LENGTH: usize = 119;
+
+/// Max length of the reason the client is kicked with, in UTF-16 code units.
+pub const MAX_KICK_REASON_LENGTH: usize = 100;
+
+/// Character starting the formatting codes.
+const FORMATTING_CODE: char = '§';
+
+/// Sanitizes the message typed by the player,
+/// returns `None` if there is nothing left to send.
+///
+/// Formatting codes are stripped so players can't impersonate the server messages.
+pub fn sanitize(message: &str) -> Option<String> {
+    let message: String = message
+        .chars()
+        .filter(|c| *c != FORMATTING_CODE && !c.is_control())
+        .collect();
+    let message = message.trim();
+
+    (!message.is_empty()).then(|| message.to_string())
+}
+
+/// Builds the `<username> message` line sent to the players.
+pub fn format_message(username: &str, message: &str) -> String {
+    truncate(&format!("<{}> {}", username, message), MAX_MESSAGE_LENGTH)
+}
+
+/// Builds the chat packet, shortening the message if it wouldn't fit.
+pub fn chat_packet(message: &str) -> Packet {
+    Packet::ChatMessage(ChatMessagePayload {
+        message: truncate(message, MAX_MESSAGE_LENGTH),
+    })
+}
+
+/// Builds the packet kicking the client, shortening the reason if it wouldn't fit.
+pub fn kick_packet(reason: &str) -> Packet {
+    Packet::DisconnectKick(DisconnectKickPayload {
+        reason: truncate(reason, MAX_KICK_REASON_LENGTH),
+    })
+}
+
+/// Shortens the message to the max length, in UTF-16 code units.
+fn truncate(message: &str, max_length: usize) -> String {
+    let mut length = 0;
+
+    message
+        .chars()
+        .take_while(|c| {
+            length += c.len_utf16();
+            length <= max_length
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_message() {
+        assert_eq!(sanitize("  hello\n"), Some("hello".to_string()));
+        assert_eq!(sanitize("§ahi§r"), Some("ahir".to_string()));
+        assert_eq!(sanitize(" § "), None);
+    }
+
+    #[test]
+    fn format_long_message() {
+        let message = format_message("ezioleq", &"a".repeat(200));
+
+        assert!(message.starts_with("<ezioleq> aaa"));
+        assert_eq!(message.encode_utf16().count(), MAX_MESSAGE_LENGTH);
+    }
+
+    #[test]
+    fn kick_with_long_reason() {
+        let reason = format!(
+            "You are banned from this server! Reason: {}",
+            "griefing ".repeat(10)
+        );
+        let Packet::DisconnectKick(kick) = kick_packet(&reason) else {
+            panic!("not a kick packet");
+        };
+
+        assert!(kick
+            .reason
+            .starts_with("You are banned from this server! Reason: griefing"));
+        assert_eq!(kick.reason.encode_utf16().count(), MAX_KICK_REASON_LENGTH);
+        assert!(Packet::DisconnectKick(kick).to_bytes().is_ok());
+    }
+}
@@ -0,0 +1,108 @@
+use protocol::{
+    packet::{EntityVelocityPayload, Packet},
+    slot::ItemStack,
+};
+
+use crate::{entity::Position, world::digging::ToolKind};
+
+/// Squared distance the players may attack each other within.
+pub const MAX_ATTACK_REACH_SQUARED: f64 = 36.0;
+
+/// Speed the attacked players are knocked back with, horizontally and vertically.
+const KNOCKBACK_SPEED: f64 = 0.4;
+
+/// Damage dealt with the fist or an item which isn't a weapon.
+const FIST_DAMAGE: i16 = 1;
+
+/// Damage dealt by the attacker holding the item.
+pub fn attack_damage(held: Option<&ItemStack>) -> i16 {
+    let Some((kind, speed)) = held.and_then(|item| ToolKind::of_item(item.id)) else {
+        return FIST_DAMAGE;
+    };
+
+    let base = match kind {
+        ToolKind::Sword => 4,
+        ToolKind::Axe => 3,
+        ToolKind::Pickaxe => 2,
+        ToolKind::Shovel => 1,
+        ToolKind::Shears => return FIST_DAMAGE,
+    };
+
+    // The tiers are told apart by the digging speed, gold being as weak as wood.
+    let tier = match speed as u8 {
+        4 => 1,
+        6 => 2,
+        8 => 3,
+        _ => 0,
+    };
+
+    base + tier
+}
+
+/// Velocity of the victim knocked back away from the attacker, in blocks per tick.
+pub fn knockback(attacker: &Position, victim: &Position) -> (f64, f64, f64) {
+    let (dx, dz) = (victim.x - attacker.x, victim.z - attacker.z);
+    let distance = dx.hypot(dz);
+
+    if distance < f64::EPSILON {
+        return (0.0, KNOCKBACK_SPEED, 0.0);
+    }
+
+    (
+        dx / distance * KNOCKBACK_SPEED,
+        KNOCKBACK_SPEED,
+        dz / distance * KNOCKBACK_SPEED,
+    )
+}
+
+/// Builds the packet setting the velocity of the entity, in blocks per tick.
+pub fn velocity_packet(entity_id: i32, (x, y, z): (f64, f64, f64)) -> Packet {
+    let scale = |value: f64| (value * 8000.0).clamp(i16::MIN.into(), i16::MAX.into()) as i16;
+
+    Packet::EntityVelocity(EntityVelocityPayload {
+        entity_id,
+        velocity_x: scale(x),
+        velocity_y: scale(y),
+        velocity_z: scale(z),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weapons_deal_more_damage() {
+        let damage = |id| attack_damage(Some(&ItemStack::new(id, 1, 0)));
+
+        assert_eq!(attack_damage(None), 1);
+        assert_eq!(damage(3), 1);
+        assert_eq!(damage(268), 4);
+        assert_eq!(damage(276), 7);
+        assert_eq!(damage(283), 4);
+        assert_eq!(damage(258), 5);
+        assert_eq!(damage(270), 2);
+        assert_eq!(damage(277), 4);
+        assert_eq!(damage(359), 1);
+    }
+
+    #[test]
+    fn knock_away_from_attacker() {
+        let attacker = Position::default();
+        let victim = Position {
+            x: 3.0,
+            ..Default::default()
+        };
+
+        assert_eq!(knockback(&attacker, &victim), (0.4, 0.4, 0.0));
+        assert_eq!(
+            velocity_packet(1, knockback(&attacker, &victim)),
+            Packet::EntityVelocity(EntityVelocityPayload {
+                entity_id: 1,
+                velocity_x: 3200,
+                velocity_y: 3200,
+                velocity_z: 0,
+            })
+        );
+    }
+}
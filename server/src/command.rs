@@ -0,0 +1,542 @@
+use std::{collections::BTreeMap, future::Future, pin::Pin};
+
+use log::{error, info};
+use protocol::slot::ItemStack;
+
+use crate::{
+    ban::{BanEntry, DEFAULT_BAN_REASON},
+    chat,
+    game_mode::{self, GameModeEntry},
+    handle::ServerHandle,
+    inventory::max_stack_size,
+    ops::{OpEntry, MODERATOR_PERMISSION_LEVEL, OPERATOR_PERMISSION_LEVEL},
+    player::Player,
+    save,
+};
+
+/// Reason of the kick when none is given.
+const DEFAULT_KICK_REASON: &str = "Kicked by an operator.";
+
+/// Permission level of the regular players.
+pub const DEFAULT_PERMISSION_LEVEL: u8 = 0;
+
+/// Reason the command has failed, sent back to the sender.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    /// The arguments don't match the usage of the command.
+    Usage,
+
+    /// The command couldn't be executed.
+    Failed(String),
+}
+
+pub type CommandResult = Result<(), CommandError>;
+
+/// Everything the command handler gets called with.
+pub struct CommandContext {
+    /// Player who issued the command.
+    pub sender: Player,
+
+    /// Arguments following the command name.
+    pub args: Vec<String>,
+
+    pub server: ServerHandle,
+}
+
+impl CommandContext {
+    /// Sends the message to the player who issued the command.
+    pub fn reply(&self, message: &str) {
+        self.sender.send(chat::chat_packet(message));
+    }
+}
+
+type CommandFuture = Pin<Box<dyn Future<Output = CommandResult> + Send>>;
+
+type Handler = Box<dyn Fn(CommandContext) -> CommandFuture + Send + Sync>;
+
+/// A registered command.
+struct Command {
+    usage: String,
+
+    /// Lowest permission level of the players allowed to use the command.
+    permission_level: u8,
+
+    handler: Handler,
+}
+
+/// Commands available to the players, keyed by the lowercase name.
+#[derive(Default)]
+pub struct CommandRegistry {
+    commands: BTreeMap<String, Command>,
+}
+
+impl CommandRegistry {
+    /// Creates the registry without any commands.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates the registry with the commands built into the server.
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        registry.register(
+            "ban",
+            "/ban <player> [reason]",
+            MODERATOR_PERMISSION_LEVEL,
+            ban,
+        );
+        // Only the operators may grant or revoke their own level.
+        registry.register("deop", "/deop <player>", OPERATOR_PERMISSION_LEVEL, deop);
+        registry.register(
+            "gamemode",
+            "/gamemode <0|1> [player]",
+            MODERATOR_PERMISSION_LEVEL,
+            gamemode,
+        );
+        registry.register(
+            "give",
+            "/give <player> <item> [count] [damage]",
+            MODERATOR_PERMISSION_LEVEL,
+            give,
+        );
+        registry.register("help", "/help", DEFAULT_PERMISSION_LEVEL, help);
+        registry.register(
+            "kick",
+            "/kick <player> [reason]",
+            MODERATOR_PERMISSION_LEVEL,
+            kick,
+        );
+        registry.register("list", "/list", DEFAULT_PERMISSION_LEVEL, list);
+        registry.register("me", "/me <action>", DEFAULT_PERMISSION_LEVEL, me);
+        registry.register("op", "/op <player>", OPERATOR_PERMISSION_LEVEL, op);
+        registry.register(
+            "save-all",
+            "/save-all",
+            MODERATOR_PERMISSION_LEVEL,
+            save_all,
+        );
+        registry.register("stop", "/stop", OPERATOR_PERMISSION_LEVEL, stop);
+        registry.register(
+            "tell",
+            "/tell <player> <message>",
+            DEFAULT_PERMISSION_LEVEL,
+            tell,
+        );
+        registry
+    }
+
+    /// Registers the command, replacing the one with the same name.
+    ///
+    /// Only the players with at least the permission level may use it.
+    pub fn register<F, Fut>(&mut self, name: &str, usage: &str, permission_level: u8, handler: F)
+    where
+        F: Fn(CommandContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = CommandResult> + Send + 'static,
+    {
+        self.commands.insert(
+            name.to_lowercase(),
+            Command {
+                usage: usage.to_string(),
+                permission_level,
+                handler: Box::new(move |context| Box::pin(handler(context))),
+            },
+        );
+    }
+
+    /// Whether the command with the name is registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.commands.contains_key(&name.to_lowercase())
+    }
+
+    /// Usages of the commands the player with the permission level may use.
+    pub fn usages(&self, permission_level: u8) -> Vec<String> {
+        self.commands
+            .values()
+            .filter(|command| command.permission_level <= permission_level)
+            .map(|command| command.usage.clone())
+            .collect()
+    }
+
+    /// Runs the command line typed by the player, without the leading slash.
+    pub async fn dispatch(&self, server: ServerHandle, sender: Player, line: &str) {
+        info!("{} issued server command: /{}", sender.username, line);
+
+        let Some((name, args)) = parse(line) else {
+            return;
+        };
+
+        let context = CommandContext {
+            sender,
+            args,
+            server,
+        };
+
+        let command = match self.commands.get(&name) {
+            Some(command) if command.permission_level <= context.sender.permission_level => command,
+            Some(_) => {
+                context.reply("§cYou do not have permission to use this command.");
+                return;
+            }
+            None => {
+                context.reply("§cUnknown command. Type /help for help.");
+                return;
+            }
+        };
+
+        let sender = context.sender.clone();
+
+        match (command.handler)(context).await {
+            Ok(()) => {}
+            Err(CommandError::Usage) => {
+                sender.send(chat::chat_packet(&format!("§cUsage: {}", command.usage)));
+            }
+            Err(CommandError::Failed(reason)) => {
+                sender.send(chat::chat_packet(&format!("§c{}", reason)));
+            }
+        }
+    }
+}
+
+/// Splits the command line into the lowercase name and the arguments.
+pub fn parse(line: &str) -> Option<(String, Vec<String>)> {
+    let mut parts = line.split_whitespace();
+    let name = parts.next()?.to_lowercase();
+
+    Some((name, parts.map(str::to_string).collect()))
+}
+
+/// Joins the arguments into the reason, `None` if there are none.
+fn reason(args: &[String]) -> Option<String> {
+    chat::sanitize(&args.join(" "))
+}
+
+async fn ban(context: CommandContext) -> CommandResult {
+    let [name, reason_args @ ..] = context.args.as_slice() else {
+        return Err(CommandError::Usage);
+    };
+    let reason = reason(reason_args).unwrap_or_else(|| DEFAULT_BAN_REASON.to_string());
+
+    let player = context.server.players.by_name(name);
+    let entry = BanEntry {
+        // Keep the case the player logged in with.
+        name: player
+            .as_ref()
+            .map_or_else(|| name.clone(), |player| player.username.clone()),
+        source: context.sender.username.clone(),
+        reason: reason.clone(),
+    };
+
+    if let Err(e) = context.server.bans.add(entry).await {
+        error!("Failed to save the ban list: {}", e);
+        return Err(CommandError::Failed("Failed to save the ban list.".into()));
+    }
+
+    if let Some(player) = player {
+        player.kick(&format!(
+            "You are banned from this server! Reason: {}",
+            reason
+        ));
+    }
+
+    info!("{} banned {}: {}", context.sender.username, name, reason);
+    context.reply(&format!("Banned {}.", name));
+    Ok(())
+}
+
+async fn deop(context: CommandContext) -> CommandResult {
+    let [name] = context.args.as_slice() else {
+        return Err(CommandError::Usage);
+    };
+
+    match context.server.ops.remove(name).await {
+        Ok(true) => {}
+        Ok(false) => return Err(CommandError::Failed(format!("{} isn't an operator.", name))),
+        Err(e) => {
+            error!("Failed to save the op list: {}", e);
+            return Err(CommandError::Failed("Failed to save the op list.".into()));
+        }
+    }
+
+    set_permission_level(&context, name, DEFAULT_PERMISSION_LEVEL);
+
+    info!("{} de-opped {}", context.sender.username, name);
+    context.reply(&format!("De-opped {}.", name));
+    Ok(())
+}
+
+async fn gamemode(context: CommandContext) -> CommandResult {
+    let (mode, name) = match context.args.as_slice() {
+        [mode] => (mode, &context.sender.username),
+        [mode, name] => (mode, name),
+        _ => return Err(CommandError::Usage),
+    };
+    let Some(mode) = mode.parse().ok().filter(|&mode| game_mode::is_valid(mode)) else {
+        return Err(CommandError::Usage);
+    };
+
+    let players = &context.server.players;
+    let player = players
+        .by_name(name)
+        .ok_or_else(|| CommandError::Failed("There's no player by that name online.".into()))?;
+
+    let entry = GameModeEntry {
+        name: player.username.clone(),
+        game_mode: mode,
+    };
+    if let Err(e) = context.server.game_modes.add(entry).await {
+        error!("Failed to save the game mode list: {}", e);
+        return Err(CommandError::Failed(
+            "Failed to save the game mode list.".into(),
+        ));
+    }
+
+    players.update(player.base.entity_id, |player| {
+        player.game_mode = mode;
+        // Only the survival players eat, the started eating is dropped.
+        player.eating = None;
+    });
+    players.send(player.base.entity_id, game_mode::change_packet(mode));
+
+    info!(
+        "{} set the game mode of {} to {}",
+        context.sender.username, player.username, mode
+    );
+    context.reply(&format!(
+        "Set the game mode of {} to {}.",
+        player.username, mode
+    ));
+    Ok(())
+}
+
+async fn give(context: CommandContext) -> CommandResult {
+    let (name, id, count, damage) = match context.args.as_slice() {
+        [name, id] => (name, id.parse().ok(), Some(1), Some(0)),
+        [name, id, count] => (name, id.parse().ok(), count.parse().ok(), Some(0)),
+        [name, id, count, damage] => (
+            name,
+            id.parse().ok(),
+            count.parse().ok(),
+            damage.parse().ok(),
+        ),
+        _ => return Err(CommandError::Usage),
+    };
+    let (Some(id), Some(count), Some(damage)) = (id, count, damage) else {
+        return Err(CommandError::Usage);
+    };
+
+    if id <= 0 {
+        return Err(CommandError::Failed(format!(
+            "There's no item with ID {}.",
+            id
+        )));
+    }
+
+    let mut item = ItemStack::new(id, count, damage);
+    if !(1..=max_stack_size(&item)).contains(&count) {
+        return Err(CommandError::Failed(format!(
+            "The count has to be between 1 and {}.",
+            max_stack_size(&item)
+        )));
+    }
+
+    let player = context
+        .server
+        .players
+        .by_name(name)
+        .ok_or_else(|| CommandError::Failed("There's no player by that name online.".into()))?;
+
+    context
+        .server
+        .players
+        .give(player.base.entity_id, &mut item);
+    let given = count - item.count;
+    if given == 0 {
+        return Err(CommandError::Failed(format!(
+            "{}'s inventory is full.",
+            player.username
+        )));
+    }
+
+    info!(
+        "{} gave {} {} of {}",
+        context.sender.username, player.username, given, id
+    );
+    context.reply(&format!("Gave {} {} of {}.", player.username, given, id));
+    Ok(())
+}
+
+async fn help(context: CommandContext) -> CommandResult {
+    for usage in context
+        .server
+        .commands
+        .usages(context.sender.permission_level)
+    {
+        context.reply(&usage);
+    }
+
+    Ok(())
+}
+
+async fn kick(context: CommandContext) -> CommandResult {
+    let [name, reason_args @ ..] = context.args.as_slice() else {
+        return Err(CommandError::Usage);
+    };
+    let reason = reason(reason_args).unwrap_or_else(|| DEFAULT_KICK_REASON.to_string());
+
+    let player = context
+        .server
+        .players
+        .by_name(name)
+        .ok_or_else(|| CommandError::Failed("There's no player by that name online.".into()))?;
+
+    player.kick(&reason);
+
+    info!(
+        "{} kicked {}: {}",
+        context.sender.username, player.username, reason
+    );
+    context.reply(&format!("Kicked {}.", player.username));
+    Ok(())
+}
+
+async fn list(context: CommandContext) -> CommandResult {
+    let names: Vec<String> = context
+        .server
+        .players
+        .all()
+        .into_iter()
+        .map(|player| player.username)
+        .collect();
+
+    context.reply(&format!("Connected players: {}", names.join(", ")));
+    Ok(())
+}
+
+async fn me(context: CommandContext) -> CommandResult {
+    let action = chat::sanitize(&context.args.join(" ")).ok_or(CommandError::Usage)?;
+
+    context
+        .server
+        .players
+        .broadcast_all(chat::chat_packet(&format!(
+            "* {} {}",
+            context.sender.username, action
+        )));
+    Ok(())
+}
+
+async fn op(context: CommandContext) -> CommandResult {
+    let [name] = context.args.as_slice() else {
+        return Err(CommandError::Usage);
+    };
+
+    let entry = OpEntry {
+        name: name.clone(),
+        level: OPERATOR_PERMISSION_LEVEL,
+    };
+
+    if let Err(e) = context.server.ops.add(entry).await {
+        error!("Failed to save the op list: {}", e);
+        return Err(CommandError::Failed("Failed to save the op list.".into()));
+    }
+
+    set_permission_level(&context, name, OPERATOR_PERMISSION_LEVEL);
+
+    info!("{} opped {}", context.sender.username, name);
+    context.reply(&format!("Opped {}.", name));
+    Ok(())
+}
+
+/// Updates the permission level of the player if it's online.
+fn set_permission_level(context: &CommandContext, name: &str, level: u8) {
+    let players = &context.server.players;
+
+    if let Some(player) = players.by_name(name) {
+        players.update(player.base.entity_id, |player| {
+            player.permission_level = level
+        });
+    }
+}
+
+async fn save_all(context: CommandContext) -> CommandResult {
+    info!("{} saved the world", context.sender.username);
+    context.reply("Saving the world...");
+    save::save_all(&context.server).await;
+    context.reply("Saved the world.");
+    Ok(())
+}
+
+async fn stop(context: CommandContext) -> CommandResult {
+    info!("{} stopped the server", context.sender.username);
+    context.server.shutdown.cancel();
+    Ok(())
+}
+
+async fn tell(context: CommandContext) -> CommandResult {
+    let [name, message @ ..] = context.args.as_slice() else {
+        return Err(CommandError::Usage);
+    };
+    let message = chat::sanitize(&message.join(" ")).ok_or(CommandError::Usage)?;
+
+    let recipient = context
+        .server
+        .players
+        .by_name(name)
+        .ok_or_else(|| CommandError::Failed("There's no player by that name online.".into()))?;
+
+    recipient.send(chat::chat_packet(&format!(
+        "§7{} whispers {}",
+        context.sender.username, message
+    )));
+    context.reply(&format!(
+        "§7You whisper to {}: {}",
+        recipient.username, message
+    ));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_command_line() {
+        assert_eq!(
+            parse("TELL ezioleq  hi there"),
+            Some((
+                "tell".to_string(),
+                vec!["ezioleq".to_string(), "hi".to_string(), "there".to_string()]
+            ))
+        );
+        assert_eq!(parse("list"), Some(("list".to_string(), vec![])));
+        assert_eq!(parse("   "), None);
+    }
+
+    #[test]
+    fn usages_respect_permission_level() {
+        let mut registry = CommandRegistry::new();
+        registry.register("list", "/list", 0, list);
+        registry.register("Stop", "/stop", 4, |_| async { Ok(()) });
+
+        assert!(registry.contains("stop"));
+        assert_eq!(registry.usages(0), vec!["/list".to_string()]);
+        assert_eq!(
+            registry.usages(4),
+            vec!["/list".to_string(), "/stop".to_string()]
+        );
+    }
+
+    #[test]
+    fn only_operators_manage_operators() {
+        let registry = CommandRegistry::with_builtins();
+
+        let moderator = registry.usages(MODERATOR_PERMISSION_LEVEL);
+        assert!(moderator.contains(&"/kick <player> [reason]".to_string()));
+        assert!(!moderator.contains(&"/op <player>".to_string()));
+        assert!(!moderator.contains(&"/deop <player>".to_string()));
+
+        let operator = registry.usages(OPERATOR_PERMISSION_LEVEL);
+        assert!(operator.contains(&"/op <player>".to_string()));
+        assert!(operator.contains(&"/deop <player>".to_string()));
+    }
+}
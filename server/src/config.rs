@@ -0,0 +1,102 @@
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    path::PathBuf,
+    time::Duration,
+};
+
+use crate::{keep_alive::KeepAliveConfig, tick::DEFAULT_TPS};
+
+/// Settings the server runs with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerConfig {
+    /// Address the server listens on.
+    pub address: SocketAddr,
+
+    /// Message of the day visible in the server browser.
+    pub motd: String,
+
+    /// A number of ticks per second.
+    pub tps: u32,
+
+    /// Max number of players simultaneously connected to the server.
+    pub max_players: u8,
+
+    /// Whether the operators may join when the server is full.
+    pub ops_bypass_player_limit: bool,
+
+    /// World level type sent in the login response.
+    pub level_type: String,
+
+    /// Seed the terrain is generated from.
+    pub seed: i64,
+
+    /// X of the block the players spawn at.
+    pub spawn_x: i32,
+
+    /// Z of the block the players spawn at, Y is the top of the terrain.
+    pub spawn_z: i32,
+
+    /// Number of the columns sent in each direction around the players.
+    pub view_distance: u8,
+
+    /// Game mode of the joining players without one set with `/gamemode`.
+    pub game_mode: i32,
+
+    /// Whether the PvP is enabled on the server.
+    pub enable_pvp: bool,
+
+    /// World difficulty.
+    pub difficulty: i8,
+
+    /// Whether the time of the day advances, the day or night lasts forever otherwise.
+    pub do_daylight_cycle: bool,
+
+    /// Whether the joining users are verified with the session server.
+    pub online_mode: bool,
+
+    /// Timing of the keep-alive packets.
+    pub keep_alive: KeepAliveConfig,
+
+    /// Path of the directory the world is saved in.
+    pub world_path: PathBuf,
+
+    /// How often the world is saved, only as the server stops if `None`.
+    pub autosave_interval: Option<Duration>,
+
+    /// Path of the file with the banned players.
+    pub ban_list_path: PathBuf,
+
+    /// Path of the file with the operators.
+    pub op_list_path: PathBuf,
+
+    /// Path of the file with the game modes set for the players.
+    pub game_mode_list_path: PathBuf,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            address: SocketAddr::from((Ipv4Addr::LOCALHOST, 25565)),
+            motd: "A Minecraft Server".to_string(),
+            tps: DEFAULT_TPS,
+            max_players: 20,
+            ops_bypass_player_limit: false,
+            level_type: "FLAT".to_string(),
+            seed: 0,
+            spawn_x: 8,
+            spawn_z: 8,
+            view_distance: 10,
+            game_mode: 1,
+            enable_pvp: true,
+            difficulty: 0,
+            do_daylight_cycle: true,
+            online_mode: false,
+            keep_alive: KeepAliveConfig::default(),
+            world_path: "world".into(),
+            autosave_interval: Some(Duration::from_secs(300)),
+            ban_list_path: "banned-players.json".into(),
+            op_list_path: "ops.json".into(),
+            game_mode_list_path: "game-modes.json".into(),
+        }
+    }
+}
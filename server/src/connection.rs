@@ -0,0 +1,1686 @@
+use std::{net::SocketAddr, sync::RwLock};
+
+use futures::{SinkExt, StreamExt};
+use log::{debug, error, info, trace, warn};
+use protocol::{
+    beta17::{self, Beta17},
+    codec::PacketCodec,
+    error::ProtocolError,
+    packet::{
+        ChunkAllocationPayload, CreativeInventoryActionPayload, Direction, DisconnectKickPayload,
+        HandshakePayload, KeepAlivePayload, LoginRequestPayload, MapChunkPayload, Packet,
+        PlayerBlockPlacementPayload, PlayerDiggingPayload, PlayerPositionAndLookPayload,
+        RespawnPayload, SoundParticleEffectPayload, SpawnPositionPayload, TransactionPayload,
+        UpdateSignPayload, WindowClickPayload,
+    },
+    slot::ItemStack,
+    v29::V29,
+    version::{ProtocolVersion, VersionCompatibility},
+};
+use tokio::{
+    net::TcpStream,
+    sync::mpsc::{self, UnboundedReceiver},
+    time::{self, Instant, MissedTickBehavior},
+};
+use tokio_util::codec::Framed;
+
+use crate::{
+    auth, bed, chat, combat,
+    entity::{EntityBase, Position},
+    experience,
+    food::{self, EATING_TICKS},
+    game_mode::{CREATIVE, SURVIVAL},
+    handle::ServerHandle,
+    health::{self, DamageCause, Health, Hurt, ATTACK_EXHAUSTION, DIG_EXHAUSTION, MAX_FOOD},
+    inventory::{max_stack_size, ClickError, HOTBAR_SIZE, HOTBAR_SLOT_OFFSET, INVENTORY_WINDOW_ID},
+    item::ItemEntity,
+    keep_alive::{KeepAlive, KeepAliveTick},
+    ops::MODERATOR_PERMISSION_LEVEL,
+    player::{is_valid_username, PacketSender, Player, EYE_HEIGHT, PLAYER_BOUNDING_BOX},
+    save, send_packet,
+    tick::ServerEvent,
+    tracker::PLAYER_TRACKING_RANGE,
+    view::ChunkView,
+    world::{
+        block::Block,
+        chunk::ChunkPos,
+        digging,
+        dimension::Dimension,
+        portal::{self, FLINT_AND_STEEL, FLINT_AND_STEEL_DURABILITY},
+        redstone,
+        sign::{self, Sign, SIGN},
+        tile_entity::TileEntity,
+        BlockChanges, BlockPos, World,
+    },
+};
+
+/// Squared distance from the eyes the players may place the blocks within.
+const MAX_REACH_SQUARED: f64 = 64.0;
+
+/// Squared distance the players may move by with a single packet.
+const MAX_MOVE_SQUARED: f64 = 100.0;
+
+/// Horizontal coordinate the players may move up to in either direction.
+const MAX_COORDINATE: f64 = 3.2e7;
+
+/// Digging status sent when the player starts digging a block.
+const STARTED_DIGGING: i8 = 0;
+
+/// Digging status sent when the player finishes digging a block.
+const FINISHED_DIGGING: i8 = 2;
+
+/// Digging status sent when the player drops the held item.
+const DROPPED_ITEM: i8 = 4;
+
+/// Digging status sent when the player stops using the held item, e.g. eating.
+const RELEASED_USE_ITEM: i8 = 5;
+
+/// Entity action sent when the player leaves the bed.
+const LEAVE_BED: i8 = 3;
+
+/// Entity action sent when the player starts sprinting.
+const START_SPRINTING: i8 = 4;
+
+/// Entity action sent when the player stops sprinting.
+const STOP_SPRINTING: i8 = 5;
+
+/// Height of the world sent to the respawning clients.
+const WORLD_HEIGHT: i16 = 256;
+
+/// Share of the break time the digging may take, leaving room for the latency.
+const DIG_TIME_TOLERANCE: f64 = 0.8;
+
+/// Effect of the block breaking, shown to the other players.
+const BLOCK_BREAK_EFFECT: i32 = 2001;
+
+/// Distance within which the players see the effects at the blocks, e.g. the blocks breaking.
+const EFFECT_RADIUS: f64 = 64.0;
+
+/// Stage of the connection, limits the packets the client may send.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// Waiting for the handshake or the server list ping.
+    Handshake,
+
+    /// Handshake done, waiting for the login request.
+    Login,
+
+    /// Logged in and playing.
+    Play,
+}
+
+impl ConnectionState {
+    /// Returns the state the connection moves to after receiving the packet.
+    ///
+    /// Packets which aren't allowed in the current state are protocol violations,
+    /// the error is the reason the client gets kicked with.
+    pub fn transition(self, packet: &Packet) -> Result<Self, &'static str> {
+        if packet.info().direction == Direction::ServerToClient {
+            return Err("Unexpected packet");
+        }
+
+        match (self, packet) {
+            // The client may leave at any time.
+            (state, Packet::DisconnectKick(_)) => Ok(state),
+            (ConnectionState::Handshake, Packet::Handshake(_)) => Ok(ConnectionState::Login),
+            (ConnectionState::Handshake, Packet::ServerListPing(_)) => {
+                Ok(ConnectionState::Handshake)
+            }
+            (ConnectionState::Handshake, _) => Err("Expected a handshake"),
+            (ConnectionState::Login, Packet::LoginRequest(_)) => Ok(ConnectionState::Play),
+            (ConnectionState::Login, _) => Err("Expected a login request"),
+            (
+                ConnectionState::Play,
+                Packet::Handshake(_) | Packet::LoginRequest(_) | Packet::ServerListPing(_),
+            ) => Err("Already logged in"),
+            (ConnectionState::Play, _) => Ok(ConnectionState::Play),
+        }
+    }
+}
+
+/// Whether the connection should keep going after handling a packet.
+type Flow = std::ops::ControlFlow<()>;
+
+/// Connection of a single client.
+struct Connection {
+    socket: Framed<TcpStream, PacketCodec>,
+    addr: SocketAddr,
+    state: ConnectionState,
+    server: ServerHandle,
+
+    /// Entity identifier of the player, once logged in.
+    entity_id: Option<i32>,
+
+    /// Queue of the packets sent to the client by the other tasks.
+    sender: PacketSender,
+    receiver: UnboundedReceiver<Packet>,
+
+    keep_alive: KeepAlive,
+
+    /// Dimension the player is in, the columns in view are of its world.
+    dimension: Dimension,
+
+    /// Columns loaded by the client.
+    view: ChunkView,
+
+    /// Block the player is digging in survival and when it started.
+    digging: Option<(BlockPos, Instant)>,
+
+    /// When the player stepped into the portal it stands in.
+    portal_entered: Option<Instant>,
+
+    /// Whether the player has to step out of the portal it stands in before it may travel,
+    /// as it has just arrived through it.
+    portal_cooldown: bool,
+
+    /// Hash sent in the handshake, in the online mode.
+    server_hash: Option<String>,
+
+    /// Whether the client turned out to be a Beta client.
+    beta: bool,
+}
+
+/// Serves a single client until it disconnects.
+pub async fn handle_connection(socket: TcpStream, addr: SocketAddr, server: ServerHandle) {
+    let (sender, receiver) = mpsc::unbounded_channel();
+
+    let mut connection = Connection {
+        socket: Framed::new(
+            socket,
+            PacketCodec::<V29>::receiving(Direction::ClientToServer),
+        ),
+        addr,
+        state: ConnectionState::Handshake,
+        keep_alive: KeepAlive::new(server.config.keep_alive),
+        dimension: Dimension::Overworld,
+        view: ChunkView::new(server.config.view_distance),
+        digging: None,
+        portal_entered: None,
+        portal_cooldown: false,
+        server,
+        entity_id: None,
+        sender,
+        receiver,
+        server_hash: None,
+        beta: false,
+    };
+
+    connection.run().await;
+
+    if let Some(entity_id) = connection.entity_id {
+        {
+            let mut world = connection.world().write().unwrap();
+            bed::wake_up(&connection.server.players, &mut world, entity_id);
+        }
+
+        if let Some(player) = connection.server.players.remove(entity_id) {
+            info!("{} left the game", player.username);
+            save::save_player(&connection.server, &player).await;
+        }
+    }
+
+    if connection.beta {
+        reject_beta_client(connection.socket, addr).await;
+    }
+}
+
+impl Connection {
+    /// World of the dimension the player is in.
+    fn world(&self) -> &RwLock<World> {
+        self.server.worlds.get(self.dimension)
+    }
+
+    async fn run(&mut self) {
+        let period = self.keep_alive.config().interval;
+        let mut keep_alive = time::interval_at(Instant::now() + period, period);
+        keep_alive.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        // The clients which don't log in are dropped as if they stopped echoing the keep-alives.
+        let login_deadline = time::sleep(self.keep_alive.config().timeout);
+        tokio::pin!(login_deadline);
+
+        loop {
+            tokio::select! {
+                packet = self.socket.next() => {
+                    let packet = match packet {
+                        Some(Ok(packet)) => packet,
+                        Some(Err(e)) => {
+                            error!("Failed to decode packet from {:?}: {}", &self.addr, e);
+                            return;
+                        }
+                        None => return,
+                    };
+
+                    if self.receive(packet).await.is_break() {
+                        return;
+                    }
+                }
+                Some(packet) = self.receiver.recv() => {
+                    let kicked = matches!(packet, Packet::DisconnectKick(_));
+
+                    if let Err(e) = self.send(packet).await {
+                        error!("Failed to send packet to {:?}: {}", &self.addr, e);
+                        return;
+                    }
+
+                    // Kicked by another task, e.g. a command.
+                    if kicked {
+                        return;
+                    }
+                }
+                _ = keep_alive.tick(), if self.state == ConnectionState::Play => {
+                    if self.keep_alive_tick().await.is_break() {
+                        return;
+                    }
+                }
+                _ = &mut login_deadline, if self.state != ConnectionState::Play => {
+                    info!("{:?} took too long to log in", &self.addr);
+                    let _ = self.kick("Took too long to log in").await;
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Sends the next keep-alive, kicks the client if it hasn't echoed the previous one.
+    async fn keep_alive_tick(&mut self) -> Flow {
+        match self.keep_alive.tick(Instant::now().into_std()) {
+            KeepAliveTick::Send(keep_alive_id) => {
+                if let Err(e) = self
+                    .send(Packet::KeepAlive(KeepAlivePayload { keep_alive_id }))
+                    .await
+                {
+                    error!("Failed to send packet to {:?}: {}", &self.addr, e);
+                    return Flow::Break(());
+                }
+            }
+            KeepAliveTick::Wait => {}
+            KeepAliveTick::TimedOut => {
+                info!("{:?} timed out", &self.addr);
+                return self.kick("Timed out").await;
+            }
+        }
+
+        Flow::Continue(())
+    }
+
+    async fn send(&mut self, packet: Packet) -> Result<(), ProtocolError> {
+        send_packet(&mut self.socket, packet).await
+    }
+
+    /// Kicks the client with the reason.
+    async fn kick(&mut self, reason: &str) -> Flow {
+        let _ = self.send(chat::kick_packet(reason)).await;
+        Flow::Break(())
+    }
+
+    async fn receive(&mut self, packet: Packet) -> Flow {
+        match self.handle(packet).await {
+            Ok(flow) => flow,
+            Err(e) => {
+                error!("Failed to send packet to {:?}: {}", &self.addr, e);
+                Flow::Break(())
+            }
+        }
+    }
+
+    /// Handles the packet, failing if a response couldn't be sent.
+    async fn handle(&mut self, packet: Packet) -> Result<Flow, ProtocolError> {
+        debug!(
+            "Got 0x{:02X} {} from {:?}",
+            packet.id(),
+            packet.name(),
+            &self.addr
+        );
+        trace!("Received: {:?}", packet);
+
+        self.state = match self.state.transition(&packet) {
+            Ok(state) => state,
+            Err(reason) => {
+                warn!(
+                    "Kicking {:?} for sending {} in {:?} state",
+                    &self.addr,
+                    packet.name(),
+                    self.state
+                );
+                return Ok(self.kick(reason).await);
+            }
+        };
+
+        match packet {
+            Packet::ServerListPing(_) => {
+                debug!("Received server ping packet!");
+
+                let status = self.server.status();
+                self.send(status.to_packet()).await?;
+            }
+            Packet::Handshake(handshake) => {
+                debug!("Received handshake packet!");
+
+                if beta17::is_beta_handshake(&handshake) {
+                    // The Beta client is kicked once the connection stops.
+                    self.beta = true;
+                    return Ok(Flow::Break(()));
+                }
+
+                // Offline servers don't verify the users, signalled by the dash.
+                let data = if self.server.config.online_mode {
+                    self.server_hash
+                        .insert(auth::generate_server_hash())
+                        .clone()
+                } else {
+                    "-".to_string()
+                };
+
+                self.send(Packet::Handshake(HandshakePayload { data }))
+                    .await?;
+            }
+            Packet::KeepAlive(keep_alive) => {
+                if !self.keep_alive.acknowledge(keep_alive.keep_alive_id) {
+                    debug!(
+                        "Ignoring unexpected keep-alive {} from {:?}",
+                        keep_alive.keep_alive_id, &self.addr
+                    );
+                }
+            }
+            Packet::LoginRequest(login) => return self.login(login).await,
+            Packet::ChatMessage(chat) => self.chat(&chat.message).await,
+            Packet::PlayerPositionAndLook(position_and_look) => {
+                debug!("Received player position and look packet!",);
+
+                let moved = self
+                    .update_position(|position| {
+                        position.x = position_and_look.x;
+                        position.y = position_and_look.stance_y_0;
+                        position.z = position_and_look.z;
+                        position.yaw = position_and_look.yaw;
+                        position.pitch = position_and_look.pitch;
+                        position.on_ground = position_and_look.on_ground != 0;
+                    })
+                    .await?;
+
+                if let Some(position) = moved {
+                    self.move_view(position.chunk()).await?;
+                    self.use_portal(&position).await?;
+                }
+            }
+            Packet::Player(player) => {
+                let moved = self
+                    .update_position(|position| position.on_ground = player.on_ground != 0)
+                    .await?;
+
+                // Sent every tick, even while standing still in the portal.
+                if let Some(position) = moved {
+                    self.use_portal(&position).await?;
+                }
+            }
+            Packet::PlayerPosition(player_position) => {
+                let moved = self
+                    .update_position(|position| {
+                        position.x = player_position.x;
+                        position.y = player_position.y;
+                        position.z = player_position.z;
+                        position.on_ground = player_position.on_ground != 0;
+                    })
+                    .await?;
+
+                if let Some(position) = moved {
+                    self.move_view(position.chunk()).await?;
+                    self.use_portal(&position).await?;
+                }
+            }
+            Packet::PlayerLook(look) => {
+                self.update_position(|position| {
+                    position.yaw = look.yaw;
+                    position.pitch = look.pitch;
+                    position.on_ground = look.on_ground != 0;
+                })
+                .await?;
+            }
+            Packet::PlayerDigging(digging) => self.dig(digging),
+            Packet::PlayerBlockPlacement(placement) => self.place_block(placement),
+            Packet::UpdateSign(update) => self.edit_sign(update),
+            Packet::WindowClick(click) => self.click_window(click),
+            Packet::CloseWindow(_) => self.close_window(),
+            Packet::CreativeInventoryAction(action) => self.set_creative_slot(action),
+            Packet::Respawn(_) => self.respawn().await?,
+            Packet::UseEntity(use_entity) if use_entity.left_click != 0 => {
+                self.attack(use_entity.target)
+            }
+            Packet::EntityAction(action) => {
+                if let Some(entity_id) = self.entity_id {
+                    self.entity_action(entity_id, action.action);
+                }
+            }
+            // Echoed back after a rejected click, the window has been resynchronized already.
+            Packet::Transaction(_) => {}
+            Packet::HoldingChange(holding) => {
+                if (0..HOTBAR_SIZE).contains(&holding.slot_id) {
+                    if let Some(entity_id) = self.entity_id {
+                        self.server.players.update(entity_id, |player| {
+                            player.held_slot = holding.slot_id;
+                            player.eating = None;
+                        });
+                    }
+                } else {
+                    debug!(
+                        "Ignoring invalid hotbar slot {} from {:?}",
+                        holding.slot_id, &self.addr
+                    );
+                }
+            }
+            Packet::DisconnectKick(disconnect) => {
+                info!("{:?} disconnected: {}", &self.addr, disconnect.reason);
+                return Ok(Flow::Break(()));
+            }
+            _ => error!("Unhandled packet type"),
+        }
+
+        Ok(Flow::Continue(()))
+    }
+
+    async fn login(&mut self, login: LoginRequestPayload) -> Result<Flow, ProtocolError> {
+        debug!("Received login request packet!");
+
+        let compatibility = VersionCompatibility::of_login(&login);
+
+        if let Some(reason) = compatibility.kick_reason() {
+            info!(
+                "Rejecting {} with protocol version {}: {:?}",
+                login.username, login.id, compatibility
+            );
+            return Ok(self.kick(reason).await);
+        }
+
+        if !is_valid_username(&login.username) {
+            info!("Rejecting the invalid name {:?}", login.username);
+            return Ok(self.kick("Invalid username!").await);
+        }
+
+        if let Some(server_hash) = &self.server_hash {
+            if let Err(reason) =
+                auth::authenticate(self.server.sessions.as_ref(), &login.username, server_hash)
+                    .await
+            {
+                return Ok(self.kick(reason).await);
+            }
+        }
+
+        if let Some(ban) = self.server.bans.get(&login.username) {
+            info!("Rejecting banned {}: {}", login.username, ban.reason);
+            return Ok(self
+                .kick(&format!(
+                    "You are banned from this server! Reason: {}",
+                    ban.reason
+                ))
+                .await);
+        }
+
+        // Saved before its file is loaded again, its connection finds it gone and doesn't save it.
+        if let Some(online) = self.server.players.by_name(&login.username) {
+            {
+                let mut world = self.server.worlds.get(online.dimension).write().unwrap();
+                bed::wake_up(&self.server.players, &mut world, online.base.entity_id);
+            }
+
+            if let Some(player) = self.server.players.remove(online.base.entity_id) {
+                info!("{} logged in from another location", player.username);
+                player.kick("You logged in from another location");
+                save::save_player(&self.server, &player).await;
+            }
+        }
+
+        if self.server.players.count() >= self.server.config.max_players.into()
+            && !(self.server.config.ops_bypass_player_limit
+                && self.server.ops.level(&login.username) >= MODERATOR_PERMISSION_LEVEL)
+        {
+            info!("Rejecting {}, the server is full", login.username);
+            return Ok(self.kick("The server is full!").await);
+        }
+
+        let entity_id = self.server.entity_ids.allocate();
+        let saved = save::load_player(&self.server, &login.username).await;
+        let game_mode = self
+            .server
+            .game_modes
+            .game_mode(&login.username)
+            .or(saved.as_ref().and_then(|saved| saved.game_mode))
+            .unwrap_or(self.server.config.game_mode);
+        let (spawn_x, spawn_y, spawn_z) = self.server.spawn_point();
+        self.dimension = saved
+            .as_ref()
+            .map_or_else(Dimension::default, |saved| saved.dimension);
+        let position = saved.as_ref().map_or(
+            Position {
+                x: spawn_x as f64 + 0.5,
+                y: spawn_y as f64,
+                z: spawn_z as f64 + 0.5,
+                yaw: -180.0,
+                pitch: 0.0,
+                on_ground: false,
+            },
+            |saved| saved.position,
+        );
+
+        self.send(Packet::LoginRequest(LoginRequestPayload {
+            id: entity_id,
+            username: "".to_string(),
+            level_type: self.server.config.level_type.clone(),
+            server_mode: game_mode,
+            dimension: self.dimension.id(),
+            difficulty: self.server.config.difficulty,
+            unused_0: 0,
+            max_players: self.server.config.max_players,
+        }))
+        .await?;
+
+        self.send(Packet::SpawnPosition(SpawnPositionPayload {
+            x: spawn_x,
+            y: spawn_y,
+            z: spawn_z,
+        }))
+        .await?;
+
+        // The client stays on the terrain download screen until it gets the position.
+        self.move_view(position.chunk()).await?;
+
+        self.send(position_packet(&position)).await?;
+
+        let (inventory, health, experience, spawn_point) = match saved {
+            Some(saved) => (
+                saved.inventory,
+                saved.health,
+                saved.experience,
+                saved.spawn_point,
+            ),
+            None => Default::default(),
+        };
+        self.send(inventory.window_items_packet()).await?;
+        self.send(health.update_packet()).await?;
+        self.send(experience.update_packet()).await?;
+
+        info!("{} joined the game", login.username);
+
+        self.server.players.add(Player {
+            base: EntityBase::new(entity_id, position, PLAYER_BOUNDING_BOX),
+            permission_level: self.server.ops.level(&login.username),
+            username: login.username,
+            dimension: self.dimension,
+            addr: self.addr,
+            game_mode,
+            held_slot: 0,
+            inventory,
+            health,
+            experience,
+            sprinting: false,
+            eating: None,
+            bed: None,
+            spawn_point,
+            sender: self.sender.clone(),
+        });
+        self.entity_id = Some(entity_id);
+
+        let _ = self
+            .server
+            .events
+            .send(ServerEvent::PlayerJoined { entity_id });
+
+        Ok(Flow::Continue(()))
+    }
+
+    /// Streams the columns to the client as it moves to another column.
+    async fn move_view(&mut self, center: ChunkPos) -> Result<(), ProtocolError> {
+        let Some(change) = self.view.move_to(center) else {
+            return Ok(());
+        };
+
+        for pos in change.unload {
+            self.send(Packet::ChunkAllocation(ChunkAllocationPayload {
+                x: pos.x,
+                z: pos.z,
+                mode: 0,
+            }))
+            .await?;
+        }
+
+        for pos in change.load {
+            let chunk = self.server.chunk_data(self.dimension, pos);
+
+            self.send(Packet::ChunkAllocation(ChunkAllocationPayload {
+                x: pos.x,
+                z: pos.z,
+                mode: 1,
+            }))
+            .await?;
+            self.send(Packet::MapChunk(MapChunkPayload {
+                x: pos.x,
+                z: pos.z,
+                chunk,
+            }))
+            .await?;
+
+            for packet in self.server.sign_updates(self.dimension, pos) {
+                self.send(packet).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Relays the chat message of the player to everyone,
+    /// or runs the command if it starts with a slash.
+    async fn chat(&self, message: &str) {
+        let Some(player) = self.entity_id.and_then(|id| self.server.players.get(id)) else {
+            return;
+        };
+
+        if let Some(line) = message.strip_prefix('/') {
+            self.server
+                .commands
+                .dispatch(self.server.clone(), player, line)
+                .await;
+            return;
+        }
+        let Some(message) = chat::sanitize(message) else {
+            return;
+        };
+
+        let line = chat::format_message(&player.username, &message);
+        info!("{}", line);
+
+        self.server.players.broadcast_all(chat::chat_packet(&line));
+    }
+
+    /// Breaks the block the player has dug, right away in creative,
+    /// after it has been dug long enough in survival.
+    fn dig(&mut self, digging: PlayerDiggingPayload) {
+        let Some(player) = self.entity_id.and_then(|id| self.server.players.get(id)) else {
+            return;
+        };
+
+        if digging.status == DROPPED_ITEM {
+            self.drop_held_item(&player);
+            return;
+        }
+
+        if digging.status == RELEASED_USE_ITEM {
+            self.server.players.update(player.base.entity_id, |player| {
+                player.eating = None;
+            });
+            return;
+        }
+
+        let pos = BlockPos::new(digging.x, digging.y.into(), digging.z);
+        if !matches!(digging.status, STARTED_DIGGING | FINISHED_DIGGING) {
+            return;
+        }
+
+        let eyes = &player.base.position;
+        if pos.distance_squared(eyes.x, eyes.y + EYE_HEIGHT, eyes.z) > MAX_REACH_SQUARED {
+            debug!("{} tried to dig a block out of reach", player.username);
+            self.digging = None;
+            self.resend_blocks(&player, &[pos]);
+            return;
+        }
+
+        let Some(block) = self.world().read().unwrap().get_block(pos.x, pos.y, pos.z) else {
+            return;
+        };
+
+        if player.game_mode == CREATIVE {
+            if digging.status == STARTED_DIGGING {
+                self.break_block(&player, pos);
+            }
+            return;
+        }
+
+        let tool = player.inventory.held_item(player.held_slot);
+        let Some(break_time) = digging::break_time(block, tool) else {
+            self.resend_blocks(&player, &[pos]);
+            return;
+        };
+
+        let dug = match digging.status {
+            STARTED_DIGGING if break_time.is_zero() => true,
+            STARTED_DIGGING => {
+                self.digging = Some((pos, Instant::now()));
+                return;
+            }
+            _ => self.digging.take().is_some_and(|(started_pos, started)| {
+                started_pos == pos && started.elapsed() >= break_time.mul_f64(DIG_TIME_TOLERANCE)
+            }),
+        };
+
+        if dug {
+            let Some(broken) = self.break_block(&player, pos) else {
+                return;
+            };
+
+            self.server.players.update(player.base.entity_id, |player| {
+                player.health.exhaust(DIG_EXHAUSTION);
+            });
+
+            if let Some(item) = digging::drop(broken, tool) {
+                let entity_id = self.server.entity_ids.allocate();
+                let item = ItemEntity::dropped_by_block(entity_id, item, pos.x, pos.y, pos.z);
+                let _ = self.server.events.send(ServerEvent::EntitySpawned {
+                    dimension: self.dimension,
+                    entity: Box::new(item),
+                });
+            }
+
+            if digging::can_harvest(broken, tool) {
+                let (x, y, z) = (pos.x as f64 + 0.5, pos.y as f64 + 0.5, pos.z as f64 + 0.5);
+                let experience = experience::block_experience(broken);
+
+                for orb in experience::drop_orbs(&self.server.entity_ids, experience, x, y, z) {
+                    let _ = self.server.events.send(ServerEvent::EntitySpawned {
+                        dimension: self.dimension,
+                        entity: Box::new(orb),
+                    });
+                }
+            }
+        } else {
+            debug!("{} dug a block too fast", player.username);
+            self.resend_blocks(&player, &[pos]);
+        }
+    }
+
+    /// Clears the block and shows the other players it breaking,
+    /// returns the broken block, `None` if there was nothing to break.
+    fn break_block(&self, player: &Player, pos: BlockPos) -> Option<Block> {
+        let broken = self
+            .world()
+            .write()
+            .unwrap()
+            .set_block(pos.x, pos.y, pos.z, Block::AIR)
+            .filter(|block| !block.is_air())?;
+
+        self.server.broadcast_block_change(self.dimension, pos);
+        self.server.update_blocks(self.dimension, pos);
+
+        // The portal breaks along with its frame.
+        if broken.id == Block::OBSIDIAN.id || broken.id == Block::PORTAL.id {
+            let collapsed = portal::collapse(&mut self.world().write().unwrap(), pos);
+            for pos in collapsed {
+                self.server.broadcast_block_change(self.dimension, pos);
+            }
+        }
+
+        let effect = Packet::SoundParticleEffect(SoundParticleEffectPayload {
+            effect_id: BLOCK_BREAK_EFFECT,
+            x: pos.x,
+            y: pos.y as u8,
+            z: pos.z,
+            data: i32::from(broken.id) | i32::from(broken.metadata) << 12,
+        });
+        self.broadcast_effect(player, pos, effect);
+
+        Some(broken)
+    }
+
+    /// Sends the effect at the block to the players nearby but the one causing it,
+    /// whose client plays it on its own.
+    fn broadcast_effect(&self, player: &Player, pos: BlockPos, effect: Packet) {
+        let (x, y, z) = (pos.x as f64 + 0.5, pos.y as f64 + 0.5, pos.z as f64 + 0.5);
+
+        self.server.players.broadcast_filter(effect, |other| {
+            other.base.entity_id != player.base.entity_id
+                && other.dimension == self.dimension
+                && other.base.position.distance_squared(x, y, z) <= EFFECT_RADIUS * EFFECT_RADIUS
+        });
+    }
+
+    /// Places the held block against the clicked one, or interacts with the clicked
+    /// block if it has a handler, e.g. opens a door.
+    ///
+    /// Rejected placements are reverted on the client by resending the actual blocks.
+    fn place_block(&self, placement: PlayerBlockPlacementPayload) {
+        let Some(player) = self.entity_id.and_then(|id| self.server.players.get(id)) else {
+            return;
+        };
+
+        // The item is used without targeting any block, e.g. food is eaten.
+        if placement.direction == -1 {
+            self.start_eating(&player);
+            return;
+        }
+
+        let clicked = BlockPos::new(placement.x, placement.y.into(), placement.z);
+        let Some(target) = clicked.neighbour(placement.direction) else {
+            debug!(
+                "Ignoring placement on invalid face {} from {:?}",
+                placement.direction, &self.addr
+            );
+            return;
+        };
+
+        let eyes = &player.base.position;
+        if clicked.distance_squared(eyes.x, eyes.y + EYE_HEIGHT, eyes.z) > MAX_REACH_SQUARED {
+            debug!("{} tried to place a block out of reach", player.username);
+            self.resend_blocks(&player, &[clicked, target]);
+            return;
+        }
+
+        let Some(block) = self
+            .world()
+            .read()
+            .unwrap()
+            .get_block(clicked.x, clicked.y, clicked.z)
+        else {
+            return;
+        };
+
+        if block.id == Block::BED.id {
+            self.use_bed(&player, clicked, block);
+            return;
+        }
+
+        // Sneaking isn't tracked yet, so clicking an interactive block always uses it.
+        if let Some(handler) = self.server.block_handlers.get(block.id) {
+            let mut changes = BlockChanges::default();
+            let handled = handler.interact(
+                &mut self.world().write().unwrap(),
+                clicked,
+                block,
+                &mut changes,
+            );
+
+            if handled {
+                for (pos, effect) in std::mem::take(&mut changes.effects) {
+                    self.broadcast_effect(&player, pos, effect);
+                }
+                self.server.apply_block_changes(self.dimension, changes);
+                return;
+            }
+        }
+
+        let held_slot = HOTBAR_SLOT_OFFSET + player.held_slot;
+        let held = player.inventory.held_item(player.held_slot);
+        if held.is_some_and(|item| item.id == FLINT_AND_STEEL) {
+            self.use_flint_and_steel(&player, target);
+            return;
+        }
+        if held.is_some_and(|item| item.id == SIGN) {
+            self.place_sign(&player, target, placement.direction);
+            return;
+        }
+        if let Some(item) = held.filter(|item| redstone::is_component_item(item.id)) {
+            self.place_redstone(&player, target, item.id, placement.direction);
+            return;
+        }
+
+        // Items can't be placed as blocks, using them is handled elsewhere.
+        let Some(item) = player
+            .inventory
+            .held_item(player.held_slot)
+            .filter(|item| (1..256).contains(&item.id))
+        else {
+            self.resend_blocks(&player, &[target]);
+            player.send(player.inventory.set_slot_packet(held_slot));
+            return;
+        };
+
+        let placed = Block::with_metadata(item.id as u16, (item.damage & 0xF) as u8);
+        if !self.set_placed_block(&player, target, placed) {
+            self.resend_blocks(&player, &[target]);
+            return;
+        }
+
+        self.server.broadcast_block_change(self.dimension, target);
+        self.server.update_blocks(self.dimension, target);
+        self.take_held_item(&player);
+    }
+
+    /// Places the blank sign, the client opens the editor and sends its text once it's done.
+    fn place_sign(&self, player: &Player, target: BlockPos, face: i8) {
+        let placed = sign::placed(face, player.base.position.yaw);
+        if !placed.is_some_and(|block| self.set_placed_block(player, target, block)) {
+            self.resend_blocks(player, &[target]);
+            return;
+        }
+
+        self.world().write().unwrap().set_tile_entity(
+            target.x,
+            target.y,
+            target.z,
+            TileEntity::Sign(Sign::new()),
+        );
+        self.server.broadcast_block_change(self.dimension, target);
+        self.take_held_item(player);
+    }
+
+    /// Places the redstone component attached to the clicked block, if the block holds it.
+    fn place_redstone(&self, player: &Player, target: BlockPos, item: i16, face: i8) {
+        let placed = redstone::placed(item, face, player.base.position.yaw)
+            .filter(|&block| redstone::is_supported(&self.world().read().unwrap(), target, block));
+        if !placed.is_some_and(|block| self.set_placed_block(player, target, block)) {
+            self.resend_blocks(player, &[target]);
+            return;
+        }
+
+        self.server.broadcast_block_change(self.dimension, target);
+        self.server.update_blocks(self.dimension, target);
+        self.take_held_item(player);
+    }
+
+    /// Sets the text of the sign the player has just placed and shows it to the others.
+    ///
+    /// The signs can be written only once, other edits are reverted on the client.
+    fn edit_sign(&self, update: UpdateSignPayload) {
+        let Some(player) = self.entity_id.and_then(|id| self.server.players.get(id)) else {
+            return;
+        };
+
+        let pos = BlockPos::new(update.x, update.y.into(), update.z);
+        let eyes = &player.base.position;
+        let in_reach =
+            pos.distance_squared(eyes.x, eyes.y + EYE_HEIGHT, eyes.z) <= MAX_REACH_SQUARED;
+
+        let edited = {
+            let mut world = self.world().write().unwrap();
+            let sign = world
+                .sign_mut(pos.x, pos.y, pos.z)
+                .filter(|sign| in_reach && sign.editable);
+
+            sign.map(|sign| {
+                sign.lines = [update.text1, update.text2, update.text3, update.text4]
+                    .map(|line| sign::sanitize(&line));
+                sign.editable = false;
+            })
+            .is_some()
+        };
+
+        if edited {
+            self.server.broadcast_sign(self.dimension, pos);
+            return;
+        }
+
+        debug!("{} tried to edit a sign it can't", player.username);
+        let sign = self
+            .world()
+            .read()
+            .unwrap()
+            .sign(pos.x, pos.y, pos.z)
+            .cloned();
+        if let Some(sign) = sign {
+            player.send(sign.update_packet(pos));
+        }
+    }
+
+    /// Lights the portal the fire is started in, or sets the block on fire,
+    /// wearing the flint and steel down in survival.
+    fn use_flint_and_steel(&self, player: &Player, target: BlockPos) {
+        let lit = {
+            let mut world = self.world().write().unwrap();
+            let empty = world
+                .get_block(target.x, target.y, target.z)
+                .is_some_and(|block| block.is_air());
+
+            empty.then(|| {
+                portal::light(&mut world, target).unwrap_or_else(|| {
+                    world.set_block(target.x, target.y, target.z, Block::FIRE);
+                    vec![target]
+                })
+            })
+        };
+
+        let Some(lit) = lit else {
+            self.resend_blocks(player, &[target]);
+            return;
+        };
+
+        for pos in lit {
+            self.server.broadcast_block_change(self.dimension, pos);
+        }
+
+        if player.game_mode == SURVIVAL {
+            let held_slot = HOTBAR_SLOT_OFFSET + player.held_slot;
+            self.server.players.update(player.base.entity_id, |player| {
+                player.inventory.wear(held_slot, FLINT_AND_STEEL_DURABILITY);
+                player.send(player.inventory.set_slot_packet(held_slot));
+            });
+        }
+    }
+
+    /// Sends the player standing in a portal long enough to the other dimension,
+    /// right away in creative.
+    async fn use_portal(&mut self, position: &Position) -> Result<(), ProtocolError> {
+        let in_portal = portal::stands_in(
+            &self.world().read().unwrap(),
+            position.x,
+            position.y,
+            position.z,
+        );
+        if !in_portal {
+            self.portal_entered = None;
+            self.portal_cooldown = false;
+            return Ok(());
+        }
+
+        let Some(player) = self.entity_id.and_then(|id| self.server.players.get(id)) else {
+            return Ok(());
+        };
+        let Some(destination) = portal::destination(self.dimension) else {
+            return Ok(());
+        };
+        if self.portal_cooldown || player.health.is_dead() {
+            return Ok(());
+        }
+
+        let entered = *self.portal_entered.get_or_insert_with(Instant::now);
+        if player.game_mode == SURVIVAL && entered.elapsed() < portal::TRAVEL_DELAY {
+            return Ok(());
+        }
+
+        let (x, z) = portal::scaled(self.dimension, destination, position.x, position.z);
+        let (x, z) = (x.floor() as i32, z.floor() as i32);
+
+        // The portal is looked for in the columns around, built if there's none.
+        let radius = portal::SEARCH_RADIUS;
+        let (from, to) = (
+            ChunkPos::of_block(x - radius, z - radius),
+            ChunkPos::of_block(x + radius, z + radius),
+        );
+        for chunk_x in from.x..=to.x {
+            for chunk_z in from.z..=to.z {
+                self.server
+                    .ensure_chunk(destination, ChunkPos::new(chunk_x, chunk_z));
+            }
+        }
+
+        let (arrival, changed) = {
+            let mut world = self.server.worlds.get(destination).write().unwrap();
+            match portal::find(&world, x, z) {
+                Some(found) => (found, Vec::new()),
+                None => portal::build(&mut world, x, z),
+            }
+        };
+        for pos in changed {
+            self.server.broadcast_block_change(destination, pos);
+        }
+
+        debug!("{} travels to the {:?}", player.username, destination);
+        self.portal_entered = None;
+        self.portal_cooldown = true;
+
+        let arrival = Position {
+            x: arrival.x as f64 + 0.5,
+            y: arrival.y as f64,
+            z: arrival.z as f64 + 0.5,
+            yaw: position.yaw,
+            pitch: position.pitch,
+            on_ground: true,
+        };
+        self.change_dimension(destination, arrival).await
+    }
+
+    /// Brings the dead player back to life at its bed, or the world spawn,
+    /// in the overworld.
+    async fn respawn(&mut self) -> Result<(), ProtocolError> {
+        let Some(player) = self.entity_id.and_then(|id| self.server.players.get(id)) else {
+            return Ok(());
+        };
+
+        if !player.health.is_dead() {
+            debug!("Ignoring respawn of the living {}", player.username);
+            return Ok(());
+        }
+
+        let overworld = self.server.worlds.get(Dimension::Overworld);
+        let bed_spot = player
+            .spawn_point
+            .and_then(|head| bed::respawn_spot(&overworld.read().unwrap(), head));
+        if player.spawn_point.is_some() && bed_spot.is_none() {
+            player.send(chat::chat_packet("Your home bed was missing or obstructed"));
+        }
+
+        let (x, y, z) = match bed_spot {
+            Some(spot) => (spot.x, spot.y, spot.z),
+            None => self.server.spawn_point(),
+        };
+        let position = Position {
+            x: x as f64 + 0.5,
+            y: y as f64,
+            z: z as f64 + 0.5,
+            yaw: player.base.position.yaw,
+            ..Default::default()
+        };
+
+        self.server.players.update(player.base.entity_id, |player| {
+            player.health = Health::default();
+            if bed_spot.is_none() {
+                player.spawn_point = None;
+            }
+        });
+
+        self.change_dimension(Dimension::Overworld, position).await
+    }
+
+    /// Moves the player to the position in the dimension, possibly the same one,
+    /// reloading the world on its client.
+    async fn change_dimension(
+        &mut self,
+        dimension: Dimension,
+        position: Position,
+    ) -> Result<(), ProtocolError> {
+        let Some(entity_id) = self.entity_id else {
+            return Ok(());
+        };
+
+        {
+            let mut world = self.world().write().unwrap();
+            bed::wake_up(&self.server.players, &mut world, entity_id);
+        }
+
+        let Some(player) = self.server.players.update(entity_id, |player| {
+            player.dimension = dimension;
+            player.base.position = position;
+            player.base.velocity = (0.0, 0.0, 0.0);
+            player.health.fall_distance = 0.0;
+            player.clone()
+        }) else {
+            return Ok(());
+        };
+        self.dimension = dimension;
+        self.digging = None;
+
+        self.send(Packet::Respawn(RespawnPayload {
+            dimension: dimension.id(),
+            difficulty: self.server.config.difficulty,
+            creative_mode: player.game_mode as i8,
+            world_height: WORLD_HEIGHT,
+            level_type: self.server.config.level_type.clone(),
+        }))
+        .await?;
+
+        // The client drops all the columns as it respawns, they have to be sent again.
+        self.view = ChunkView::new(self.server.config.view_distance);
+        self.move_view(position.chunk()).await?;
+
+        self.send(position_packet(&position)).await?;
+        self.send(player.health.update_packet()).await?;
+        self.send(player.experience.update_packet()).await?;
+        self.send(player.inventory.window_items_packet()).await?;
+
+        let _ = self
+            .server
+            .events
+            .send(ServerEvent::PlayerRespawned { entity_id });
+
+        Ok(())
+    }
+
+    /// Hurts the attacked player and knocks it back, if the PvP is enabled,
+    /// or has the tick loop hurt the attacked mob.
+    fn attack(&self, target: i32) {
+        let players = &self.server.players;
+        let Some(attacker) = self.entity_id.and_then(|id| players.get(id)) else {
+            return;
+        };
+
+        if target == attacker.base.entity_id || attacker.health.is_dead() {
+            return;
+        }
+
+        let damage = combat::attack_damage(attacker.inventory.held_item(attacker.held_slot));
+        let Some(victim) = players.get(target) else {
+            // The mobs live in the tick loop, it hurts the attacked one if there's any.
+            let _ = self.server.events.send(ServerEvent::MobAttacked {
+                attacker: attacker.base.entity_id,
+                target,
+                damage,
+            });
+            return;
+        };
+
+        if !self.server.config.enable_pvp {
+            return;
+        }
+
+        let (from, to) = (&attacker.base.position, &victim.base.position);
+        if from.distance_squared(to.x, to.y, to.z) > combat::MAX_ATTACK_REACH_SQUARED {
+            debug!("{} tried to attack out of reach", attacker.username);
+            return;
+        }
+
+        match health::hurt(players, target, damage) {
+            Hurt::Ignored => return,
+            Hurt::Hurt => {}
+            Hurt::Killed => {
+                let _ = self.server.events.send(ServerEvent::PlayerDied {
+                    entity_id: target,
+                    cause: DamageCause::Player(attacker.username.clone()),
+                });
+            }
+        }
+
+        players.broadcast_near(
+            self.dimension,
+            to.x,
+            to.y,
+            to.z,
+            PLAYER_TRACKING_RANGE,
+            combat::velocity_packet(target, combat::knockback(from, to)),
+        );
+
+        players.update(attacker.base.entity_id, |player| {
+            player.health.exhaust(ATTACK_EXHAUSTION);
+        });
+    }
+
+    /// Gets the player out of the bed or updates whether it sprints.
+    fn entity_action(&self, entity_id: i32, action: i8) {
+        match action {
+            LEAVE_BED => {
+                let mut world = self.world().write().unwrap();
+                bed::wake_up(&self.server.players, &mut world, entity_id);
+            }
+            START_SPRINTING | STOP_SPRINTING => {
+                self.server.players.update(entity_id, |player| {
+                    player.sprinting = action == START_SPRINTING;
+                });
+            }
+            _ => {}
+        }
+    }
+
+    /// Starts eating the held food, finished by the tick loop unless interrupted.
+    fn start_eating(&self, player: &Player) {
+        let Some(item) = player.inventory.held_item(player.held_slot) else {
+            return;
+        };
+
+        if player.game_mode == SURVIVAL && food::can_eat(item, player.health.food, MAX_FOOD) {
+            self.server.players.update(player.base.entity_id, |player| {
+                player.eating = Some(EATING_TICKS);
+            });
+        }
+    }
+
+    /// Lays the player down in the bed and sets the respawn point to it,
+    /// or tells why it can't sleep.
+    fn use_bed(&self, player: &Player, pos: BlockPos, block: Block) {
+        if player.bed.is_some() {
+            return;
+        }
+
+        let lay_down = bed::lie_down(&mut self.world().write().unwrap(), player, pos, block);
+        let head = match lay_down {
+            Ok(head) => head,
+            Err(error) => {
+                player.send(chat::chat_packet(error.message()));
+                return;
+            }
+        };
+
+        let entity_id = player.base.entity_id;
+        self.server.players.update(entity_id, |player| {
+            player.bed = Some(head);
+            player.spawn_point = Some(head);
+        });
+
+        let position = &player.base.position;
+        self.server.players.broadcast_near(
+            self.dimension,
+            position.x,
+            position.y,
+            position.z,
+            PLAYER_TRACKING_RANGE,
+            bed::use_bed_packet(entity_id, head),
+        );
+    }
+
+    /// Throws a single item of the held stack in the direction the player looks.
+    fn drop_held_item(&self, player: &Player) {
+        let held_slot = HOTBAR_SLOT_OFFSET + player.held_slot;
+        let item = self.server.players.update(player.base.entity_id, |player| {
+            let item = player.inventory.take_one(held_slot);
+            player.send(player.inventory.set_slot_packet(held_slot));
+            item
+        });
+
+        if let Some(item) = item.flatten() {
+            self.throw_item(player, item);
+        }
+    }
+
+    /// Spawns the item thrown by the player.
+    fn throw_item(&self, player: &Player, item: ItemStack) {
+        let entity_id = self.server.entity_ids.allocate();
+        let item = ItemEntity::thrown_by(entity_id, item, &player.base.position);
+        let _ = self.server.events.send(ServerEvent::EntitySpawned {
+            dimension: self.dimension,
+            entity: Box::new(item),
+        });
+    }
+
+    /// Applies the click in the player's inventory window, confirming or rejecting it.
+    fn click_window(&self, click: WindowClickPayload) {
+        let Some(entity_id) = self.entity_id else {
+            return;
+        };
+
+        // Only the player's own inventory can be opened so far.
+        let result = (click.window_id == INVENTORY_WINDOW_ID).then(|| {
+            self.server.players.update(entity_id, |player| {
+                let result = player.inventory.click(
+                    click.slot,
+                    click.right_click != 0,
+                    click.shift != 0,
+                    &click.clicked_item,
+                );
+
+                // The client expects the whole window after a rejection.
+                if result.is_err() {
+                    player.send(player.inventory.window_items_packet());
+                    player.send(player.inventory.cursor_packet());
+                }
+
+                result
+            })
+        });
+
+        let result = result.flatten().unwrap_or(Err(ClickError::InvalidSlot));
+        if let Err(error) = result {
+            debug!(
+                "Rejecting the click on slot {} from {:?}: {:?}",
+                click.slot, &self.addr, error
+            );
+        }
+
+        self.sender
+            .send(Packet::Transaction(TransactionPayload {
+                window_id: click.window_id,
+                action_number: click.action_number,
+                accepted: result.is_ok().into(),
+            }))
+            .ok();
+
+        if let (Ok(Some(item)), Some(player)) = (result, self.server.players.get(entity_id)) {
+            self.throw_item(&player, item);
+        }
+    }
+
+    /// Puts the cursor stack and the crafting grid back into the storage,
+    /// throwing out what doesn't fit.
+    fn close_window(&self) {
+        let Some(entity_id) = self.entity_id else {
+            return;
+        };
+
+        let leftovers = self.server.players.update(entity_id, |player| {
+            let leftovers = player.inventory.close();
+            player.send(player.inventory.window_items_packet());
+            leftovers
+        });
+
+        if let Some(player) = self.server.players.get(entity_id) {
+            for item in leftovers.unwrap_or_default() {
+                self.throw_item(&player, item);
+            }
+        }
+    }
+
+    /// Sets the slot of the creative player's inventory, or throws the item out.
+    fn set_creative_slot(&self, action: CreativeInventoryActionPayload) {
+        let Some(player) = self.entity_id.and_then(|id| self.server.players.get(id)) else {
+            return;
+        };
+
+        if player.game_mode != CREATIVE {
+            debug!(
+                "Ignoring creative inventory action from {} in survival",
+                player.username
+            );
+            player.send(player.inventory.window_items_packet());
+            return;
+        }
+
+        let valid = action
+            .clicked_item
+            .item()
+            .is_none_or(|item| item.id > 0 && (1..=max_stack_size(item)).contains(&item.count));
+        if !valid {
+            player.send(player.inventory.set_slot_packet(action.slot));
+            return;
+        }
+
+        if action.slot == -1 {
+            if let Some(item) = action.clicked_item.0 {
+                self.throw_item(&player, item);
+            }
+            return;
+        }
+
+        self.server.players.update(player.base.entity_id, |player| {
+            player.inventory.set_slot(action.slot, action.clicked_item);
+        });
+    }
+
+    /// Takes the placed item out of the player's hand, unless it's in creative.
+    fn take_held_item(&self, player: &Player) {
+        if player.game_mode != SURVIVAL {
+            return;
+        }
+
+        let held_slot = HOTBAR_SLOT_OFFSET + player.held_slot;
+        self.server.players.update(player.base.entity_id, |player| {
+            player.inventory.take_one(held_slot);
+            player.send(player.inventory.set_slot_packet(held_slot));
+        });
+    }
+
+    /// Sets the placed block, returns `false` if the position is taken
+    /// by a solid block or the player itself.
+    fn set_placed_block(&self, player: &Player, pos: BlockPos, block: Block) -> bool {
+        let feet = BlockPos::new(
+            player.base.position.x.floor() as i32,
+            player.base.position.y.floor() as i32,
+            player.base.position.z.floor() as i32,
+        );
+        if pos == feet || pos == feet.offset(0, 1, 0) {
+            return false;
+        }
+
+        let mut world = self.world().write().unwrap();
+        let replaceable = world
+            .get_block(pos.x, pos.y, pos.z)
+            .is_some_and(|current| current.is_replaceable());
+
+        replaceable && world.set_block(pos.x, pos.y, pos.z, block).is_some()
+    }
+
+    /// Sends the actual blocks to the player, undoing what its client predicted.
+    fn resend_blocks(&self, player: &Player, positions: &[BlockPos]) {
+        for &pos in positions {
+            if let Some(packet) = self.server.block_change(self.dimension, pos) {
+                player.send(packet);
+            }
+        }
+    }
+
+    /// Updates the registered position of the player, hurting it once it lands after a fall.
+    ///
+    /// The moves too far or out of the world are rejected, the player's client is moved back
+    /// to the registered position instead.
+    ///
+    /// Returns the updated position, `None` if the player isn't logged in or the move is rejected.
+    async fn update_position(
+        &mut self,
+        f: impl FnOnce(&mut Position),
+    ) -> Result<Option<Position>, ProtocolError> {
+        let Some(entity_id) = self.entity_id else {
+            return Ok(None);
+        };
+
+        let updated = {
+            let world = self.world().read().unwrap();
+            self.server.players.update(entity_id, |player| {
+                let previous = player.base.position;
+                f(&mut player.base.position);
+
+                if !is_valid_move(&previous, &player.base.position) {
+                    player.base.position = previous;
+                    return Err(previous);
+                }
+
+                Ok(land(player, &world, &previous))
+            })
+        };
+
+        let (position, fall_damage) = match updated {
+            Some(Ok(updated)) => updated,
+            Some(Err(previous)) => {
+                warn!("{:?} moved wrongly, moving them back", &self.addr);
+                self.send(position_packet(&previous)).await?;
+                return Ok(None);
+            }
+            None => return Ok(None),
+        };
+
+        let killed = fall_damage.is_some_and(|damage| {
+            health::hurt(&self.server.players, entity_id, damage) == Hurt::Killed
+        });
+        if killed {
+            let _ = self.server.events.send(ServerEvent::PlayerDied {
+                entity_id,
+                cause: DamageCause::Fall,
+            });
+        }
+
+        Ok(Some(position))
+    }
+}
+
+/// Whether the player may move from the previous position to the position with a single packet,
+/// not too far and staying within the world.
+fn is_valid_move(previous: &Position, position: &Position) -> bool {
+    let coordinates = [position.x, position.y, position.z];
+    coordinates.iter().all(|coordinate| coordinate.is_finite())
+        && position.x.abs() <= MAX_COORDINATE
+        && position.z.abs() <= MAX_COORDINATE
+        && previous.distance_squared(position.x, position.y, position.z) <= MAX_MOVE_SQUARED
+}
+
+/// Exhausts the player moved from the previous position and lands it after a fall,
+/// returns its position with the damage of the fall.
+fn land(player: &mut Player, world: &World, previous: &Position) -> (Position, Option<i16>) {
+    let position = player.base.position;
+    let feet = world.get_block(
+        position.x.floor() as i32,
+        position.y.floor() as i32,
+        position.z.floor() as i32,
+    );
+    let in_water = feet.is_some_and(|block| block.is_water());
+    let in_liquid = in_water || feet.is_some_and(|block| block.is_lava());
+
+    if player.game_mode == SURVIVAL {
+        let sprinting = player.sprinting;
+        player
+            .health
+            .exhaust_movement(previous, &position, in_water, sprinting);
+    }
+
+    let fall_damage = player
+        .health
+        .fall(position.y - previous.y, position.on_ground, in_liquid);
+    (position, fall_damage)
+}
+
+/// Builds the packet moving the player's client to the position.
+fn position_packet(position: &Position) -> Packet {
+    Packet::PlayerPositionAndLook(PlayerPositionAndLookPayload {
+        x: position.x,
+        stance_y_0: position.y + EYE_HEIGHT,
+        stance_y_1: position.y,
+        z: position.z,
+        yaw: position.yaw,
+        pitch: position.pitch,
+        on_ground: 0,
+    })
+}
+
+/// Kicks the Beta client, after its handshake has been received, with the reason
+/// encoded the way it can show it.
+///
+/// The Beta clients can't play in the 1.2.5 worlds, only their handshake is understood
+/// to tell them apart from the 1.2.5 clients on the same port.
+async fn reject_beta_client(socket: Framed<TcpStream, PacketCodec>, addr: SocketAddr) {
+    info!(
+        "Rejecting the {} client of {:?}",
+        Beta17::GAME_VERSION,
+        &addr
+    );
+
+    let mut socket = socket.map_codec(|_| PacketCodec::<Beta17>::default());
+    let reason = format!("Outdated client! Please use {}", V29::GAME_VERSION);
+    if let Err(e) = socket
+        .send(beta17::Packet::DisconnectKick(DisconnectKickPayload {
+            reason,
+        }))
+        .await
+    {
+        error!("Failed to send packet to {:?}: {}", &addr, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use protocol::packet::ServerListPingPayload;
+
+    use super::*;
+
+    #[test]
+    fn state_transitions() {
+        let handshake = Packet::Handshake(HandshakePayload {
+            data: "ezioleq;localhost:25565".to_string(),
+        });
+        let keep_alive = Packet::KeepAlive(KeepAlivePayload { keep_alive_id: 0 });
+
+        assert_eq!(
+            ConnectionState::Handshake.transition(&handshake),
+            Ok(ConnectionState::Login)
+        );
+        assert_eq!(
+            ConnectionState::Handshake.transition(&Packet::ServerListPing(ServerListPingPayload)),
+            Ok(ConnectionState::Handshake)
+        );
+        assert!(ConnectionState::Login.transition(&keep_alive).is_err());
+        assert!(ConnectionState::Play.transition(&handshake).is_err());
+        assert_eq!(
+            ConnectionState::Play.transition(&keep_alive),
+            Ok(ConnectionState::Play)
+        );
+    }
+
+    #[test]
+    fn server_to_client_packets_are_rejected() {
+        let spawn = Packet::SpawnPosition(SpawnPositionPayload { x: 0, y: 0, z: 0 });
+
+        assert!(ConnectionState::Play.transition(&spawn).is_err());
+    }
+
+    #[test]
+    fn moves_too_far_are_rejected() {
+        let previous = Position {
+            x: 0.5,
+            y: 64.0,
+            z: 0.5,
+            ..Default::default()
+        };
+        let at = |x, y, z| Position {
+            x,
+            y,
+            z,
+            ..previous
+        };
+
+        assert!(is_valid_move(&previous, &at(4.5, 66.0, -2.5)));
+        assert!(!is_valid_move(&previous, &at(40.5, 64.0, 0.5)));
+        assert!(!is_valid_move(&previous, &at(0.5, f64::NAN, 0.5)));
+
+        let edge = at(3.2e7, 64.0, 0.5);
+        assert!(!is_valid_move(&edge, &at(3.2e7 + 1.0, 64.0, 0.5)));
+    }
+}
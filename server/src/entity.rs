@@ -0,0 +1,302 @@
+use std::{
+    any::Any,
+    collections::HashMap,
+    fmt::Debug,
+    sync::atomic::{AtomicI32, Ordering},
+};
+
+use protocol::{metadata::Metadata, packet::Packet};
+
+use crate::world::{chunk::ChunkPos, World};
+
+/// Hands out the identifiers of all the entities, the players, items, mobs and objects.
+///
+/// Identifiers aren't reused, the clients may still refer to the removed entities.
+#[derive(Debug)]
+pub struct EntityIdAllocator {
+    next: AtomicI32,
+}
+
+impl Default for EntityIdAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EntityIdAllocator {
+    pub fn new() -> Self {
+        Self {
+            next: AtomicI32::new(1),
+        }
+    }
+
+    /// Allocates a new unique entity identifier.
+    pub fn allocate(&self) -> i32 {
+        self.next.fetch_add(1, Ordering::Relaxed)
+    }
+}
+
+/// Converts the coordinate into the fixed-point number the entity packets carry.
+pub fn fixed_point(coordinate: f64) -> i32 {
+    (coordinate * 32.0).floor() as i32
+}
+
+/// Converts the angle in degrees into the steps of 1/256 of a full turn.
+pub fn angle_steps(degrees: f32) -> i8 {
+    (degrees.rem_euclid(360.0) * 256.0 / 360.0) as i32 as i8
+}
+
+/// Position and rotation of an entity.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Position {
+    pub x: f64,
+    /// Feet position.
+    pub y: f64,
+    pub z: f64,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub on_ground: bool,
+}
+
+impl Position {
+    /// Column the position is in.
+    pub fn chunk(&self) -> ChunkPos {
+        ChunkPos::of_block(self.x.floor() as i32, self.z.floor() as i32)
+    }
+
+    /// Squared distance to the point, ignoring the rotation.
+    pub fn distance_squared(&self, x: f64, y: f64, z: f64) -> f64 {
+        (self.x - x).powi(2) + (self.y - y).powi(2) + (self.z - z).powi(2)
+    }
+}
+
+/// Size of the entity's box colliding with the world and the other entities,
+/// centered horizontally on the position and standing on it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl BoundingBox {
+    pub const fn new(width: f64, height: f64) -> Self {
+        Self { width, height }
+    }
+}
+
+/// Components shared by all the entities.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntityBase {
+    pub entity_id: i32,
+    pub position: Position,
+
+    /// Velocity in blocks per tick.
+    pub velocity: (f64, f64, f64),
+
+    pub bounding_box: BoundingBox,
+
+    /// Values shown by the clients, e.g. whether the entity is on fire.
+    pub metadata: Metadata,
+
+    /// Number of ticks since the entity was spawned.
+    pub age: u64,
+}
+
+impl EntityBase {
+    pub fn new(entity_id: i32, position: Position, bounding_box: BoundingBox) -> Self {
+        Self {
+            entity_id,
+            position,
+            velocity: (0.0, 0.0, 0.0),
+            bounding_box,
+            metadata: Metadata::new(),
+            age: 0,
+        }
+    }
+
+    /// Whether the bounding boxes overlap, with this one grown by the reach horizontally.
+    pub fn touches(&self, other: &EntityBase, reach: f64) -> bool {
+        let (a, b) = (&self.position, &other.position);
+        let half_widths = (self.bounding_box.width + other.bounding_box.width) / 2.0 + reach;
+
+        (a.x - b.x).abs() <= half_widths
+            && (a.z - b.z).abs() <= half_widths
+            && a.y <= b.y + other.bounding_box.height
+            && b.y <= a.y + self.bounding_box.height
+    }
+
+    /// Moves the entity by its velocity, pulled down by the gravity and slowed down
+    /// by the drag, landing on the solid blocks.
+    ///
+    /// Only the blocks below stop the entity, it slides through the walls.
+    pub fn apply_motion(&mut self, world: &World, gravity: f64, drag: f64, friction: f64) {
+        let (vx, vy, vz) = self.velocity;
+        let vy = vy - gravity;
+        let position = &mut self.position;
+        let (x, y, z) = (position.x + vx, position.y + vy, position.z + vz);
+
+        let landed = vy < 0.0
+            && world
+                .get_block(x.floor() as i32, y.floor() as i32, z.floor() as i32)
+                .is_some_and(|block| !block.is_replaceable());
+
+        (position.x, position.z) = (x, z);
+        position.on_ground = landed;
+
+        if landed {
+            position.y = y.floor() + 1.0;
+            self.velocity = (vx * drag * friction, 0.0, vz * drag * friction);
+        } else {
+            position.y = y;
+            self.velocity = (vx * drag, vy * drag, vz * drag);
+        }
+    }
+}
+
+/// An entity living in the world, e.g. a player, a dropped item or a mob.
+pub trait Entity: Debug + Send + Sync {
+    fn base(&self) -> &EntityBase;
+
+    fn base_mut(&mut self) -> &mut EntityBase;
+
+    /// Builds the packet spawning the entity on the clients.
+    fn spawn_packet(&self) -> Packet;
+
+    /// Advances the entity by a single tick.
+    fn tick(&mut self, _world: &World) {
+        self.base_mut().age += 1;
+    }
+
+    /// Whether the entity is gone and should be removed from the world, e.g. has despawned.
+    fn is_removed(&self) -> bool {
+        false
+    }
+
+    /// Allows the code handling a specific kind of entities to downcast it.
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+/// Entities ticked by the server, keyed by the entity identifier.
+///
+/// The players are kept by the `Players` registry, shared with their connections.
+#[derive(Debug, Default)]
+pub struct Entities {
+    entities: HashMap<i32, Box<dyn Entity>>,
+}
+
+impl Entities {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the entity, replacing the one with the same identifier.
+    pub fn add(&mut self, entity: Box<dyn Entity>) {
+        self.entities.insert(entity.base().entity_id, entity);
+    }
+
+    pub fn remove(&mut self, entity_id: i32) -> Option<Box<dyn Entity>> {
+        self.entities.remove(&entity_id)
+    }
+
+    pub fn get(&self, entity_id: i32) -> Option<&dyn Entity> {
+        self.entities.get(&entity_id).map(|entity| entity.as_ref())
+    }
+
+    pub fn get_mut(&mut self, entity_id: i32) -> Option<&mut Box<dyn Entity>> {
+        self.entities.get_mut(&entity_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Entity> {
+        self.entities.values().map(|entity| entity.as_ref())
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Box<dyn Entity>> {
+        self.entities.values_mut()
+    }
+
+    /// Ticks the entities in the loaded columns, the others wait for their columns
+    /// to be loaded again rather than fall through the missing blocks.
+    pub fn tick(&mut self, world: &World) {
+        for entity in self.entities.values_mut() {
+            if world.is_loaded(entity.base().position.chunk()) {
+                entity.tick(world);
+            }
+        }
+    }
+
+    /// Removes the entities which are gone and returns them.
+    pub fn remove_gone(&mut self) -> Vec<Box<dyn Entity>> {
+        let removed: Vec<i32> = self
+            .entities
+            .iter()
+            .filter(|(_, entity)| entity.is_removed())
+            .map(|(&entity_id, _)| entity_id)
+            .collect();
+
+        removed
+            .into_iter()
+            .filter_map(|entity_id| self.entities.remove(&entity_id))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, sync::Arc, thread};
+
+    use super::*;
+
+    #[test]
+    fn allocate_unique_ids_across_threads() {
+        let allocator = Arc::new(EntityIdAllocator::new());
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let allocator = allocator.clone();
+                thread::spawn(move || (0..100).map(|_| allocator.allocate()).collect::<Vec<_>>())
+            })
+            .collect();
+
+        let ids: HashSet<i32> = handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect();
+
+        assert_eq!(ids.len(), 400);
+        assert!(!ids.contains(&0));
+    }
+
+    #[test]
+    fn bounding_boxes_touch() {
+        let at = |x, y, z| Position {
+            x,
+            y,
+            z,
+            ..Default::default()
+        };
+        let player = EntityBase::new(1, at(0.5, 64.0, 0.5), BoundingBox::new(0.6, 1.8));
+        let item = EntityBase::new(2, at(1.5, 65.0, 0.5), BoundingBox::new(0.25, 0.25));
+
+        assert!(player.touches(&item, 1.0));
+        assert!(!player.touches(&item, 0.0));
+
+        let above = EntityBase::new(3, at(0.5, 66.0, 0.5), BoundingBox::new(0.25, 0.25));
+        assert!(!player.touches(&above, 1.0));
+    }
+
+    #[test]
+    fn angles_wrap_into_steps() {
+        assert_eq!(angle_steps(0.0), 0);
+        assert_eq!(angle_steps(90.0), 64);
+        assert_eq!(angle_steps(-90.0), -64);
+        assert_eq!(fixed_point(-0.5), -16);
+    }
+}
@@ -0,0 +1,306 @@
+use std::any::Any;
+
+use protocol::packet::{ExperienceOrbPayload, Packet, SetExperiencePayload};
+
+use crate::{
+    entity::{fixed_point, BoundingBox, Entity, EntityBase, EntityIdAllocator, Position},
+    player::EYE_HEIGHT,
+    world::{block::Block, World},
+};
+
+/// Number of ticks the orb lies in the world before it despawns, 5 minutes.
+pub const ORB_DESPAWN_TICKS: u64 = 6000;
+
+/// Most experience the dying players drop.
+const MAX_DEATH_EXPERIENCE: i32 = 100;
+
+/// Experience dropped by the dying player per its level.
+const DEATH_EXPERIENCE_PER_LEVEL: i32 = 7;
+
+/// Values of the orbs the experience is split into, the largest first.
+const ORB_VALUES: [i16; 11] = [2477, 1237, 617, 307, 149, 73, 37, 17, 7, 3, 1];
+
+/// Bounding box of the experience orbs.
+const ORB_BOUNDING_BOX: BoundingBox = BoundingBox::new(0.5, 0.5);
+
+/// Distance the orbs are drawn towards the players from.
+const ATTRACTION_RANGE: f64 = 8.0;
+
+/// How fast the orbs speed up towards the players right next to them.
+const ATTRACTION_SPEED: f64 = 0.1;
+
+/// How far around the player's bounding box the orbs get collected, horizontally.
+const PICKUP_REACH: f64 = 1.0;
+
+/// Speed the orb falls faster by each tick.
+const GRAVITY: f64 = 0.03;
+
+/// Share of the velocity the orb keeps each tick.
+const DRAG: f64 = 0.98;
+
+/// Share of the horizontal velocity the orb keeps each tick it slides on the ground.
+const GROUND_FRICTION: f64 = 0.6;
+
+/// Experience level and progress of a player.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Experience {
+    pub level: i32,
+
+    /// Experience collected towards the next level.
+    pub points: i32,
+
+    /// Experience collected since the last death.
+    pub total: i32,
+}
+
+impl Experience {
+    /// Experience needed to advance from the level to the next one.
+    pub fn level_cost(level: i32) -> i32 {
+        if level >= 30 {
+            62 + (level - 30) * 7
+        } else if level >= 15 {
+            17 + (level - 15) * 3
+        } else {
+            17
+        }
+    }
+
+    /// Collects the experience, advancing the levels once enough is collected.
+    pub fn add(&mut self, amount: i32) {
+        self.total = self.total.saturating_add(amount);
+        self.points += amount;
+
+        while self.points >= Self::level_cost(self.level) {
+            self.points -= Self::level_cost(self.level);
+            self.level += 1;
+        }
+    }
+
+    /// Experience dropped by the player dying with this experience.
+    pub fn dropped_on_death(&self) -> i32 {
+        (self.level * DEATH_EXPERIENCE_PER_LEVEL).min(MAX_DEATH_EXPERIENCE)
+    }
+
+    /// Builds the packet updating the experience bar shown by the player's client.
+    pub fn update_packet(&self) -> Packet {
+        let clamp = |value: i32| value.clamp(0, i16::MAX.into()) as i16;
+
+        Packet::SetExperience(SetExperiencePayload {
+            experience_bar: self.points as f32 / Self::level_cost(self.level) as f32,
+            level: clamp(self.level),
+            total_experience: clamp(self.total),
+        })
+    }
+}
+
+/// Experience dropped by breaking the block, `0` for all but the ores and the spawners.
+pub fn block_experience(block: Block) -> i32 {
+    let range = match block.id {
+        16 => 0..=2,
+        21 => 2..=5,
+        52 => 15..=43,
+        56 => 3..=7,
+        73 | 74 => 1..=5,
+        _ => return 0,
+    };
+
+    rand::random_range(range)
+}
+
+/// Splits the experience into the values of the orbs it's dropped as.
+pub fn split_into_orbs(mut experience: i32) -> Vec<i16> {
+    let mut orbs = Vec::new();
+
+    while experience > 0 {
+        let value = ORB_VALUES
+            .into_iter()
+            .find(|&value| i32::from(value) <= experience)
+            .unwrap_or(1);
+
+        experience -= i32::from(value);
+        orbs.push(value);
+    }
+
+    orbs
+}
+
+/// Creates the orbs the experience is dropped as at the position.
+pub fn drop_orbs(
+    entity_ids: &EntityIdAllocator,
+    experience: i32,
+    x: f64,
+    y: f64,
+    z: f64,
+) -> Vec<ExperienceOrb> {
+    split_into_orbs(experience)
+        .into_iter()
+        .map(|value| ExperienceOrb::new(entity_ids.allocate(), value, x, y, z))
+        .collect()
+}
+
+/// An orb of experience lying in the world, drawn towards the nearby players.
+///
+/// The clients move the orbs towards the players on their own,
+/// so only the spawn and the collection are sent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExperienceOrb {
+    pub base: EntityBase,
+
+    /// Experience given to the player collecting the orb, `0` once collected.
+    pub value: i16,
+}
+
+impl ExperienceOrb {
+    /// Creates the orb popping out at the position in a random direction.
+    pub fn new(entity_id: i32, value: i16, x: f64, y: f64, z: f64) -> Self {
+        let position = Position {
+            x,
+            y,
+            z,
+            ..Default::default()
+        };
+
+        let mut base = EntityBase::new(entity_id, position, ORB_BOUNDING_BOX);
+        base.velocity = (
+            rand::random::<f64>() * 0.4 - 0.2,
+            rand::random::<f64>() * 0.4,
+            rand::random::<f64>() * 0.4 - 0.2,
+        );
+
+        Self { base, value }
+    }
+
+    /// Speeds the orb up towards the player, the more the closer it is.
+    pub fn attract(&mut self, player: &Position) {
+        let position = &self.base.position;
+        let (dx, dy, dz) = (
+            (player.x - position.x) / ATTRACTION_RANGE,
+            (player.y + EYE_HEIGHT / 2.0 - position.y) / ATTRACTION_RANGE,
+            (player.z - position.z) / ATTRACTION_RANGE,
+        );
+        let distance = (dx * dx + dy * dy + dz * dz).sqrt();
+
+        let pull = 1.0 - distance;
+        if pull <= 0.0 || distance < f64::EPSILON {
+            return;
+        }
+
+        let speed = pull * pull * ATTRACTION_SPEED / distance;
+        let velocity = &mut self.base.velocity;
+        velocity.0 += dx * speed;
+        velocity.1 += dy * speed;
+        velocity.2 += dz * speed;
+    }
+
+    /// Whether the player is close enough to be drawn in.
+    pub fn attracts(&self, player: &Position) -> bool {
+        let position = &self.base.position;
+        player.distance_squared(position.x, position.y, position.z)
+            < ATTRACTION_RANGE * ATTRACTION_RANGE
+    }
+
+    /// Whether the player collects the orb.
+    pub fn touches(&self, player: &EntityBase) -> bool {
+        player.touches(&self.base, PICKUP_REACH)
+    }
+}
+
+impl Entity for ExperienceOrb {
+    fn base(&self) -> &EntityBase {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut EntityBase {
+        &mut self.base
+    }
+
+    fn spawn_packet(&self) -> Packet {
+        let position = &self.base.position;
+
+        Packet::ExperienceOrb(ExperienceOrbPayload {
+            entity_id: self.base.entity_id,
+            x: fixed_point(position.x),
+            y: fixed_point(position.y),
+            z: fixed_point(position.z),
+            count: self.value,
+        })
+    }
+
+    /// Moves the orb by a single tick, letting it fall onto the solid blocks.
+    fn tick(&mut self, world: &World) {
+        self.base.age += 1;
+        self.base
+            .apply_motion(world, GRAVITY, DRAG, GROUND_FRICTION);
+    }
+
+    fn is_removed(&self) -> bool {
+        self.base.age >= ORB_DESPAWN_TICKS || self.value <= 0
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_advance() {
+        let mut experience = Experience::default();
+
+        experience.add(16);
+        assert_eq!((experience.level, experience.points), (0, 16));
+
+        experience.add(20);
+        assert_eq!((experience.level, experience.points), (2, 2));
+        assert_eq!(experience.total, 36);
+
+        experience.add(17 * 13);
+        assert_eq!((experience.level, experience.points), (15, 2));
+        assert_eq!(Experience::level_cost(15), 17);
+        assert_eq!(Experience::level_cost(16), 20);
+        assert_eq!(Experience::level_cost(31), 69);
+
+        assert_eq!(experience.dropped_on_death(), 100);
+        assert_eq!(
+            Experience {
+                level: 2,
+                ..Default::default()
+            }
+            .dropped_on_death(),
+            14
+        );
+    }
+
+    #[test]
+    fn split_experience_into_orbs() {
+        assert_eq!(split_into_orbs(0), Vec::<i16>::new());
+        assert_eq!(split_into_orbs(1), vec![1]);
+        assert_eq!(split_into_orbs(25), vec![17, 7, 1]);
+        assert_eq!(split_into_orbs(100), vec![73, 17, 7, 3]);
+    }
+
+    #[test]
+    fn orbs_are_drawn_to_players() {
+        let mut orb = ExperienceOrb::new(2, 1, 0.5, 64.0, 0.5);
+        orb.base.velocity = (0.0, 0.0, 0.0);
+
+        let far = Position {
+            x: 10.5,
+            y: 64.0,
+            z: 0.5,
+            ..Default::default()
+        };
+        assert!(!orb.attracts(&far));
+        orb.attract(&far);
+        assert_eq!(orb.base.velocity, (0.0, 0.0, 0.0));
+
+        let near = Position { x: 4.5, ..far };
+        assert!(orb.attracts(&near));
+        orb.attract(&near);
+        assert!(orb.base.velocity.0 > 0.0);
+        assert_eq!(orb.base.velocity.2, 0.0);
+    }
+}
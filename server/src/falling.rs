@@ -0,0 +1,211 @@
+use std::any::Any;
+
+use protocol::packet::{AddObjectPayload, Packet};
+
+use crate::{
+    entity::{fixed_point, BoundingBox, Entity, EntityBase, EntityIdAllocator, Position},
+    world::{block::Block, BlockPos, World},
+};
+
+/// Object type of the falling sand.
+const FALLING_SAND_OBJECT: i8 = 70;
+
+/// Object type of the falling gravel.
+const FALLING_GRAVEL_OBJECT: i8 = 71;
+
+/// Bounding box of the falling blocks, a bit smaller than a block.
+const FALLING_BLOCK_BOUNDING_BOX: BoundingBox = BoundingBox::new(0.98, 0.98);
+
+/// Speed the block falls faster by each tick.
+const GRAVITY: f64 = 0.04;
+
+/// Share of the velocity the block keeps each tick.
+const DRAG: f64 = 0.98;
+
+/// Whether the falling blocks fall through the block below them when it's removed,
+/// e.g. air or a fluid.
+fn falls_through(block: Block) -> bool {
+    block.is_air() || block.id == Block::FIRE.id || block.is_water() || block.is_lava()
+}
+
+/// A block of sand or gravel falling down, placed back as a block once it lands.
+///
+/// The position is the center of the block, as the clients expect it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FallingBlock {
+    pub base: EntityBase,
+    pub block: Block,
+
+    /// Block the falling block has landed in, to be placed there.
+    pub landed: Option<BlockPos>,
+}
+
+impl FallingBlock {
+    /// Creates the block starting to fall from the position.
+    pub fn new(entity_id: i32, block: Block, pos: BlockPos) -> Self {
+        let position = Position {
+            x: pos.x as f64 + 0.5,
+            y: pos.y as f64 + 0.5,
+            z: pos.z as f64 + 0.5,
+            ..Default::default()
+        };
+
+        Self {
+            base: EntityBase::new(entity_id, position, FALLING_BLOCK_BOUNDING_BOX),
+            block,
+            landed: None,
+        }
+    }
+}
+
+impl Entity for FallingBlock {
+    fn base(&self) -> &EntityBase {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut EntityBase {
+        &mut self.base
+    }
+
+    fn spawn_packet(&self) -> Packet {
+        let position = &self.base.position;
+        let object_type = if self.block.id == Block::GRAVEL.id {
+            FALLING_GRAVEL_OBJECT
+        } else {
+            FALLING_SAND_OBJECT
+        };
+
+        Packet::AddObject(AddObjectPayload {
+            entity_id: self.base.entity_id,
+            object_type,
+            x: fixed_point(position.x),
+            y: fixed_point(position.y),
+            z: fixed_point(position.z),
+            thrower: None,
+        })
+    }
+
+    /// Moves the block down by a single tick, landing it on the solid blocks.
+    fn tick(&mut self, world: &World) {
+        self.base.age += 1;
+        if self.landed.is_some() {
+            return;
+        }
+
+        let position = &mut self.base.position;
+        let vy = self.base.velocity.1 - GRAVITY;
+        let y = position.y + vy;
+        let (x, z) = (position.x.floor() as i32, position.z.floor() as i32);
+        let below = (y - 0.5).floor() as i32;
+
+        let landed = world
+            .get_block(x, below, z)
+            .is_some_and(|block| block.is_solid());
+
+        if landed {
+            position.y = below as f64 + 1.5;
+            position.on_ground = true;
+            self.base.velocity.1 = 0.0;
+            self.landed = Some(BlockPos::new(x, below + 1, z));
+        } else {
+            position.y = y;
+            self.base.velocity.1 = vy * DRAG;
+        }
+    }
+
+    /// The block is gone once it lands or falls out of the world.
+    fn is_removed(&self) -> bool {
+        self.landed.is_some() || self.base.position.y < 0.0
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Starts the sand and gravel at the position and the ones stacked on it falling,
+/// if there's nothing below them, clearing their blocks.
+///
+/// Returns the falling blocks with the positions they've fallen from.
+pub fn start_falling(
+    world: &mut World,
+    entity_ids: &EntityIdAllocator,
+    pos: BlockPos,
+) -> Vec<(BlockPos, FallingBlock)> {
+    let mut falling = Vec::new();
+    let mut pos = pos;
+
+    while let Some(block) = world.get_block(pos.x, pos.y, pos.z).filter(Block::falls) {
+        let below = world.get_block(pos.x, pos.y - 1, pos.z);
+        if !below.is_some_and(falls_through) {
+            break;
+        }
+
+        world.set_block(pos.x, pos.y, pos.z, Block::AIR);
+        falling.push((pos, FallingBlock::new(entity_ids.allocate(), block, pos)));
+        pos = pos.offset(0, 1, 0);
+    }
+
+    falling
+}
+
+/// Places the landed block in the world, returns `false` if its place is taken,
+/// e.g. by a torch, and it breaks into an item instead.
+pub fn land(world: &mut World, block: Block, pos: BlockPos) -> bool {
+    let replaceable = world
+        .get_block(pos.x, pos.y, pos.z)
+        .is_some_and(|current| current.is_replaceable());
+
+    replaceable && world.set_block(pos.x, pos.y, pos.z, block).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::world::chunk::{ChunkColumn, ChunkPos};
+
+    use super::*;
+
+    #[test]
+    fn stacked_sand_falls_and_lands() {
+        let mut world = World::new();
+        world.insert_chunk(ChunkColumn::new(ChunkPos::new(0, 0)));
+        world.set_block(2, 60, 2, Block::STONE);
+        world.set_block(2, 66, 2, Block::SAND);
+        world.set_block(2, 67, 2, Block::GRAVEL);
+        world.set_block(2, 68, 2, Block::DIRT);
+
+        let entity_ids = EntityIdAllocator::new();
+        let falling = start_falling(&mut world, &entity_ids, BlockPos::new(2, 66, 2));
+        assert_eq!(falling.len(), 2);
+        assert_eq!(falling[1].0, BlockPos::new(2, 67, 2));
+        assert_eq!(world.get_block(2, 66, 2), Some(Block::AIR));
+        assert_eq!(world.get_block(2, 67, 2), Some(Block::AIR));
+
+        let (_, mut sand) = falling.into_iter().next().unwrap();
+        for _ in 0..100 {
+            sand.tick(&world);
+        }
+        assert_eq!(sand.landed, Some(BlockPos::new(2, 61, 2)));
+        assert_eq!(sand.base.position.y, 61.5);
+        assert!(sand.is_removed());
+
+        assert!(land(&mut world, sand.block, BlockPos::new(2, 61, 2)));
+        assert_eq!(world.get_block(2, 61, 2), Some(Block::SAND));
+    }
+
+    #[test]
+    fn supported_sand_stays() {
+        let mut world = World::new();
+        world.insert_chunk(ChunkColumn::new(ChunkPos::new(0, 0)));
+        world.set_block(2, 65, 2, Block::STONE);
+        world.set_block(2, 66, 2, Block::SAND);
+        world.set_block(3, 66, 3, Block::with_metadata(50, 5));
+
+        let entity_ids = EntityIdAllocator::new();
+        assert!(start_falling(&mut world, &entity_ids, BlockPos::new(2, 66, 2)).is_empty());
+        assert_eq!(world.get_block(2, 66, 2), Some(Block::SAND));
+
+        // The torch doesn't hold the sand, which breaks on it.
+        assert!(!land(&mut world, Block::SAND, BlockPos::new(3, 66, 3)));
+    }
+}
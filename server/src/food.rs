@@ -0,0 +1,108 @@
+use protocol::{
+    packet::{EntityStatusPayload, Packet},
+    slot::{ItemStack, Slot},
+};
+
+use crate::{inventory::HOTBAR_SLOT_OFFSET, player::Player};
+
+/// Number of ticks the players eat the food for.
+pub const EATING_TICKS: u32 = 32;
+
+/// Golden apple, the only food eaten with the full food level.
+const GOLDEN_APPLE: i16 = 322;
+
+/// Mushroom stew, leaving the bowl once eaten.
+const MUSHROOM_STEW: i16 = 282;
+
+/// Bowl left by the mushroom stew.
+const BOWL: i16 = 281;
+
+/// Entity status of the eating being accepted.
+const EATEN_STATUS: i8 = 9;
+
+/// Food level restored by eating the item and its saturation modifier,
+/// `None` if the item isn't food.
+pub fn food_value(id: i16) -> Option<(i16, f32)> {
+    let food = match id {
+        260 => (4, 0.3),
+        282 => (6, 0.6),
+        297 => (5, 0.6),
+        319 => (3, 0.3),
+        320 => (8, 0.8),
+        322 => (4, 1.2),
+        349 => (2, 0.3),
+        350 => (5, 0.6),
+        357 => (1, 0.1),
+        360 => (2, 0.3),
+        363 => (3, 0.3),
+        364 => (8, 0.8),
+        365 => (2, 0.3),
+        366 => (6, 0.6),
+        367 => (4, 0.1),
+        375 => (2, 0.8),
+        _ => return None,
+    };
+
+    Some(food)
+}
+
+/// Whether the player with the food level may start eating the item.
+pub fn can_eat(item: &ItemStack, food: i16, max_food: i16) -> bool {
+    food_value(item.id).is_some() && (food < max_food || item.id == GOLDEN_APPLE)
+}
+
+/// Item left in the hand once the food is eaten, e.g. the bowl of the stew.
+pub fn leftover(id: i16) -> Option<ItemStack> {
+    (id == MUSHROOM_STEW).then(|| ItemStack::new(BOWL, 1, 0))
+}
+
+/// Advances the eating of the held food, the food is eaten once the time is up.
+pub fn tick_eating(player: &mut Player) {
+    let Some(ticks) = player.eating else {
+        return;
+    };
+
+    if ticks > 1 {
+        player.eating = Some(ticks - 1);
+        return;
+    }
+    player.eating = None;
+
+    let held_slot = HOTBAR_SLOT_OFFSET + player.held_slot;
+    let Some(item) = player.inventory.held_item(player.held_slot).cloned() else {
+        return;
+    };
+    let Some((food, saturation_modifier)) = food_value(item.id) else {
+        return;
+    };
+
+    player.inventory.take_one(held_slot);
+    if let Some(leftover) = leftover(item.id) {
+        player.inventory.set_slot(held_slot, Slot(Some(leftover)));
+    }
+    player.health.eat(food, saturation_modifier);
+
+    player.send(player.inventory.set_slot_packet(held_slot));
+    player.send(Packet::EntityStatus(EntityStatusPayload {
+        entity_id: player.base.entity_id,
+        status: EATEN_STATUS,
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edible_items() {
+        let apple = ItemStack::new(260, 1, 0);
+        let golden_apple = ItemStack::new(GOLDEN_APPLE, 1, 0);
+
+        assert!(can_eat(&apple, 19, 20));
+        assert!(!can_eat(&apple, 20, 20));
+        assert!(can_eat(&golden_apple, 20, 20));
+        assert!(!can_eat(&ItemStack::new(1, 1, 0), 0, 20));
+        assert_eq!(leftover(MUSHROOM_STEW), Some(ItemStack::new(BOWL, 1, 0)));
+        assert_eq!(leftover(260), None);
+    }
+}
@@ -0,0 +1,80 @@
+use protocol::packet::{ChangeGameStatePayload, Packet};
+use serde::{Deserialize, Serialize};
+
+use crate::persist::{NameList, NamedEntry};
+
+/// Game mode the players survive in, taking damage and getting hungry.
+pub const SURVIVAL: i32 = 0;
+
+/// Game mode the players fly and break the blocks instantly in.
+pub const CREATIVE: i32 = 1;
+
+/// Reason of the game state change switching the client's game mode.
+const CHANGE_GAME_MODE_REASON: i8 = 3;
+
+/// Whether the game mode is one the clients support.
+pub fn is_valid(game_mode: i32) -> bool {
+    matches!(game_mode, SURVIVAL | CREATIVE)
+}
+
+/// Builds the packet switching the client to the game mode.
+pub fn change_packet(game_mode: i32) -> Packet {
+    Packet::ChangeGameState(ChangeGameStatePayload {
+        reason: CHANGE_GAME_MODE_REASON,
+        game_mode: game_mode as i8,
+    })
+}
+
+/// Game mode of a player, as stored in the game mode list file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameModeEntry {
+    /// Name of the player.
+    pub name: String,
+
+    pub game_mode: i32,
+}
+
+impl NamedEntry for GameModeEntry {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Game modes set for the players, persisted in a JSON file.
+///
+/// The players without an entry join in the game mode from the config.
+pub type GameModeList = NameList<GameModeEntry>;
+
+impl GameModeList {
+    /// Game mode set for the player, ignoring the case of the name.
+    pub fn game_mode(&self, name: &str) -> Option<i32> {
+        self.get(name).map(|entry| entry.game_mode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn persist_game_modes() {
+        let path = std::env::temp_dir().join(format!("game-modes-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let game_modes = GameModeList::load(&path).unwrap();
+        assert_eq!(game_modes.game_mode("ezioleq"), None);
+
+        game_modes
+            .add(GameModeEntry {
+                name: "Ezioleq".to_string(),
+                game_mode: SURVIVAL,
+            })
+            .await
+            .unwrap();
+
+        let game_modes = GameModeList::load(&path).unwrap();
+        assert_eq!(game_modes.game_mode("EZIOLEQ"), Some(SURVIVAL));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
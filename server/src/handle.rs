@@ -0,0 +1,266 @@
+use std::sync::Arc;
+
+use log::error;
+use protocol::{
+    chunk::{ChunkData, CHUNK_WIDTH},
+    packet::Packet,
+    status::StatusResponse,
+};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    auth::SessionVerifier,
+    ban::BanList,
+    command::CommandRegistry,
+    config::ServerConfig,
+    entity::{Entity, EntityIdAllocator},
+    falling,
+    game_mode::GameModeList,
+    item::ItemEntity,
+    ops::OpList,
+    player::Players,
+    tick::{EventSender, ServerEvent},
+    world::{
+        anvil::AnvilStorage, block_change, block_changed, block_changes, chunk::ChunkPos,
+        dimension::Dimension, generator::Generators, handler::BlockHandlers,
+        tile_entity::TileEntity, BlockChanges, BlockPos, Worlds,
+    },
+};
+
+/// Shared state of the running server, cheap to clone.
+#[derive(Clone)]
+pub struct ServerHandle {
+    pub config: Arc<ServerConfig>,
+    pub players: Arc<Players>,
+
+    /// Identifiers of the players and all the other entities.
+    pub entity_ids: Arc<EntityIdAllocator>,
+
+    /// Queue of the events handled by the tick loop.
+    pub events: EventSender,
+
+    pub commands: Arc<CommandRegistry>,
+
+    /// Players refused at login.
+    pub bans: Arc<BanList>,
+
+    /// Players with the elevated permission level.
+    pub ops: Arc<OpList>,
+
+    /// Game modes set for the players with `/gamemode`.
+    pub game_modes: Arc<GameModeList>,
+
+    /// Verifies the joining users in the online mode.
+    pub sessions: Arc<dyn SessionVerifier>,
+
+    /// Worlds of the overworld, the nether and the end.
+    pub worlds: Arc<Worlds>,
+
+    /// Columns saved in the world directory.
+    pub storage: Arc<AnvilStorage>,
+
+    /// Held while saving, so an older copy of the world can't overwrite a newer one.
+    pub saving: Arc<tokio::sync::Mutex<()>>,
+
+    /// Generate the columns of each dimension which aren't loaded yet.
+    pub generators: Arc<Generators>,
+
+    /// Reactions of the interactive blocks to the players clicking them.
+    pub block_handlers: Arc<BlockHandlers>,
+
+    /// Cancelled to stop the server.
+    pub shutdown: CancellationToken,
+}
+
+impl ServerHandle {
+    /// Builds the status shown in the server list from the configured MOTD
+    /// and the live player count.
+    pub fn status(&self) -> StatusResponse {
+        StatusResponse::new(
+            self.config.motd.as_str(),
+            self.players.count(),
+            self.config.max_players.into(),
+        )
+    }
+
+    /// Loads the column of the dimension from the disk, or generates it
+    /// if it was never saved, unless it's loaded already.
+    pub fn ensure_chunk(&self, dimension: Dimension, pos: ChunkPos) {
+        let world = self.worlds.get(dimension);
+        if world.read().unwrap().is_loaded(pos) {
+            return;
+        }
+
+        // Loaded outside the lock, another task may load it meanwhile.
+        let generator = self.generators.get(dimension);
+        let chunk = match self.storage.load(dimension, pos) {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => generator.generate(pos),
+            Err(e) => {
+                error!(
+                    "Failed to load the column at {}, {} in the {:?}: {}",
+                    pos.x, pos.z, dimension, e
+                );
+                generator.generate(pos)
+            }
+        };
+        let mut world = world.write().unwrap();
+
+        if !world.is_loaded(pos) {
+            world.insert_chunk(chunk);
+        }
+    }
+
+    /// Builds the data of the column of the dimension sent to the clients, loading it first.
+    pub fn chunk_data(&self, dimension: Dimension, pos: ChunkPos) -> ChunkData {
+        // Loaded again if it's unloaded meanwhile, left out of the view of all the players.
+        loop {
+            self.ensure_chunk(dimension, pos);
+
+            let world = self.worlds.get(dimension).read().unwrap();
+            if let Some(chunk) = world.chunk(pos) {
+                return chunk.to_chunk_data();
+            }
+        }
+    }
+
+    /// Builds the packet telling the client the current block at the position
+    /// in the dimension, `None` if its column isn't loaded.
+    pub fn block_change(&self, dimension: Dimension, pos: BlockPos) -> Option<Packet> {
+        let block = self
+            .worlds
+            .get(dimension)
+            .read()
+            .unwrap()
+            .get_block(pos.x, pos.y, pos.z)?;
+
+        Some(block_change(pos, block))
+    }
+
+    /// Sends the current block at the position in the dimension to the players
+    /// having its column in view.
+    pub fn broadcast_block_change(&self, dimension: Dimension, pos: BlockPos) {
+        if let Some(packet) = self.block_change(dimension, pos) {
+            self.broadcast_in_view(dimension, pos.chunk(), packet);
+        }
+    }
+
+    /// Builds the packets showing the text of the signs in the column of the dimension,
+    /// sent after the column itself.
+    pub fn sign_updates(&self, dimension: Dimension, pos: ChunkPos) -> Vec<Packet> {
+        let world = self.worlds.get(dimension).read().unwrap();
+        let Some(chunk) = world.chunk(pos) else {
+            return Vec::new();
+        };
+
+        chunk
+            .tile_entities()
+            .filter_map(|((x, y, z), tile_entity)| match tile_entity {
+                TileEntity::Sign(sign) => Some(sign.update_packet(BlockPos::new(
+                    pos.x * CHUNK_WIDTH as i32 + x as i32,
+                    y as i32,
+                    pos.z * CHUNK_WIDTH as i32 + z as i32,
+                ))),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Sends the text of the sign at the position in the dimension to the players
+    /// having its column in view.
+    pub fn broadcast_sign(&self, dimension: Dimension, pos: BlockPos) {
+        let packet = {
+            let world = self.worlds.get(dimension).read().unwrap();
+            let Some(sign) = world.sign(pos.x, pos.y, pos.z) else {
+                return;
+            };
+            sign.update_packet(pos)
+        };
+
+        self.broadcast_in_view(dimension, pos.chunk(), packet);
+    }
+
+    /// Sends the packet to the players of the dimension having the column in view.
+    fn broadcast_in_view(&self, dimension: Dimension, chunk: ChunkPos, packet: Packet) {
+        let view_distance = i32::from(self.config.view_distance);
+        self.players
+            .broadcast_in_view(dimension, chunk, view_distance, packet);
+    }
+
+    /// Sends the current blocks at the positions in the dimension to the players
+    /// having their columns in view, batched by the column.
+    pub fn broadcast_block_changes(&self, dimension: Dimension, positions: &[BlockPos]) {
+        let packets = block_changes(&self.worlds.get(dimension).read().unwrap(), positions);
+
+        for (chunk, packet) in packets {
+            self.broadcast_in_view(dimension, chunk, packet);
+        }
+    }
+
+    /// Lets the blocks around the changed one in the dimension react to it, the fluids
+    /// flow into its place, the redstone switches and the sand and gravel above fall
+    /// if it no longer holds them.
+    pub fn update_blocks(&self, dimension: Dimension, pos: BlockPos) {
+        let mut changes = BlockChanges::default();
+        let falling = {
+            let mut world = self.worlds.get(dimension).write().unwrap();
+            block_changed(&mut world, pos, &mut changes);
+
+            let mut falling = falling::start_falling(&mut world, &self.entity_ids, pos);
+            let above = pos.offset(0, 1, 0);
+            falling.extend(falling::start_falling(&mut world, &self.entity_ids, above));
+
+            for &(pos, _) in &falling {
+                changes.changed.push(pos);
+                block_changed(&mut world, pos, &mut changes);
+            }
+            falling
+        };
+
+        self.apply_block_changes(dimension, changes);
+        for (_, entity) in falling {
+            self.spawn_entity(dimension, Box::new(entity));
+        }
+    }
+
+    /// Sends the changed blocks of the dimension and the effects played at them
+    /// to the players having them in view and drops the items of the broken ones.
+    pub fn apply_block_changes(&self, dimension: Dimension, changes: BlockChanges) {
+        self.broadcast_block_changes(dimension, &changes.changed);
+        for (pos, packet) in changes.effects {
+            self.broadcast_in_view(dimension, pos.chunk(), packet);
+        }
+
+        for (pos, item) in changes.drops {
+            let item =
+                ItemEntity::dropped_by_block(self.entity_ids.allocate(), item, pos.x, pos.y, pos.z);
+            self.spawn_entity(dimension, Box::new(item));
+        }
+    }
+
+    /// Hands the entity over to the tick loop, spawning it in the world of the dimension.
+    fn spawn_entity(&self, dimension: Dimension, entity: Box<dyn Entity>) {
+        let _ = self
+            .events
+            .send(ServerEvent::EntitySpawned { dimension, entity });
+    }
+
+    /// Block the players spawn at in the overworld, on top of the terrain
+    /// at the configured X and Z.
+    pub fn spawn_point(&self) -> (i32, i32, i32) {
+        let (x, z) = (self.config.spawn_x, self.config.spawn_z);
+        self.ensure_chunk(Dimension::Overworld, ChunkPos::of_block(x, z));
+
+        let world = self.worlds.get(Dimension::Overworld).read().unwrap();
+        let y = (0..256)
+            .rev()
+            .find(|&y| {
+                world
+                    .get_block(x, y, z)
+                    .is_some_and(|block| !block.is_air())
+            })
+            .map_or(0, |y| y + 1);
+
+        (x, y, z)
+    }
+}
@@ -0,0 +1,497 @@
+use protocol::packet::{EntityStatusPayload, Packet, UpdateHealthPayload};
+
+use crate::{
+    entity::Position,
+    game_mode::SURVIVAL,
+    mob::MobKind,
+    player::{Players, EYE_HEIGHT},
+    tracker::PLAYER_TRACKING_RANGE,
+    world::{block::Block, World},
+};
+
+/// Full health of the players, in half-hearts.
+pub const MAX_HEALTH: i16 = 20;
+
+/// Full food level of the players, in half-shanks.
+pub const MAX_FOOD: i16 = 20;
+
+/// Food saturation of the joining players.
+pub const INITIAL_SATURATION: f32 = 5.0;
+
+/// Number of ticks the players can hold their breath under water for.
+pub const MAX_AIR: i16 = 300;
+
+/// Exhaustion which costs a point of the saturation, or the food once it has run out.
+const EXHAUSTION_PER_FOOD: f32 = 4.0;
+
+/// Highest exhaustion the players may accumulate.
+const MAX_EXHAUSTION: f32 = 40.0;
+
+/// Exhaustion of getting hurt.
+pub const DAMAGE_EXHAUSTION: f32 = 0.3;
+
+/// Exhaustion of attacking.
+pub const ATTACK_EXHAUSTION: f32 = 0.3;
+
+/// Exhaustion of breaking a block.
+pub const DIG_EXHAUSTION: f32 = 0.025;
+
+/// Exhaustion of swimming a block.
+const SWIM_EXHAUSTION: f32 = 0.015;
+
+/// Exhaustion of sprinting a block.
+const SPRINT_EXHAUSTION: f32 = 0.1;
+
+/// Exhaustion of jumping.
+const JUMP_EXHAUSTION: f32 = 0.2;
+
+/// Exhaustion of jumping while sprinting.
+const SPRINT_JUMP_EXHAUSTION: f32 = 0.8;
+
+/// Food level the players regenerate the health above.
+const REGENERATION_FOOD: i16 = 18;
+
+/// Number of ticks between the health regenerated or lost to the starvation.
+const FOOD_TICKS: u32 = 80;
+
+/// Damage taken when starving.
+const STARVATION_DAMAGE: i16 = 1;
+
+/// Health the starvation stops at on the easy difficulty.
+const EASY_STARVATION_HEALTH: i16 = 10;
+
+/// Health the starvation stops at on the normal difficulty.
+const NORMAL_STARVATION_HEALTH: i16 = 1;
+
+/// Number of ticks the players can't be hurt for after getting hurt.
+const INVULNERABLE_TICKS: u8 = 10;
+
+/// Distance the players may fall without getting hurt.
+const SAFE_FALL_DISTANCE: f64 = 3.0;
+
+/// Damage taken each tick below the world.
+const VOID_DAMAGE: i16 = 4;
+
+/// Damage taken once the air has run out, every second.
+const DROWNING_DAMAGE: i16 = 2;
+
+/// Air the players gasp for before the next drowning damage.
+const DROWNING_AIR: i16 = -20;
+
+/// Damage taken when standing in the lava.
+const LAVA_DAMAGE: i16 = 4;
+
+/// Damage taken when standing in the fire.
+const FIRE_DAMAGE: i16 = 1;
+
+/// Entity status of getting hurt.
+const HURT_STATUS: i8 = 2;
+
+/// Entity status of dying.
+const DEAD_STATUS: i8 = 3;
+
+/// What has hurt the player, tells how it died.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DamageCause {
+    Fall,
+    Void,
+    Drowning,
+    Lava,
+    Fire,
+    Starvation,
+
+    /// Attacked by the player with the username.
+    Player(String),
+
+    /// Attacked by the mob.
+    Mob(MobKind),
+}
+
+impl DamageCause {
+    /// Message announcing the death of the player in the chat.
+    pub fn death_message(&self, username: &str) -> String {
+        match self {
+            DamageCause::Fall => format!("{} hit the ground too hard", username),
+            DamageCause::Void => format!("{} fell out of the world", username),
+            DamageCause::Drowning => format!("{} drowned", username),
+            DamageCause::Lava => format!("{} tried to swim in lava", username),
+            DamageCause::Fire => format!("{} went up in flames", username),
+            DamageCause::Starvation => format!("{} starved to death", username),
+            DamageCause::Player(killer) => format!("{} was slain by {}", username, killer),
+            DamageCause::Mob(kind) => format!("{} was slain by {}", username, kind.name()),
+        }
+    }
+}
+
+/// Outcome of hurting a player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hurt {
+    /// The player can't be hurt right now, e.g. is in creative or has just been hurt.
+    Ignored,
+
+    Hurt,
+
+    /// The player has been hurt and died, the death is yet to be handled.
+    Killed,
+}
+
+/// Health, food and breath of a player.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Health {
+    pub health: i16,
+    pub food: i16,
+    pub food_saturation: f32,
+
+    /// Remaining ticks of the breath, drops below `0` while drowning.
+    pub air: i16,
+
+    /// Distance fallen since the player last stood on the ground.
+    pub fall_distance: f64,
+
+    /// Remaining ticks the player can't be hurt for.
+    pub invulnerable_ticks: u8,
+
+    /// Exhaustion of the actions taken, draining the saturation and the food.
+    pub exhaustion: f32,
+
+    /// Ticks since the health was last regenerated or lost to the starvation.
+    pub food_ticks: u32,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self {
+            health: MAX_HEALTH,
+            food: MAX_FOOD,
+            food_saturation: INITIAL_SATURATION,
+            air: MAX_AIR,
+            fall_distance: 0.0,
+            invulnerable_ticks: 0,
+            exhaustion: 0.0,
+            food_ticks: 0,
+        }
+    }
+}
+
+impl Health {
+    pub fn is_dead(&self) -> bool {
+        self.health <= 0
+    }
+
+    /// Takes the damage, returns `false` if the player can't be hurt right now.
+    pub fn damage(&mut self, amount: i16) -> bool {
+        if self.is_dead() || self.invulnerable_ticks > 0 || amount <= 0 {
+            return false;
+        }
+
+        self.health = (self.health - amount).max(0);
+        self.invulnerable_ticks = INVULNERABLE_TICKS;
+        self.exhaust(DAMAGE_EXHAUSTION);
+        true
+    }
+
+    /// Adds the exhaustion of an action, e.g. sprinting or digging.
+    pub fn exhaust(&mut self, exhaustion: f32) {
+        self.exhaustion = (self.exhaustion + exhaustion).min(MAX_EXHAUSTION);
+    }
+
+    /// Adds the exhaustion of moving between the positions, swimming, sprinting or jumping.
+    pub fn exhaust_movement(
+        &mut self,
+        from: &Position,
+        to: &Position,
+        in_water: bool,
+        sprinting: bool,
+    ) {
+        let distance = (to.x - from.x).hypot(to.z - from.z) as f32;
+
+        if in_water {
+            self.exhaust(SWIM_EXHAUSTION * distance);
+        } else if sprinting {
+            self.exhaust(SPRINT_EXHAUSTION * distance);
+        }
+
+        if from.on_ground && !to.on_ground && to.y > from.y {
+            self.exhaust(if sprinting {
+                SPRINT_JUMP_EXHAUSTION
+            } else {
+                JUMP_EXHAUSTION
+            });
+        }
+    }
+
+    /// Restores the food level and the saturation by eating the food.
+    pub fn eat(&mut self, food: i16, saturation_modifier: f32) {
+        self.food = (self.food + food).min(MAX_FOOD);
+        self.food_saturation = (self.food_saturation + f32::from(food) * saturation_modifier * 2.0)
+            .min(f32::from(self.food));
+    }
+
+    /// Drains the food by the exhaustion and regenerates the health while well fed,
+    /// returns the starvation damage once the food has run out.
+    pub fn tick_hunger(&mut self, difficulty: i8) -> Option<i16> {
+        if self.exhaustion > EXHAUSTION_PER_FOOD {
+            self.exhaustion -= EXHAUSTION_PER_FOOD;
+
+            if self.food_saturation > 0.0 {
+                self.food_saturation = (self.food_saturation - 1.0).max(0.0);
+            } else if difficulty > 0 {
+                self.food = (self.food - 1).max(0);
+            }
+        }
+
+        if self.food >= REGENERATION_FOOD && self.health < MAX_HEALTH {
+            self.food_ticks += 1;
+
+            if self.food_ticks >= FOOD_TICKS {
+                self.health += 1;
+                self.food_ticks = 0;
+            }
+        } else if self.food <= 0 {
+            self.food_ticks += 1;
+
+            if self.food_ticks >= FOOD_TICKS {
+                self.food_ticks = 0;
+
+                let starving = self.health > EASY_STARVATION_HEALTH
+                    || difficulty >= 3
+                    || (self.health > NORMAL_STARVATION_HEALTH && difficulty >= 2);
+                if starving {
+                    return Some(STARVATION_DAMAGE);
+                }
+            }
+        } else {
+            self.food_ticks = 0;
+        }
+
+        None
+    }
+
+    /// Accumulates the distance fallen by the move,
+    /// returns the damage taken once the player lands.
+    pub fn fall(&mut self, dy: f64, on_ground: bool, in_liquid: bool) -> Option<i16> {
+        if in_liquid {
+            self.fall_distance = 0.0;
+            return None;
+        }
+
+        if dy < 0.0 {
+            self.fall_distance -= dy;
+        }
+
+        if !on_ground {
+            return None;
+        }
+
+        let damage = (self.fall_distance - SAFE_FALL_DISTANCE).ceil();
+        self.fall_distance = 0.0;
+        (damage > 0.0).then_some(damage as i16)
+    }
+
+    /// Advances the breath and the invulnerability by a single tick,
+    /// returns the damage the player takes from its surroundings, the worst one if many.
+    pub fn tick(&mut self, world: &World, position: &Position) -> Option<(i16, DamageCause)> {
+        self.invulnerable_ticks = self.invulnerable_ticks.saturating_sub(1);
+
+        let block_at = |y: f64| {
+            world
+                .get_block(
+                    position.x.floor() as i32,
+                    y.floor() as i32,
+                    position.z.floor() as i32,
+                )
+                .unwrap_or(Block::AIR)
+        };
+        let (feet, eyes) = (block_at(position.y), block_at(position.y + EYE_HEIGHT));
+
+        let mut damages = Vec::new();
+
+        if position.y < 0.0 {
+            damages.push((VOID_DAMAGE, DamageCause::Void));
+        }
+
+        if eyes.is_water() {
+            self.air -= 1;
+            if self.air <= DROWNING_AIR {
+                self.air = 0;
+                damages.push((DROWNING_DAMAGE, DamageCause::Drowning));
+            }
+        } else {
+            self.air = MAX_AIR;
+        }
+
+        if feet.is_lava() || eyes.is_lava() {
+            damages.push((LAVA_DAMAGE, DamageCause::Lava));
+        } else if feet.id == Block::FIRE.id || eyes.id == Block::FIRE.id {
+            damages.push((FIRE_DAMAGE, DamageCause::Fire));
+        }
+
+        damages.into_iter().max_by_key(|(damage, _)| *damage)
+    }
+
+    /// Builds the packet updating the health shown by the player's client.
+    pub fn update_packet(&self) -> Packet {
+        Packet::UpdateHealth(UpdateHealthPayload {
+            health: self.health,
+            food: self.food,
+            food_saturation: self.food_saturation,
+        })
+    }
+}
+
+/// Hurts the player in survival, sends it the new health and shows it getting hurt,
+/// or dying, to everyone nearby.
+pub fn hurt(players: &Players, entity_id: i32, amount: i16) -> Hurt {
+    let hurt = players.update(entity_id, |player| {
+        if player.game_mode != SURVIVAL || !player.health.damage(amount) {
+            return None;
+        }
+
+        player.send(player.health.update_packet());
+        Some((
+            player.dimension,
+            player.base.position,
+            player.health.is_dead(),
+        ))
+    });
+
+    let Some((dimension, position, dead)) = hurt.flatten() else {
+        return Hurt::Ignored;
+    };
+
+    players.broadcast_near(
+        dimension,
+        position.x,
+        position.y,
+        position.z,
+        PLAYER_TRACKING_RANGE,
+        Packet::EntityStatus(EntityStatusPayload {
+            entity_id,
+            status: if dead { DEAD_STATUS } else { HURT_STATUS },
+        }),
+    );
+
+    if dead {
+        Hurt::Killed
+    } else {
+        Hurt::Hurt
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::world::chunk::{ChunkColumn, ChunkPos};
+
+    use super::*;
+
+    #[test]
+    fn fall_damage() {
+        let mut health = Health::default();
+
+        assert_eq!(health.fall(-2.0, false, false), None);
+        assert_eq!(health.fall(-3.5, false, false), None);
+        assert_eq!(health.fall(-0.1, true, false), Some(3));
+        assert_eq!(health.fall_distance, 0.0);
+
+        assert_eq!(health.fall(-10.0, false, false), None);
+        assert_eq!(health.fall(-1.0, false, true), None);
+        assert_eq!(health.fall(0.0, true, false), None);
+    }
+
+    #[test]
+    fn damage_respects_invulnerability() {
+        let mut health = Health::default();
+
+        assert!(health.damage(5));
+        assert!(!health.damage(5));
+        assert_eq!(health.health, 15);
+
+        health.invulnerable_ticks = 0;
+        assert!(health.damage(100));
+        assert!(health.is_dead());
+        assert_eq!(health.health, 0);
+    }
+
+    #[test]
+    fn hunger_regenerates_and_starves() {
+        let mut health = Health {
+            health: 15,
+            ..Default::default()
+        };
+
+        let starved: Vec<_> = (0..FOOD_TICKS)
+            .filter_map(|_| health.tick_hunger(1))
+            .collect();
+        assert!(starved.is_empty());
+        assert_eq!(health.health, 16);
+
+        health.exhaust(EXHAUSTION_PER_FOOD * 6.0 + 0.1);
+        for _ in 0..6 {
+            health.tick_hunger(1);
+        }
+        assert_eq!((health.food, health.food_saturation), (19, 0.0));
+
+        health.food = 0;
+        health.food_ticks = 0;
+        let starved: Vec<_> = (0..FOOD_TICKS)
+            .filter_map(|_| health.tick_hunger(1))
+            .collect();
+        assert_eq!(starved, vec![STARVATION_DAMAGE]);
+
+        health.health = EASY_STARVATION_HEALTH;
+        assert!((0..FOOD_TICKS).all(|_| health.tick_hunger(1).is_none()));
+
+        health.eat(8, 0.8);
+        assert_eq!((health.food, health.food_saturation), (8, 8.0));
+
+        health.exhaustion = 0.0;
+        let from = Position {
+            on_ground: true,
+            ..Default::default()
+        };
+        let to = Position {
+            x: 3.0,
+            y: 0.4,
+            ..Default::default()
+        };
+        health.exhaust_movement(&from, &to, false, true);
+        assert!((health.exhaustion - 1.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn drowning_and_burning() {
+        let mut world = World::new();
+        world.insert_chunk(ChunkColumn::new(ChunkPos::new(0, 0)));
+        world.set_block(0, 65, 0, Block::WATER);
+
+        let position = Position {
+            x: 0.5,
+            y: 64.0,
+            z: 0.5,
+            ..Default::default()
+        };
+
+        let mut health = Health::default();
+        let damage: Vec<_> = (0..MAX_AIR - DROWNING_AIR)
+            .filter_map(|_| health.tick(&world, &position))
+            .collect();
+        assert_eq!(damage, vec![(DROWNING_DAMAGE, DamageCause::Drowning)]);
+
+        world.set_block(0, 65, 0, Block::AIR);
+        world.set_block(0, 64, 0, Block::LAVA);
+        assert_eq!(
+            health.tick(&world, &position),
+            Some((LAVA_DAMAGE, DamageCause::Lava))
+        );
+        assert_eq!(health.air, MAX_AIR);
+
+        let void = Position {
+            y: -10.0,
+            ..position
+        };
+        assert_eq!(
+            health.tick(&world, &void),
+            Some((VOID_DAMAGE, DamageCause::Void))
+        );
+    }
+}
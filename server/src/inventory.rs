@@ -0,0 +1,622 @@
+use protocol::{
+    nbt::{Compound, Nbt, Tag},
+    packet::{Packet, SetSlotPayload, WindowItemsPayload},
+    slot::{ItemStack, Slot},
+};
+
+/// Number of the slots of the player's inventory window.
+pub const INVENTORY_SIZE: usize = 45;
+
+/// Identifier of the player's inventory window.
+pub const INVENTORY_WINDOW_ID: i8 = 0;
+
+/// Window identifier of the stack held by the cursor, sent with the slot `-1`.
+const CURSOR_WINDOW_ID: i8 = -1;
+
+/// Slot clicked when the player clicks outside of the window, dropping the cursor stack.
+pub const OUTSIDE_WINDOW_SLOT: i16 = -999;
+
+/// Slot of the crafting output.
+pub const CRAFTING_OUTPUT_SLOT: i16 = 0;
+
+/// First slot of the 2x2 crafting grid.
+pub const CRAFTING_GRID_OFFSET: i16 = 1;
+
+/// First slot of the armor, the helmet, followed by the chestplate, leggings and boots.
+pub const ARMOR_SLOT_OFFSET: i16 = 5;
+
+/// First slot of the main storage, above the hotbar.
+pub const MAIN_SLOT_OFFSET: i16 = 9;
+
+/// First slot of the hotbar.
+pub const HOTBAR_SLOT_OFFSET: i16 = 36;
+
+/// Number of the hotbar slots.
+pub const HOTBAR_SIZE: i16 = 9;
+
+/// Slot of the boots in the saved inventory, followed by the leggings, chestplate and helmet.
+const SAVED_ARMOR_OFFSET: i16 = 100;
+
+/// Slot past the helmet in the saved inventory.
+const SAVED_ARMOR_END: i16 = SAVED_ARMOR_OFFSET + 4;
+
+/// Most items of the kind a single slot can hold.
+pub fn max_stack_size(item: &ItemStack) -> i8 {
+    if item.is_damageable() {
+        return 1;
+    }
+
+    match item.id {
+        // Stew, signs, buckets, minecarts, doors, saddles, boats, cake, beds,
+        // maps, potions and records.
+        282 | 323..=330 | 333 | 335 | 342 | 343 | 354 | 355 | 358 | 373 | 2256..=2266 => 1,
+        // Snowballs, eggs and ender pearls.
+        332 | 344 | 368 => 16,
+        _ => 64,
+    }
+}
+
+/// Whether the stacks hold the same item and can be merged.
+fn stacks_with(a: &ItemStack, b: &ItemStack) -> bool {
+    a.id == b.id && a.damage == b.damage && a.nbt == b.nbt
+}
+
+/// Armor slot the item fits in, `None` if it isn't wearable.
+fn armor_slot(item: &ItemStack) -> Option<i16> {
+    match item.id {
+        // Pumpkins can be worn as helmets.
+        86 => Some(ARMOR_SLOT_OFFSET),
+        298..=317 => Some(ARMOR_SLOT_OFFSET + (item.id - 298) % 4),
+        _ => None,
+    }
+}
+
+/// Reason the window click has been rejected, the client gets resynchronized after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClickError {
+    /// The slot isn't in the window.
+    InvalidSlot,
+
+    /// The client sees another item in the slot than the server.
+    Desynchronized,
+
+    /// The item can't be put into the slot, e.g. a block into an armor slot.
+    NotAllowed,
+}
+
+/// Slots of the player's inventory window, numbered as the client numbers them.
+///
+/// `0` is the crafting output, `1..=4` the crafting grid, `5..=8` the armor,
+/// `9..=35` the main storage and `36..=44` the hotbar.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inventory {
+    slots: Vec<Slot>,
+
+    /// Stack held by the cursor while the window is open.
+    pub cursor: Slot,
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Inventory {
+    pub fn new() -> Self {
+        Self {
+            slots: vec![Slot::EMPTY; INVENTORY_SIZE],
+            cursor: Slot::EMPTY,
+        }
+    }
+
+    /// Item in the hotbar slot, from `0` to `8`.
+    pub fn held_item(&self, held_slot: i16) -> Option<&ItemStack> {
+        self.slot(HOTBAR_SLOT_OFFSET + held_slot)?.item()
+    }
+
+    /// Takes a single item out of the slot, `None` if it's empty.
+    pub fn take_one(&mut self, slot: i16) -> Option<ItemStack> {
+        let contents = self.slots.get_mut(usize::try_from(slot).ok()?)?;
+        let stack = contents.0.as_mut()?;
+
+        let taken = ItemStack {
+            count: 1,
+            ..stack.clone()
+        };
+
+        stack.count -= 1;
+        if stack.count <= 0 {
+            *contents = Slot::EMPTY;
+        }
+
+        Some(taken)
+    }
+
+    /// Wears the tool in the slot down by a use, breaking it once it has been used
+    /// more times than its durability.
+    pub fn wear(&mut self, slot: i16, durability: i16) {
+        let Some(contents) = usize::try_from(slot)
+            .ok()
+            .and_then(|slot| self.slots.get_mut(slot))
+        else {
+            return;
+        };
+        let Some(tool) = contents.0.as_mut() else {
+            return;
+        };
+
+        tool.damage += 1;
+        if tool.damage > durability {
+            *contents = Slot::EMPTY;
+        }
+    }
+
+    /// Returns the slot, `None` if it's out of the window.
+    pub fn slot(&self, slot: i16) -> Option<&Slot> {
+        self.slots.get(usize::try_from(slot).ok()?)
+    }
+
+    /// Replaces the contents of the slot, ignored if it's out of the window.
+    pub fn set_slot(&mut self, slot: i16, item: Slot) {
+        if let Some(current) = usize::try_from(slot)
+            .ok()
+            .and_then(|slot| self.slots.get_mut(slot))
+        {
+            *current = item;
+        }
+    }
+
+    /// Inserts as much of the item stack as fits, the hotbar first,
+    /// leaving the rest in the stack.
+    ///
+    /// Returns the changed slots.
+    pub fn insert(&mut self, item: &mut ItemStack) -> Vec<i16> {
+        let storage: Vec<i16> = (HOTBAR_SLOT_OFFSET..INVENTORY_SIZE as i16)
+            .chain(MAIN_SLOT_OFFSET..HOTBAR_SLOT_OFFSET)
+            .collect();
+
+        self.insert_into(item, &storage)
+    }
+
+    /// Inserts as much of the item stack as fits into the slots, in their order.
+    fn insert_into(&mut self, item: &mut ItemStack, slots: &[i16]) -> Vec<i16> {
+        let max = max_stack_size(item);
+        let mut changed = Vec::new();
+
+        // Topping up the existing stacks first.
+        for &slot in slots {
+            if item.count <= 0 {
+                break;
+            }
+
+            if let Some(stack) = &mut self.slots[slot as usize].0 {
+                if stacks_with(stack, item) && stack.count < max {
+                    let moved = item.count.min(max - stack.count);
+                    stack.count += moved;
+                    item.count -= moved;
+                    changed.push(slot);
+                }
+            }
+        }
+
+        for &slot in slots {
+            if item.count <= 0 {
+                break;
+            }
+
+            if self.slots[slot as usize].is_empty() {
+                let moved = item.count.min(max);
+                self.slots[slot as usize] = Slot::new(ItemStack {
+                    count: moved,
+                    ..item.clone()
+                });
+                item.count -= moved;
+                changed.push(slot);
+            }
+        }
+
+        changed
+    }
+
+    /// Applies the click on the slot of the inventory window, `clicked` is the item
+    /// the client sees in it.
+    ///
+    /// Returns the stack thrown out of the window, if any.
+    pub fn click(
+        &mut self,
+        slot: i16,
+        right_click: bool,
+        shift: bool,
+        clicked: &Slot,
+    ) -> Result<Option<ItemStack>, ClickError> {
+        if slot == OUTSIDE_WINDOW_SLOT {
+            return Ok(self.drop_cursor(right_click));
+        }
+
+        let current = self.slot(slot).ok_or(ClickError::InvalidSlot)?;
+        if current != clicked {
+            return Err(ClickError::Desynchronized);
+        }
+
+        // Crafting isn't supported, the output always stays empty.
+        if slot == CRAFTING_OUTPUT_SLOT {
+            return if current.is_empty() {
+                Ok(None)
+            } else {
+                Err(ClickError::NotAllowed)
+            };
+        }
+
+        if shift {
+            self.shift_click(slot);
+            return Ok(None);
+        }
+
+        let is_armor_slot = (ARMOR_SLOT_OFFSET..MAIN_SLOT_OFFSET).contains(&slot);
+        if let Some(cursor) = self.cursor.item() {
+            if is_armor_slot && armor_slot(cursor) != Some(slot) {
+                return Err(ClickError::NotAllowed);
+            }
+        }
+
+        let slot_item = self.slots[slot as usize].0.take();
+        let (slot_item, cursor) = place(slot_item, self.cursor.0.take(), right_click);
+
+        self.slots[slot as usize] = Slot(slot_item);
+        self.cursor = Slot(cursor);
+        Ok(None)
+    }
+
+    /// Moves the stack between the hotbar and the main storage,
+    /// or out of the crafting grid and the armor into either of them.
+    fn shift_click(&mut self, slot: i16) {
+        let Some(mut item) = self.slots[slot as usize].0.take() else {
+            return;
+        };
+
+        let target: Vec<i16> = if (MAIN_SLOT_OFFSET..HOTBAR_SLOT_OFFSET).contains(&slot) {
+            (HOTBAR_SLOT_OFFSET..INVENTORY_SIZE as i16).collect()
+        } else if slot >= HOTBAR_SLOT_OFFSET {
+            (MAIN_SLOT_OFFSET..HOTBAR_SLOT_OFFSET).collect()
+        } else {
+            (MAIN_SLOT_OFFSET..INVENTORY_SIZE as i16).collect()
+        };
+
+        self.insert_into(&mut item, &target);
+
+        if item.count > 0 {
+            self.slots[slot as usize] = Slot::new(item);
+        }
+    }
+
+    /// Throws the cursor stack out of the window, or a single item of it.
+    fn drop_cursor(&mut self, single: bool) -> Option<ItemStack> {
+        let cursor = self.cursor.0.as_mut()?;
+
+        if single && cursor.count > 1 {
+            cursor.count -= 1;
+            return Some(ItemStack {
+                count: 1,
+                ..cursor.clone()
+            });
+        }
+
+        self.cursor.0.take()
+    }
+
+    /// Returns the cursor stack and the crafting grid into the storage as the window
+    /// gets closed.
+    ///
+    /// Returns the stacks which don't fit and get thrown out.
+    pub fn close(&mut self) -> Vec<ItemStack> {
+        let grid = CRAFTING_GRID_OFFSET..ARMOR_SLOT_OFFSET;
+        let mut leftovers = Vec::new();
+
+        let items: Vec<ItemStack> = std::iter::once(self.cursor.0.take())
+            .chain(grid.map(|slot| self.slots[slot as usize].0.take()))
+            .flatten()
+            .collect();
+
+        for mut item in items {
+            self.insert(&mut item);
+
+            if item.count > 0 {
+                leftovers.push(item);
+            }
+        }
+
+        leftovers
+    }
+
+    /// Takes all the stacks out of the inventory, e.g. to be dropped as the player dies.
+    pub fn clear(&mut self) -> Vec<ItemStack> {
+        let mut items: Vec<ItemStack> = self.cursor.0.take().into_iter().collect();
+
+        for (slot, item) in self.slots.iter_mut().enumerate() {
+            let item = item.0.take();
+
+            // The crafting output is only a preview of the grid.
+            if slot != CRAFTING_OUTPUT_SLOT as usize {
+                items.extend(item);
+            }
+        }
+
+        items
+    }
+
+    /// Builds the list of the stacks the inventory is saved as in the player file.
+    ///
+    /// The stacks are numbered as in the player files, the hotbar from `0`,
+    /// the main storage from `9` and the armor from `100`, the crafting grid isn't saved.
+    pub fn to_nbt(&self) -> Vec<Tag> {
+        let mut list = Vec::new();
+
+        for (slot, item) in self.slots.iter().enumerate() {
+            let slot = slot as i16;
+            let saved_slot = match slot {
+                ARMOR_SLOT_OFFSET..MAIN_SLOT_OFFSET => {
+                    SAVED_ARMOR_OFFSET + MAIN_SLOT_OFFSET - 1 - slot
+                }
+                MAIN_SLOT_OFFSET..HOTBAR_SLOT_OFFSET => slot,
+                HOTBAR_SLOT_OFFSET.. => slot - HOTBAR_SLOT_OFFSET,
+                _ => continue,
+            };
+            let Some(item) = item.item() else {
+                continue;
+            };
+
+            let mut compound = Compound::new();
+            compound.insert("Slot".to_string(), Tag::Byte(saved_slot as i8));
+            compound.insert("id".to_string(), Tag::Short(item.id));
+            compound.insert("Count".to_string(), Tag::Byte(item.count));
+            compound.insert("Damage".to_string(), Tag::Short(item.damage));
+            if let Some(nbt) = &item.nbt {
+                compound.insert("tag".to_string(), Tag::Compound(nbt.root.clone()));
+            }
+
+            list.push(Tag::Compound(compound));
+        }
+
+        list
+    }
+
+    /// Restores the inventory from the list of the stacks saved in the player file,
+    /// skipping the stacks in the unknown slots.
+    pub fn from_nbt(list: &[Tag]) -> Self {
+        let mut inventory = Self::new();
+
+        for compound in list.iter().filter_map(Tag::as_compound) {
+            let short = |name: &str| compound.get(name).and_then(Tag::as_short);
+            let (Some(saved_slot), Some(id), Some(count)) = (
+                compound.get("Slot").and_then(Tag::as_byte),
+                short("id"),
+                compound.get("Count").and_then(Tag::as_byte),
+            ) else {
+                continue;
+            };
+
+            let saved_slot = i16::from(saved_slot as u8);
+            let slot = match saved_slot {
+                0..HOTBAR_SIZE => HOTBAR_SLOT_OFFSET + saved_slot,
+                MAIN_SLOT_OFFSET..HOTBAR_SLOT_OFFSET => saved_slot,
+                SAVED_ARMOR_OFFSET..SAVED_ARMOR_END => {
+                    SAVED_ARMOR_OFFSET + MAIN_SLOT_OFFSET - 1 - saved_slot
+                }
+                _ => continue,
+            };
+
+            let mut item = ItemStack::new(id, count, short("Damage").unwrap_or(0));
+            item.nbt = compound
+                .get("tag")
+                .and_then(Tag::as_compound)
+                .map(|tag| Nbt::new("tag", tag.clone()));
+            inventory.set_slot(slot, Slot::new(item));
+        }
+
+        inventory
+    }
+
+    /// Builds the packet synchronizing the whole window with the client.
+    pub fn window_items_packet(&self) -> Packet {
+        Packet::WindowItems(WindowItemsPayload {
+            window_id: INVENTORY_WINDOW_ID,
+            slots: self.slots.clone(),
+        })
+    }
+
+    /// Builds the packet synchronizing the slot with the client.
+    pub fn set_slot_packet(&self, slot: i16) -> Packet {
+        Packet::SetSlot(SetSlotPayload {
+            window_id: INVENTORY_WINDOW_ID,
+            slot,
+            item: self.slot(slot).cloned().unwrap_or_default(),
+        })
+    }
+
+    /// Builds the packet synchronizing the cursor stack with the client.
+    pub fn cursor_packet(&self) -> Packet {
+        Packet::SetSlot(SetSlotPayload {
+            window_id: CURSOR_WINDOW_ID,
+            slot: -1,
+            item: self.cursor.clone(),
+        })
+    }
+}
+
+/// Puts the cursor stack into the slot, or picks the slot up with the cursor.
+///
+/// Returns the new contents of the slot and the cursor.
+fn place(
+    slot_item: Option<ItemStack>,
+    cursor: Option<ItemStack>,
+    right_click: bool,
+) -> (Option<ItemStack>, Option<ItemStack>) {
+    match (slot_item, cursor) {
+        (None, None) => (None, None),
+        // Picking up the whole stack, or the bigger half of it.
+        (Some(mut stack), None) => {
+            if right_click && stack.count > 1 {
+                let taken = (stack.count + 1) / 2;
+                stack.count -= taken;
+                let cursor = ItemStack {
+                    count: taken,
+                    ..stack.clone()
+                };
+                (Some(stack), Some(cursor))
+            } else {
+                (None, Some(stack))
+            }
+        }
+        // Putting down the whole stack, or a single item.
+        (None, Some(mut cursor)) => {
+            let placed = if right_click {
+                1
+            } else {
+                cursor.count.min(max_stack_size(&cursor))
+            };
+
+            cursor.count -= placed;
+            let stack = ItemStack {
+                count: placed,
+                ..cursor.clone()
+            };
+            (Some(stack), (cursor.count > 0).then_some(cursor))
+        }
+        (Some(mut stack), Some(mut cursor)) if stacks_with(&stack, &cursor) => {
+            let wanted = if right_click { 1 } else { cursor.count };
+            let placed = wanted.min(max_stack_size(&stack) - stack.count).max(0);
+
+            stack.count += placed;
+            cursor.count -= placed;
+            (Some(stack), (cursor.count > 0).then_some(cursor))
+        }
+        // Swapping the different items.
+        (Some(stack), Some(cursor)) => (Some(cursor), Some(stack)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_stacks_and_fills_hotbar_first() {
+        let mut inventory = Inventory::new();
+        inventory.set_slot(9, Slot::new(ItemStack::new(4, 60, 0)));
+
+        let mut item = ItemStack::new(4, 10, 0);
+        assert_eq!(inventory.insert(&mut item), vec![9, 36]);
+        assert_eq!(item.count, 0);
+        assert_eq!(
+            inventory.slot(9),
+            Some(&Slot::new(ItemStack::new(4, 64, 0)))
+        );
+        assert_eq!(
+            inventory.slot(36),
+            Some(&Slot::new(ItemStack::new(4, 6, 0)))
+        );
+
+        let mut sword = ItemStack::new(276, 2, 0);
+        assert_eq!(inventory.insert(&mut sword), vec![37, 38]);
+    }
+
+    #[test]
+    fn save_slots_numbered_as_player_files() {
+        let mut inventory = Inventory::new();
+        inventory.set_slot(5, Slot::new(ItemStack::new(310, 1, 3)));
+        inventory.set_slot(2, Slot::new(ItemStack::new(5, 1, 0)));
+        inventory.set_slot(9, Slot::new(ItemStack::new(4, 60, 0)));
+        inventory.set_slot(44, Slot::new(ItemStack::new(3, 1, 0)));
+
+        let slots: Vec<(i8, i16)> = inventory
+            .to_nbt()
+            .iter()
+            .map(|tag| {
+                let compound = tag.as_compound().unwrap();
+                let slot = compound["Slot"].as_byte().unwrap();
+                (slot, compound["id"].as_short().unwrap())
+            })
+            .collect();
+        assert_eq!(slots, vec![(103, 310), (9, 4), (8, 3)]);
+
+        let restored = Inventory::from_nbt(&inventory.to_nbt());
+        assert_eq!(restored.slot(5), inventory.slot(5));
+        assert_eq!(restored.slot(9), inventory.slot(9));
+        assert_eq!(restored.slot(44), inventory.slot(44));
+        assert_eq!(restored.slot(2), Some(&Slot::EMPTY));
+    }
+
+    #[test]
+    fn tools_wear_down() {
+        let mut inventory = Inventory::new();
+        inventory.set_slot(36, Slot::new(ItemStack::new(259, 1, 63)));
+
+        inventory.wear(36, 64);
+        assert_eq!(
+            inventory.slot(36),
+            Some(&Slot::new(ItemStack::new(259, 1, 64)))
+        );
+        inventory.wear(36, 64);
+        assert_eq!(inventory.slot(36), Some(&Slot::EMPTY));
+        inventory.wear(36, 64);
+    }
+
+    #[test]
+    fn insert_leaves_what_doesnt_fit() {
+        let mut inventory = Inventory::new();
+        for slot in MAIN_SLOT_OFFSET..INVENTORY_SIZE as i16 {
+            inventory.set_slot(slot, Slot::new(ItemStack::new(3, 63, 0)));
+        }
+
+        let mut item = ItemStack::new(3, 40, 0);
+        assert_eq!(inventory.insert(&mut item).len(), 36);
+        assert_eq!(item.count, 4);
+
+        let mut other = ItemStack::new(1, 1, 0);
+        assert!(inventory.insert(&mut other).is_empty());
+        assert_eq!(other.count, 1);
+    }
+
+    #[test]
+    fn click_moves_stacks_through_the_cursor() {
+        let mut inventory = Inventory::new();
+        let dirt = Slot::new(ItemStack::new(3, 5, 0));
+        inventory.set_slot(36, dirt.clone());
+
+        // Picking up the bigger half, then putting a single item down.
+        assert_eq!(inventory.click(36, true, false, &dirt), Ok(None));
+        assert_eq!(inventory.cursor, Slot::new(ItemStack::new(3, 3, 0)));
+        assert_eq!(inventory.click(9, true, false, &Slot::EMPTY), Ok(None));
+        assert_eq!(inventory.slot(9), Some(&Slot::new(ItemStack::new(3, 1, 0))));
+
+        // Clicking with a stale view of the slot.
+        assert_eq!(
+            inventory.click(36, false, false, &dirt),
+            Err(ClickError::Desynchronized)
+        );
+
+        // Blocks aren't wearable.
+        assert_eq!(
+            inventory.click(5, false, false, &Slot::EMPTY),
+            Err(ClickError::NotAllowed)
+        );
+
+        assert_eq!(
+            inventory.click(OUTSIDE_WINDOW_SLOT, false, false, &Slot::EMPTY),
+            Ok(Some(ItemStack::new(3, 2, 0)))
+        );
+        assert!(inventory.cursor.is_empty());
+    }
+
+    #[test]
+    fn shift_click_moves_to_the_hotbar() {
+        let mut inventory = Inventory::new();
+        let boots = Slot::new(ItemStack::new(301, 1, 0));
+        inventory.set_slot(20, boots.clone());
+
+        assert_eq!(inventory.click(20, false, true, &boots), Ok(None));
+        assert_eq!(inventory.slot(20), Some(&Slot::EMPTY));
+        assert_eq!(inventory.slot(36), Some(&boots));
+    }
+}
@@ -0,0 +1,236 @@
+use std::{any::Any, f64::consts::TAU};
+
+use protocol::{
+    packet::{Packet, PickupSpawnPayload},
+    slot::ItemStack,
+};
+
+use crate::{
+    entity::{fixed_point, BoundingBox, Entity, EntityBase, Position},
+    player::EYE_HEIGHT,
+    world::World,
+};
+
+/// Number of ticks the dropped item lies in the world before it despawns, 5 minutes.
+pub const DESPAWN_TICKS: u64 = 6000;
+
+/// Number of ticks before the item dropped by a block can be picked up.
+pub const PICKUP_DELAY_TICKS: u64 = 10;
+
+/// Number of ticks before the item thrown by a player can be picked up,
+/// long enough for it to fly out of the player's reach.
+pub const THROWN_PICKUP_DELAY_TICKS: u64 = 40;
+
+/// Speed the items are thrown with by the players.
+const THROW_SPEED: f64 = 0.3;
+
+/// Highest speed the items of the dying players are scattered with.
+const DEATH_SCATTER_SPEED: f64 = 0.5;
+
+/// Bounding box of the dropped items.
+const ITEM_BOUNDING_BOX: BoundingBox = BoundingBox::new(0.25, 0.25);
+
+/// How far around the player's bounding box the items get picked up, horizontally.
+const PICKUP_REACH: f64 = 1.0;
+
+/// Speed the item falls faster by each tick.
+const GRAVITY: f64 = 0.04;
+
+/// Share of the velocity the item keeps each tick.
+const DRAG: f64 = 0.98;
+
+/// Share of the horizontal velocity the item keeps each tick it slides on the ground.
+const GROUND_FRICTION: f64 = 0.6;
+
+/// An item stack lying in the world, waiting to be picked up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemEntity {
+    pub base: EntityBase,
+    pub item: ItemStack,
+
+    /// Age the item can be picked up at.
+    pub pickup_delay: u64,
+}
+
+impl ItemEntity {
+    pub fn new(entity_id: i32, item: ItemStack, x: f64, y: f64, z: f64) -> Self {
+        let position = Position {
+            x,
+            y,
+            z,
+            ..Default::default()
+        };
+
+        Self {
+            base: EntityBase::new(entity_id, position, ITEM_BOUNDING_BOX),
+            item,
+            pickup_delay: PICKUP_DELAY_TICKS,
+        }
+    }
+
+    /// Creates the item thrown by the player from the eyes, in the direction it looks.
+    pub fn thrown_by(entity_id: i32, item: ItemStack, position: &Position) -> Self {
+        let mut entity = Self::new(
+            entity_id,
+            item,
+            position.x,
+            position.y + EYE_HEIGHT - 0.3,
+            position.z,
+        );
+
+        let (yaw, pitch) = (
+            f64::from(position.yaw).to_radians(),
+            f64::from(position.pitch).to_radians(),
+        );
+        entity.base.velocity = (
+            -yaw.sin() * pitch.cos() * THROW_SPEED,
+            -pitch.sin() * THROW_SPEED + 0.1,
+            yaw.cos() * pitch.cos() * THROW_SPEED,
+        );
+        entity.pickup_delay = THROWN_PICKUP_DELAY_TICKS;
+        entity
+    }
+
+    /// Creates the item dropped by the dying player, scattered around it.
+    pub fn dropped_on_death(entity_id: i32, item: ItemStack, position: &Position) -> Self {
+        let mut entity = Self::new(
+            entity_id,
+            item,
+            position.x,
+            position.y + EYE_HEIGHT - 0.3,
+            position.z,
+        );
+
+        let speed = rand::random::<f64>() * DEATH_SCATTER_SPEED;
+        let angle = rand::random::<f64>() * TAU;
+        entity.base.velocity = (-angle.sin() * speed, 0.2, angle.cos() * speed);
+        entity.pickup_delay = THROWN_PICKUP_DELAY_TICKS;
+        entity
+    }
+
+    /// Creates the item dropped by the broken block, popping out of it in a random direction.
+    pub fn dropped_by_block(entity_id: i32, item: ItemStack, x: i32, y: i32, z: i32) -> Self {
+        let offset = || rand::random::<f64>() * 0.7 + 0.15;
+        let mut entity = Self::new(
+            entity_id,
+            item,
+            x as f64 + offset(),
+            y as f64 + offset(),
+            z as f64 + offset(),
+        );
+
+        entity.base.velocity = (
+            rand::random::<f64>() * 0.2 - 0.1,
+            0.2,
+            rand::random::<f64>() * 0.2 - 0.1,
+        );
+        entity
+    }
+
+    /// Whether the item has been lying around long enough to despawn.
+    pub fn is_expired(&self) -> bool {
+        self.base.age >= DESPAWN_TICKS
+    }
+
+    /// Whether the player picks the item up, once it can be picked up.
+    pub fn touches(&self, player: &EntityBase) -> bool {
+        self.base.age >= self.pickup_delay && player.touches(&self.base, PICKUP_REACH)
+    }
+}
+
+impl Entity for ItemEntity {
+    fn base(&self) -> &EntityBase {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut EntityBase {
+        &mut self.base
+    }
+
+    fn spawn_packet(&self) -> Packet {
+        let position = &self.base.position;
+        // The rotation bytes carry the velocity, scaled by 128.
+        let velocity = |value: f64| (value * 128.0).clamp(-128.0, 127.0) as i8;
+
+        Packet::PickupSpawn(PickupSpawnPayload {
+            entity_id: self.base.entity_id,
+            item: self.item.id,
+            count: self.item.count,
+            damage: self.item.damage,
+            x: fixed_point(position.x),
+            y: fixed_point(position.y),
+            z: fixed_point(position.z),
+            rotation: velocity(self.base.velocity.0),
+            pitch: velocity(self.base.velocity.1),
+            roll: velocity(self.base.velocity.2),
+        })
+    }
+
+    /// Moves the item by a single tick, letting it fall onto the solid blocks.
+    fn tick(&mut self, world: &World) {
+        self.base.age += 1;
+        self.base
+            .apply_motion(world, GRAVITY, DRAG, GROUND_FRICTION);
+    }
+
+    fn is_removed(&self) -> bool {
+        self.is_expired() || self.item.count <= 0
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        player::PLAYER_BOUNDING_BOX,
+        world::{
+            block::Block,
+            chunk::{ChunkColumn, ChunkPos},
+        },
+    };
+
+    use super::*;
+
+    #[test]
+    fn item_falls_onto_the_ground() {
+        let mut world = World::new();
+        world.insert_chunk(ChunkColumn::new(ChunkPos::new(0, 0)));
+        world.set_block(1, 63, 1, Block::STONE);
+
+        let mut item = ItemEntity::new(2, ItemStack::new(4, 1, 0), 1.5, 66.0, 1.5);
+        for _ in 0..100 {
+            item.tick(&world);
+        }
+
+        let position = item.base.position;
+        assert_eq!((position.x, position.y, position.z), (1.5, 64.0, 1.5));
+        assert!(position.on_ground);
+        assert_eq!(item.base.velocity, (0.0, 0.0, 0.0));
+        assert!(!item.is_removed());
+    }
+
+    #[test]
+    fn players_touch_items_nearby() {
+        let mut item = ItemEntity::new(2, ItemStack::new(4, 1, 0), 1.5, 64.0, 1.5);
+        let position = Position {
+            x: 2.5,
+            y: 64.0,
+            z: 0.5,
+            ..Default::default()
+        };
+        let player = |position| EntityBase::new(1, position, PLAYER_BOUNDING_BOX);
+
+        assert!(!item.touches(&player(position)));
+
+        item.base.age = PICKUP_DELAY_TICKS;
+        assert!(item.touches(&player(position)));
+        assert!(!item.touches(&player(Position {
+            y: 66.0,
+            ..position
+        })));
+        assert!(!item.touches(&player(Position { x: 4.0, ..position })));
+    }
+}
@@ -0,0 +1,120 @@
+use std::time::{Duration, Instant};
+
+/// Timing of the keep-alive packets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeepAliveConfig {
+    /// How often the keep-alive packets are sent.
+    pub interval: Duration,
+
+    /// How long the client may take to echo the keep-alive before being kicked.
+    pub timeout: Duration,
+}
+
+impl Default for KeepAliveConfig {
+    fn default() -> Self {
+        KeepAliveConfig {
+            interval: Duration::from_secs(15),
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// What the connection should do on a keep-alive tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeepAliveTick {
+    /// Send a keep-alive with the identifier.
+    Send(i32),
+
+    /// The previous keep-alive is still awaiting the echo.
+    Wait,
+
+    /// The client didn't echo the keep-alive in time.
+    TimedOut,
+}
+
+/// Keep-alive state of a single connection.
+#[derive(Debug)]
+pub struct KeepAlive {
+    config: KeepAliveConfig,
+
+    /// Identifier of the keep-alive awaiting the echo and when it was sent.
+    pending: Option<(i32, Instant)>,
+}
+
+impl KeepAlive {
+    pub fn new(config: KeepAliveConfig) -> Self {
+        KeepAlive {
+            config,
+            pending: None,
+        }
+    }
+
+    pub fn config(&self) -> KeepAliveConfig {
+        self.config
+    }
+
+    /// Decides whether a new keep-alive should be sent or the client has timed out.
+    pub fn tick(&mut self, now: Instant) -> KeepAliveTick {
+        match self.pending {
+            Some((_, sent_at)) if now.duration_since(sent_at) >= self.config.timeout => {
+                KeepAliveTick::TimedOut
+            }
+            Some(_) => KeepAliveTick::Wait,
+            None => {
+                let id = rand::random();
+                self.pending = Some((id, now));
+                KeepAliveTick::Send(id)
+            }
+        }
+    }
+
+    /// Handles the keep-alive echoed by the client,
+    /// returns `false` if the identifier doesn't match the pending one.
+    pub fn acknowledge(&mut self, id: i32) -> bool {
+        match self.pending {
+            Some((pending, _)) if pending == id => {
+                self.pending = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acknowledged_keep_alive_allows_next_one() {
+        let mut keep_alive = KeepAlive::new(KeepAliveConfig::default());
+        let now = Instant::now();
+
+        let KeepAliveTick::Send(id) = keep_alive.tick(now) else {
+            panic!("Expected a keep-alive to be sent");
+        };
+
+        assert_eq!(keep_alive.tick(now), KeepAliveTick::Wait);
+        assert!(!keep_alive.acknowledge(id.wrapping_add(1)));
+        assert!(keep_alive.acknowledge(id));
+        assert!(matches!(keep_alive.tick(now), KeepAliveTick::Send(_)));
+    }
+
+    #[test]
+    fn unacknowledged_keep_alive_times_out() {
+        let config = KeepAliveConfig::default();
+        let mut keep_alive = KeepAlive::new(config);
+        let now = Instant::now();
+
+        keep_alive.tick(now);
+
+        assert_eq!(
+            keep_alive.tick(now + config.timeout - Duration::from_millis(1)),
+            KeepAliveTick::Wait
+        );
+        assert_eq!(
+            keep_alive.tick(now + config.timeout),
+            KeepAliveTick::TimedOut
+        );
+    }
+}
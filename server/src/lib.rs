@@ -1,139 +1,178 @@
-use log::{debug, error, info, trace};
-use protocol::packet::{
-    DisconnectKickPayload, HandshakePayload, LoginRequestPayload, Packet,
-    PlayerPositionAndLookPayload, SpawnPositionPayload,
-};
-use tokio::{
-    io::{AsyncReadExt, AsyncWriteExt},
-    net::{TcpListener, TcpStream},
+use std::{sync::Arc, time::Duration};
+
+use futures::SinkExt;
+use log::{debug, info, trace};
+use protocol::{codec::PacketCodec, error::ProtocolError, packet::Packet};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_util::{codec::Framed, sync::CancellationToken};
+
+use crate::{
+    auth::LegacySessionServer,
+    ban::BanList,
+    command::CommandRegistry,
+    config::ServerConfig,
+    entity::EntityIdAllocator,
+    game_mode::GameModeList,
+    handle::ServerHandle,
+    ops::OpList,
+    player::Players,
+    tick::TickLoop,
+    world::{anvil::AnvilStorage, generator::Generators, handler::BlockHandlers, Worlds},
 };
 
-pub async fn send_packet(socket: &mut TcpStream, packet: Packet) -> std::io::Result<()> {
-    let data = packet.to_bytes()?;
-    socket.write_all(&data).await?;
+/// Provides the behavior of the mobs, wandering, fleeing and chasing the players.
+pub mod ai;
+/// Provides the verification of the users with the session server.
+pub mod auth;
+/// Provides the list of the banned players.
+pub mod ban;
+/// Provides the sleeping in the beds.
+pub mod bed;
+/// Provides the chat message handling.
+pub mod chat;
+/// Provides the fights between the players.
+pub mod combat;
+/// Provides the slash commands typed by the players.
+pub mod command;
+/// Provides the settings of the server.
+pub mod config;
+/// Provides the per-client connection handling.
+pub mod connection;
+/// Provides the entities living in the world.
+pub mod entity;
+/// Provides the experience of the players and the orbs it's dropped as.
+pub mod experience;
+/// Provides the sand and gravel falling when there's nothing below them.
+pub mod falling;
+/// Provides the food the players eat.
+pub mod food;
+/// Provides the game modes of the players.
+pub mod game_mode;
+/// Provides the shared state of the running server.
+pub mod handle;
+/// Provides the health of the players and the damage they take.
+pub mod health;
+/// Provides the inventories of the players.
+pub mod inventory;
+/// Provides the items dropped in the world.
+pub mod item;
+/// Provides the keep-alive tracking of the connections.
+pub mod keep_alive;
+/// Provides the mobs living in the world, e.g. the animals.
+pub mod mob;
+/// Provides the list of the operators.
+pub mod ops;
+/// Provides the JSON files the server state is persisted in.
+pub mod persist;
+/// Provides the registry of the connected players.
+pub mod player;
+/// Provides the saving and loading of the world and the players.
+pub mod save;
+/// Provides the loop driving the time-dependent parts of the server.
+pub mod tick;
+/// Provides the tracking of the players seen by each other and their movement.
+pub mod tracker;
+/// Provides the tracking of the columns loaded by the clients.
+pub mod view;
+/// Provides the blocks and chunks the world is made of.
+pub mod world;
+
+pub async fn send_packet(
+    socket: &mut Framed<TcpStream, PacketCodec>,
+    packet: Packet,
+) -> Result<(), ProtocolError> {
+    debug!("Sending 0x{:02X} {}", packet.id(), packet.name());
+    trace!("Sent: {:?}", packet);
+    socket.send(packet).await
+}
 
-    trace!("Sent: {:?} {:02X?}", packet, &data);
-    Ok(())
+/// Runs the server with the built-in commands.
+pub async fn start_server(config: ServerConfig) {
+    start_server_with_commands(config, CommandRegistry::with_builtins()).await;
 }
 
-pub async fn start_server() {
+/// Runs the server with the commands of the registry,
+/// e.g. the built-in ones extended by the embedding application.
+pub async fn start_server_with_commands(config: ServerConfig, commands: CommandRegistry) {
     info!("Hello! :3");
-    let listener = TcpListener::bind("127.0.0.1:25565").await.unwrap();
+    let listener = TcpListener::bind(config.address).await.unwrap();
+    info!("Listening on {}", config.address);
+
+    let bans = BanList::load(&config.ban_list_path).expect("Failed to load the ban list");
+    let ops = OpList::load(&config.op_list_path).expect("Failed to load the op list");
+    let game_modes =
+        GameModeList::load(&config.game_mode_list_path).expect("Failed to load the game mode list");
+    let mut config = config;
+    let worlds = Worlds::new();
+    let storage = AnvilStorage::new(&config.world_path);
+    match storage.load_level() {
+        Ok(Some(level)) => {
+            save::apply_level(&level, &mut config, &worlds).expect("Failed to load the level");
+            info!("Loaded the world from {}", config.world_path.display());
+        }
+        Ok(None) => info!("Creating a new world in {}", config.world_path.display()),
+        Err(e) => panic!("Failed to load the level: {}", e),
+    }
+
+    let players = Arc::new(Players::new());
+    let worlds = Arc::new(worlds);
+    let generators = Arc::new(Generators::new(&config.level_type, config.seed));
+
+    let entity_ids = Arc::new(EntityIdAllocator::new());
+    let (tick_loop, events) =
+        TickLoop::new(&config, players.clone(), worlds.clone(), entity_ids.clone());
+    tokio::spawn(tick_loop.run());
+
+    let server = ServerHandle {
+        config: Arc::new(config),
+        players,
+        entity_ids,
+        events,
+        commands: Arc::new(commands),
+        bans: Arc::new(bans),
+        ops: Arc::new(ops),
+        game_modes: Arc::new(game_modes),
+        sessions: Arc::new(LegacySessionServer),
+        storage: Arc::new(storage),
+        saving: Arc::default(),
+        worlds,
+        generators,
+        block_handlers: Arc::new(BlockHandlers::with_builtins()),
+        shutdown: CancellationToken::new(),
+    };
+    tokio::spawn(save::autosave(server.clone()));
+    tokio::spawn(save::unload_idle(server.clone()));
 
     loop {
-        let (mut socket, addr) = listener.accept().await.unwrap();
+        let (socket, addr) = tokio::select! {
+            accepted = listener.accept() => accepted.unwrap(),
+            _ = server.shutdown.cancelled() => break,
+        };
         debug!("Connection from {:?}", &addr);
 
-        tokio::spawn(async move {
-            let mut buffer = vec![0u8; 256];
-
-            loop {
-                let n = socket
-                    .read(&mut buffer)
-                    .await
-                    .expect("Failed to read data from socket");
-
-                if n == 0 {
-                    return;
-                }
-
-                trace!("Received: {:02X?}", buffer);
-
-                let Ok(packet) = Packet::from_bytes(buffer.as_ref()) else {
-                    return;
-                };
-
-                match packet {
-                    Packet::ServerListPing(_) => {
-                        debug!("Received server ping packet!");
-
-                        send_packet(
-                            &mut socket,
-                            Packet::DisconnectKick(DisconnectKickPayload {
-                                reason: "A Minecraft Server§0§20".to_string(),
-                            }),
-                        )
-                        .await
-                        .unwrap();
-                    }
-                    Packet::Handshake(_) => {
-                        debug!("Received handshake packet!");
-
-                        send_packet(
-                            &mut socket,
-                            Packet::Handshake(HandshakePayload {
-                                data: "-".to_string(),
-                            }),
-                        )
-                        .await
-                        .unwrap();
-                    }
-                    Packet::LoginRequest(_) => {
-                        debug!("Received login request packet!");
-
-                        send_packet(
-                            &mut socket,
-                            Packet::LoginRequest(LoginRequestPayload {
-                                id: 1234,
-                                username: "".to_string(),
-                                level_type: "default".to_string(),
-                                server_mode: 1,
-                                dimension: 0,
-                                difficulty: 0,
-                                unused_0: 0,
-                                max_players: 20,
-                            }),
-                        )
-                        .await
-                        .unwrap();
-
-                        // spawn position
-
-                        send_packet(
-                            &mut socket,
-                            Packet::SpawnPosition(SpawnPositionPayload { x: 8, y: 65, z: 8 }),
-                        )
-                        .await
-                        .unwrap();
-
-                        // position and look
-
-                        send_packet(
-                            &mut socket,
-                            Packet::PlayerPositionAndLook(PlayerPositionAndLookPayload {
-                                x: 8.5,
-                                stance_y_0: 66.62,
-                                stance_y_1: 65.0,
-                                z: 8.5,
-                                yaw: -180.0,
-                                pitch: 0.0,
-                                on_ground: 0,
-                            }),
-                        )
-                        .await
-                        .unwrap();
-                    }
-                    Packet::PlayerPositionAndLook(position_and_look) => {
-                        debug!("Received player position and look packet!",);
-
-                        send_packet(
-                            &mut socket,
-                            Packet::PlayerPositionAndLook(PlayerPositionAndLookPayload {
-                                x: position_and_look.x,
-                                stance_y_0: position_and_look.stance_y_0,
-                                stance_y_1: position_and_look.stance_y_1,
-                                z: position_and_look.z,
-                                yaw: position_and_look.yaw,
-                                pitch: position_and_look.pitch,
-                                on_ground: position_and_look.on_ground,
-                            }),
-                        )
-                        .await
-                        .unwrap();
-                    }
-                    _ => error!("Unhandled packet type"),
-                }
-            }
-        });
+        tokio::spawn(connection::handle_connection(socket, addr, server.clone()));
     }
+
+    stop_server(&server).await;
+}
+
+/// Kicks all the players, waits a moment for the connections to close
+/// and saves the world.
+async fn stop_server(server: &ServerHandle) {
+    info!("Stopping the server");
+
+    for player in server.players.all() {
+        player.kick("Server closed");
+    }
+
+    for _ in 0..50 {
+        if server.players.count() == 0 {
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    info!("Saving the world");
+    save::save_all(server).await;
 }
@@ -0,0 +1,625 @@
+use std::any::Any;
+
+use protocol::{
+    chunk::{CHUNK_HEIGHT, CHUNK_WIDTH},
+    metadata::MetadataValue,
+    packet::{EntityStatusPayload, MobSpawnPayload, Packet},
+};
+
+use crate::{
+    entity::{angle_steps, fixed_point, BoundingBox, Entity, EntityBase, Position},
+    world::{block::Block, chunk::ChunkPos, BlockPos, World},
+};
+
+/// Number of ticks between the attempts to spawn the passive mobs, 20 seconds.
+pub const PASSIVE_SPAWN_INTERVAL: u64 = 400;
+
+/// Most passive mobs spawned in a single chunk column.
+pub const MAX_PASSIVE_MOBS_PER_CHUNK: usize = 2;
+
+/// Chance of a chunk column below the cap spawning a passive mob on each attempt.
+pub const PASSIVE_SPAWN_CHANCE: f64 = 0.1;
+
+/// Number of ticks between the attempts to spawn the hostile mobs, a second.
+pub const HOSTILE_SPAWN_INTERVAL: u64 = 20;
+
+/// Most hostile mobs spawned in a single chunk column.
+pub const MAX_HOSTILE_MOBS_PER_CHUNK: usize = 2;
+
+/// Chance of a chunk column below the cap spawning a hostile mob on each attempt.
+pub const HOSTILE_SPAWN_CHANCE: f64 = 0.05;
+
+/// Brightest light the hostile mobs spawn in.
+const MAX_HOSTILE_SPAWN_LIGHT: u8 = 7;
+
+/// Distance from the players the mobs spawn beyond.
+const MIN_SPAWN_DISTANCE: f64 = 24.0;
+
+/// Distance from all the players the mobs despawn at right away.
+const DESPAWN_RANGE: f64 = 128.0;
+
+/// Distance from the players the mobs may randomly despawn beyond, once idle.
+const IDLE_RANGE: f64 = 32.0;
+
+/// Number of ticks the mob has to be away from the players before it may despawn.
+const IDLE_TICKS: u64 = 600;
+
+/// Chance of the idle mob despawning each tick, 1 in 800.
+const IDLE_DESPAWN_CHANCE: f64 = 1.0 / 800.0;
+
+/// Number of ticks the mob lies dead before it's removed, showing the death animation.
+const DEATH_TICKS: u64 = 20;
+
+/// Number of ticks the undead burn for once set on fire by the daylight.
+const BURN_TICKS: u32 = 160;
+
+/// Number of ticks between the damages taken while burning.
+const BURN_DAMAGE_INTERVAL: u64 = 20;
+
+/// Damage taken while burning.
+const BURN_DAMAGE: i16 = 1;
+
+/// Metadata index of the flags of the entity.
+const FLAGS_INDEX: u8 = 0;
+
+/// Flag of the entity being on fire.
+const ON_FIRE_FLAG: i8 = 0x1;
+
+/// Metadata index of the fleece color of the sheep.
+const FLEECE_INDEX: u8 = 16;
+
+/// Number of ticks the mobs can't be hurt for after getting hurt.
+const INVULNERABLE_TICKS: u8 = 10;
+
+/// Number of ticks the passive mobs run away for after getting hurt.
+const PANIC_TICKS: u32 = 60;
+
+/// Entity status of getting hurt.
+const HURT_STATUS: i8 = 2;
+
+/// Entity status of dying.
+const DEAD_STATUS: i8 = 3;
+
+/// Speed the mob falls faster by each tick.
+const GRAVITY: f64 = 0.08;
+
+/// Share of the velocity the mob keeps each tick.
+const DRAG: f64 = 0.98;
+
+/// Share of the horizontal velocity the mob keeps each tick it walks on the ground.
+const GROUND_FRICTION: f64 = 0.6;
+
+/// Kind of a mob.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MobKind {
+    Pig,
+    Sheep,
+    Cow,
+    Chicken,
+    Creeper,
+    Skeleton,
+    Spider,
+    Zombie,
+}
+
+impl MobKind {
+    /// Mobs spawned on the grass, which never attack the players.
+    pub const PASSIVE: [MobKind; 4] =
+        [MobKind::Pig, MobKind::Sheep, MobKind::Cow, MobKind::Chicken];
+
+    /// Mobs spawned in the dark, which attack the players.
+    pub const HOSTILE: [MobKind; 4] = [
+        MobKind::Creeper,
+        MobKind::Skeleton,
+        MobKind::Spider,
+        MobKind::Zombie,
+    ];
+
+    pub fn is_hostile(self) -> bool {
+        Self::HOSTILE.contains(&self)
+    }
+
+    /// Whether the mob burns in the daylight.
+    pub fn is_undead(self) -> bool {
+        matches!(self, MobKind::Skeleton | MobKind::Zombie)
+    }
+
+    /// Identifier of the mob type in the Mob Spawn packet.
+    pub fn type_id(self) -> i8 {
+        match self {
+            MobKind::Pig => 90,
+            MobKind::Sheep => 91,
+            MobKind::Cow => 92,
+            MobKind::Chicken => 93,
+            MobKind::Creeper => 50,
+            MobKind::Skeleton => 51,
+            MobKind::Spider => 52,
+            MobKind::Zombie => 54,
+        }
+    }
+
+    pub fn bounding_box(self) -> BoundingBox {
+        match self {
+            MobKind::Pig => BoundingBox::new(0.9, 0.9),
+            MobKind::Sheep | MobKind::Cow => BoundingBox::new(0.9, 1.3),
+            MobKind::Chicken => BoundingBox::new(0.3, 0.4),
+            MobKind::Creeper | MobKind::Skeleton | MobKind::Zombie => BoundingBox::new(0.6, 1.8),
+            MobKind::Spider => BoundingBox::new(1.4, 0.9),
+        }
+    }
+
+    /// Health the mob spawns with, in half-hearts.
+    pub fn max_health(self) -> i16 {
+        match self {
+            MobKind::Pig | MobKind::Cow => 10,
+            MobKind::Sheep => 8,
+            MobKind::Chicken => 4,
+            MobKind::Creeper | MobKind::Skeleton | MobKind::Zombie => 20,
+            MobKind::Spider => 16,
+        }
+    }
+
+    /// Damage dealt by the hostile mob attacking a player.
+    ///
+    /// All of them fight in melee for now, the skeletons don't shoot
+    /// and the creepers don't explode.
+    pub fn attack_damage(self) -> i16 {
+        match self {
+            MobKind::Zombie => 4,
+            MobKind::Creeper | MobKind::Skeleton => 3,
+            MobKind::Spider => 2,
+            _ => 0,
+        }
+    }
+
+    /// Experience dropped by the mob killed by a player.
+    pub fn experience(self) -> i32 {
+        if self.is_hostile() {
+            5
+        } else {
+            rand::random_range(1..=3)
+        }
+    }
+
+    /// Name of the mob shown in the death messages.
+    pub fn name(self) -> &'static str {
+        match self {
+            MobKind::Pig => "Pig",
+            MobKind::Sheep => "Sheep",
+            MobKind::Cow => "Cow",
+            MobKind::Chicken => "Chicken",
+            MobKind::Creeper => "Creeper",
+            MobKind::Skeleton => "Skeleton",
+            MobKind::Spider => "Spider",
+            MobKind::Zombie => "Zombie",
+        }
+    }
+}
+
+/// A mob living in the world, e.g. a pig.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mob {
+    pub base: EntityBase,
+    pub kind: MobKind,
+    pub health: i16,
+
+    /// Whether the mob stays in the world when away from the players.
+    pub persistent: bool,
+
+    /// Number of ticks since a player was last nearby.
+    pub idle_ticks: u64,
+
+    /// Whether the mob has despawned, to be removed.
+    pub despawned: bool,
+
+    /// Remaining ticks of burning.
+    pub fire_ticks: u32,
+
+    /// Number of ticks since the mob died.
+    pub death_ticks: u64,
+
+    /// Entity statuses to be shown to the players nearby, e.g. getting hurt.
+    pub statuses: Vec<i8>,
+
+    /// Remaining ticks the mob can't be hurt for.
+    pub invulnerable_ticks: u8,
+
+    /// Remaining ticks the passive mob runs away for.
+    pub panic_ticks: u32,
+
+    /// Player chased by the hostile mob.
+    pub target: Option<i32>,
+
+    /// Remaining ticks before the mob may attack again.
+    pub attack_cooldown: u32,
+
+    /// Blocks the mob walks through, the next one last.
+    pub path: Vec<BlockPos>,
+}
+
+impl Mob {
+    pub fn new(entity_id: i32, kind: MobKind, position: Position) -> Self {
+        let mut base = EntityBase::new(entity_id, position, kind.bounding_box());
+        base.metadata.set(FLAGS_INDEX, MetadataValue::Byte(0));
+        if kind == MobKind::Sheep {
+            base.metadata
+                .set(FLEECE_INDEX, MetadataValue::Byte(random_fleece_color()));
+        }
+
+        Self {
+            base,
+            kind,
+            health: kind.max_health(),
+            persistent: false,
+            idle_ticks: 0,
+            despawned: false,
+            fire_ticks: 0,
+            death_ticks: 0,
+            statuses: Vec::new(),
+            invulnerable_ticks: 0,
+            panic_ticks: 0,
+            target: None,
+            attack_cooldown: 0,
+            path: Vec::new(),
+        }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.health <= 0
+    }
+
+    /// Takes the damage, queueing the status showing it and scaring the passive mob,
+    /// returns `false` if the mob can't be hurt right now.
+    pub fn damage(&mut self, amount: i16) -> bool {
+        if self.is_dead() || self.invulnerable_ticks > 0 || amount <= 0 {
+            return false;
+        }
+
+        self.health = (self.health - amount).max(0);
+        self.invulnerable_ticks = INVULNERABLE_TICKS;
+        if !self.kind.is_hostile() {
+            self.panic_ticks = PANIC_TICKS;
+            self.path.clear();
+        }
+        self.statuses.push(if self.is_dead() {
+            DEAD_STATUS
+        } else {
+            HURT_STATUS
+        });
+        true
+    }
+
+    /// Builds the packets showing the queued statuses, emptying the queue.
+    pub fn take_status_packets(&mut self) -> Vec<Packet> {
+        let entity_id = self.base.entity_id;
+
+        self.statuses
+            .drain(..)
+            .map(|status| Packet::EntityStatus(EntityStatusPayload { entity_id, status }))
+            .collect()
+    }
+
+    /// Sets the undead seeing the sky at the day on fire, puts out the ones in the water
+    /// and hurts the burning ones.
+    fn tick_burning(&mut self, world: &World) {
+        let position = &self.base.position;
+        let (x, y, z) = (
+            position.x.floor() as i32,
+            position.y.floor() as i32,
+            position.z.floor() as i32,
+        );
+        let in_water = world
+            .get_block(x, y, z)
+            .is_some_and(|block| block.is_water());
+
+        if in_water {
+            self.fire_ticks = 0;
+        } else if self.kind.is_undead() && !world.is_night() && world.sees_sky(x, y + 1, z) {
+            self.fire_ticks = self.fire_ticks.max(BURN_TICKS);
+        }
+
+        if self.fire_ticks > 0 {
+            self.fire_ticks -= 1;
+            if self.base.age.is_multiple_of(BURN_DAMAGE_INTERVAL) {
+                self.damage(BURN_DAMAGE);
+            }
+        }
+
+        let flags = if self.fire_ticks > 0 { ON_FIRE_FLAG } else { 0 };
+        self.base
+            .metadata
+            .set(FLAGS_INDEX, MetadataValue::Byte(flags));
+    }
+
+    /// Despawns the mob far away from the players, unless it's persistent.
+    ///
+    /// Takes the squared distance to the nearest player, `None` if there are none.
+    /// The chunks are never unloaded, so the mobs left behind have to go
+    /// for their count to stay bounded.
+    pub fn tick_despawn(&mut self, nearest_squared: Option<f64>) {
+        if self.persistent {
+            return;
+        }
+
+        let nearest_squared = nearest_squared.unwrap_or(f64::INFINITY);
+        if nearest_squared > DESPAWN_RANGE * DESPAWN_RANGE {
+            self.despawned = true;
+            return;
+        }
+
+        if nearest_squared <= IDLE_RANGE * IDLE_RANGE {
+            self.idle_ticks = 0;
+            return;
+        }
+
+        self.idle_ticks += 1;
+        if self.idle_ticks > IDLE_TICKS && rand::random::<f64>() < IDLE_DESPAWN_CHANCE {
+            self.despawned = true;
+        }
+    }
+}
+
+impl Entity for Mob {
+    fn base(&self) -> &EntityBase {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut EntityBase {
+        &mut self.base
+    }
+
+    fn spawn_packet(&self) -> Packet {
+        let position = &self.base.position;
+
+        Packet::MobSpawn(MobSpawnPayload {
+            entity_id: self.base.entity_id,
+            mob_type: self.kind.type_id(),
+            x: fixed_point(position.x),
+            y: fixed_point(position.y),
+            z: fixed_point(position.z),
+            yaw: angle_steps(position.yaw),
+            pitch: angle_steps(position.pitch),
+            head_yaw: angle_steps(position.yaw),
+            metadata: self.base.metadata.clone(),
+        })
+    }
+
+    /// Moves the mob by a single tick, letting it fall onto the solid blocks,
+    /// and burns the undead in the daylight.
+    fn tick(&mut self, world: &World) {
+        self.base.age += 1;
+        self.base
+            .apply_motion(world, GRAVITY, DRAG, GROUND_FRICTION);
+        self.invulnerable_ticks = self.invulnerable_ticks.saturating_sub(1);
+
+        if self.is_dead() {
+            self.death_ticks += 1;
+        } else {
+            self.tick_burning(world);
+        }
+    }
+
+    fn is_removed(&self) -> bool {
+        self.despawned || self.death_ticks >= DEATH_TICKS
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Random fleece color of a spawned sheep, mostly white.
+fn random_fleece_color() -> i8 {
+    let roll = rand::random_range(0..100);
+
+    match roll {
+        0..5 => 15,
+        5..10 => 7,
+        10..15 => 8,
+        15..18 => 12,
+        18 if rand::random_range(0..500) == 0 => 6,
+        _ => 0,
+    }
+}
+
+/// Picks a random spot in the column a passive mob may spawn at,
+/// standing on the grass with room above it.
+pub fn passive_spawn_spot(world: &World, pos: ChunkPos) -> Option<Position> {
+    let chunk = world.chunk(pos)?;
+    let (x, z) = (
+        rand::random_range(0..CHUNK_WIDTH),
+        rand::random_range(0..CHUNK_WIDTH),
+    );
+
+    let ground = chunk
+        .highest_block(x, z)
+        .filter(|&ground| ground + 2 < CHUNK_HEIGHT)?;
+    let free = |y: usize| {
+        let block = chunk.get_block(x, y, z);
+        block.is_replaceable() && !block.is_water() && !block.is_lava()
+    };
+    if chunk.get_block(x, ground, z).id != Block::GRASS.id || !free(ground + 1) || !free(ground + 2)
+    {
+        return None;
+    }
+
+    Some(spawn_position(pos, x, ground + 1, z))
+}
+
+/// Picks a random spot in the column a hostile mob may spawn at,
+/// in the dark on a solid block with room above it.
+pub fn hostile_spawn_spot(world: &World, pos: ChunkPos) -> Option<Position> {
+    let chunk = world.chunk(pos)?;
+    let (x, z) = (
+        rand::random_range(0..CHUNK_WIDTH),
+        rand::random_range(0..CHUNK_WIDTH),
+    );
+
+    let highest = chunk.highest_block(x, z)?;
+    let y = rand::random_range(1..=highest + 1);
+    if y + 1 >= CHUNK_HEIGHT {
+        return None;
+    }
+
+    let free = |y: usize| {
+        let block = chunk.get_block(x, y, z);
+        block.is_replaceable() && !block.is_water() && !block.is_lava()
+    };
+    let floor = chunk.get_block(x, y - 1, z);
+    if floor.is_replaceable() || floor.id == Block::BEDROCK.id || !free(y) || !free(y + 1) {
+        return None;
+    }
+
+    let position = spawn_position(pos, x, y, z);
+    let (block_x, block_z) = (position.x.floor() as i32, position.z.floor() as i32);
+    world
+        .light(block_x, y as i32, block_z)
+        .filter(|&light| light <= MAX_HOSTILE_SPAWN_LIGHT)?;
+
+    Some(position)
+}
+
+/// Whether the mob may spawn with the nearest player at the squared distance,
+/// `None` if there are no players.
+///
+/// The mobs don't spawn right next to the players nor where they would despawn.
+pub fn spawn_distance_allowed(nearest_squared: Option<f64>) -> bool {
+    nearest_squared.is_some_and(|distance| {
+        (MIN_SPAWN_DISTANCE * MIN_SPAWN_DISTANCE..=DESPAWN_RANGE * DESPAWN_RANGE)
+            .contains(&distance)
+    })
+}
+
+/// Position of the mob spawned in the middle of the block of the column, facing randomly.
+fn spawn_position(pos: ChunkPos, x: usize, y: usize, z: usize) -> Position {
+    Position {
+        x: (pos.x * CHUNK_WIDTH as i32 + x as i32) as f64 + 0.5,
+        y: y as f64,
+        z: (pos.z * CHUNK_WIDTH as i32 + z as i32) as f64 + 0.5,
+        yaw: rand::random::<f32>() * 360.0,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::world::{chunk::ChunkColumn, NIGHT_START};
+
+    use super::*;
+
+    #[test]
+    fn spawn_on_grass_only() {
+        let mut world = World::new();
+        let pos = ChunkPos::new(1, -1);
+        world.insert_chunk(ChunkColumn::new(pos));
+        assert_eq!(passive_spawn_spot(&world, pos), None);
+
+        for x in 16..32 {
+            for z in -16..0 {
+                world.set_block(x, 64, z, Block::GRASS);
+            }
+        }
+
+        let spot = passive_spawn_spot(&world, pos).unwrap();
+        assert_eq!(spot.chunk(), pos);
+        assert_eq!(spot.y, 65.0);
+
+        let mob = Mob::new(1, MobKind::Sheep, spot);
+        assert!(matches!(
+            mob.base.metadata.get(FLEECE_INDEX),
+            Some(MetadataValue::Byte(_))
+        ));
+        assert!(matches!(
+            mob.spawn_packet(),
+            Packet::MobSpawn(MobSpawnPayload { mob_type: 91, .. })
+        ));
+    }
+
+    #[test]
+    fn despawn_away_from_players() {
+        let mut mob = Mob::new(1, MobKind::Pig, Position::default());
+
+        mob.idle_ticks = IDLE_TICKS * 100;
+        mob.tick_despawn(Some(16.0));
+        assert_eq!(mob.idle_ticks, 0);
+        assert!(!mob.is_removed());
+
+        mob.persistent = true;
+        mob.tick_despawn(None);
+        assert!(!mob.is_removed());
+
+        mob.persistent = false;
+        mob.tick_despawn(Some(200.0 * 200.0));
+        assert!(mob.is_removed());
+
+        assert!(!spawn_distance_allowed(None));
+        assert!(!spawn_distance_allowed(Some(10.0 * 10.0)));
+        assert!(spawn_distance_allowed(Some(40.0 * 40.0)));
+    }
+
+    #[test]
+    fn hostile_mobs_spawn_in_the_dark() {
+        let mut world = World::new();
+        let pos = ChunkPos::new(0, 0);
+        world.insert_chunk(ChunkColumn::new(pos));
+        for x in 0..16 {
+            for z in 0..16 {
+                for y in 0..10 {
+                    world.set_block(x, y, z, Block::STONE);
+                }
+            }
+        }
+
+        assert!((0..100).all(|_| hostile_spawn_spot(&world, pos).is_none()));
+
+        world.set_time(NIGHT_START);
+        let spot = (0..1000)
+            .find_map(|_| hostile_spawn_spot(&world, pos))
+            .unwrap();
+        assert_eq!(spot.y, 10.0);
+    }
+
+    #[test]
+    fn undead_burn_in_daylight() {
+        let mut world = World::new();
+        world.insert_chunk(ChunkColumn::new(ChunkPos::new(0, 0)));
+        world.set_block(0, 63, 0, Block::STONE);
+        let position = Position {
+            x: 0.5,
+            y: 64.0,
+            z: 0.5,
+            ..Default::default()
+        };
+
+        let mut zombie = Mob::new(1, MobKind::Zombie, position);
+        let mut creeper = Mob::new(2, MobKind::Creeper, position);
+        for _ in 0..BURN_DAMAGE_INTERVAL {
+            zombie.tick(&world);
+            creeper.tick(&world);
+        }
+
+        assert_eq!(zombie.health, 19);
+        assert_eq!(
+            zombie.base.metadata.get(FLAGS_INDEX),
+            Some(&MetadataValue::Byte(ON_FIRE_FLAG))
+        );
+        assert_eq!(
+            zombie.take_status_packets(),
+            vec![Packet::EntityStatus(EntityStatusPayload {
+                entity_id: 1,
+                status: HURT_STATUS,
+            })]
+        );
+        assert!(zombie.statuses.is_empty());
+        assert_eq!(creeper.health, 20);
+
+        assert!(!zombie.damage(1));
+        zombie.invulnerable_ticks = 0;
+        assert!(zombie.damage(100));
+        for _ in 0..DEATH_TICKS {
+            assert!(!zombie.is_removed());
+            zombie.tick(&world);
+        }
+        assert!(zombie.is_removed());
+    }
+}
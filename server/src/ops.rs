@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    command::DEFAULT_PERMISSION_LEVEL,
+    persist::{NameList, NamedEntry},
+};
+
+/// Permission level needed to manage the players, e.g. kick, ban or op them.
+pub const MODERATOR_PERMISSION_LEVEL: u8 = 3;
+
+/// Highest permission level, given by `/op` and needed to stop the server.
+pub const OPERATOR_PERMISSION_LEVEL: u8 = 4;
+
+/// An operator, as stored in the op list file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpEntry {
+    /// Name of the operator.
+    pub name: String,
+
+    /// Permission level of the operator.
+    pub level: u8,
+}
+
+impl NamedEntry for OpEntry {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Players with the elevated permission level, persisted in a JSON file.
+pub type OpList = NameList<OpEntry>;
+
+impl OpList {
+    /// Permission level of the player, ignoring the case of the name.
+    pub fn level(&self, name: &str) -> u8 {
+        self.get(name)
+            .map_or(DEFAULT_PERMISSION_LEVEL, |entry| entry.level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn persist_ops() {
+        let path = std::env::temp_dir().join(format!("ops-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let ops = OpList::load(&path).unwrap();
+        assert_eq!(ops.level("ezioleq"), DEFAULT_PERMISSION_LEVEL);
+
+        ops.add(OpEntry {
+            name: "Ezioleq".to_string(),
+            level: OPERATOR_PERMISSION_LEVEL,
+        })
+        .await
+        .unwrap();
+
+        let ops = OpList::load(&path).unwrap();
+        assert_eq!(ops.level("ezioleq"), OPERATOR_PERMISSION_LEVEL);
+        assert!(ops.remove("EZIOLEQ").await.unwrap());
+        assert!(!ops.remove("ezioleq").await.unwrap());
+
+        let ops = OpList::load(&path).unwrap();
+        assert_eq!(ops.level("ezioleq"), DEFAULT_PERMISSION_LEVEL);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,134 @@
+use std::{
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+    sync::RwLock,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::sync::Mutex;
+
+/// Entry of a [`NameList`], belonging to a player.
+pub trait NamedEntry: Clone + Serialize + DeserializeOwned {
+    /// Name of the player the entry belongs to.
+    fn name(&self) -> &str;
+}
+
+/// Entries of the players, persisted in a JSON file, e.g. the bans.
+#[derive(Debug)]
+pub struct NameList<T> {
+    path: PathBuf,
+
+    /// Entries keyed by the lowercase name.
+    entries: RwLock<BTreeMap<String, T>>,
+
+    /// Held while the file is written, so an older list can't replace a newer one.
+    saving: Mutex<()>,
+}
+
+impl<T: NamedEntry> NameList<T> {
+    /// Loads the list from the file, which may not exist yet.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let entries: Vec<T> = load_json_list(&path)?;
+
+        Ok(Self {
+            path,
+            entries: RwLock::new(
+                entries
+                    .into_iter()
+                    .map(|entry| (entry.name().to_lowercase(), entry))
+                    .collect(),
+            ),
+            saving: Mutex::new(()),
+        })
+    }
+
+    /// Returns the entry of the player, ignoring the case of the name.
+    pub fn get(&self, name: &str) -> Option<T> {
+        self.entries
+            .read()
+            .unwrap()
+            .get(&name.to_lowercase())
+            .cloned()
+    }
+
+    /// Adds the entry, replacing the previous one of the player, and saves the list.
+    pub async fn add(&self, entry: T) -> io::Result<()> {
+        self.entries
+            .write()
+            .unwrap()
+            .insert(entry.name().to_lowercase(), entry);
+        self.save().await
+    }
+
+    /// Removes the entry of the player and saves the list, returns `false` if it had none.
+    pub async fn remove(&self, name: &str) -> io::Result<bool> {
+        let removed = self
+            .entries
+            .write()
+            .unwrap()
+            .remove(&name.to_lowercase())
+            .is_some();
+
+        if removed {
+            self.save().await?;
+        }
+
+        Ok(removed)
+    }
+
+    /// Writes the entries to the file, the snapshot is taken once the previous write finishes.
+    async fn save(&self) -> io::Result<()> {
+        let _saving = self.saving.lock().await;
+        let entries: Vec<T> = self.entries.read().unwrap().values().cloned().collect();
+        save_json_list(&self.path, &entries).await
+    }
+}
+
+/// Loads the JSON array of the entries, the file may not exist yet.
+fn load_json_list<T: DeserializeOwned>(path: &Path) -> io::Result<Vec<T>> {
+    match std::fs::read(path) {
+        Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Saves the entries as a JSON array, replacing the file.
+async fn save_json_list<T: Serialize>(path: &Path, entries: &[T]) -> io::Result<()> {
+    let json = serde_json::to_vec_pretty(entries)?;
+    tokio::fs::write(path, json).await
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::future;
+
+    use super::*;
+    use crate::ban::BanEntry;
+
+    #[tokio::test]
+    async fn concurrent_saves_keep_all_the_entries() {
+        let path = std::env::temp_dir().join(format!("name-list-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let list: NameList<BanEntry> = NameList::load(&path).unwrap();
+        let saves = (0..16).map(|i| {
+            list.add(BanEntry {
+                name: format!("player{}", i),
+                source: "Server".to_string(),
+                reason: "Griefing".to_string(),
+            })
+        });
+        for saved in future::join_all(saves).await {
+            saved.unwrap();
+        }
+
+        let list: NameList<BanEntry> = NameList::load(&path).unwrap();
+        assert_eq!(list.entries.read().unwrap().len(), 16);
+        assert!(list.get("PLAYER15").is_some());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
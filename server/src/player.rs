@@ -0,0 +1,283 @@
+use std::{any::Any, collections::HashMap, net::SocketAddr, sync::RwLock};
+
+use protocol::{
+    packet::{NamedEntitySpawnPayload, Packet},
+    slot::ItemStack,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    chat,
+    entity::{angle_steps, fixed_point, BoundingBox, Entity, EntityBase},
+    experience::Experience,
+    health::Health,
+    inventory::Inventory,
+    world::{chunk::ChunkPos, dimension::Dimension, BlockPos},
+};
+
+/// Sending half of the queue of the packets written to the player's socket.
+pub type PacketSender = UnboundedSender<Packet>;
+
+/// Bounding box of the players.
+pub const PLAYER_BOUNDING_BOX: BoundingBox = BoundingBox::new(0.6, 1.8);
+
+/// Height of the player's eyes above the feet.
+pub const EYE_HEIGHT: f64 = 1.62;
+
+/// Longest name the players may log in with.
+pub const MAX_USERNAME_LENGTH: usize = 16;
+
+/// Whether the players may log in with the name, made of the letters, digits and underscores,
+/// which keeps it a plain file name for the player's data.
+pub fn is_valid_username(username: &str) -> bool {
+    (1..=MAX_USERNAME_LENGTH).contains(&username.len())
+        && username
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || byte == b'_')
+}
+
+/// A logged in player.
+#[derive(Debug, Clone)]
+pub struct Player {
+    /// Entity of the player, at the last position reported by the client.
+    pub base: EntityBase,
+
+    /// Name the player logged in with.
+    pub username: String,
+
+    /// Dimension the player is in.
+    pub dimension: Dimension,
+
+    /// Address of the player's connection.
+    pub addr: SocketAddr,
+
+    /// Game mode, `0` for survival, `1` for creative.
+    pub game_mode: i32,
+
+    /// Permission level, `0` for the regular players, up to `4` for the operators.
+    pub permission_level: u8,
+
+    /// Selected hotbar slot, from `0` to `8`.
+    pub held_slot: i16,
+
+    pub inventory: Inventory,
+
+    pub health: Health,
+
+    pub experience: Experience,
+
+    pub sprinting: bool,
+
+    /// Remaining ticks of eating the held food.
+    pub eating: Option<u32>,
+
+    /// Head of the bed the player sleeps in.
+    pub bed: Option<BlockPos>,
+
+    /// Head of the bed the player respawns at, the world spawn if not set.
+    pub spawn_point: Option<BlockPos>,
+
+    /// Queue of the packets to be sent to the player.
+    pub sender: PacketSender,
+}
+
+impl Player {
+    /// Queues the packet to be sent to the player.
+    ///
+    /// Returns `false` if the player has already disconnected.
+    pub fn send(&self, packet: Packet) -> bool {
+        self.sender.send(packet).is_ok()
+    }
+
+    /// Queues the kick, the connection gets closed once it's sent.
+    pub fn kick(&self, reason: &str) -> bool {
+        self.send(chat::kick_packet(reason))
+    }
+}
+
+impl Entity for Player {
+    fn base(&self) -> &EntityBase {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut EntityBase {
+        &mut self.base
+    }
+
+    fn spawn_packet(&self) -> Packet {
+        let position = &self.base.position;
+
+        Packet::NamedEntitySpawn(NamedEntitySpawnPayload {
+            entity_id: self.base.entity_id,
+            player_name: self.username.clone(),
+            x: fixed_point(position.x),
+            y: fixed_point(position.y),
+            z: fixed_point(position.z),
+            rotation: angle_steps(position.yaw),
+            pitch: angle_steps(position.pitch),
+            current_item: self
+                .inventory
+                .held_item(self.held_slot)
+                .map_or(0, |item| item.id),
+        })
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Registry of the players connected to the server, shared by all the connections.
+///
+/// Returned players are snapshots, the registry is the source of truth.
+#[derive(Debug)]
+pub struct Players {
+    players: RwLock<HashMap<i32, Player>>,
+}
+
+impl Default for Players {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Players {
+    pub fn new() -> Self {
+        Self {
+            players: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Registers the player, replacing the one with the same entity identifier.
+    pub fn add(&self, player: Player) {
+        self.players
+            .write()
+            .unwrap()
+            .insert(player.base.entity_id, player);
+    }
+
+    /// Unregisters the player, returning it if it was registered.
+    pub fn remove(&self, entity_id: i32) -> Option<Player> {
+        self.players.write().unwrap().remove(&entity_id)
+    }
+
+    /// Number of the connected players.
+    pub fn count(&self) -> usize {
+        self.players.read().unwrap().len()
+    }
+
+    pub fn get(&self, entity_id: i32) -> Option<Player> {
+        self.players.read().unwrap().get(&entity_id).cloned()
+    }
+
+    /// Looks up the player by the username, ignoring the case.
+    pub fn by_name(&self, username: &str) -> Option<Player> {
+        self.players
+            .read()
+            .unwrap()
+            .values()
+            .find(|player| player.username.eq_ignore_ascii_case(username))
+            .cloned()
+    }
+
+    /// Returns all the connected players.
+    pub fn all(&self) -> Vec<Player> {
+        self.players.read().unwrap().values().cloned().collect()
+    }
+
+    /// Returns the players in the dimension.
+    pub fn in_dimension(&self, dimension: Dimension) -> Vec<Player> {
+        self.players
+            .read()
+            .unwrap()
+            .values()
+            .filter(|player| player.dimension == dimension)
+            .cloned()
+            .collect()
+    }
+
+    /// Modifies the registered player, returns `None` if it isn't registered.
+    pub fn update<T>(&self, entity_id: i32, f: impl FnOnce(&mut Player) -> T) -> Option<T> {
+        self.players.write().unwrap().get_mut(&entity_id).map(f)
+    }
+
+    /// Inserts the item stack into the player's inventory and sends the changed slots,
+    /// leaving what doesn't fit in the stack.
+    ///
+    /// Returns `false` if nothing fit or the player isn't registered.
+    pub fn give(&self, entity_id: i32, item: &mut ItemStack) -> bool {
+        self.update(entity_id, |player| {
+            let changed = player.inventory.insert(item);
+
+            for &slot in &changed {
+                player.send(player.inventory.set_slot_packet(slot));
+            }
+
+            !changed.is_empty()
+        })
+        .unwrap_or(false)
+    }
+
+    /// Sends the packet to the player, returns `false` if it isn't registered
+    /// or has already disconnected.
+    pub fn send(&self, entity_id: i32, packet: Packet) -> bool {
+        self.players
+            .read()
+            .unwrap()
+            .get(&entity_id)
+            .is_some_and(|player| player.send(packet))
+    }
+
+    /// Sends the packet to all the players.
+    pub fn broadcast_all(&self, packet: Packet) {
+        self.broadcast_filter(packet, |_| true);
+    }
+
+    /// Sends the packet to all the players but the one with the entity identifier,
+    /// usually the player who caused it.
+    pub fn broadcast_except(&self, entity_id: i32, packet: Packet) {
+        self.broadcast_filter(packet, |player| player.base.entity_id != entity_id);
+    }
+
+    /// Sends the packet to the players within the radius of the position in the dimension.
+    pub fn broadcast_near(
+        &self,
+        dimension: Dimension,
+        x: f64,
+        y: f64,
+        z: f64,
+        radius: f64,
+        packet: Packet,
+    ) {
+        self.broadcast_filter(packet, |player| {
+            player.dimension == dimension
+                && player.base.position.distance_squared(x, y, z) <= radius * radius
+        });
+    }
+
+    /// Sends the packet to the players in the dimension having the column in view,
+    /// within the view distance of the column they're in.
+    pub fn broadcast_in_view(
+        &self,
+        dimension: Dimension,
+        chunk: ChunkPos,
+        view_distance: i32,
+        packet: Packet,
+    ) {
+        self.broadcast_filter(packet, |player| {
+            let center = player.base.position.chunk();
+            player.dimension == dimension
+                && (center.x - chunk.x).abs() <= view_distance
+                && (center.z - chunk.z).abs() <= view_distance
+        });
+    }
+
+    /// Sends the packet to the players matching the predicate.
+    pub fn broadcast_filter(&self, packet: Packet, predicate: impl Fn(&Player) -> bool) {
+        let players = self.players.read().unwrap();
+
+        for player in players.values().filter(|player| predicate(player)) {
+            player.send(packet.clone());
+        }
+    }
+}
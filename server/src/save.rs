@@ -0,0 +1,585 @@
+use std::{
+    collections::HashSet,
+    io,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use log::{debug, error};
+use protocol::nbt::{Compound, Nbt, Tag};
+use tokio::time::{self, Instant, MissedTickBehavior};
+
+use crate::{
+    config::ServerConfig,
+    entity::Position,
+    experience::Experience,
+    handle::ServerHandle,
+    health::Health,
+    inventory::Inventory,
+    player::Player,
+    view::chunks_in_view,
+    world::{chunk::ChunkPos, dimension::Dimension, region::region_of, BlockPos, Worlds},
+};
+
+/// Version of the Anvil format stored in `level.dat`.
+const ANVIL_VERSION: i32 = 19133;
+
+/// Name of the world stored in `level.dat` if its directory has none.
+const DEFAULT_LEVEL_NAME: &str = "world";
+
+/// How often the columns out of the view of all the players are unloaded.
+const UNLOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Saves the changed columns, the world properties and the players at the configured
+/// interval, until the server stops.
+pub async fn autosave(server: ServerHandle) {
+    let Some(period) = server.config.autosave_interval else {
+        return;
+    };
+
+    let mut interval = time::interval_at(Instant::now() + period, period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => save_all(&server).await,
+            _ = server.shutdown.cancelled() => break,
+        }
+    }
+}
+
+/// Unloads the columns out of the view of all the players, saving them first,
+/// and closes the region files none of the loaded columns are in, until the server stops.
+pub async fn unload_idle(server: ServerHandle) {
+    let mut interval = time::interval_at(Instant::now() + UNLOAD_INTERVAL, UNLOAD_INTERVAL);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => unload_columns(&server).await,
+            _ = server.shutdown.cancelled() => break,
+        }
+    }
+}
+
+/// Unloads the columns out of the view of all the players, once they're saved,
+/// and closes the region files of the columns unloaded.
+pub async fn unload_columns(server: &ServerHandle) {
+    let _saving = server.saving.lock().await;
+    let server = server.clone();
+
+    let unloaded = tokio::task::spawn_blocking(move || {
+        let unloaded = unload_idle_columns(&server);
+
+        let used: HashSet<_> = server
+            .worlds
+            .iter()
+            .flat_map(|(dimension, world)| {
+                let world = world.read().unwrap();
+                let regions: Vec<_> = world
+                    .loaded_chunks()
+                    .map(|pos| {
+                        let (x, z) = region_of(pos);
+                        (dimension, x, z)
+                    })
+                    .collect();
+                regions
+            })
+            .collect();
+        server.storage.close_regions_except(&used);
+
+        unloaded
+    })
+    .await;
+
+    match unloaded {
+        Ok(0) => {}
+        Ok(columns) => debug!("Unloaded {} columns", columns),
+        Err(e) => error!("Failed to unload the columns: {}", e),
+    }
+}
+
+/// Saves and removes the columns of all the dimensions out of the view of all the players,
+/// keeping a column of margin, returns their number.
+///
+/// The columns failing to save, or changed while being saved, stay loaded.
+fn unload_idle_columns(server: &ServerHandle) -> usize {
+    let radius = i32::from(server.config.view_distance) + 1;
+    let mut unloaded = 0;
+
+    for (dimension, world) in server.worlds.iter() {
+        let in_view: HashSet<ChunkPos> = server
+            .players
+            .in_dimension(dimension)
+            .iter()
+            .flat_map(|player| chunks_in_view(player.base.position.chunk(), radius))
+            .collect();
+        let (idle, time) = {
+            let world = world.read().unwrap();
+            let idle: Vec<_> = world
+                .loaded_chunks()
+                .filter(|pos| !in_view.contains(pos))
+                .collect();
+
+            (idle, world.time())
+        };
+
+        for pos in idle {
+            let Some((chunk, changes)) = world
+                .read()
+                .unwrap()
+                .chunk(pos)
+                .filter(|chunk| chunk.is_dirty())
+                .map(|chunk| (chunk.clone(), chunk.changes()))
+            else {
+                // Unchanged since it was loaded or saved, nothing to write.
+                let mut world = world.write().unwrap();
+                if world.chunk(pos).is_some_and(|chunk| !chunk.is_dirty()) {
+                    world.remove_chunk(pos);
+                    unloaded += 1;
+                }
+                continue;
+            };
+
+            if let Err(e) = server.storage.save(dimension, &chunk, time) {
+                error!(
+                    "Failed to save the column at {}, {} in the {:?}: {}",
+                    pos.x, pos.z, dimension, e
+                );
+                continue;
+            }
+
+            let mut world = world.write().unwrap();
+            let Some(chunk) = world.chunk_mut(pos) else {
+                continue;
+            };
+            chunk.mark_saved_at(changes);
+            if !chunk.is_dirty() {
+                world.remove_chunk(pos);
+                unloaded += 1;
+            }
+        }
+    }
+
+    unloaded
+}
+
+/// Saves the changed columns, the world properties and the online players.
+///
+/// The files are written on a blocking thread and each column is copied out
+/// of the world under its own short lock, so the tick loop doesn't wait for the disk.
+pub async fn save_all(server: &ServerHandle) {
+    let _saving = server.saving.lock().await;
+    let server = server.clone();
+
+    let saved = tokio::task::spawn_blocking(move || {
+        let columns = save_columns(&server);
+
+        if let Err(e) = server.storage.save_level(&level_nbt(&server)) {
+            error!("Failed to save the level: {}", e);
+        }
+
+        for player in server.players.all() {
+            if let Err(e) = server
+                .storage
+                .save_player(&player.username, &player_nbt(&player))
+            {
+                error!("Failed to save {}: {}", player.username, e);
+            }
+        }
+
+        columns
+    })
+    .await;
+
+    match saved {
+        Ok(columns) => debug!("Saved the world, {} columns changed", columns),
+        Err(e) => error!("Failed to save the world: {}", e),
+    }
+}
+
+/// Saves the player, e.g. as it leaves the game.
+pub async fn save_player(server: &ServerHandle, player: &Player) {
+    let _saving = server.saving.lock().await;
+    let storage = server.storage.clone();
+    let (username, nbt) = (player.username.clone(), player_nbt(player));
+
+    let saved = tokio::task::spawn_blocking(move || storage.save_player(&username, &nbt)).await;
+    if let Err(e) = saved.map_err(Into::into).and_then(|saved| saved) {
+        error!("Failed to save {}: {}", player.username, e);
+    }
+}
+
+/// Saves the columns of all the dimensions which changed since they were last saved,
+/// returns their number.
+fn save_columns(server: &ServerHandle) -> usize {
+    let mut saved = 0;
+
+    for (dimension, world) in server.worlds.iter() {
+        let (dirty, time) = {
+            let world = world.read().unwrap();
+            let dirty: Vec<_> = world
+                .loaded_chunks()
+                .filter(|&pos| world.chunk(pos).is_some_and(|chunk| chunk.is_dirty()))
+                .collect();
+
+            (dirty, world.time())
+        };
+
+        for pos in dirty {
+            let Some((chunk, changes)) = world
+                .read()
+                .unwrap()
+                .chunk(pos)
+                .map(|chunk| (chunk.clone(), chunk.changes()))
+            else {
+                continue;
+            };
+
+            // The column stays dirty if it fails, to be tried again by the next save.
+            if let Err(e) = server.storage.save(dimension, &chunk, time) {
+                error!(
+                    "Failed to save the column at {}, {} in the {:?}: {}",
+                    pos.x, pos.z, dimension, e
+                );
+                continue;
+            }
+
+            // The changes made while it was being written are saved the next time.
+            if let Some(chunk) = world.write().unwrap().chunk_mut(pos) {
+                chunk.mark_saved_at(changes);
+            }
+            saved += 1;
+        }
+    }
+
+    saved
+}
+
+/// Restores the world properties saved in `level.dat`, e.g. by the vanilla server,
+/// overriding the configured seed, level type and spawn.
+///
+/// The time is shared by all the dimensions, as in the vanilla worlds.
+pub fn apply_level(nbt: &Nbt, config: &mut ServerConfig, worlds: &Worlds) -> io::Result<()> {
+    let data = nbt
+        .root
+        .get("Data")
+        .and_then(Tag::as_compound)
+        .ok_or_else(|| invalid_data("the level has no Data compound"))?;
+    let int = |name: &str| data.get(name).and_then(Tag::as_int);
+
+    if let Some(seed) = data.get("RandomSeed").and_then(Tag::as_long) {
+        config.seed = seed;
+    }
+    if let Some(generator) = data.get("generatorName").and_then(Tag::as_str) {
+        config.level_type = generator.to_string();
+    }
+    if let (Some(x), Some(z)) = (int("SpawnX"), int("SpawnZ")) {
+        (config.spawn_x, config.spawn_z) = (x, z);
+    }
+    if let Some(time) = data.get("Time").and_then(Tag::as_long) {
+        for (_, world) in worlds.iter() {
+            world.write().unwrap().set_time(time);
+        }
+    }
+
+    Ok(())
+}
+
+/// Builds the world properties stored in `level.dat`.
+fn level_nbt(server: &ServerHandle) -> Nbt {
+    let config = &server.config;
+    let (spawn_x, spawn_y, spawn_z) = server.spawn_point();
+    let time = server
+        .worlds
+        .get(Dimension::Overworld)
+        .read()
+        .unwrap()
+        .time();
+    let name = config
+        .world_path
+        .file_name()
+        .map_or(DEFAULT_LEVEL_NAME.into(), |name| name.to_string_lossy());
+    let last_played = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_millis() as i64);
+
+    let mut data = Compound::new();
+    data.insert("LevelName".to_string(), Tag::String(name.into_owned()));
+    data.insert("version".to_string(), Tag::Int(ANVIL_VERSION));
+    data.insert("RandomSeed".to_string(), Tag::Long(config.seed));
+    data.insert(
+        "generatorName".to_string(),
+        Tag::String(config.level_type.to_lowercase()),
+    );
+    data.insert("generatorVersion".to_string(), Tag::Int(0));
+    data.insert("GameType".to_string(), Tag::Int(config.game_mode));
+    data.insert("MapFeatures".to_string(), Tag::Byte(1));
+    data.insert("hardcore".to_string(), Tag::Byte(0));
+    data.insert("Time".to_string(), Tag::Long(time));
+    data.insert("LastPlayed".to_string(), Tag::Long(last_played));
+    data.insert("SpawnX".to_string(), Tag::Int(spawn_x));
+    data.insert("SpawnY".to_string(), Tag::Int(spawn_y));
+    data.insert("SpawnZ".to_string(), Tag::Int(spawn_z));
+    data.insert("SizeOnDisk".to_string(), Tag::Long(0));
+    data.insert("raining".to_string(), Tag::Byte(0));
+    data.insert("rainTime".to_string(), Tag::Int(0));
+    data.insert("thundering".to_string(), Tag::Byte(0));
+    data.insert("thunderTime".to_string(), Tag::Int(0));
+
+    let mut root = Compound::new();
+    root.insert("Data".to_string(), Tag::Compound(data));
+    Nbt::new("", root)
+}
+
+/// State of a player restored from its file.
+#[derive(Debug, Clone)]
+pub struct SavedPlayer {
+    /// Dimension the player left in, the overworld if it's unknown.
+    pub dimension: Dimension,
+
+    pub position: Position,
+
+    /// Game mode the player left in, `None` in the files without one.
+    pub game_mode: Option<i32>,
+
+    pub inventory: Inventory,
+    pub health: Health,
+    pub experience: Experience,
+
+    /// Head of the bed the player respawns at.
+    pub spawn_point: Option<BlockPos>,
+}
+
+/// Loads the player's data saved as it last left, `None` if it never played in the world.
+pub async fn load_player(server: &ServerHandle, username: &str) -> Option<SavedPlayer> {
+    let storage = server.storage.clone();
+    let name = username.to_string();
+
+    let loaded = tokio::task::spawn_blocking(move || storage.load_player(&name))
+        .await
+        .map_err(Into::into)
+        .and_then(|loaded| loaded)
+        .and_then(|nbt| nbt.as_ref().map(read_player).transpose());
+
+    loaded.unwrap_or_else(|e| {
+        error!("Failed to load {}: {}", username, e);
+        None
+    })
+}
+
+/// Restores the player's state from its file, the missing values are left at the defaults.
+pub fn read_player(nbt: &Nbt) -> io::Result<SavedPlayer> {
+    let root = &nbt.root;
+    let list = |name: &str| root.get(name).and_then(Tag::as_list).unwrap_or_default();
+    let int = |name: &str| root.get(name).and_then(Tag::as_int);
+    let short = |name: &str| root.get(name).and_then(Tag::as_short);
+    let float = |name: &str| root.get(name).and_then(Tag::as_float);
+
+    let &[Tag::Double(x), Tag::Double(y), Tag::Double(z)] = list("Pos") else {
+        return Err(invalid_data("the player has no position"));
+    };
+    let (yaw, pitch) = match list("Rotation") {
+        &[Tag::Float(yaw), Tag::Float(pitch)] => (yaw, pitch),
+        _ => (0.0, 0.0),
+    };
+    let position = Position {
+        x,
+        y,
+        z,
+        yaw,
+        pitch,
+        on_ground: root.get("OnGround").and_then(Tag::as_byte) == Some(1),
+    };
+
+    let defaults = Health::default();
+    let health = Health {
+        health: short("Health").unwrap_or(defaults.health),
+        food: int("foodLevel").map_or(defaults.food, |food| food as i16),
+        food_saturation: float("foodSaturationLevel").unwrap_or(defaults.food_saturation),
+        air: short("Air").unwrap_or(defaults.air),
+        fall_distance: float("FallDistance").map_or(0.0, f64::from),
+        exhaustion: float("foodExhaustionLevel").unwrap_or(0.0),
+        food_ticks: int("foodTickTimer").map_or(0, |ticks| ticks.max(0) as u32),
+        ..defaults
+    };
+
+    let level = int("XpLevel").unwrap_or(0);
+    let experience = Experience {
+        level,
+        points: (float("XpP").unwrap_or(0.0) * Experience::level_cost(level) as f32).round() as i32,
+        total: int("XpTotal").unwrap_or(0),
+    };
+
+    let spawn_point = match (int("SpawnX"), int("SpawnY"), int("SpawnZ")) {
+        (Some(x), Some(y), Some(z)) => Some(BlockPos::new(x, y, z)),
+        _ => None,
+    };
+
+    Ok(SavedPlayer {
+        dimension: int("Dimension")
+            .and_then(Dimension::from_id)
+            .unwrap_or_default(),
+        position,
+        game_mode: int("playerGameType"),
+        inventory: Inventory::from_nbt(list("Inventory")),
+        health,
+        experience,
+        spawn_point,
+    })
+}
+
+/// Builds the player's data stored in its file, as the vanilla server stores it.
+pub fn player_nbt(player: &Player) -> Nbt {
+    let position = &player.base.position;
+    let (vx, vy, vz) = player.base.velocity;
+    let health = &player.health;
+    let experience = &player.experience;
+
+    let mut root = Compound::new();
+    root.insert(
+        "Pos".to_string(),
+        Tag::List(vec![
+            Tag::Double(position.x),
+            Tag::Double(position.y),
+            Tag::Double(position.z),
+        ]),
+    );
+    root.insert(
+        "Motion".to_string(),
+        Tag::List(vec![Tag::Double(vx), Tag::Double(vy), Tag::Double(vz)]),
+    );
+    root.insert(
+        "Rotation".to_string(),
+        Tag::List(vec![Tag::Float(position.yaw), Tag::Float(position.pitch)]),
+    );
+    root.insert("OnGround".to_string(), Tag::Byte(position.on_ground.into()));
+    root.insert("Dimension".to_string(), Tag::Int(player.dimension.id()));
+    root.insert(
+        "FallDistance".to_string(),
+        Tag::Float(health.fall_distance as f32),
+    );
+    root.insert("Air".to_string(), Tag::Short(health.air));
+    root.insert("Health".to_string(), Tag::Short(health.health));
+    root.insert("foodLevel".to_string(), Tag::Int(health.food.into()));
+    root.insert(
+        "foodSaturationLevel".to_string(),
+        Tag::Float(health.food_saturation),
+    );
+    root.insert(
+        "foodExhaustionLevel".to_string(),
+        Tag::Float(health.exhaustion),
+    );
+    root.insert(
+        "foodTickTimer".to_string(),
+        Tag::Int(health.food_ticks as i32),
+    );
+    root.insert("XpLevel".to_string(), Tag::Int(experience.level));
+    root.insert(
+        "XpP".to_string(),
+        Tag::Float(experience.points as f32 / Experience::level_cost(experience.level) as f32),
+    );
+    root.insert("XpTotal".to_string(), Tag::Int(experience.total));
+    root.insert("playerGameType".to_string(), Tag::Int(player.game_mode));
+    root.insert(
+        "Sleeping".to_string(),
+        Tag::Byte(player.bed.is_some().into()),
+    );
+    root.insert(
+        "Inventory".to_string(),
+        Tag::List(player.inventory.to_nbt()),
+    );
+
+    if let Some(spawn_point) = player.spawn_point {
+        root.insert("SpawnX".to_string(), Tag::Int(spawn_point.x));
+        root.insert("SpawnY".to_string(), Tag::Int(spawn_point.y));
+        root.insert("SpawnZ".to_string(), Tag::Int(spawn_point.z));
+    }
+
+    Nbt::new("", root)
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use protocol::slot::{ItemStack, Slot};
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::{entity::EntityBase, game_mode::SURVIVAL, player::PLAYER_BOUNDING_BOX};
+
+    #[test]
+    fn players_survive_saving() {
+        let position = Position {
+            x: 10.5,
+            y: 70.0,
+            z: -3.25,
+            yaw: 90.0,
+            pitch: -10.0,
+            on_ground: true,
+        };
+        let mut inventory = Inventory::new();
+        inventory.set_slot(36, Slot::new(ItemStack::new(276, 1, 12)));
+
+        let player = Player {
+            base: EntityBase::new(1, position, PLAYER_BOUNDING_BOX),
+            username: "ezioleq".to_string(),
+            dimension: Dimension::Nether,
+            addr: "127.0.0.1:25565".parse().unwrap(),
+            game_mode: SURVIVAL,
+            permission_level: 0,
+            held_slot: 0,
+            inventory,
+            health: Health {
+                health: 13,
+                food: 7,
+                ..Default::default()
+            },
+            experience: Experience {
+                level: 3,
+                points: 4,
+                total: 55,
+            },
+            sprinting: false,
+            eating: None,
+            bed: None,
+            spawn_point: Some(BlockPos::new(4, 65, 8)),
+            sender: mpsc::unbounded_channel().0,
+        };
+
+        let saved = read_player(&player_nbt(&player)).unwrap();
+        assert_eq!(saved.dimension, Dimension::Nether);
+        assert_eq!(saved.position, position);
+        assert_eq!(saved.game_mode, Some(SURVIVAL));
+        assert_eq!(saved.inventory, player.inventory);
+        assert_eq!(saved.health, player.health);
+        assert_eq!(saved.experience, player.experience);
+        assert_eq!(saved.spawn_point, player.spawn_point);
+
+        assert!(read_player(&Nbt::default()).is_err());
+    }
+
+    #[test]
+    fn restore_the_level() {
+        let mut data = Compound::new();
+        data.insert("RandomSeed".to_string(), Tag::Long(-42));
+        data.insert("generatorName".to_string(), Tag::String("default".into()));
+        data.insert("SpawnX".to_string(), Tag::Int(120));
+        data.insert("SpawnY".to_string(), Tag::Int(64));
+        data.insert("SpawnZ".to_string(), Tag::Int(-30));
+        data.insert("Time".to_string(), Tag::Long(30000));
+        let mut root = Compound::new();
+        root.insert("Data".to_string(), Tag::Compound(data));
+
+        let (mut config, worlds) = (ServerConfig::default(), Worlds::new());
+        apply_level(&Nbt::new("", root), &mut config, &worlds).unwrap();
+        assert_eq!(config.seed, -42);
+        assert_eq!(config.level_type, "default");
+        assert_eq!((config.spawn_x, config.spawn_z), (120, -30));
+        for (_, world) in worlds.iter() {
+            assert_eq!(world.read().unwrap().time(), 30000);
+        }
+    }
+}
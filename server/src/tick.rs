@@ -0,0 +1,819 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use log::{debug, info, warn};
+use protocol::{
+    packet::{CollectItemPayload, DestroyEntityPayload, Packet, TimeUpdatePayload},
+    slot::ItemStack,
+};
+use tokio::{
+    sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+    time::{self, MissedTickBehavior},
+};
+
+use crate::{
+    ai,
+    bed::{self, SLEEP_TICKS},
+    chat, combat,
+    config::ServerConfig,
+    entity::{Entities, Entity, EntityBase, EntityIdAllocator, Position},
+    experience::{self, ExperienceOrb},
+    falling::{self, FallingBlock},
+    food,
+    game_mode::SURVIVAL,
+    health::{self, DamageCause, Hurt, ATTACK_EXHAUSTION},
+    item::ItemEntity,
+    mob::{self, Mob, MobKind},
+    player::{Player, Players},
+    tracker::{EntityTracker, PlayerTracker},
+    world::{
+        block_changed, block_changes, chunk::ChunkPos, dimension::Dimension, redstone::Occupants,
+        BlockChanges, Worlds, DAY_LENGTH,
+    },
+};
+
+/// Default number of ticks per second.
+pub const DEFAULT_TPS: u32 = 20;
+
+/// Number of ticks between the time updates sent to the players.
+const TIME_UPDATE_INTERVAL: u64 = 20;
+
+/// Distance within which the players see the entities.
+const ENTITY_TRACKING_RANGE: f64 = 64.0;
+
+/// Event enqueued by the connection tasks, handled on the next tick.
+#[derive(Debug)]
+pub enum ServerEvent {
+    /// The player has logged in.
+    PlayerJoined { entity_id: i32 },
+
+    /// The entity has been spawned into the world of the dimension,
+    /// e.g. an item has been dropped.
+    EntitySpawned {
+        dimension: Dimension,
+        entity: Box<dyn Entity>,
+    },
+
+    /// The player has been killed, its death is to be announced.
+    PlayerDied { entity_id: i32, cause: DamageCause },
+
+    /// The player has respawned or moved to another dimension, reloading the world.
+    PlayerRespawned { entity_id: i32 },
+
+    /// The player has attacked an entity other than a player, possibly a mob.
+    MobAttacked {
+        attacker: i32,
+        target: i32,
+        damage: i16,
+    },
+}
+
+pub type EventSender = UnboundedSender<ServerEvent>;
+
+/// Entities living in a dimension and what the players in it have been shown.
+struct DimensionState {
+    dimension: Dimension,
+
+    /// Entities living in the dimension, the players excluded.
+    entities: Entities,
+
+    /// Players shown to each other.
+    tracker: PlayerTracker,
+
+    /// Entities other than the players shown to the players.
+    entity_tracker: EntityTracker,
+}
+
+impl DimensionState {
+    fn new(dimension: Dimension) -> Self {
+        Self {
+            dimension,
+            entities: Entities::new(),
+            tracker: PlayerTracker::new(),
+            entity_tracker: EntityTracker::new(ENTITY_TRACKING_RANGE),
+        }
+    }
+}
+
+/// Loop driving everything time-dependent on the server.
+pub struct TickLoop {
+    tps: u32,
+    players: Arc<Players>,
+    worlds: Arc<Worlds>,
+    entity_ids: Arc<EntityIdAllocator>,
+    events: UnboundedReceiver<ServerEvent>,
+
+    /// Entities and trackers of each dimension.
+    dimensions: [DimensionState; Dimension::ALL.len()],
+
+    /// Whether the time of the worlds advances, frozen otherwise.
+    daylight_cycle: bool,
+
+    /// World difficulty, how hard the hunger hits.
+    difficulty: i8,
+
+    /// Number of the columns the players see in each direction.
+    view_distance: i32,
+
+    /// Number of the consecutive ticks all the players have been asleep.
+    asleep_ticks: u64,
+
+    /// Number of ticks since the server started.
+    tick: u64,
+}
+
+impl TickLoop {
+    /// Creates the tick loop and the sender of the events handled by it.
+    pub fn new(
+        config: &ServerConfig,
+        players: Arc<Players>,
+        worlds: Arc<Worlds>,
+        entity_ids: Arc<EntityIdAllocator>,
+    ) -> (Self, EventSender) {
+        let (sender, events) = mpsc::unbounded_channel();
+
+        let tick_loop = TickLoop {
+            tps: config.tps.max(1),
+            players,
+            worlds,
+            entity_ids,
+            events,
+            dimensions: Dimension::ALL.map(DimensionState::new),
+            daylight_cycle: config.do_daylight_cycle,
+            difficulty: config.difficulty,
+            view_distance: i32::from(config.view_distance),
+            asleep_ticks: 0,
+            tick: 0,
+        };
+
+        (tick_loop, sender)
+    }
+
+    /// Duration of a single tick.
+    pub fn tick_duration(&self) -> Duration {
+        Duration::from_secs(1) / self.tps
+    }
+
+    /// Ticks until all the event senders are dropped.
+    pub async fn run(mut self) {
+        let mut interval = time::interval(self.tick_duration());
+        // Catching up on the missed ticks would only make the lag worse.
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            interval.tick().await;
+
+            loop {
+                match self.events.try_recv() {
+                    Ok(event) => self.handle_event(event),
+                    Err(mpsc::error::TryRecvError::Empty) => break,
+                    Err(mpsc::error::TryRecvError::Disconnected) => {
+                        debug!("Stopping the tick loop");
+                        return;
+                    }
+                }
+            }
+
+            let started = time::Instant::now();
+            self.tick();
+
+            let elapsed = started.elapsed();
+            if elapsed > self.tick_duration() {
+                warn!("Tick {} took {:?}", self.tick, elapsed);
+            }
+        }
+    }
+
+    fn handle_event(&mut self, event: ServerEvent) {
+        debug!("Handling {:?}", event);
+
+        match event {
+            ServerEvent::PlayerJoined { entity_id } => {
+                if let Some(player) = self.players.get(entity_id) {
+                    player.send(self.time_update(player.dimension));
+                }
+            }
+            ServerEvent::EntitySpawned { dimension, entity } => {
+                self.dimension_mut(dimension).entities.add(entity)
+            }
+            ServerEvent::PlayerDied { entity_id, cause } => self.kill(entity_id, cause),
+            ServerEvent::PlayerRespawned { entity_id } => {
+                // The player may have left another dimension, where it's forgotten too.
+                for state in &mut self.dimensions {
+                    for (viewer, packet) in state.tracker.forget(entity_id) {
+                        self.players.send(viewer, packet);
+                    }
+                    state.entity_tracker.forget(entity_id);
+                }
+
+                if let Some(player) = self.players.get(entity_id) {
+                    player.send(self.time_update(player.dimension));
+                }
+            }
+            ServerEvent::MobAttacked {
+                attacker,
+                target,
+                damage,
+            } => self.attack_mob(attacker, target, damage),
+        }
+    }
+
+    fn dimension_mut(&mut self, dimension: Dimension) -> &mut DimensionState {
+        &mut self.dimensions[dimension.index()]
+    }
+
+    /// Drops the inventory and the experience of the killed player and announces its death.
+    fn kill(&mut self, entity_id: i32, cause: DamageCause) {
+        let Some(dimension) = self.players.get(entity_id).map(|player| player.dimension) else {
+            return;
+        };
+        bed::wake_up(
+            &self.players,
+            &mut self.worlds.get(dimension).write().unwrap(),
+            entity_id,
+        );
+
+        let died = self.players.update(entity_id, |player| {
+            let items = player.inventory.clear();
+            player.send(player.inventory.window_items_packet());
+            let experience = std::mem::take(&mut player.experience);
+
+            (
+                player.username.clone(),
+                player.base.position,
+                items,
+                experience.dropped_on_death(),
+            )
+        });
+
+        let Some((username, position, items, experience)) = died else {
+            return;
+        };
+
+        let entities = &mut self.dimensions[dimension.index()].entities;
+        for item in items {
+            let item = ItemEntity::dropped_on_death(self.entity_ids.allocate(), item, &position);
+            entities.add(Box::new(item));
+        }
+
+        let (x, y, z) = (position.x, position.y, position.z);
+        for orb in experience::drop_orbs(&self.entity_ids, experience, x, y, z) {
+            entities.add(Box::new(orb));
+        }
+
+        let message = cause.death_message(&username);
+        info!("{}", message);
+        self.players.broadcast_all(chat::chat_packet(&message));
+    }
+
+    /// Hurts the mob attacked by the player and knocks it back,
+    /// dropping the experience once it's killed.
+    fn attack_mob(&mut self, attacker: i32, target: i32, damage: i16) {
+        let Some(player) = self
+            .players
+            .get(attacker)
+            .filter(|player| !player.health.is_dead())
+        else {
+            return;
+        };
+        let dimension = player.dimension;
+        let Some(mob) = self.dimensions[dimension.index()]
+            .entities
+            .get_mut(target)
+            .and_then(|entity| entity.as_any_mut().downcast_mut::<Mob>())
+        else {
+            return;
+        };
+
+        let (from, to) = (player.base.position, mob.base.position);
+        if from.distance_squared(to.x, to.y, to.z) > combat::MAX_ATTACK_REACH_SQUARED {
+            debug!("{} tried to attack out of reach", player.username);
+            return;
+        }
+
+        if !mob.damage(damage) {
+            return;
+        }
+
+        let knockback = combat::knockback(&from, &to);
+        mob.base.velocity = knockback;
+        broadcast_around(
+            &self.players,
+            dimension,
+            &mob.base,
+            combat::velocity_packet(target, knockback),
+        );
+        let killed = mob.is_dead().then_some(mob.kind);
+
+        self.players.update(attacker, |player| {
+            player.health.exhaust(ATTACK_EXHAUSTION);
+        });
+
+        if let Some(kind) = killed {
+            let orbs = experience::drop_orbs(&self.entity_ids, kind.experience(), to.x, to.y, to.z);
+            for orb in orbs {
+                self.dimension_mut(dimension).entities.add(Box::new(orb));
+            }
+        }
+    }
+
+    /// Advances the server by a single tick.
+    fn tick(&mut self) {
+        self.tick += 1;
+
+        if self.daylight_cycle {
+            for (_, world) in self.worlds.iter() {
+                let mut world = world.write().unwrap();
+                let time = world.time();
+                world.set_time(time + 1);
+            }
+        }
+
+        if self.tick.is_multiple_of(TIME_UPDATE_INTERVAL) {
+            self.broadcast_time();
+        }
+
+        self.tick_players();
+        self.skip_night();
+        self.track_players();
+
+        if self.tick.is_multiple_of(mob::PASSIVE_SPAWN_INTERVAL) {
+            self.spawn_mobs(false);
+        }
+        // There are no hostile mobs on the peaceful difficulty.
+        if self.difficulty > 0 && self.tick.is_multiple_of(mob::HOSTILE_SPAWN_INTERVAL) {
+            self.spawn_mobs(true);
+        }
+        self.tick_blocks();
+        self.tick_entities();
+        self.track_entities();
+    }
+
+    /// Hurts the players drowning, burning, starving or falling out of the world,
+    /// lets them eat and regenerate the health.
+    fn tick_players(&mut self) {
+        let difficulty = self.difficulty;
+
+        let damages: Vec<(i32, (i16, DamageCause))> = self
+            .players
+            .all()
+            .iter()
+            .flat_map(|player| {
+                let entity_id = player.base.entity_id;
+                let world = self.worlds.get(player.dimension).read().unwrap();
+                let damages = self.players.update(entity_id, |player| {
+                    if player.health.is_dead() {
+                        return Vec::new();
+                    }
+
+                    let health = &player.health;
+                    let before = (health.health, health.food, health.food_saturation);
+
+                    let mut damages: Vec<_> = player
+                        .health
+                        .tick(&world, &player.base.position)
+                        .into_iter()
+                        .collect();
+
+                    if player.game_mode == SURVIVAL {
+                        let starvation = player.health.tick_hunger(difficulty);
+                        damages.extend(starvation.map(|damage| (damage, DamageCause::Starvation)));
+                        food::tick_eating(player);
+                    }
+
+                    let health = &player.health;
+                    if before != (health.health, health.food, health.food_saturation) {
+                        player.send(health.update_packet());
+                    }
+
+                    damages
+                });
+
+                damages
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(move |damage| (entity_id, damage))
+            })
+            .collect();
+
+        for (entity_id, (damage, cause)) in damages {
+            if health::hurt(&self.players, entity_id, damage) == Hurt::Killed {
+                self.kill(entity_id, cause);
+            }
+        }
+    }
+
+    /// Sets the time to the morning and wakes everyone up,
+    /// once all the players in the overworld have been asleep long enough.
+    fn skip_night(&mut self) {
+        let players = self.players.in_dimension(Dimension::Overworld);
+
+        if players.is_empty() || players.iter().any(|player| player.bed.is_none()) {
+            self.asleep_ticks = 0;
+            return;
+        }
+
+        self.asleep_ticks += 1;
+        if self.asleep_ticks < SLEEP_TICKS {
+            return;
+        }
+        self.asleep_ticks = 0;
+
+        {
+            let mut world = self.worlds.get(Dimension::Overworld).write().unwrap();
+            let morning = world.time() + DAY_LENGTH - world.time_of_day();
+            world.set_time(morning);
+
+            for player in &players {
+                bed::wake_up(&self.players, &mut world, player.base.entity_id);
+            }
+        }
+
+        self.broadcast_time();
+    }
+
+    /// Spawns, moves and destroys the players on each other's clients as they move around,
+    /// join, leave and change the dimensions.
+    fn track_players(&mut self) {
+        for state in &mut self.dimensions {
+            let players = self.players.in_dimension(state.dimension);
+
+            for (entity_id, packet) in state.tracker.update(&players) {
+                self.players.send(entity_id, packet);
+            }
+        }
+    }
+
+    /// Spawns the passive mobs on the grass, or the hostile ones in the dark,
+    /// of the loaded columns of the overworld below the cap, away from the players.
+    fn spawn_mobs(&mut self, hostile: bool) {
+        let (cap, chance, kinds) = if hostile {
+            (
+                mob::MAX_HOSTILE_MOBS_PER_CHUNK,
+                mob::HOSTILE_SPAWN_CHANCE,
+                MobKind::HOSTILE,
+            )
+        } else {
+            (
+                mob::MAX_PASSIVE_MOBS_PER_CHUNK,
+                mob::PASSIVE_SPAWN_CHANCE,
+                MobKind::PASSIVE,
+            )
+        };
+
+        let dimension = Dimension::Overworld;
+        let entities = &mut self.dimensions[dimension.index()].entities;
+
+        let mut mobs_per_chunk: HashMap<ChunkPos, usize> = HashMap::new();
+        for entity in entities.iter_mut() {
+            let chunk = entity.base().position.chunk();
+            if let Some(mob) = entity.as_any_mut().downcast_mut::<Mob>() {
+                if mob.kind.is_hostile() == hostile {
+                    *mobs_per_chunk.entry(chunk).or_default() += 1;
+                }
+            }
+        }
+
+        let players = self.players.in_dimension(dimension);
+        let world = self.worlds.get(dimension).read().unwrap();
+        let chunks: Vec<ChunkPos> = world
+            .loaded_chunks()
+            .filter(|pos| mobs_per_chunk.get(pos).copied().unwrap_or_default() < cap)
+            .collect();
+
+        for pos in chunks {
+            if rand::random::<f64>() >= chance {
+                continue;
+            }
+
+            let spot = if hostile {
+                mob::hostile_spawn_spot(&world, pos)
+            } else {
+                mob::passive_spawn_spot(&world, pos)
+            };
+            let Some(position) =
+                spot.filter(|spot| mob::spawn_distance_allowed(nearest_player(&players, spot)))
+            else {
+                continue;
+            };
+
+            let kind = kinds[rand::random_range(0..kinds.len())];
+            let mob = Mob::new(self.entity_ids.allocate(), kind, position);
+            entities.add(Box::new(mob));
+        }
+    }
+
+    /// Ticks the entities, lets the players pick up the items and the experience orbs,
+    /// moves the mobs around, despawns the ones away from the players, or the hostile
+    /// ones on the peaceful difficulty, places the landed falling blocks and removes
+    /// the entities which are gone.
+    fn tick_entities(&mut self) {
+        let mut attacks = Vec::new();
+        let mut landings = Vec::new();
+
+        for state in &mut self.dimensions {
+            let dimension = state.dimension;
+            let world = self.worlds.get(dimension).read().unwrap();
+            state.entities.tick(&world);
+
+            let players = self.players.in_dimension(dimension);
+            for entity in state.entities.iter_mut() {
+                if let Some(item) = entity.as_any_mut().downcast_mut::<ItemEntity>() {
+                    pick_up(&self.players, &players, dimension, item);
+                } else if let Some(orb) = entity.as_any_mut().downcast_mut::<ExperienceOrb>() {
+                    collect_orb(&self.players, &players, dimension, orb);
+                } else if let Some(mob) = entity.as_any_mut().downcast_mut::<Mob>() {
+                    if let Some(attack) = ai::tick(mob, &world, &players) {
+                        attacks.push((dimension, mob.kind, mob.base.position, attack));
+                    }
+
+                    mob.tick_despawn(nearest_player(&players, &mob.base.position));
+                    if self.difficulty == 0 && mob.kind.is_hostile() {
+                        mob.despawned = true;
+                    }
+
+                    for packet in mob.take_status_packets() {
+                        broadcast_around(&self.players, dimension, &mob.base, packet);
+                    }
+                } else if let Some(falling) = entity.as_any_mut().downcast_mut::<FallingBlock>() {
+                    if let Some(pos) = falling.landed {
+                        landings.push((dimension, falling.block, pos));
+                    }
+                }
+            }
+        }
+
+        for (dimension, block, pos) in landings {
+            let mut changes = BlockChanges::default();
+            {
+                let mut world = self.worlds.get(dimension).write().unwrap();
+                if falling::land(&mut world, block, pos) {
+                    changes.changed.push(pos);
+                    block_changed(&mut world, pos, &mut changes);
+                } else {
+                    changes
+                        .drops
+                        .push((pos, ItemStack::new(block.id as i16, 1, 0)));
+                }
+            }
+
+            self.apply_block_changes(dimension, changes);
+        }
+
+        for (dimension, kind, from, attack) in attacks {
+            match health::hurt(&self.players, attack.target, attack.damage) {
+                Hurt::Ignored => continue,
+                Hurt::Hurt => {}
+                Hurt::Killed => self.kill(attack.target, DamageCause::Mob(kind)),
+            }
+
+            if let Some(victim) = self.players.get(attack.target) {
+                let to = &victim.base.position;
+                self.players.broadcast_near(
+                    dimension,
+                    to.x,
+                    to.y,
+                    to.z,
+                    ENTITY_TRACKING_RANGE,
+                    combat::velocity_packet(attack.target, combat::knockback(&from, to)),
+                );
+            }
+        }
+
+        for state in &mut self.dimensions {
+            state.entities.remove_gone();
+        }
+    }
+
+    /// Runs the block ticks due in each dimension, e.g. spreads the fluids,
+    /// and presses the pressure plates under the players and the other entities.
+    fn tick_blocks(&mut self) {
+        for dimension in Dimension::ALL {
+            let mut occupants = Occupants::new();
+            for player in self.players.in_dimension(dimension) {
+                let position = &player.base.position;
+                if !player.health.is_dead() {
+                    occupants.add(position.x, position.y, position.z, true);
+                }
+            }
+            for entity in self.dimension_mut(dimension).entities.iter_mut() {
+                let living = entity.as_any_mut().is::<Mob>();
+                let position = &entity.base().position;
+                occupants.add(position.x, position.y, position.z, living);
+            }
+
+            let changes = self
+                .worlds
+                .get(dimension)
+                .write()
+                .unwrap()
+                .tick_blocks(&occupants);
+            self.apply_block_changes(dimension, changes);
+        }
+    }
+
+    /// Sends the changed blocks of the dimension and the effects played at them
+    /// to the players having them in view and drops the items of the broken ones.
+    fn apply_block_changes(&mut self, dimension: Dimension, changes: BlockChanges) {
+        let packets = block_changes(
+            &self.worlds.get(dimension).read().unwrap(),
+            &changes.changed,
+        );
+        for (chunk, packet) in packets {
+            self.players
+                .broadcast_in_view(dimension, chunk, self.view_distance, packet);
+        }
+        for (pos, packet) in changes.effects {
+            self.players
+                .broadcast_in_view(dimension, pos.chunk(), self.view_distance, packet);
+        }
+
+        for (pos, item) in changes.drops {
+            let item =
+                ItemEntity::dropped_by_block(self.entity_ids.allocate(), item, pos.x, pos.y, pos.z);
+            self.dimension_mut(dimension).entities.add(Box::new(item));
+        }
+    }
+
+    /// Spawns, moves and destroys the entities on the clients of the players
+    /// as they come into and leave their range.
+    fn track_entities(&mut self) {
+        for state in &mut self.dimensions {
+            let players = self.players.in_dimension(state.dimension);
+            let entities: Vec<&dyn Entity> = state.entities.iter().collect();
+
+            for (entity_id, packet) in state.entity_tracker.update(&players, &entities) {
+                self.players.send(entity_id, packet);
+            }
+        }
+    }
+
+    /// Sends the time of each dimension to the players in it.
+    fn broadcast_time(&self) {
+        for (dimension, _) in self.worlds.iter() {
+            self.players
+                .broadcast_filter(self.time_update(dimension), |player| {
+                    player.dimension == dimension
+                });
+        }
+    }
+
+    fn time_update(&self, dimension: Dimension) -> Packet {
+        Packet::TimeUpdate(TimeUpdatePayload {
+            time: self.worlds.get(dimension).read().unwrap().time(),
+        })
+    }
+}
+
+/// Squared distance from the position to the nearest of the players, `None` if there are none.
+fn nearest_player(players: &[Player], position: &Position) -> Option<f64> {
+    players
+        .iter()
+        .map(|player| {
+            player
+                .base
+                .position
+                .distance_squared(position.x, position.y, position.z)
+        })
+        .min_by(f64::total_cmp)
+}
+
+/// Sends the packet to the players within the tracking range of the entity in the dimension.
+fn broadcast_around(players: &Players, dimension: Dimension, entity: &EntityBase, packet: Packet) {
+    let position = &entity.position;
+
+    players.broadcast_near(
+        dimension,
+        position.x,
+        position.y,
+        position.z,
+        ENTITY_TRACKING_RANGE,
+        packet,
+    );
+}
+
+/// Inserts the item into the inventory of the first of the nearby players touching it.
+///
+/// Partially picked up items get respawned with the rest of the stack,
+/// the whole ones are left with an empty stack to be removed.
+fn pick_up(players: &Players, nearby: &[Player], dimension: Dimension, item: &mut ItemEntity) {
+    let Some(collector) = nearby
+        .iter()
+        .find(|player| !player.health.is_dead() && item.touches(&player.base))
+    else {
+        return;
+    };
+
+    if !players.give(collector.base.entity_id, &mut item.item) {
+        return;
+    }
+
+    if item.item.count > 0 {
+        let destroy = Packet::DestroyEntity(DestroyEntityPayload {
+            entity_id: item.base.entity_id,
+        });
+        for packet in [destroy, item.spawn_packet()] {
+            broadcast_around(players, dimension, &item.base, packet);
+        }
+        return;
+    }
+
+    let collect = Packet::CollectItem(CollectItemPayload {
+        collected_entity_id: item.base.entity_id,
+        collector_entity_id: collector.base.entity_id,
+    });
+    broadcast_around(players, dimension, &item.base, collect);
+}
+
+/// Draws the orb towards the nearest of the nearby players and gives it the experience
+/// once touching.
+fn collect_orb(
+    players: &Players,
+    nearby: &[Player],
+    dimension: Dimension,
+    orb: &mut ExperienceOrb,
+) {
+    let position = orb.base.position;
+    let Some(nearest) = nearby
+        .iter()
+        .filter(|player| !player.health.is_dead() && orb.attracts(&player.base.position))
+        .min_by(|a, b| {
+            let distance = |player: &Player| {
+                player
+                    .base
+                    .position
+                    .distance_squared(position.x, position.y, position.z)
+            };
+            distance(a).total_cmp(&distance(b))
+        })
+    else {
+        return;
+    };
+
+    orb.attract(&nearest.base.position);
+    if !orb.touches(&nearest.base) {
+        return;
+    }
+
+    let collector = nearest.base.entity_id;
+    let value = i32::from(orb.value);
+    players.update(collector, |player| {
+        player.experience.add(value);
+        player.send(player.experience.update_packet());
+    });
+    orb.value = 0;
+
+    let collect = Packet::CollectItem(CollectItemPayload {
+        collected_entity_id: orb.base.entity_id,
+        collector_entity_id: collector,
+    });
+    broadcast_around(players, dimension, &orb.base, collect);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tick_loop_for(config: &ServerConfig, worlds: Arc<Worlds>) -> TickLoop {
+        let players = Arc::new(Players::new());
+        let entity_ids = Arc::new(EntityIdAllocator::new());
+
+        TickLoop::new(config, players, worlds, entity_ids).0
+    }
+
+    #[test]
+    fn tick_duration_follows_tps() {
+        let worlds = Arc::new(Worlds::new());
+
+        let tick_loop = tick_loop_for(&ServerConfig::default(), worlds.clone());
+        assert_eq!(tick_loop.tick_duration(), Duration::from_millis(50));
+
+        let config = ServerConfig {
+            tps: 0,
+            ..Default::default()
+        };
+        assert_eq!(
+            tick_loop_for(&config, worlds).tick_duration(),
+            Duration::from_secs(1)
+        );
+    }
+
+    #[test]
+    fn time_advances_unless_frozen() {
+        let worlds = Arc::new(Worlds::new());
+        let time = |dimension| worlds.get(dimension).read().unwrap().time();
+
+        let mut tick_loop = tick_loop_for(&ServerConfig::default(), worlds.clone());
+        tick_loop.tick();
+        tick_loop.tick();
+        assert_eq!(time(Dimension::Overworld), 2);
+        assert_eq!(time(Dimension::Nether), 2);
+
+        let config = ServerConfig {
+            do_daylight_cycle: false,
+            ..Default::default()
+        };
+        let mut tick_loop = tick_loop_for(&config, worlds.clone());
+        tick_loop.tick();
+        assert_eq!(time(Dimension::Overworld), 2);
+    }
+}
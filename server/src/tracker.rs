@@ -0,0 +1,429 @@
+use std::collections::{HashMap, HashSet};
+
+use protocol::{
+    metadata::Metadata,
+    packet::{
+        DestroyEntityPayload, EntityHeadLookPayload, EntityLookAndRelativeMovePayload,
+        EntityLookPayload, EntityMetadataPayload, EntityRelativeMovePayload, EntityTeleportPayload,
+        Packet,
+    },
+};
+
+use crate::{
+    entity::{angle_steps, fixed_point, Entity, EntityBase},
+    player::Player,
+};
+
+/// Distance within which the players see each other.
+pub const PLAYER_TRACKING_RANGE: f64 = 64.0;
+
+/// Number of ticks between the absolute positions sent to correct the drift
+/// of the relative moves, 20 seconds.
+pub const POSITION_SYNC_INTERVAL: u64 = 400;
+
+/// Position of an entity as last sent to the clients, in the units of the packets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrackedPosition {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    pub yaw: i8,
+    pub pitch: i8,
+}
+
+impl TrackedPosition {
+    pub fn of(entity: &EntityBase) -> Self {
+        let position = &entity.position;
+
+        Self {
+            x: fixed_point(position.x),
+            y: fixed_point(position.y),
+            z: fixed_point(position.z),
+            yaw: angle_steps(position.yaw),
+            pitch: angle_steps(position.pitch),
+        }
+    }
+
+    /// Builds the packets moving the entity from this position to the new one.
+    ///
+    /// Relative moves are used while the deltas fit in a byte, up to 4 blocks,
+    /// the teleport otherwise or when the absolute position is to be synced.
+    pub fn movement_to(&self, to: &TrackedPosition, entity_id: i32, sync: bool) -> Vec<Packet> {
+        let delta = |from: i32, to: i32| i8::try_from(to - from).ok();
+        let moved = (self.x, self.y, self.z) != (to.x, to.y, to.z);
+        let rotated = (self.yaw, self.pitch) != (to.yaw, to.pitch);
+
+        let mut packets = Vec::new();
+
+        match (
+            delta(self.x, to.x),
+            delta(self.y, to.y),
+            delta(self.z, to.z),
+        ) {
+            _ if sync => packets.push(teleport(entity_id, to)),
+            (Some(dx), Some(dy), Some(dz)) if moved && rotated => {
+                packets.push(Packet::EntityLookAndRelativeMove(
+                    EntityLookAndRelativeMovePayload {
+                        entity_id,
+                        dx,
+                        dy,
+                        dz,
+                        yaw: to.yaw,
+                        pitch: to.pitch,
+                    },
+                ));
+            }
+            (Some(dx), Some(dy), Some(dz)) if moved => {
+                packets.push(Packet::EntityRelativeMove(EntityRelativeMovePayload {
+                    entity_id,
+                    dx,
+                    dy,
+                    dz,
+                }));
+            }
+            (Some(_), Some(_), Some(_)) if rotated => {
+                packets.push(Packet::EntityLook(EntityLookPayload {
+                    entity_id,
+                    yaw: to.yaw,
+                    pitch: to.pitch,
+                }));
+            }
+            (Some(_), Some(_), Some(_)) => {}
+            _ => packets.push(teleport(entity_id, to)),
+        }
+
+        if self.yaw != to.yaw {
+            packets.push(Packet::EntityHeadLook(EntityHeadLookPayload {
+                entity_id,
+                head_yaw: to.yaw,
+            }));
+        }
+
+        packets
+    }
+}
+
+/// Keeps track of the entities each player has been shown, spawning and destroying
+/// them on its client as they come into and leave the range, and relays their movement
+/// and the changes of their metadata.
+#[derive(Debug, Clone)]
+pub struct EntityTracker {
+    /// Distance within which the players see the entities.
+    range: f64,
+
+    /// Entities seen by each player, both keyed by the entity identifier.
+    seen: HashMap<i32, HashSet<i32>>,
+
+    /// Positions of the entities last sent to the clients.
+    positions: HashMap<i32, TrackedPosition>,
+
+    /// Metadata of the entities last sent to the clients.
+    metadata: HashMap<i32, Metadata>,
+
+    /// Number of the updates so far.
+    ticks: u64,
+}
+
+impl EntityTracker {
+    pub fn new(range: f64) -> Self {
+        Self {
+            range,
+            seen: HashMap::new(),
+            positions: HashMap::new(),
+            metadata: HashMap::new(),
+            ticks: 0,
+        }
+    }
+
+    /// Updates who sees what, returns the packets to be sent to each of the players.
+    ///
+    /// The entities missing from the list are gone, e.g. have despawned, they get
+    /// destroyed on the clients of the players which have seen them.
+    /// The players never see themselves.
+    pub fn update(&mut self, viewers: &[Player], entities: &[&dyn Entity]) -> Vec<(i32, Packet)> {
+        let mut packets = Vec::new();
+        let connected: HashSet<i32> = viewers.iter().map(|player| player.base.entity_id).collect();
+        let existing: HashSet<i32> = entities
+            .iter()
+            .map(|entity| entity.base().entity_id)
+            .collect();
+
+        self.seen.retain(|viewer, _| connected.contains(viewer));
+        self.positions
+            .retain(|entity_id, _| existing.contains(entity_id));
+        self.metadata
+            .retain(|entity_id, _| existing.contains(entity_id));
+
+        self.ticks += 1;
+        let sync = self.ticks.is_multiple_of(POSITION_SYNC_INTERVAL);
+
+        // Changes are relayed before the spawns, which already carry the current state.
+        for entity in entities {
+            let base = entity.base();
+            let entity_id = base.entity_id;
+            let current = TrackedPosition::of(base);
+
+            let Some(last) = self.positions.insert(entity_id, current) else {
+                self.metadata.insert(entity_id, base.metadata.clone());
+                continue;
+            };
+
+            let mut changes = last.movement_to(&current, entity_id, sync);
+            if self.metadata.get(&entity_id) != Some(&base.metadata) {
+                self.metadata.insert(entity_id, base.metadata.clone());
+                changes.push(Packet::EntityMetadata(EntityMetadataPayload {
+                    entity_id,
+                    metadata: base.metadata.clone(),
+                }));
+            }
+
+            for packet in changes {
+                for (&viewer, seen) in &self.seen {
+                    if seen.contains(&entity_id) {
+                        packets.push((viewer, packet.clone()));
+                    }
+                }
+            }
+        }
+
+        for viewer in viewers {
+            let position = &viewer.base.position;
+            let seen = self.seen.entry(viewer.base.entity_id).or_default();
+
+            for entity in entities {
+                let entity_id = entity.base().entity_id;
+                if entity_id == viewer.base.entity_id {
+                    continue;
+                }
+
+                let other_position = &entity.base().position;
+                let in_range =
+                    position.distance_squared(other_position.x, other_position.y, other_position.z)
+                        <= self.range * self.range;
+
+                if in_range && seen.insert(entity_id) {
+                    packets.push((viewer.base.entity_id, entity.spawn_packet()));
+                } else if !in_range && seen.remove(&entity_id) {
+                    packets.push((viewer.base.entity_id, destroy(entity_id)));
+                }
+            }
+
+            let gone: Vec<i32> = seen
+                .iter()
+                .copied()
+                .filter(|entity_id| !existing.contains(entity_id))
+                .collect();
+
+            for entity_id in gone {
+                seen.remove(&entity_id);
+                packets.push((viewer.base.entity_id, destroy(entity_id)));
+            }
+        }
+
+        packets
+    }
+
+    /// Forgets the entity, which gets spawned again on the next update,
+    /// and the entities the player with the identifier has been shown,
+    /// e.g. as its client reloads the world.
+    ///
+    /// Returns the packets destroying the entity on the clients which have seen it.
+    pub fn forget(&mut self, entity_id: i32) -> Vec<(i32, Packet)> {
+        self.seen.remove(&entity_id);
+        self.positions.remove(&entity_id);
+        self.metadata.remove(&entity_id);
+
+        self.seen
+            .iter_mut()
+            .filter_map(|(&viewer, seen)| {
+                seen.remove(&entity_id)
+                    .then(|| (viewer, destroy(entity_id)))
+            })
+            .collect()
+    }
+
+    /// Whether the viewer has been shown the entity.
+    pub fn sees(&self, viewer: i32, entity_id: i32) -> bool {
+        self.seen
+            .get(&viewer)
+            .is_some_and(|seen| seen.contains(&entity_id))
+    }
+}
+
+/// Keeps track of the players each player has been shown, see `EntityTracker`.
+#[derive(Debug, Clone)]
+pub struct PlayerTracker {
+    tracker: EntityTracker,
+}
+
+impl Default for PlayerTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlayerTracker {
+    pub fn new() -> Self {
+        Self {
+            tracker: EntityTracker::new(PLAYER_TRACKING_RANGE),
+        }
+    }
+
+    /// Updates who sees whom, returns the packets to be sent to each of the players.
+    ///
+    /// The players missing from the list have disconnected, they get destroyed
+    /// on the clients of the ones which have seen them.
+    pub fn update(&mut self, players: &[Player]) -> Vec<(i32, Packet)> {
+        let entities: Vec<&dyn Entity> = players.iter().map(|player| player as _).collect();
+        self.tracker.update(players, &entities)
+    }
+
+    /// Forgets the player, which gets spawned again on the next update,
+    /// and the players it has been shown, e.g. as its client reloads the world.
+    ///
+    /// Returns the packets destroying the player on the clients which have seen it.
+    pub fn forget(&mut self, entity_id: i32) -> Vec<(i32, Packet)> {
+        self.tracker.forget(entity_id)
+    }
+
+    /// Whether the viewer has been shown the player.
+    pub fn sees(&self, viewer: i32, entity_id: i32) -> bool {
+        self.tracker.sees(viewer, entity_id)
+    }
+}
+
+fn destroy(entity_id: i32) -> Packet {
+    Packet::DestroyEntity(DestroyEntityPayload { entity_id })
+}
+
+fn teleport(entity_id: i32, to: &TrackedPosition) -> Packet {
+    Packet::EntityTeleport(EntityTeleportPayload {
+        entity_id,
+        x: to.x,
+        y: to.y,
+        z: to.z,
+        yaw: to.yaw,
+        pitch: to.pitch,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+
+    use crate::{
+        entity::{EntityBase, Position},
+        experience::Experience,
+        health::Health,
+        inventory::Inventory,
+        player::PLAYER_BOUNDING_BOX,
+        world::dimension::Dimension,
+    };
+
+    use super::*;
+
+    fn player(entity_id: i32, x: f64) -> Player {
+        let position = Position {
+            x,
+            y: 64.0,
+            ..Default::default()
+        };
+
+        Player {
+            base: EntityBase::new(entity_id, position, PLAYER_BOUNDING_BOX),
+            username: format!("player{}", entity_id),
+            dimension: Dimension::Overworld,
+            addr: "127.0.0.1:25565".parse().unwrap(),
+            game_mode: 0,
+            permission_level: 0,
+            held_slot: 0,
+            inventory: Inventory::new(),
+            health: Health::default(),
+            experience: Experience::default(),
+            sprinting: false,
+            eating: None,
+            bed: None,
+            spawn_point: None,
+            sender: mpsc::unbounded_channel().0,
+        }
+    }
+
+    #[test]
+    fn players_see_each_other_in_range() {
+        let mut tracker = PlayerTracker::new();
+
+        let packets = tracker.update(&[player(1, 0.0), player(2, 10.0)]);
+        assert_eq!(packets.len(), 2);
+        assert!(tracker.sees(1, 2) && tracker.sees(2, 1));
+        assert!(matches!(&packets[0].1, Packet::NamedEntitySpawn(_)));
+
+        assert!(tracker
+            .update(&[player(1, 0.0), player(2, 10.0)])
+            .is_empty());
+
+        let packets = tracker.update(&[player(1, 0.0), player(2, 100.0)]);
+        assert!(packets.contains(&(1, destroy(2))));
+        assert!(packets.contains(&(2, destroy(1))));
+        assert!(!tracker.sees(1, 2));
+
+        tracker.update(&[player(1, 0.0), player(2, 10.0)]);
+        assert_eq!(tracker.forget(2), vec![(1, destroy(2))]);
+        assert!(!tracker.sees(2, 1));
+        assert_eq!(tracker.update(&[player(1, 0.0), player(2, 10.0)]).len(), 2);
+
+        assert_eq!(tracker.update(&[player(1, 0.0)]), vec![(1, destroy(2))]);
+        assert!(!tracker.sees(2, 1));
+    }
+
+    #[test]
+    fn movement_picks_relative_moves_or_teleport() {
+        let from = TrackedPosition {
+            x: 0,
+            y: 2048,
+            z: 0,
+            yaw: 0,
+            pitch: 0,
+        };
+
+        assert!(from.movement_to(&from, 1, false).is_empty());
+        assert_eq!(
+            from.movement_to(&TrackedPosition { x: 32, ..from }, 1, false),
+            vec![Packet::EntityRelativeMove(EntityRelativeMovePayload {
+                entity_id: 1,
+                dx: 32,
+                dy: 0,
+                dz: 0,
+            })]
+        );
+        assert_eq!(
+            from.movement_to(&TrackedPosition { pitch: 10, ..from }, 1, false),
+            vec![Packet::EntityLook(EntityLookPayload {
+                entity_id: 1,
+                yaw: 0,
+                pitch: 10,
+            })]
+        );
+
+        let turned = from.movement_to(
+            &TrackedPosition {
+                z: -8,
+                yaw: 64,
+                ..from
+            },
+            1,
+            false,
+        );
+        assert!(matches!(turned[0], Packet::EntityLookAndRelativeMove(_)));
+        assert_eq!(
+            turned[1],
+            Packet::EntityHeadLook(EntityHeadLookPayload {
+                entity_id: 1,
+                head_yaw: 64,
+            })
+        );
+
+        let far = TrackedPosition { x: 128, ..from };
+        assert_eq!(from.movement_to(&far, 1, false), vec![teleport(1, &far)]);
+        assert_eq!(from.movement_to(&from, 1, true), vec![teleport(1, &from)]);
+    }
+}
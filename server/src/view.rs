@@ -0,0 +1,110 @@
+use std::collections::HashSet;
+
+use crate::world::chunk::ChunkPos;
+
+/// Columns loaded by a client around the column it stands in.
+#[derive(Debug, Clone)]
+pub struct ChunkView {
+    /// Number of the columns loaded in each direction from the center.
+    radius: i32,
+
+    center: Option<ChunkPos>,
+    loaded: HashSet<ChunkPos>,
+}
+
+/// Columns the client should load and unload after moving.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ViewChange {
+    /// Columns which came into the view, nearest first.
+    pub load: Vec<ChunkPos>,
+
+    /// Columns which left the view.
+    pub unload: Vec<ChunkPos>,
+}
+
+impl ChunkView {
+    pub fn new(radius: u8) -> Self {
+        Self {
+            radius: radius.into(),
+            center: None,
+            loaded: HashSet::new(),
+        }
+    }
+
+    /// Moves the view, returns `None` if the center hasn't changed.
+    pub fn move_to(&mut self, center: ChunkPos) -> Option<ViewChange> {
+        if self.center == Some(center) {
+            return None;
+        }
+        self.center = Some(center);
+
+        let visible = chunks_in_view(center, self.radius);
+        let visible_set: HashSet<ChunkPos> = visible.iter().copied().collect();
+
+        let mut unload: Vec<ChunkPos> = self.loaded.difference(&visible_set).copied().collect();
+        unload.sort();
+
+        let load: Vec<ChunkPos> = visible
+            .into_iter()
+            .filter(|pos| !self.loaded.contains(pos))
+            .collect();
+
+        self.loaded = visible_set;
+
+        Some(ViewChange { load, unload })
+    }
+
+    /// Whether the client has the column loaded.
+    pub fn contains(&self, pos: ChunkPos) -> bool {
+        self.loaded.contains(&pos)
+    }
+}
+
+/// Columns of the square around the center, nearest first.
+pub fn chunks_in_view(center: ChunkPos, radius: i32) -> Vec<ChunkPos> {
+    let mut positions: Vec<ChunkPos> = (-radius..=radius)
+        .flat_map(|dx| {
+            (-radius..=radius).map(move |dz| ChunkPos::new(center.x + dx, center.z + dz))
+        })
+        .collect();
+
+    positions.sort_by_key(|pos| (pos.x - center.x).pow(2) + (pos.z - center.z).pow(2));
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn move_view() {
+        let mut view = ChunkView::new(1);
+
+        let change = view.move_to(ChunkPos::new(0, 0)).unwrap();
+        assert_eq!(change.load.len(), 9);
+        assert_eq!(change.load[0], ChunkPos::new(0, 0));
+        assert!(change.unload.is_empty());
+
+        assert_eq!(view.move_to(ChunkPos::new(0, 0)), None);
+
+        let change = view.move_to(ChunkPos::new(1, 0)).unwrap();
+        assert_eq!(
+            change.load,
+            vec![
+                ChunkPos::new(2, 0),
+                ChunkPos::new(2, -1),
+                ChunkPos::new(2, 1)
+            ]
+        );
+        assert_eq!(
+            change.unload,
+            vec![
+                ChunkPos::new(-1, -1),
+                ChunkPos::new(-1, 0),
+                ChunkPos::new(-1, 1)
+            ]
+        );
+        assert!(view.contains(ChunkPos::new(2, 1)));
+        assert!(!view.contains(ChunkPos::new(-1, 0)));
+    }
+}
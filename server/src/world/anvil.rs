@@ -0,0 +1,497 @@
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use log::warn;
+use protocol::{
+    chunk::{CHUNK_WIDTH, SECTION_COUNT, SECTION_HEIGHT},
+    nbt::{Compound, Compression, Nbt, Tag},
+};
+
+use crate::{
+    player::is_valid_username,
+    world::{
+        block::Block,
+        chunk::{ChunkColumn, ChunkPos, ChunkSection},
+        dimension::Dimension,
+        region::{region_file_name, region_of, RegionFile},
+        tile_entity::TileEntity,
+        BlockPos,
+    },
+};
+
+/// Number of the blocks in a section, each stored as a byte or a nibble.
+const SECTION_VOLUME: usize = CHUNK_WIDTH * SECTION_HEIGHT * CHUNK_WIDTH;
+
+/// Name of the directory the region files are stored in, within the directory of the dimension.
+const REGION_DIRECTORY: &str = "region";
+
+/// Name of the directory the player files are stored in, within the world directory.
+const PLAYER_DIRECTORY: &str = "players";
+
+/// Name of the file with the world properties, e.g. the seed and the time.
+const LEVEL_FILE: &str = "level.dat";
+
+/// A world stored in its directory, the columns in the region files
+/// and the world properties and the players in the gzipped NBT files.
+#[derive(Debug)]
+pub struct AnvilStorage {
+    world_dir: PathBuf,
+
+    /// Region files opened so far, keyed by the dimension and the region position.
+    regions: Mutex<HashMap<(Dimension, i32, i32), RegionFile>>,
+}
+
+impl AnvilStorage {
+    /// Creates the storage of the world directory, which may not exist yet.
+    pub fn new(world_dir: impl AsRef<Path>) -> Self {
+        Self {
+            world_dir: world_dir.as_ref().to_path_buf(),
+            regions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Loads the column of the dimension, `None` if it was never saved.
+    pub fn load(&self, dimension: Dimension, pos: ChunkPos) -> io::Result<Option<ChunkColumn>> {
+        let region = region_of(pos);
+        let mut regions = self.regions.lock().unwrap();
+
+        let file = match regions.entry((dimension, region.0, region.1)) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let path = self.region_dir(dimension).join(region_file_name(region));
+
+                // Missing regions aren't created until a column is saved in them.
+                if !path.exists() {
+                    return Ok(None);
+                }
+
+                entry.insert(RegionFile::open(path)?)
+            }
+        };
+
+        let Some(nbt) = file.read(pos)? else {
+            return Ok(None);
+        };
+
+        read_column(&nbt).map(Some)
+    }
+
+    /// Saves the column of the dimension, replacing the previously saved one.
+    ///
+    /// `time` is the world time the column is saved at.
+    pub fn save(&self, dimension: Dimension, chunk: &ChunkColumn, time: i64) -> io::Result<()> {
+        let nbt = write_column(chunk, time);
+        let region = region_of(chunk.pos);
+        let mut regions = self.regions.lock().unwrap();
+
+        let file = match regions.entry((dimension, region.0, region.1)) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let region_dir = self.region_dir(dimension);
+                std::fs::create_dir_all(&region_dir)?;
+                entry.insert(RegionFile::open(region_dir.join(region_file_name(region)))?)
+            }
+        };
+
+        file.write(chunk.pos, &nbt)
+    }
+
+    /// Closes the region files of the dimensions other than the used ones,
+    /// given as the dimensions with the region positions.
+    pub fn close_regions_except(&self, used: &HashSet<(Dimension, i32, i32)>) {
+        self.regions
+            .lock()
+            .unwrap()
+            .retain(|region, _| used.contains(region));
+    }
+
+    /// Directory the region files of the dimension are stored in,
+    /// e.g. `DIM-1/region` for the nether.
+    fn region_dir(&self, dimension: Dimension) -> PathBuf {
+        match dimension.directory() {
+            Some(directory) => self.world_dir.join(directory).join(REGION_DIRECTORY),
+            None => self.world_dir.join(REGION_DIRECTORY),
+        }
+    }
+
+    /// Loads the world properties from `level.dat`, `None` if the world is new.
+    pub fn load_level(&self) -> io::Result<Option<Nbt>> {
+        read_file(&self.world_dir.join(LEVEL_FILE))
+    }
+
+    /// Saves the world properties to `level.dat`.
+    pub fn save_level(&self, nbt: &Nbt) -> io::Result<()> {
+        write_file(&self.world_dir.join(LEVEL_FILE), nbt)
+    }
+
+    /// Loads the player's data, `None` if the player has never played in the world.
+    pub fn load_player(&self, username: &str) -> io::Result<Option<Nbt>> {
+        read_file(&self.player_path(username)?)
+    }
+
+    /// Saves the player's data to its file in the `players` directory.
+    pub fn save_player(&self, username: &str, nbt: &Nbt) -> io::Result<()> {
+        write_file(&self.player_path(username)?, nbt)
+    }
+
+    /// Path of the player's file, refusing the names which aren't plain file names,
+    /// e.g. leading out of the `players` directory.
+    fn player_path(&self, username: &str) -> io::Result<PathBuf> {
+        if !is_valid_username(username) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid player name {:?}", username),
+            ));
+        }
+
+        Ok(self
+            .world_dir
+            .join(PLAYER_DIRECTORY)
+            .join(format!("{}.dat", username)))
+    }
+}
+
+/// Reads the gzipped NBT file, `None` if it doesn't exist.
+fn read_file(path: &Path) -> io::Result<Option<Nbt>> {
+    let data = match std::fs::read(path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    Nbt::read(&data, Compression::Gzip)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Writes the gzipped NBT file, replacing it only once it's fully written.
+fn write_file(path: &Path, nbt: &Nbt) -> io::Result<()> {
+    let data = nbt
+        .write(Compression::Gzip)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let temporary = path.with_extension("dat.tmp");
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&temporary, data)?;
+    std::fs::rename(temporary, path)
+}
+
+/// Builds the NBT the column is stored as, with the `Level` compound at the root.
+pub fn write_column(chunk: &ChunkColumn, time: i64) -> Nbt {
+    let sections = chunk
+        .sections()
+        .map(|(i, section)| Tag::Compound(write_section(i, section)))
+        .collect();
+
+    let tile_entities = chunk
+        .tile_entities()
+        .map(|((x, y, z), tile_entity)| {
+            let x = chunk.pos.x * CHUNK_WIDTH as i32 + x as i32;
+            let z = chunk.pos.z * CHUNK_WIDTH as i32 + z as i32;
+            Tag::Compound(tile_entity.to_compound(BlockPos::new(x, y as i32, z)))
+        })
+        .collect();
+
+    let mut biomes = Vec::with_capacity(CHUNK_WIDTH * CHUNK_WIDTH);
+    let mut height_map = Vec::with_capacity(CHUNK_WIDTH * CHUNK_WIDTH);
+    for z in 0..CHUNK_WIDTH {
+        for x in 0..CHUNK_WIDTH {
+            biomes.push(chunk.biome(x, z));
+            height_map.push(chunk.highest_block(x, z).map_or(0, |y| y as i32 + 1));
+        }
+    }
+
+    let mut level = Compound::new();
+    level.insert("xPos".to_string(), Tag::Int(chunk.pos.x));
+    level.insert("zPos".to_string(), Tag::Int(chunk.pos.z));
+    level.insert("LastUpdate".to_string(), Tag::Long(time));
+    level.insert("TerrainPopulated".to_string(), Tag::Byte(1));
+    level.insert("Biomes".to_string(), Tag::ByteArray(biomes));
+    level.insert("HeightMap".to_string(), Tag::IntArray(height_map));
+    level.insert("Sections".to_string(), Tag::List(sections));
+    level.insert("Entities".to_string(), Tag::List(Vec::new()));
+    level.insert("TileEntities".to_string(), Tag::List(tile_entities));
+
+    let mut root = Compound::new();
+    root.insert("Level".to_string(), Tag::Compound(level));
+    Nbt::new("", root)
+}
+
+/// Builds the compound of the section, the block IDs above 255 are stored in `Add`.
+fn write_section(index: usize, section: &ChunkSection) -> Compound {
+    let mut blocks = Vec::with_capacity(SECTION_VOLUME);
+    let mut add = vec![0u8; SECTION_VOLUME / 2];
+    let mut data = vec![0u8; SECTION_VOLUME / 2];
+    let mut block_light = vec![0u8; SECTION_VOLUME / 2];
+    let mut sky_light = vec![0u8; SECTION_VOLUME / 2];
+
+    for (i, (x, y, z)) in section_positions().enumerate() {
+        let block = section.get_block(x, y, z);
+
+        blocks.push(block.id as u8);
+        set_nibble(&mut add, i, (block.id >> 8) as u8);
+        set_nibble(&mut data, i, block.metadata);
+        set_nibble(&mut block_light, i, section.block_light(x, y, z));
+        set_nibble(&mut sky_light, i, section.sky_light(x, y, z));
+    }
+
+    let mut compound = Compound::new();
+    compound.insert("Y".to_string(), Tag::Byte(index as i8));
+    compound.insert("Blocks".to_string(), Tag::ByteArray(blocks));
+    if add.iter().any(|&nibbles| nibbles != 0) {
+        compound.insert("Add".to_string(), Tag::ByteArray(add));
+    }
+    compound.insert("Data".to_string(), Tag::ByteArray(data));
+    compound.insert("BlockLight".to_string(), Tag::ByteArray(block_light));
+    compound.insert("SkyLight".to_string(), Tag::ByteArray(sky_light));
+    compound
+}
+
+/// Builds the column from the NBT it's stored as.
+pub fn read_column(nbt: &Nbt) -> io::Result<ChunkColumn> {
+    let level = nbt
+        .root
+        .get("Level")
+        .and_then(Tag::as_compound)
+        .ok_or_else(|| invalid_data("the column has no Level compound"))?;
+
+    let int = |name: &str| {
+        level
+            .get(name)
+            .and_then(Tag::as_int)
+            .ok_or_else(|| invalid_data(format!("the column has no {}", name)))
+    };
+    let mut chunk = ChunkColumn::new(ChunkPos::new(int("xPos")?, int("zPos")?));
+
+    // Columns saved before the biomes were stored have none.
+    if let Some(biomes) = level.get("Biomes").and_then(Tag::as_byte_array) {
+        if biomes.len() != CHUNK_WIDTH * CHUNK_WIDTH {
+            return Err(invalid_data("the biomes have an invalid length"));
+        }
+
+        for (i, &biome) in biomes.iter().enumerate() {
+            chunk.set_biome(i % CHUNK_WIDTH, i / CHUNK_WIDTH, biome);
+        }
+    }
+
+    let sections = level
+        .get("Sections")
+        .and_then(Tag::as_list)
+        .unwrap_or_default();
+    for section in sections {
+        let section = section
+            .as_compound()
+            .ok_or_else(|| invalid_data("a section isn't a compound"))?;
+        let (index, section) = read_section(section)?;
+        chunk.set_section(index, section);
+    }
+
+    // The kinds of the tile entities which aren't kept, e.g. the mob spawners, are dropped.
+    let tile_entities = level
+        .get("TileEntities")
+        .and_then(Tag::as_list)
+        .unwrap_or_default();
+    for compound in tile_entities.iter().filter_map(Tag::as_compound) {
+        let Some((pos, tile_entity)) = TileEntity::from_compound(compound)? else {
+            continue;
+        };
+        let (x, z) = (
+            pos.x.rem_euclid(CHUNK_WIDTH as i32),
+            pos.z.rem_euclid(CHUNK_WIDTH as i32),
+        );
+        let placed = pos.chunk() == chunk.pos
+            && usize::try_from(pos.y).is_ok_and(|y| {
+                y < SECTION_COUNT * SECTION_HEIGHT
+                    && chunk.set_tile_entity(x as usize, y, z as usize, tile_entity)
+            });
+
+        if !placed {
+            warn!(
+                "Dropping the tile entity at {}, {}, {} which doesn't belong to its block of the column",
+                pos.x, pos.y, pos.z
+            );
+        }
+    }
+
+    chunk.mark_saved();
+    Ok(chunk)
+}
+
+/// Builds the section from its compound, returns it with its index from the bottom.
+fn read_section(compound: &Compound) -> io::Result<(usize, ChunkSection)> {
+    let index = compound
+        .get("Y")
+        .and_then(Tag::as_byte)
+        .and_then(|y| usize::try_from(y).ok())
+        .filter(|&y| y < SECTION_COUNT)
+        .ok_or_else(|| invalid_data("a section has an invalid Y"))?;
+
+    let array = |name: &str, length: usize| match compound.get(name) {
+        Some(Tag::ByteArray(array)) if array.len() == length => Ok(Some(array.as_slice())),
+        Some(_) => Err(invalid_data(format!("the section's {} is invalid", name))),
+        None => Ok(None),
+    };
+    let blocks =
+        array("Blocks", SECTION_VOLUME)?.ok_or_else(|| invalid_data("a section has no blocks"))?;
+    let add = array("Add", SECTION_VOLUME / 2)?;
+    let data = array("Data", SECTION_VOLUME / 2)?;
+    let block_light = array("BlockLight", SECTION_VOLUME / 2)?;
+    let sky_light = array("SkyLight", SECTION_VOLUME / 2)?;
+
+    let mut section = ChunkSection::new();
+    for (i, (x, y, z)) in section_positions().enumerate() {
+        let id = u16::from(blocks[i]) | u16::from(add.map_or(0, |add| nibble(add, i))) << 8;
+        let metadata = data.map_or(0, |data| nibble(data, i));
+        section.set_block(x, y, z, Block::with_metadata(id, metadata));
+
+        if let Some(block_light) = block_light {
+            section.set_block_light(x, y, z, nibble(block_light, i));
+        }
+        if let Some(sky_light) = sky_light {
+            section.set_sky_light(x, y, z, nibble(sky_light, i));
+        }
+    }
+
+    Ok((index, section))
+}
+
+/// Positions within a section in the order they're stored in, by Y, then Z, then X.
+fn section_positions() -> impl Iterator<Item = (usize, usize, usize)> {
+    (0..SECTION_HEIGHT)
+        .flat_map(|y| (0..CHUNK_WIDTH).flat_map(move |z| (0..CHUNK_WIDTH).map(move |x| (x, y, z))))
+}
+
+/// Returns the nibble at the index, the even ones are stored in the low half of the byte.
+fn nibble(array: &[u8], index: usize) -> u8 {
+    (array[index / 2] >> (index % 2 * 4)) & 0x0F
+}
+
+fn set_nibble(array: &mut [u8], index: usize, value: u8) {
+    array[index / 2] |= (value & 0x0F) << (index % 2 * 4);
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::{note_block::NoteBlock, sign::Sign};
+
+    #[test]
+    fn columns_survive_saving() {
+        let mut chunk = ChunkColumn::new(ChunkPos::new(-3, 40));
+        chunk.set_block(0, 0, 0, Block::STONE);
+        chunk.set_block(15, 70, 3, Block::with_metadata(35, 14));
+        chunk.set_block(1, 255, 2, Block::with_metadata(300, 2));
+        chunk.section_mut(70).unwrap().set_block_light(4, 6, 5, 12);
+        chunk.section_mut(70).unwrap().set_sky_light(4, 6, 5, 3);
+        chunk.set_biome(2, 9, 4);
+        chunk.set_block(6, 72, 7, Block::with_metadata(68, 2));
+        let lines = [
+            "Welcome".to_string(),
+            String::new(),
+            "to".to_string(),
+            "spawn".to_string(),
+        ];
+        let sign = TileEntity::Sign(Sign::with_lines(lines));
+        assert!(chunk.set_tile_entity(6, 72, 7, sign.clone()));
+        chunk.set_block(2, 64, 9, Block::NOTE_BLOCK);
+        let note_block = TileEntity::NoteBlock(NoteBlock {
+            note: 7,
+            powered: false,
+        });
+        assert!(chunk.set_tile_entity(2, 64, 9, note_block.clone()));
+
+        let nbt = write_column(&chunk, 1200);
+        let level = nbt.root["Level"].as_compound().unwrap();
+        assert_eq!(level["Sections"].as_list().unwrap().len(), 3);
+        assert_eq!(level["HeightMap"].as_int_array().unwrap()[3 * 16 + 15], 71);
+        let tile_entities = level["TileEntities"].as_list().unwrap();
+        assert_eq!(tile_entities.len(), 2);
+        let saved_sign = tile_entities
+            .iter()
+            .filter_map(Tag::as_compound)
+            .find(|compound| compound["id"].as_str() == Some("Sign"))
+            .unwrap();
+        assert_eq!(saved_sign["x"].as_int(), Some(-3 * 16 + 6));
+        assert_eq!(saved_sign["Text3"].as_str(), Some("to"));
+
+        let nbt = Nbt::read(&nbt.write(Compression::Zlib).unwrap(), Compression::Zlib).unwrap();
+        let loaded = read_column(&nbt).unwrap();
+        assert_eq!(loaded.pos, chunk.pos);
+        assert!(!loaded.is_dirty());
+        assert_eq!(loaded.get_block(0, 0, 0), Block::STONE);
+        assert_eq!(loaded.get_block(15, 70, 3), Block::with_metadata(35, 14));
+        assert_eq!(loaded.get_block(1, 255, 2), Block::with_metadata(300, 2));
+        assert_eq!(loaded.get_block(1, 100, 2), Block::AIR);
+        assert_eq!(loaded.section(70).unwrap().block_light(4, 6, 5), 12);
+        assert_eq!(loaded.section(70).unwrap().sky_light(4, 6, 5), 3);
+        assert_eq!(loaded.biome(2, 9), 4);
+        assert_eq!(loaded.tile_entity(6, 72, 7), Some(&sign));
+        assert_eq!(loaded.tile_entity(2, 64, 9), Some(&note_block));
+    }
+
+    #[test]
+    fn store_columns_in_regions() {
+        let dir = std::env::temp_dir().join(format!("world-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let (overworld, nether) = (Dimension::Overworld, Dimension::Nether);
+        let storage = AnvilStorage::new(&dir);
+        assert!(storage
+            .load(overworld, ChunkPos::new(0, 0))
+            .unwrap()
+            .is_none());
+        assert!(!dir.exists());
+
+        let mut chunk = ChunkColumn::new(ChunkPos::new(-1, 32));
+        chunk.set_block(8, 64, 8, Block::GRASS);
+        storage.save(overworld, &chunk, 0).unwrap();
+        assert!(dir.join("region").join("r.-1.1.mca").exists());
+        chunk.set_block(8, 64, 8, Block::NETHERRACK);
+        storage.save(nether, &chunk, 0).unwrap();
+        assert!(dir.join("DIM-1/region/r.-1.1.mca").exists());
+
+        let storage = AnvilStorage::new(&dir);
+        let loaded = storage
+            .load(overworld, ChunkPos::new(-1, 32))
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.get_block(8, 64, 8), Block::GRASS);
+        let loaded = storage
+            .load(nether, ChunkPos::new(-1, 32))
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.get_block(8, 64, 8), Block::NETHERRACK);
+        assert!(storage
+            .load(overworld, ChunkPos::new(0, 32))
+            .unwrap()
+            .is_none());
+        assert!(storage
+            .load(Dimension::End, ChunkPos::new(-1, 32))
+            .unwrap()
+            .is_none());
+
+        let used = HashSet::from([(nether, -1, 1)]);
+        storage.close_regions_except(&used);
+        assert_eq!(storage.regions.lock().unwrap().len(), 1);
+        let loaded = storage
+            .load(overworld, ChunkPos::new(-1, 32))
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.get_block(8, 64, 8), Block::GRASS);
+
+        let escaping = storage.load_player("../level").unwrap_err();
+        assert_eq!(escaping.kind(), io::ErrorKind::InvalidInput);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
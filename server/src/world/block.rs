@@ -0,0 +1,205 @@
+/// A block with its metadata.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Block {
+    /// Block ID, 12 bits at most.
+    pub id: u16,
+
+    /// 4 bits of the block-specific data, e.g. the wool color.
+    pub metadata: u8,
+}
+
+impl Block {
+    pub const AIR: Block = Block::new(0);
+    pub const STONE: Block = Block::new(1);
+    pub const GRASS: Block = Block::new(2);
+    pub const DIRT: Block = Block::new(3);
+    pub const COBBLESTONE: Block = Block::new(4);
+    pub const BEDROCK: Block = Block::new(7);
+    pub const WATER: Block = Block::new(9);
+    pub const LAVA: Block = Block::new(11);
+    pub const SAND: Block = Block::new(12);
+    pub const GRAVEL: Block = Block::new(13);
+    pub const DISPENSER: Block = Block::new(23);
+    pub const NOTE_BLOCK: Block = Block::new(25);
+    pub const BED: Block = Block::new(26);
+    pub const OBSIDIAN: Block = Block::new(49);
+    pub const FIRE: Block = Block::new(51);
+    pub const CHEST: Block = Block::new(54);
+    pub const REDSTONE_WIRE: Block = Block::new(55);
+    pub const FURNACE: Block = Block::new(61);
+    pub const BURNING_FURNACE: Block = Block::new(62);
+    pub const SIGN_POST: Block = Block::new(63);
+    pub const WOODEN_DOOR: Block = Block::new(64);
+    pub const WALL_SIGN: Block = Block::new(68);
+    pub const LEVER: Block = Block::new(69);
+    pub const STONE_PRESSURE_PLATE: Block = Block::new(70);
+    pub const IRON_DOOR: Block = Block::new(71);
+    pub const WOODEN_PRESSURE_PLATE: Block = Block::new(72);
+    pub const REDSTONE_TORCH_OFF: Block = Block::new(75);
+    pub const REDSTONE_TORCH_ON: Block = Block::new(76);
+    pub const STONE_BUTTON: Block = Block::new(77);
+    pub const NETHERRACK: Block = Block::new(87);
+    pub const PORTAL: Block = Block::new(90);
+    pub const TRAPDOOR: Block = Block::new(96);
+    pub const FENCE_GATE: Block = Block::new(107);
+    pub const END_STONE: Block = Block::new(121);
+
+    /// Creates the block without any metadata.
+    pub const fn new(id: u16) -> Self {
+        Self { id, metadata: 0 }
+    }
+
+    /// Creates the block with the metadata.
+    pub const fn with_metadata(id: u16, metadata: u8) -> Self {
+        Self { id, metadata }
+    }
+
+    pub fn is_air(&self) -> bool {
+        self.id == 0
+    }
+
+    /// Whether the block is flowing or still water.
+    pub fn is_water(&self) -> bool {
+        matches!(self.id, 8 | 9)
+    }
+
+    /// Whether the block is flowing or still lava.
+    pub fn is_lava(&self) -> bool {
+        matches!(self.id, 10 | 11)
+    }
+
+    /// Whether the block is a sign post or a wall sign, holding the text of a sign.
+    pub fn is_sign(&self) -> bool {
+        self.id == Block::SIGN_POST.id || self.id == Block::WALL_SIGN.id
+    }
+
+    /// Whether the block falls when there's nothing below it, sand and gravel.
+    pub fn falls(&self) -> bool {
+        self.id == Block::SAND.id || self.id == Block::GRAVEL.id
+    }
+
+    /// Whether the entities falling onto the block land on it, unlike on the blocks
+    /// without a collision box, e.g. the torches, the flowers or the rails.
+    pub fn is_solid(&self) -> bool {
+        // Saplings, rails, plants, torches, fire, redstone, signs, levers,
+        // pressure plates, buttons, portals and vines.
+        !self.is_replaceable()
+            && !matches!(
+                self.id,
+                6 | 27
+                    | 28
+                    | 31
+                    | 32
+                    | 37..=40
+                    | 50
+                    | 51
+                    | 55
+                    | 59
+                    | 63
+                    | 66
+                    | 68..=70
+                    | 72
+                    | 75..=77
+                    | 83
+                    | 90
+                    | 104..=106
+                    | 115
+                    | 119
+            )
+    }
+
+    /// Whether the block is a full opaque cube, e.g. stone, unlike glass, slabs or stairs.
+    ///
+    /// Only these conduct the redstone power and hold the redstone torches.
+    pub fn is_opaque_cube(&self) -> bool {
+        // Leaves, glass, beds, pistons, cobwebs, slabs, spawners, stairs, chests, farmland,
+        // doors, ladders, ice, cacti, fences, glowstone, cakes, repeaters, trapdoors,
+        // iron bars, glass panes, fence gates, lily pads and the like.
+        self.is_solid()
+            && !matches!(
+                self.id,
+                18 | 20
+                    | 26
+                    | 29
+                    | 30
+                    | 33
+                    | 34
+                    | 44
+                    | 52..=54
+                    | 60
+                    | 64
+                    | 65
+                    | 67
+                    | 71
+                    | 79
+                    | 81
+                    | 85
+                    | 89
+                    | 92..=94
+                    | 96
+                    | 101
+                    | 102
+                    | 107..=109
+                    | 111
+                    | 113
+                    | 114
+                    | 116..=118
+                    | 120
+                    | 122
+            )
+    }
+
+    /// Whether placing a block in its place replaces it, e.g. water or tall grass.
+    pub fn is_replaceable(&self) -> bool {
+        // Air, water, lava, tall grass, fire, snow and vines.
+        matches!(self.id, 0 | 8..=11 | 31 | 51 | 78 | 106)
+    }
+
+    /// How hard the block is to break, `None` if it can't be broken in survival.
+    pub fn hardness(&self) -> Option<f32> {
+        let hardness = match self.id {
+            // Bedrock, fluids, portals and end portal frames.
+            7 | 8..=11 | 90 | 119 | 120 => return None,
+            // Plants, torches, fire, redstone dust, repeaters and the like.
+            0
+            | 6
+            | 31
+            | 32
+            | 37..=40
+            | 46
+            | 50
+            | 51
+            | 55
+            | 59
+            | 75
+            | 76
+            | 83
+            | 93..=95
+            | 104
+            | 105
+            | 111
+            | 115 => 0.0,
+            78 => 0.1,
+            18 | 26 | 80 | 99 | 100 | 106 => 0.2,
+            20 | 89 | 102 | 123 | 124 => 0.3,
+            65 | 81 | 87 => 0.4,
+            3 | 12 | 29 | 33 | 34 | 69 | 70 | 72 | 77 | 79 | 88 | 92 | 117 => 0.5,
+            2 | 13 | 19 | 60 | 82 | 110 => 0.6,
+            27 | 28 | 66 => 0.7,
+            97 => 0.75,
+            24 | 25 | 35 => 0.8,
+            63 | 68 | 86 | 91 | 103 => 1.0,
+            1 | 47 | 98 | 109 => 1.5,
+            4 | 5 | 17 | 43..=45 | 48 | 53 | 67 | 84 | 85 | 107 | 108 | 112..=114 | 118 => 2.0,
+            54 | 58 => 2.5,
+            14..=16 | 21 | 22 | 41 | 56 | 64 | 73 | 74 | 96 | 121 | 122 => 3.0,
+            23 | 61 | 62 => 3.5,
+            30 => 4.0,
+            42 | 52 | 57 | 71 | 101 | 116 => 5.0,
+            49 => 50.0,
+            _ => 1.0,
+        };
+
+        Some(hardness)
+    }
+}
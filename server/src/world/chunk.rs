@@ -0,0 +1,352 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use protocol::chunk::{
+    ChunkData, ChunkDataBuilder, CHUNK_HEIGHT, CHUNK_WIDTH, SECTION_COUNT, SECTION_HEIGHT,
+};
+
+use crate::world::{block::Block, tile_entity::TileEntity, BlockPos};
+
+/// Number of blocks in a chunk section.
+const SECTION_VOLUME: usize = CHUNK_WIDTH * SECTION_HEIGHT * CHUNK_WIDTH;
+
+/// Light level of the blocks exposed to the sky.
+const FULL_LIGHT: u8 = 15;
+
+/// Position of a chunk column, in chunks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ChunkPos {
+    pub x: i32,
+    pub z: i32,
+}
+
+impl ChunkPos {
+    pub fn new(x: i32, z: i32) -> Self {
+        Self { x, z }
+    }
+
+    /// Position of the column containing the block.
+    pub fn of_block(x: i32, z: i32) -> Self {
+        Self::new(x >> 4, z >> 4)
+    }
+}
+
+/// A 16×16×16 cube of blocks.
+#[derive(Debug, Clone)]
+pub struct ChunkSection {
+    blocks: Box<[Block; SECTION_VOLUME]>,
+    /// Light levels are stored as a byte per block for simplicity.
+    block_light: Box<[u8; SECTION_VOLUME]>,
+    sky_light: Box<[u8; SECTION_VOLUME]>,
+
+    /// Number of the blocks other than air, the section is empty when zero.
+    non_air: usize,
+}
+
+impl Default for ChunkSection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChunkSection {
+    /// Creates the section filled with air lit by the sky.
+    pub fn new() -> Self {
+        Self {
+            blocks: Box::new([Block::AIR; SECTION_VOLUME]),
+            block_light: Box::new([0; SECTION_VOLUME]),
+            sky_light: Box::new([FULL_LIGHT; SECTION_VOLUME]),
+            non_air: 0,
+        }
+    }
+
+    /// Returns the block, `y` is relative to the section.
+    pub fn get_block(&self, x: usize, y: usize, z: usize) -> Block {
+        self.blocks[index(x, y, z)]
+    }
+
+    /// Sets the block, `y` is relative to the section, returns the replaced block.
+    pub fn set_block(&mut self, x: usize, y: usize, z: usize, block: Block) -> Block {
+        let previous = std::mem::replace(&mut self.blocks[index(x, y, z)], block);
+
+        match (previous.is_air(), block.is_air()) {
+            (true, false) => self.non_air += 1,
+            (false, true) => self.non_air -= 1,
+            _ => {}
+        }
+
+        previous
+    }
+
+    pub fn block_light(&self, x: usize, y: usize, z: usize) -> u8 {
+        self.block_light[index(x, y, z)]
+    }
+
+    pub fn set_block_light(&mut self, x: usize, y: usize, z: usize, level: u8) {
+        self.block_light[index(x, y, z)] = level.min(FULL_LIGHT);
+    }
+
+    pub fn sky_light(&self, x: usize, y: usize, z: usize) -> u8 {
+        self.sky_light[index(x, y, z)]
+    }
+
+    pub fn set_sky_light(&mut self, x: usize, y: usize, z: usize, level: u8) {
+        self.sky_light[index(x, y, z)] = level.min(FULL_LIGHT);
+    }
+
+    /// Whether the section holds air only.
+    pub fn is_empty(&self) -> bool {
+        self.non_air == 0
+    }
+}
+
+/// Index of the block within its section, ordered by Y, then Z, then X.
+fn index(x: usize, y: usize, z: usize) -> usize {
+    y << 8 | z << 4 | x
+}
+
+/// A 16×256×16 column of blocks, made of the sections stacked on each other.
+///
+/// Coordinates are relative to the column, `x` and `z` in `0..16`, `y` in `0..256`.
+#[derive(Debug, Clone)]
+pub struct ChunkColumn {
+    pub pos: ChunkPos,
+    sections: [Option<ChunkSection>; SECTION_COUNT],
+    /// Biomes of the vertical columns of blocks, indexed by `z * 16 + x`.
+    biomes: [u8; CHUNK_WIDTH * CHUNK_WIDTH],
+    /// Tile entities of the blocks, keyed by their positions within the column.
+    tile_entities: HashMap<(usize, usize, usize), TileEntity>,
+
+    /// Number of the changes made to the column, telling whether it changed
+    /// since a copy of it was taken to be saved.
+    changes: u64,
+    /// Number of the changes the last saved copy has.
+    saved_changes: u64,
+}
+
+impl ChunkColumn {
+    /// Creates the column filled with air.
+    pub fn new(pos: ChunkPos) -> Self {
+        Self {
+            pos,
+            sections: std::array::from_fn(|_| None),
+            biomes: [0; CHUNK_WIDTH * CHUNK_WIDTH],
+            tile_entities: HashMap::new(),
+            changes: 1,
+            saved_changes: 0,
+        }
+    }
+
+    pub fn get_block(&self, x: usize, y: usize, z: usize) -> Block {
+        self.section(y).map_or(Block::AIR, |section| {
+            section.get_block(x, y % SECTION_HEIGHT, z)
+        })
+    }
+
+    /// Sets the block, returns the replaced block.
+    ///
+    /// The tile entity of the replaced block is dropped along with it,
+    /// unless it belongs to the new block as well.
+    pub fn set_block(&mut self, x: usize, y: usize, z: usize, block: Block) -> Block {
+        let slot = &mut self.sections[y / SECTION_HEIGHT];
+
+        if slot.is_none() && block.is_air() {
+            return Block::AIR;
+        }
+
+        let section = slot.get_or_insert_with(ChunkSection::new);
+        let previous = section.set_block(x, y % SECTION_HEIGHT, z, block);
+        if previous != block {
+            self.changes += 1;
+        }
+
+        if previous.id != block.id {
+            let pos = (x, y, z);
+            if self
+                .tile_entities
+                .get(&pos)
+                .is_some_and(|tile_entity| !tile_entity.accepts(block))
+            {
+                self.tile_entities.remove(&pos);
+            }
+        }
+
+        if section.is_empty() {
+            *slot = None;
+        }
+
+        previous
+    }
+
+    pub fn section(&self, y: usize) -> Option<&ChunkSection> {
+        self.sections.get(y / SECTION_HEIGHT)?.as_ref()
+    }
+
+    /// Returns the section for changing, marking the column as changed.
+    pub fn section_mut(&mut self, y: usize) -> Option<&mut ChunkSection> {
+        self.changes += 1;
+        self.sections.get_mut(y / SECTION_HEIGHT)?.as_mut()
+    }
+
+    /// Sections holding any blocks, with their indices from the bottom.
+    pub fn sections(&self) -> impl Iterator<Item = (usize, &ChunkSection)> {
+        self.sections
+            .iter()
+            .enumerate()
+            .filter_map(|(i, section)| Some((i, section.as_ref()?)))
+    }
+
+    /// Replaces the section at the index from the bottom, dropping it if it's empty.
+    pub fn set_section(&mut self, index: usize, section: ChunkSection) {
+        self.sections[index] = (!section.is_empty()).then_some(section);
+        self.changes += 1;
+    }
+
+    pub fn tile_entity(&self, x: usize, y: usize, z: usize) -> Option<&TileEntity> {
+        self.tile_entities.get(&(x, y, z))
+    }
+
+    /// Returns the tile entity for changing, marking the column as changed.
+    ///
+    /// The empty tile entity of the block is created if it has none yet,
+    /// `None` if the block doesn't hold any.
+    pub fn tile_entity_mut(&mut self, x: usize, y: usize, z: usize) -> Option<&mut TileEntity> {
+        let block = self.get_block(x, y, z);
+        let tile_entity = match self.tile_entities.entry((x, y, z)) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(TileEntity::new(block)?),
+        };
+
+        self.changes += 1;
+        Some(tile_entity)
+    }
+
+    /// Stores the tile entity, returns `false` if it doesn't belong to the block.
+    pub fn set_tile_entity(
+        &mut self,
+        x: usize,
+        y: usize,
+        z: usize,
+        tile_entity: TileEntity,
+    ) -> bool {
+        if !tile_entity.accepts(self.get_block(x, y, z)) {
+            return false;
+        }
+
+        self.tile_entities.insert((x, y, z), tile_entity);
+        self.changes += 1;
+        true
+    }
+
+    /// Tile entities of the column with their positions within it.
+    pub fn tile_entities(&self) -> impl Iterator<Item = ((usize, usize, usize), &TileEntity)> {
+        self.tile_entities
+            .iter()
+            .map(|(&pos, tile_entity)| (pos, tile_entity))
+    }
+
+    /// Runs the ticks of the tile entities, updating the blocks showing their state,
+    /// returns the absolute positions of the blocks changed.
+    pub fn tick_tile_entities(&mut self) -> Vec<BlockPos> {
+        let mut changed = Vec::new();
+        let mut shown = Vec::new();
+
+        for (&(x, y, z), tile_entity) in &mut self.tile_entities {
+            if !tile_entity.tick() {
+                continue;
+            }
+            self.changes += 1;
+
+            let block = self.sections[y / SECTION_HEIGHT]
+                .as_ref()
+                .map_or(Block::AIR, |section| {
+                    section.get_block(x, y % SECTION_HEIGHT, z)
+                });
+            let block_shown = tile_entity.shown_block(block);
+            if block_shown != block {
+                shown.push(((x, y, z), block_shown));
+            }
+        }
+
+        for ((x, y, z), block) in shown {
+            self.set_block(x, y, z, block);
+            changed.push(BlockPos::new(
+                self.pos.x * CHUNK_WIDTH as i32 + x as i32,
+                y as i32,
+                self.pos.z * CHUNK_WIDTH as i32 + z as i32,
+            ));
+        }
+
+        changed
+    }
+
+    pub fn biome(&self, x: usize, z: usize) -> u8 {
+        self.biomes[z * CHUNK_WIDTH + x]
+    }
+
+    pub fn set_biome(&mut self, x: usize, z: usize, biome: u8) {
+        self.biomes[z * CHUNK_WIDTH + x] = biome;
+        self.changes += 1;
+    }
+
+    /// Whether the column changed since it was last saved, the new columns never were.
+    pub fn is_dirty(&self) -> bool {
+        self.changes != self.saved_changes
+    }
+
+    /// Marks the column as matching its saved copy.
+    pub fn mark_saved(&mut self) {
+        self.saved_changes = self.changes;
+    }
+
+    /// Number of the changes made to the column so far, to be passed to `mark_saved_at`
+    /// once the copy taken along has been saved.
+    pub fn changes(&self) -> u64 {
+        self.changes
+    }
+
+    /// Marks the column as saved with the changes up to the number,
+    /// the ones made since the copy was taken still have to be saved.
+    pub fn mark_saved_at(&mut self, changes: u64) {
+        self.saved_changes = self.saved_changes.max(changes);
+    }
+
+    /// Y of the highest block other than air, `None` if the column is empty.
+    pub fn highest_block(&self, x: usize, z: usize) -> Option<usize> {
+        (0..CHUNK_HEIGHT)
+            .rev()
+            .find(|&y| !self.get_block(x, y, z).is_air())
+    }
+
+    /// Builds the data of the whole column sent in the Map Chunk packet.
+    pub fn to_chunk_data(&self) -> ChunkData {
+        let mut builder = ChunkDataBuilder::new();
+
+        for (i, section) in self.sections.iter().enumerate() {
+            let Some(section) = section else {
+                continue;
+            };
+
+            for y in 0..SECTION_HEIGHT {
+                for z in 0..CHUNK_WIDTH {
+                    for x in 0..CHUNK_WIDTH {
+                        let column_y = i * SECTION_HEIGHT + y;
+                        let block = section.get_block(x, y, z);
+
+                        builder
+                            .set_block(x, column_y, z, block.id, block.metadata)
+                            .set_block_light(x, column_y, z, section.block_light(x, y, z))
+                            .set_sky_light(x, column_y, z, section.sky_light(x, y, z));
+                    }
+                }
+            }
+        }
+
+        for z in 0..CHUNK_WIDTH {
+            for x in 0..CHUNK_WIDTH {
+                builder.set_biome(x, z, self.biome(x, z));
+            }
+        }
+
+        builder.build()
+    }
+}
@@ -0,0 +1,264 @@
+use std::time::Duration;
+
+use protocol::slot::ItemStack;
+
+use crate::world::block::Block;
+
+/// Duration of a game tick the client digs in.
+const TICK: Duration = Duration::from_millis(50);
+
+/// Kind of the tool digging some blocks faster than the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolKind {
+    Pickaxe,
+    Shovel,
+    Axe,
+    Sword,
+    Shears,
+}
+
+impl ToolKind {
+    /// Kind of the tool and its speed on the effective blocks, `None` if the item isn't a tool.
+    pub fn of_item(id: i16) -> Option<(ToolKind, f32)> {
+        let tool = match id {
+            256 => (ToolKind::Shovel, 6.0),
+            257 => (ToolKind::Pickaxe, 6.0),
+            258 => (ToolKind::Axe, 6.0),
+            267 => (ToolKind::Sword, 6.0),
+            268 => (ToolKind::Sword, 2.0),
+            269 => (ToolKind::Shovel, 2.0),
+            270 => (ToolKind::Pickaxe, 2.0),
+            271 => (ToolKind::Axe, 2.0),
+            272 => (ToolKind::Sword, 4.0),
+            273 => (ToolKind::Shovel, 4.0),
+            274 => (ToolKind::Pickaxe, 4.0),
+            275 => (ToolKind::Axe, 4.0),
+            276 => (ToolKind::Sword, 8.0),
+            277 => (ToolKind::Shovel, 8.0),
+            278 => (ToolKind::Pickaxe, 8.0),
+            279 => (ToolKind::Axe, 8.0),
+            283 => (ToolKind::Sword, 12.0),
+            284 => (ToolKind::Shovel, 12.0),
+            285 => (ToolKind::Pickaxe, 12.0),
+            286 => (ToolKind::Axe, 12.0),
+            359 => (ToolKind::Shears, 1.0),
+            _ => return None,
+        };
+
+        Some(tool)
+    }
+
+    /// Speed of the tool of the kind on the block, `None` if the tool isn't effective on it.
+    fn speed_on(self, block: Block, tier_speed: f32) -> Option<f32> {
+        match (self, block.id) {
+            (ToolKind::Sword | ToolKind::Shears, 30) => Some(15.0),
+            (ToolKind::Shears, 18) => Some(15.0),
+            (ToolKind::Shears, 35) => Some(5.0),
+            (ToolKind::Sword, _) => Some(1.5),
+            (kind, _) if effective_tool(block) == Some(kind) => Some(tier_speed),
+            _ => None,
+        }
+    }
+}
+
+/// Kind of the tool digging the block faster.
+fn effective_tool(block: Block) -> Option<ToolKind> {
+    if needs_pickaxe(block) || matches!(block.id, 27 | 28 | 66 | 79) {
+        return Some(ToolKind::Pickaxe);
+    }
+
+    match block.id {
+        2 | 3 | 12 | 13 | 60 | 78 | 80 | 82 | 88 | 110 => Some(ToolKind::Shovel),
+        5 | 17 | 47 | 53 | 54 | 58 | 64 | 72 | 84..=86 | 91 | 96 | 107 => Some(ToolKind::Axe),
+        _ => None,
+    }
+}
+
+/// Whether the block only drops when mined with a pickaxe, digging it without one is slower.
+fn needs_pickaxe(block: Block) -> bool {
+    matches!(
+        block.id,
+        1 | 4
+            | 14..=16
+            | 21..=24
+            | 41..=45
+            | 48
+            | 49
+            | 52
+            | 56
+            | 57
+            | 61
+            | 62
+            | 67
+            | 71
+            | 73
+            | 74
+            | 87
+            | 98
+            | 101
+            | 108
+            | 109
+            | 112..=114
+            | 116..=118
+            | 121
+    )
+}
+
+/// Whether the block can be harvested only with the right tool.
+fn needs_tool(block: Block) -> bool {
+    needs_pickaxe(block) || matches!(block.id, 30 | 78 | 80)
+}
+
+/// Item the block drops when broken in survival with the tool, `None` if it drops nothing.
+pub fn drop(block: Block, tool: Option<&ItemStack>) -> Option<ItemStack> {
+    if !can_harvest(block, tool) {
+        return None;
+    }
+
+    let (id, count, damage) = match block.id {
+        // Air, fluids, glass, leaves, fire, ice, portals and the like drop nothing.
+        0
+        | 7..=11
+        | 18
+        | 20
+        | 30
+        | 31
+        | 34
+        | 36
+        | 51
+        | 52
+        | 59
+        | 60
+        | 78
+        | 79
+        | 90
+        | 92
+        | 97
+        | 99
+        | 100
+        | 102
+        | 104..=106
+        | 115
+        | 119
+        | 120
+        | 124 => return None,
+        1 => (4, 1, 0),
+        2 | 110 => (3, 1, 0),
+        13 => (13, 1, 0),
+        16 => (263, 1, 0),
+        21 => (351, 4, 4),
+        26 => (355, 1, 0),
+        43 => (44, 2, i16::from(block.metadata)),
+        47 => (340, 3, 0),
+        55 => (331, 1, 0),
+        56 => (264, 1, 0),
+        62 => (61, 1, 0),
+        63 | 68 => (323, 1, 0),
+        64 => (324, 1, 0),
+        71 => (330, 1, 0),
+        73 | 74 => (331, 4, 0),
+        75 | 76 => (76, 1, 0),
+        80 => (332, 4, 0),
+        82 => (337, 4, 0),
+        83 => (338, 1, 0),
+        89 => (348, 2, 0),
+        93 | 94 => (356, 1, 0),
+        103 => (360, 3, 0),
+        117 => (379, 1, 0),
+        118 => (380, 1, 0),
+        123 => (123, 1, 0),
+        // Blocks whose metadata holds the variant, e.g. the wool color.
+        5 | 6 | 17 | 24 | 35 | 44 | 98 => (block.id as i16, 1, i16::from(block.metadata)),
+        id => (id as i16, 1, 0),
+    };
+
+    Some(ItemStack::new(id, count, damage))
+}
+
+/// Whether the block drops anything when dug with the tool.
+///
+/// Only the kind of the tool is checked, e.g. a wooden pickaxe harvests obsidian too.
+pub fn can_harvest(block: Block, tool: Option<&ItemStack>) -> bool {
+    if !needs_tool(block) {
+        return true;
+    }
+
+    tool.and_then(|tool| ToolKind::of_item(tool.id))
+        .is_some_and(|(kind, _)| match block.id {
+            30 => matches!(kind, ToolKind::Sword | ToolKind::Shears),
+            _ => effective_tool(block) == Some(kind),
+        })
+}
+
+/// How long it takes to break the block with the tool, `None` if it can't be broken.
+///
+/// Blocks breaking within a single tick take no time, the client breaks them on click.
+pub fn break_time(block: Block, tool: Option<&ItemStack>) -> Option<Duration> {
+    let hardness = block.hardness()?;
+
+    // Blocks needing a tool are dug a lot slower without it, whatever is held.
+    let ticks = if can_harvest(block, tool) {
+        let speed = tool
+            .and_then(|tool| ToolKind::of_item(tool.id))
+            .and_then(|(kind, tier_speed)| kind.speed_on(block, tier_speed))
+            .unwrap_or(1.0);
+
+        hardness * 30.0 / speed
+    } else {
+        hardness * 100.0
+    };
+
+    if ticks <= 1.0 {
+        return Some(Duration::ZERO);
+    }
+
+    Some(TICK * ticks.ceil() as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn break_times() {
+        let pickaxe = ItemStack::new(257, 1, 0);
+
+        assert_eq!(
+            break_time(Block::STONE, None),
+            Some(Duration::from_millis(7500))
+        );
+        assert_eq!(
+            break_time(Block::STONE, Some(&pickaxe)),
+            Some(Duration::from_millis(400))
+        );
+        assert_eq!(
+            break_time(Block::DIRT, None),
+            Some(Duration::from_millis(750))
+        );
+        assert_eq!(break_time(Block::new(31), None), Some(Duration::ZERO));
+        assert_eq!(break_time(Block::BEDROCK, Some(&pickaxe)), None);
+
+        assert_eq!(
+            break_time(Block::STONE, Some(&ItemStack::new(276, 1, 0))),
+            Some(Duration::from_millis(7500))
+        );
+    }
+
+    #[test]
+    fn block_drops() {
+        let pickaxe = ItemStack::new(270, 1, 0);
+
+        assert_eq!(
+            drop(Block::STONE, Some(&pickaxe)),
+            Some(ItemStack::new(4, 1, 0))
+        );
+        assert_eq!(drop(Block::STONE, None), None);
+        assert_eq!(drop(Block::GRASS, None), Some(ItemStack::new(3, 1, 0)));
+        assert_eq!(
+            drop(Block::with_metadata(35, 14), None),
+            Some(ItemStack::new(35, 1, 14))
+        );
+        assert_eq!(drop(Block::new(20), None), None);
+        assert_eq!(drop(Block::AIR, None), None);
+    }
+}
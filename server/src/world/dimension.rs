@@ -0,0 +1,65 @@
+use protocol::chunk::CHUNK_HEIGHT;
+
+/// A dimension, each with a world of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub enum Dimension {
+    #[default]
+    Overworld,
+    Nether,
+    End,
+}
+
+impl Dimension {
+    pub const ALL: [Dimension; 3] = [Dimension::Overworld, Dimension::Nether, Dimension::End];
+
+    /// Identifier sent in the Login Request and Respawn packets and stored in the player files.
+    pub fn id(self) -> i32 {
+        match self {
+            Dimension::Overworld => 0,
+            Dimension::Nether => -1,
+            Dimension::End => 1,
+        }
+    }
+
+    /// Returns the dimension with the identifier, `None` if it's unknown.
+    pub fn from_id(id: i32) -> Option<Self> {
+        Self::ALL.into_iter().find(|dimension| dimension.id() == id)
+    }
+
+    /// Whether the dimension has a sky lighting it and a day and night cycle.
+    pub fn has_sky(self) -> bool {
+        self == Dimension::Overworld
+    }
+
+    /// Number of the blocks from the bottom to the top of the dimension,
+    /// the nether is closed off by the bedrock half way up.
+    pub fn height(self) -> usize {
+        match self {
+            Dimension::Nether => 128,
+            _ => CHUNK_HEIGHT,
+        }
+    }
+
+    /// Number of the overworld blocks a block of the dimension stands for, horizontally.
+    pub fn scale(self) -> f64 {
+        match self {
+            Dimension::Nether => 8.0,
+            _ => 1.0,
+        }
+    }
+
+    /// Directory the columns of the dimension are stored in, within the world directory,
+    /// `None` for the overworld stored right in it.
+    pub fn directory(self) -> Option<String> {
+        (self != Dimension::Overworld).then(|| format!("DIM{}", self.id()))
+    }
+
+    /// Position in `ALL`.
+    pub fn index(self) -> usize {
+        match self {
+            Dimension::Overworld => 0,
+            Dimension::Nether => 1,
+            Dimension::End => 2,
+        }
+    }
+}
@@ -0,0 +1,546 @@
+use crate::world::{
+    self, block::Block, digging, dimension::Dimension, BlockChanges, BlockPos, World,
+};
+
+/// Metadata of the source blocks, the flowing ones count up from it as they spread.
+const SOURCE_LEVEL: i32 = 0;
+
+/// Bit of the metadata set on the fluids falling down.
+const FALLING: i32 = 8;
+
+/// Neighbours the fluids spread to, in pairs of the opposite directions.
+const HORIZONTAL: [(i32, i32); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+/// Cost of flowing in a direction leading to no drop, or blocked.
+const NO_DROP_COST: u32 = 1000;
+
+/// Number of the blocks the fluids look ahead for a drop to flow towards.
+const DROP_SEARCH_DISTANCE: u32 = 4;
+
+/// Kind of the fluid, each flowing at its own pace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fluid {
+    Water,
+    Lava,
+}
+
+impl Fluid {
+    /// Fluid the block is made of, still or flowing.
+    pub fn of(block: Block) -> Option<Self> {
+        if block.is_water() {
+            Some(Fluid::Water)
+        } else if block.is_lava() {
+            Some(Fluid::Lava)
+        } else {
+            None
+        }
+    }
+
+    /// ID of the flowing block of the fluid, the ticking one.
+    pub fn flowing(self) -> u16 {
+        match self {
+            Fluid::Water => 8,
+            Fluid::Lava => 10,
+        }
+    }
+
+    /// ID of the still block of the fluid, resting until its neighbours change.
+    pub fn still(self) -> u16 {
+        self.flowing() + 1
+    }
+
+    /// Number of the ticks between the spreading steps.
+    pub fn tick_rate(self) -> u64 {
+        match self {
+            Fluid::Water => 5,
+            Fluid::Lava => 30,
+        }
+    }
+
+    /// Level the fluid loses with each block it spreads away, the lava
+    /// spreads only half as far outside of the nether.
+    fn decay(self, dimension: Dimension) -> i32 {
+        match (self, dimension) {
+            (Fluid::Lava, Dimension::Overworld | Dimension::End) => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// Lets the fluids at the position and around it react to the block there changing,
+/// the still ones start flowing again and the lava touching the water hardens.
+pub fn block_changed(world: &mut World, pos: BlockPos, changes: &mut BlockChanges) {
+    neighbour_changed(world, pos, changes);
+    notify_neighbours(world, pos, changes);
+}
+
+/// Spreads the flowing fluid at the position by a single step,
+/// drying it up if the fluid feeding it is gone, or settling it once it stops changing.
+pub fn tick(world: &mut World, pos: BlockPos, changes: &mut BlockChanges) {
+    let Some(fluid) = world.get_block(pos.x, pos.y, pos.z).and_then(Fluid::of) else {
+        return;
+    };
+    let decay = fluid.decay(world.dimension());
+    let mut level = level_at(world, fluid, pos).unwrap_or(SOURCE_LEVEL);
+
+    if level > SOURCE_LEVEL {
+        let mut sources = 0;
+        let mut smallest = -1;
+        for (dx, dz) in HORIZONTAL {
+            smallest = smallest_level(world, fluid, pos.offset(dx, 0, dz), smallest, &mut sources);
+        }
+
+        let mut new_level = smallest + decay;
+        if new_level >= FALLING || smallest < 0 {
+            new_level = -1;
+        }
+
+        // The fluid above keeps feeding the block, falling down into it.
+        if let Some(above) = level_at(world, fluid, pos.offset(0, 1, 0)) {
+            new_level = if above >= FALLING {
+                above
+            } else {
+                above + FALLING
+            };
+        }
+
+        // Water between two sources becomes a source too, if it has something to rest on.
+        if sources >= 2 && fluid == Fluid::Water {
+            let below = pos.offset(0, -1, 0);
+            let resting = world
+                .get_block(below.x, below.y, below.z)
+                .is_some_and(|block| block.is_solid());
+
+            if resting || level_at(world, fluid, below) == Some(SOURCE_LEVEL) {
+                new_level = SOURCE_LEVEL;
+            }
+        }
+
+        // The lava spreads further only sometimes, slowing it down.
+        let mut settle = true;
+        if fluid == Fluid::Lava
+            && level < FALLING
+            && new_level < FALLING
+            && new_level > level
+            && rand::random_range(0..4) != 0
+        {
+            new_level = level;
+            settle = false;
+        }
+
+        if new_level != level {
+            level = new_level;
+
+            if level < 0 {
+                set_block(world, pos, Block::AIR, changes);
+                return;
+            }
+            set_fluid(world, fluid, pos, level, changes);
+        } else if settle {
+            make_still(world, fluid, pos, changes);
+        }
+    } else {
+        make_still(world, fluid, pos, changes);
+    }
+
+    let below = pos.offset(0, -1, 0);
+    if can_displace(world, fluid, below) {
+        // The lava falling onto the water turns it into stone.
+        let water_below = world
+            .get_block(below.x, below.y, below.z)
+            .is_some_and(|block| block.is_water());
+        if fluid == Fluid::Lava && water_below {
+            set_block(world, below, Block::STONE, changes);
+            return;
+        }
+
+        let falling = if level >= FALLING {
+            level
+        } else {
+            level + FALLING
+        };
+        flow_into(world, fluid, below, falling, changes);
+    } else if level == SOURCE_LEVEL || blocks_flow(world, below) {
+        let spread = if level >= FALLING { 1 } else { level + decay };
+        if spread >= FALLING {
+            return;
+        }
+
+        let directions = flow_directions(world, fluid, pos);
+        for (&(dx, dz), flows) in HORIZONTAL.iter().zip(directions) {
+            if flows {
+                flow_into(world, fluid, pos.offset(dx, 0, dz), spread, changes);
+            }
+        }
+    }
+}
+
+/// Level of the fluid at the position, `None` if there's another block.
+fn level_at(world: &World, fluid: Fluid, pos: BlockPos) -> Option<i32> {
+    world
+        .get_block(pos.x, pos.y, pos.z)
+        .filter(|block| Fluid::of(*block) == Some(fluid))
+        .map(|block| i32::from(block.metadata))
+}
+
+/// Lowest level of the fluid next to the block, counting the sources,
+/// the falling fluid counts as a source.
+fn smallest_level(
+    world: &World,
+    fluid: Fluid,
+    pos: BlockPos,
+    smallest: i32,
+    sources: &mut u32,
+) -> i32 {
+    let Some(level) = level_at(world, fluid, pos) else {
+        return smallest;
+    };
+
+    if level == SOURCE_LEVEL {
+        *sources += 1;
+    }
+    let level = if level >= FALLING { 0 } else { level };
+
+    if smallest >= 0 && level >= smallest {
+        smallest
+    } else {
+        level
+    }
+}
+
+/// Whether the fluids can't flow into the block, a solid block, a door,
+/// a sign, a ladder or sugar cane, or a block of a column which isn't loaded.
+fn blocks_flow(world: &World, pos: BlockPos) -> bool {
+    let Some(block) = world.get_block(pos.x, pos.y, pos.z) else {
+        return true;
+    };
+
+    matches!(block.id, 63..=65 | 68 | 71 | 83) || block.is_solid()
+}
+
+/// Whether the fluid flows into the block, washing it away unless it's air.
+fn can_displace(world: &World, fluid: Fluid, pos: BlockPos) -> bool {
+    let Some(block) = world.get_block(pos.x, pos.y, pos.z) else {
+        return false;
+    };
+
+    Fluid::of(block) != Some(fluid) && !block.is_lava() && !blocks_flow(world, pos)
+}
+
+/// Whether the block is a source of the fluid, which it never flows into.
+fn is_source(world: &World, fluid: Fluid, pos: BlockPos) -> bool {
+    level_at(world, fluid, pos) == Some(SOURCE_LEVEL)
+}
+
+/// Directions the fluid spreads in, the ones leading to the nearest drop,
+/// or all of the open ones if there's none nearby.
+fn flow_directions(world: &World, fluid: Fluid, pos: BlockPos) -> [bool; 4] {
+    let mut costs = [NO_DROP_COST; 4];
+
+    for (i, (dx, dz)) in HORIZONTAL.into_iter().enumerate() {
+        let next = pos.offset(dx, 0, dz);
+        if blocks_flow(world, next) || is_source(world, fluid, next) {
+            continue;
+        }
+
+        costs[i] = if blocks_flow(world, next.offset(0, -1, 0)) {
+            flow_cost(world, fluid, next, 1, i)
+        } else {
+            0
+        };
+    }
+
+    let lowest = costs.into_iter().min().unwrap_or(NO_DROP_COST);
+    costs.map(|cost| cost == lowest)
+}
+
+/// Number of the blocks to the nearest drop reachable from the block,
+/// not going back the way the search came from.
+fn flow_cost(world: &World, fluid: Fluid, pos: BlockPos, distance: u32, from: usize) -> u32 {
+    let mut cost = NO_DROP_COST;
+
+    for (i, (dx, dz)) in HORIZONTAL.into_iter().enumerate() {
+        // The directions are paired, `i ^ 1` is the opposite one.
+        if i == from ^ 1 {
+            continue;
+        }
+
+        let next = pos.offset(dx, 0, dz);
+        if blocks_flow(world, next) || is_source(world, fluid, next) {
+            continue;
+        }
+        if !blocks_flow(world, next.offset(0, -1, 0)) {
+            return distance;
+        }
+        if distance < DROP_SEARCH_DISTANCE {
+            cost = cost.min(flow_cost(world, fluid, next, distance + 1, i));
+        }
+    }
+
+    cost
+}
+
+/// Flows the fluid into the block if it can, breaking the block there into its item.
+fn flow_into(
+    world: &mut World,
+    fluid: Fluid,
+    pos: BlockPos,
+    level: i32,
+    changes: &mut BlockChanges,
+) {
+    if !can_displace(world, fluid, pos) {
+        return;
+    }
+
+    // The lava burns the blocks it flows into.
+    if let Some(block) = world.get_block(pos.x, pos.y, pos.z) {
+        if fluid == Fluid::Water {
+            if let Some(item) = digging::drop(block, None) {
+                changes.drops.push((pos, item));
+            }
+        }
+    }
+
+    set_fluid(world, fluid, pos, level, changes);
+}
+
+/// Sets the flowing fluid of the level and schedules its next step,
+/// unless it's the lava hardening right away.
+fn set_fluid(
+    world: &mut World,
+    fluid: Fluid,
+    pos: BlockPos,
+    level: i32,
+    changes: &mut BlockChanges,
+) {
+    let block = Block::with_metadata(fluid.flowing(), level as u8);
+    set_block(world, pos, block, changes);
+
+    if fluid == Fluid::Lava && harden(world, pos, block, changes) {
+        return;
+    }
+    world.schedule_tick(pos, fluid.flowing(), fluid.tick_rate());
+}
+
+/// Turns the flowing fluid still, it stays so until its neighbours change.
+fn make_still(world: &mut World, fluid: Fluid, pos: BlockPos, changes: &mut BlockChanges) {
+    let Some(block) = world.get_block(pos.x, pos.y, pos.z) else {
+        return;
+    };
+
+    world.set_block(
+        pos.x,
+        pos.y,
+        pos.z,
+        Block::with_metadata(fluid.still(), block.metadata),
+    );
+    changes.changed.push(pos);
+}
+
+/// Sets the block and lets the blocks around it react.
+fn set_block(world: &mut World, pos: BlockPos, block: Block, changes: &mut BlockChanges) {
+    if world.set_block(pos.x, pos.y, pos.z, block).is_none() {
+        return;
+    }
+
+    changes.changed.push(pos);
+    world::block_changed(world, pos, changes);
+}
+
+fn notify_neighbours(world: &mut World, pos: BlockPos, changes: &mut BlockChanges) {
+    for face in 0..6 {
+        if let Some(neighbour) = pos.neighbour(face) {
+            neighbour_changed(world, neighbour, changes);
+        }
+    }
+}
+
+/// Hardens the lava touching the water, or lets the fluid flow again.
+fn neighbour_changed(world: &mut World, pos: BlockPos, changes: &mut BlockChanges) {
+    let Some(block) = world.get_block(pos.x, pos.y, pos.z) else {
+        return;
+    };
+    let Some(fluid) = Fluid::of(block) else {
+        return;
+    };
+
+    if fluid == Fluid::Lava && harden(world, pos, block, changes) {
+        return;
+    }
+
+    // The block is switched without notifying the neighbours, they haven't changed.
+    if block.id == fluid.still() {
+        world.set_block(
+            pos.x,
+            pos.y,
+            pos.z,
+            Block::with_metadata(fluid.flowing(), block.metadata),
+        );
+        changes.changed.push(pos);
+    }
+    world.schedule_tick(pos, fluid.flowing(), fluid.tick_rate());
+}
+
+/// Hardens the lava touching the water from above or a side, the source into obsidian
+/// and the lava close to its source into cobblestone, returns whether it has hardened.
+fn harden(world: &mut World, pos: BlockPos, lava: Block, changes: &mut BlockChanges) -> bool {
+    let touches_water = [1, 2, 3, 4, 5].into_iter().any(|face| {
+        pos.neighbour(face)
+            .and_then(|neighbour| world.get_block(neighbour.x, neighbour.y, neighbour.z))
+            .is_some_and(|block| block.is_water())
+    });
+    if !touches_water {
+        return false;
+    }
+
+    let hardened = match i32::from(lava.metadata) {
+        SOURCE_LEVEL => Block::OBSIDIAN,
+        1..=4 => Block::COBBLESTONE,
+        _ => return false,
+    };
+    set_block(world, pos, hardened, changes);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::world::{
+        chunk::{ChunkColumn, ChunkPos},
+        redstone::Occupants,
+    };
+
+    use super::*;
+
+    /// World with a stone floor at Y 63.
+    fn flat_world() -> World {
+        let mut world = World::new();
+        for x in -1..=1 {
+            for z in -1..=1 {
+                world.insert_chunk(ChunkColumn::new(ChunkPos::new(x, z)));
+            }
+        }
+        for x in -16..32 {
+            for z in -16..32 {
+                world.set_block(x, 63, z, Block::STONE);
+            }
+        }
+        world
+    }
+
+    fn run_ticks(world: &mut World, ticks: usize) -> BlockChanges {
+        let mut changes = BlockChanges::default();
+        for _ in 0..ticks {
+            let tick = world.tick_blocks(&Occupants::new());
+            changes.changed.extend(tick.changed);
+            changes.drops.extend(tick.drops);
+        }
+        changes
+    }
+
+    #[test]
+    fn water_spreads_seven_blocks() {
+        let mut world = flat_world();
+        world.set_block(8, 64, 8, Block::WATER);
+        block_changed(
+            &mut world,
+            BlockPos::new(8, 64, 8),
+            &mut BlockChanges::default(),
+        );
+
+        let changes = run_ticks(&mut world, 200);
+        assert!(!changes.changed.is_empty());
+        assert!(world.scheduled_ticks().is_empty());
+
+        let block = |x, z| world.get_block(x, 64, z).unwrap();
+        assert_eq!(block(8, 8), Block::WATER);
+        assert_eq!(block(9, 8), Block::with_metadata(9, 1));
+        assert_eq!(block(15, 8), Block::with_metadata(9, 7));
+        assert_eq!(block(16, 8), Block::AIR);
+        assert_eq!(block(11, 12), Block::with_metadata(9, 7));
+        assert_eq!(block(12, 12), Block::AIR);
+    }
+
+    #[test]
+    fn water_flows_towards_the_drop() {
+        let mut world = flat_world();
+        world.set_block(8, 64, 8, Block::WATER);
+        world.set_block(10, 63, 8, Block::AIR);
+        block_changed(
+            &mut world,
+            BlockPos::new(8, 64, 8),
+            &mut BlockChanges::default(),
+        );
+
+        run_ticks(&mut world, 40);
+        let block = |x, y, z| world.get_block(x, y, z).unwrap();
+        assert!(block(9, 64, 8).is_water());
+        assert!(block(10, 63, 8).is_water());
+        assert!(block(7, 64, 8).is_air());
+        assert!(block(8, 64, 9).is_air());
+    }
+
+    #[test]
+    fn water_washes_torches_away() {
+        let mut world = flat_world();
+        world.set_block(8, 64, 8, Block::WATER);
+        world.set_block(9, 64, 8, Block::with_metadata(50, 5));
+        block_changed(
+            &mut world,
+            BlockPos::new(8, 64, 8),
+            &mut BlockChanges::default(),
+        );
+
+        let changes = run_ticks(&mut world, 20);
+        assert!(world.get_block(9, 64, 8).unwrap().is_water());
+        assert_eq!(changes.drops.len(), 1);
+        assert_eq!(changes.drops[0].1.id, 50);
+    }
+
+    #[test]
+    fn lava_hardens_next_to_water() {
+        let mut world = flat_world();
+        world.set_block(8, 64, 8, Block::LAVA);
+        world.set_block(-1, 64, 8, Block::WATER);
+        block_changed(
+            &mut world,
+            BlockPos::new(8, 64, 8),
+            &mut BlockChanges::default(),
+        );
+        block_changed(
+            &mut world,
+            BlockPos::new(-1, 64, 8),
+            &mut BlockChanges::default(),
+        );
+        run_ticks(&mut world, 40);
+
+        // The water spreads 7 blocks, meeting the lava next to its source.
+        let block = |x| world.get_block(x, 64, 8).unwrap();
+        assert_eq!(block(6), Block::with_metadata(9, 7));
+        assert_eq!(block(7), Block::COBBLESTONE);
+        assert!(block(8).is_lava() && block(8).metadata == 0);
+
+        // A source touching the water turns into obsidian.
+        world.set_block(8, 65, 8, Block::WATER);
+        block_changed(
+            &mut world,
+            BlockPos::new(8, 65, 8),
+            &mut BlockChanges::default(),
+        );
+        assert_eq!(world.get_block(8, 64, 8), Some(Block::OBSIDIAN));
+    }
+
+    #[test]
+    fn lava_falling_onto_water_makes_stone() {
+        let mut world = flat_world();
+        world.set_block(8, 64, 8, Block::WATER);
+        world.set_block(8, 66, 8, Block::LAVA);
+        block_changed(
+            &mut world,
+            BlockPos::new(8, 66, 8),
+            &mut BlockChanges::default(),
+        );
+        run_ticks(&mut world, 100);
+
+        assert_eq!(world.get_block(8, 64, 8), Some(Block::STONE));
+    }
+}
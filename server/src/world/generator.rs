@@ -0,0 +1,380 @@
+use protocol::chunk::{CHUNK_HEIGHT, CHUNK_WIDTH};
+
+use crate::world::{
+    block::Block,
+    chunk::{ChunkColumn, ChunkPos},
+    dimension::Dimension,
+    noise::OctaveNoise,
+};
+
+/// Highest Y filled with water by the default generator.
+pub const SEA_LEVEL: usize = 63;
+
+/// Highest Y filled with lava by the nether generator.
+pub const NETHER_LAVA_LEVEL: usize = 31;
+
+/// Biome ID of the plains.
+const PLAINS_BIOME: u8 = 1;
+
+/// Biome ID of the nether.
+const HELL_BIOME: u8 = 8;
+
+/// Biome ID of the end.
+const SKY_BIOME: u8 = 9;
+
+/// Generates the terrain of the chunk columns.
+pub trait ChunkGenerator: Send + Sync {
+    /// Generates the column, the same position always yields the same column.
+    fn generate(&self, pos: ChunkPos) -> ChunkColumn;
+}
+
+/// Creates the generator of the level type, the default one if it's unknown.
+pub fn generator_for(level_type: &str, seed: i64) -> Box<dyn ChunkGenerator> {
+    if level_type.eq_ignore_ascii_case("flat") {
+        Box::new(FlatGenerator::default())
+    } else {
+        Box::new(DefaultGenerator::new(seed))
+    }
+}
+
+/// Generators of the terrain of each dimension.
+pub struct Generators {
+    generators: [Box<dyn ChunkGenerator>; Dimension::ALL.len()],
+}
+
+impl Generators {
+    /// Creates the generators, the overworld's one of the level type.
+    pub fn new(level_type: &str, seed: i64) -> Self {
+        Self {
+            generators: [
+                generator_for(level_type, seed),
+                Box::new(NetherGenerator::new(seed)),
+                Box::new(EndGenerator::new(seed)),
+            ],
+        }
+    }
+
+    pub fn get(&self, dimension: Dimension) -> &dyn ChunkGenerator {
+        self.generators[dimension.index()].as_ref()
+    }
+}
+
+/// Generates a random world seed.
+pub fn random_seed() -> i64 {
+    rand::random()
+}
+
+/// Generates the same layers of blocks everywhere.
+#[derive(Debug, Clone)]
+pub struct FlatGenerator {
+    /// Layers from the bottom of the world.
+    pub layers: Vec<Block>,
+}
+
+impl Default for FlatGenerator {
+    /// Bedrock, two layers of dirt and grass on top, like the vanilla flat world.
+    fn default() -> Self {
+        Self {
+            layers: vec![Block::BEDROCK, Block::DIRT, Block::DIRT, Block::GRASS],
+        }
+    }
+}
+
+impl ChunkGenerator for FlatGenerator {
+    fn generate(&self, pos: ChunkPos) -> ChunkColumn {
+        let mut chunk = ChunkColumn::new(pos);
+
+        for (y, &block) in self.layers.iter().enumerate().take(CHUNK_HEIGHT) {
+            for z in 0..CHUNK_WIDTH {
+                for x in 0..CHUNK_WIDTH {
+                    chunk.set_block(x, y, z, block);
+                    chunk.set_biome(x, z, PLAINS_BIOME);
+                }
+            }
+        }
+
+        chunk
+    }
+}
+
+/// Generates the hills and the seas out of the layered noise heightmap.
+#[derive(Debug, Clone)]
+pub struct DefaultGenerator {
+    heightmap: OctaveNoise,
+}
+
+impl DefaultGenerator {
+    /// Horizontal scale of the heightmap, bigger is smoother.
+    const SCALE: f64 = 128.0;
+    /// Surface height at the noise level zero.
+    const BASE_HEIGHT: f64 = 64.0;
+    /// Difference between the highest and the lowest surface and the base height.
+    const AMPLITUDE: f64 = 28.0;
+    /// Thickness of the dirt below the surface.
+    const DIRT_DEPTH: usize = 3;
+
+    pub fn new(seed: i64) -> Self {
+        Self {
+            heightmap: OctaveNoise::new(seed as u64, 6, 0.5),
+        }
+    }
+
+    /// Y of the surface block at the absolute block coordinates.
+    pub fn surface_height(&self, x: i32, z: i32) -> usize {
+        let noise = self
+            .heightmap
+            .sample(x as f64 / Self::SCALE, z as f64 / Self::SCALE);
+        let height = Self::BASE_HEIGHT + noise * Self::AMPLITUDE;
+
+        height.clamp(1.0, (CHUNK_HEIGHT - 1) as f64) as usize
+    }
+}
+
+impl ChunkGenerator for DefaultGenerator {
+    fn generate(&self, pos: ChunkPos) -> ChunkColumn {
+        let mut chunk = ChunkColumn::new(pos);
+
+        for z in 0..CHUNK_WIDTH {
+            for x in 0..CHUNK_WIDTH {
+                let surface = self.surface_height(
+                    pos.x * CHUNK_WIDTH as i32 + x as i32,
+                    pos.z * CHUNK_WIDTH as i32 + z as i32,
+                );
+
+                chunk.set_block(x, 0, z, Block::BEDROCK);
+
+                for y in 1..=surface.max(SEA_LEVEL) {
+                    let block = if y + Self::DIRT_DEPTH < surface {
+                        Block::STONE
+                    } else if y < surface {
+                        Block::DIRT
+                    } else if y == surface && surface > SEA_LEVEL {
+                        Block::GRASS
+                    } else if y == surface {
+                        // The bottom of the sea.
+                        Block::SAND
+                    } else {
+                        Block::WATER
+                    };
+
+                    chunk.set_block(x, y, z, block);
+                }
+
+                chunk.set_biome(x, z, PLAINS_BIOME);
+            }
+        }
+
+        chunk
+    }
+}
+
+/// Generates the netherrack caverns between the bedrock floor and ceiling with the lava sea.
+#[derive(Debug, Clone)]
+pub struct NetherGenerator {
+    floor: OctaveNoise,
+    ceiling: OctaveNoise,
+}
+
+impl NetherGenerator {
+    /// Y of the bedrock ceiling, the nether is half as high as the overworld.
+    pub const CEILING: usize = 127;
+    /// Horizontal scale of the floor and the ceiling, bigger is smoother.
+    const SCALE: f64 = 48.0;
+    /// Height of the floor and the ceiling at the noise level zero.
+    const BASE_FLOOR: f64 = 36.0;
+    const BASE_CEILING: f64 = 100.0;
+    /// Difference between the highest and the lowest floor and ceiling and their base heights.
+    const AMPLITUDE: f64 = 24.0;
+
+    pub fn new(seed: i64) -> Self {
+        Self {
+            floor: OctaveNoise::new(seed as u64 ^ 0x4E45_5448, 4, 0.5),
+            ceiling: OctaveNoise::new(seed as u64 ^ 0x4345_494C, 4, 0.5),
+        }
+    }
+
+    /// Y of the top of the floor and the bottom of the ceiling at the absolute block coordinates.
+    pub fn cavern(&self, x: i32, z: i32) -> (usize, usize) {
+        let (x, z) = (x as f64 / Self::SCALE, z as f64 / Self::SCALE);
+        let floor = Self::BASE_FLOOR + self.floor.sample(x, z) * Self::AMPLITUDE;
+        let ceiling = Self::BASE_CEILING + self.ceiling.sample(x, z) * Self::AMPLITUDE;
+
+        let floor = floor.clamp(1.0, (Self::CEILING - 2) as f64) as usize;
+        let ceiling = ceiling.clamp(floor as f64 + 1.0, (Self::CEILING - 1) as f64) as usize;
+        (floor, ceiling)
+    }
+}
+
+impl ChunkGenerator for NetherGenerator {
+    fn generate(&self, pos: ChunkPos) -> ChunkColumn {
+        let mut chunk = ChunkColumn::new(pos);
+
+        for z in 0..CHUNK_WIDTH {
+            for x in 0..CHUNK_WIDTH {
+                let (floor, ceiling) = self.cavern(
+                    pos.x * CHUNK_WIDTH as i32 + x as i32,
+                    pos.z * CHUNK_WIDTH as i32 + z as i32,
+                );
+
+                chunk.set_block(x, 0, z, Block::BEDROCK);
+                chunk.set_block(x, Self::CEILING, z, Block::BEDROCK);
+
+                for y in 1..Self::CEILING {
+                    let block = if y <= floor || y >= ceiling {
+                        Block::NETHERRACK
+                    } else if y <= NETHER_LAVA_LEVEL {
+                        Block::LAVA
+                    } else {
+                        continue;
+                    };
+
+                    chunk.set_block(x, y, z, block);
+                }
+
+                chunk.set_biome(x, z, HELL_BIOME);
+            }
+        }
+
+        chunk
+    }
+}
+
+/// Generates the end stone island floating around the origin over the void.
+#[derive(Debug, Clone)]
+pub struct EndGenerator {
+    surface: OctaveNoise,
+}
+
+impl EndGenerator {
+    /// Distance from the origin the island reaches, in blocks.
+    pub const RADIUS: f64 = 96.0;
+    /// Horizontal scale of the surface, bigger is smoother.
+    const SCALE: f64 = 32.0;
+    /// Surface height at the noise level zero.
+    const BASE_HEIGHT: f64 = 60.0;
+    /// Difference between the highest and the lowest surface and the base height.
+    const AMPLITUDE: f64 = 4.0;
+    /// Thickness of the island at its center, it thins out towards the edges.
+    const DEPTH: f64 = 40.0;
+
+    pub fn new(seed: i64) -> Self {
+        Self {
+            surface: OctaveNoise::new(seed as u64 ^ 0x0045_4E44, 3, 0.5),
+        }
+    }
+
+    /// Y of the bottom and the top of the island at the absolute block coordinates,
+    /// `None` over the void.
+    pub fn island(&self, x: i32, z: i32) -> Option<(usize, usize)> {
+        let distance = (x as f64).hypot(z as f64) / Self::RADIUS;
+        if distance >= 1.0 {
+            return None;
+        }
+
+        let noise = self
+            .surface
+            .sample(x as f64 / Self::SCALE, z as f64 / Self::SCALE);
+        let top = Self::BASE_HEIGHT + noise * Self::AMPLITUDE;
+        let bottom = top - (1.0 - distance * distance) * Self::DEPTH;
+
+        let top = top as usize;
+        (bottom < top as f64).then(|| (bottom.max(0.0) as usize, top))
+    }
+}
+
+impl ChunkGenerator for EndGenerator {
+    fn generate(&self, pos: ChunkPos) -> ChunkColumn {
+        let mut chunk = ChunkColumn::new(pos);
+
+        for z in 0..CHUNK_WIDTH {
+            for x in 0..CHUNK_WIDTH {
+                let island = self.island(
+                    pos.x * CHUNK_WIDTH as i32 + x as i32,
+                    pos.z * CHUNK_WIDTH as i32 + z as i32,
+                );
+
+                if let Some((bottom, top)) = island {
+                    for y in bottom..=top {
+                        chunk.set_block(x, y, z, Block::END_STONE);
+                    }
+                }
+
+                chunk.set_biome(x, z, SKY_BIOME);
+            }
+        }
+
+        chunk
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_flat_chunk() {
+        let chunk = FlatGenerator::default().generate(ChunkPos::new(3, -7));
+
+        assert_eq!(chunk.get_block(5, 0, 5), Block::BEDROCK);
+        assert_eq!(chunk.get_block(5, 3, 5), Block::GRASS);
+        assert_eq!(chunk.highest_block(5, 5), Some(3));
+    }
+
+    #[test]
+    fn generate_default_chunk() {
+        let generator = DefaultGenerator::new(1234);
+        let pos = ChunkPos::new(-2, 5);
+        let chunk = generator.generate(pos);
+
+        for z in 0..CHUNK_WIDTH {
+            for x in 0..CHUNK_WIDTH {
+                let surface = generator.surface_height(-32 + x as i32, 80 + z as i32);
+
+                assert_eq!(chunk.get_block(x, 0, z), Block::BEDROCK);
+                assert_eq!(chunk.highest_block(x, z), Some(surface.max(SEA_LEVEL)));
+
+                let expected = if surface > SEA_LEVEL {
+                    Block::GRASS
+                } else {
+                    Block::SAND
+                };
+                assert_eq!(chunk.get_block(x, surface, z), expected);
+            }
+        }
+
+        let same = generator.generate(pos);
+        assert_eq!(same.to_chunk_data(), chunk.to_chunk_data());
+    }
+
+    #[test]
+    fn generate_nether_and_end_chunks() {
+        let nether = NetherGenerator::new(1234);
+        let chunk = nether.generate(ChunkPos::new(1, -1));
+        let (floor, ceiling) = nether.cavern(16, -16);
+
+        assert_eq!(chunk.get_block(0, 0, 0), Block::BEDROCK);
+        assert_eq!(
+            chunk.get_block(0, NetherGenerator::CEILING, 0),
+            Block::BEDROCK
+        );
+        for y in floor + 1..ceiling {
+            let expected = if y <= NETHER_LAVA_LEVEL {
+                Block::LAVA
+            } else {
+                Block::AIR
+            };
+            assert_eq!(chunk.get_block(0, y, 0), expected);
+        }
+        assert_eq!(chunk.get_block(0, floor, 0), Block::NETHERRACK);
+        assert_eq!(chunk.get_block(0, ceiling, 0), Block::NETHERRACK);
+
+        let end = EndGenerator::new(1234);
+        let (_, top) = end.island(0, 0).unwrap();
+        assert_eq!(
+            end.generate(ChunkPos::new(0, 0)).highest_block(0, 0),
+            Some(top)
+        );
+        assert_eq!(end.island(200, 0), None);
+        assert_eq!(end.generate(ChunkPos::new(20, 0)).highest_block(0, 0), None);
+    }
+}
@@ -0,0 +1,338 @@
+use std::{collections::HashMap, sync::Arc};
+
+use protocol::packet::{Packet, SoundParticleEffectPayload};
+
+use crate::world::{
+    self,
+    block::Block,
+    redstone::{BUTTON_DELAY, SWITCHED_ON},
+    BlockChanges, BlockPos, World,
+};
+
+/// Bit of the door, trapdoor and fence gate metadata set when they're open.
+pub const OPEN: u8 = 0x4;
+
+/// Bit of the door metadata set on its upper half.
+pub const DOOR_TOP: u8 = 0x8;
+
+/// Effect of the door, the trapdoor or the fence gate opening or closing, its sound.
+const DOOR_SOUND_EFFECT: i32 = 1003;
+
+/// Reacts to the players right-clicking the blocks.
+pub trait BlockHandler: Send + Sync {
+    /// Handles the click on the block, recording the blocks it changes and the effects played.
+    ///
+    /// Returns `false` to let the player place the held block against it instead.
+    fn interact(
+        &self,
+        world: &mut World,
+        pos: BlockPos,
+        block: Block,
+        changes: &mut BlockChanges,
+    ) -> bool;
+}
+
+/// Handlers of the interactive blocks, keyed by the block ID.
+#[derive(Default)]
+pub struct BlockHandlers {
+    handlers: HashMap<u16, Arc<dyn BlockHandler>>,
+}
+
+impl BlockHandlers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates the registry with the handlers of the vanilla blocks.
+    pub fn with_builtins() -> Self {
+        let mut handlers = Self::new();
+        handlers.register(Block::WOODEN_DOOR.id, DoorHandler);
+        handlers.register(Block::IRON_DOOR.id, DoorHandler);
+        handlers.register(Block::TRAPDOOR.id, ToggleHandler);
+        handlers.register(Block::FENCE_GATE.id, ToggleHandler);
+        handlers.register(Block::LEVER.id, LeverHandler);
+        handlers.register(Block::STONE_BUTTON.id, ButtonHandler);
+        handlers
+    }
+
+    /// Registers the handler of the block, replacing the previous one.
+    pub fn register(&mut self, id: u16, handler: impl BlockHandler + 'static) {
+        self.handlers.insert(id, Arc::new(handler));
+    }
+
+    pub fn get(&self, id: u16) -> Option<Arc<dyn BlockHandler>> {
+        self.handlers.get(&id).cloned()
+    }
+}
+
+/// Builds the packet playing the sound of the door opening or closing at the position.
+pub fn door_sound(pos: BlockPos) -> Packet {
+    Packet::SoundParticleEffect(SoundParticleEffectPayload {
+        effect_id: DOOR_SOUND_EFFECT,
+        x: pos.x,
+        y: pos.y as u8,
+        z: pos.z,
+        data: 0,
+    })
+}
+
+/// Side of the block the panel of the door is on, the open doors turn
+/// a quarter from the side they're closed on.
+fn door_panel(lower: Block) -> u8 {
+    if lower.metadata & OPEN != 0 {
+        lower.metadata & 0x3
+    } else {
+        (lower.metadata + 3) & 0x3
+    }
+}
+
+/// Opens and closes both halves of a door along with the door paired with it,
+/// the lower halves hold the state.
+///
+/// The doors are paired when they stand side by side with their panels in line,
+/// the second one placed mirrored, so they swing apart.
+struct DoorHandler;
+
+impl BlockHandler for DoorHandler {
+    fn interact(
+        &self,
+        world: &mut World,
+        pos: BlockPos,
+        block: Block,
+        changes: &mut BlockChanges,
+    ) -> bool {
+        // The iron doors open only by the redstone.
+        if block.id == Block::IRON_DOOR.id {
+            return true;
+        }
+
+        let lower_pos = if block.metadata & DOOR_TOP != 0 {
+            pos.offset(0, -1, 0)
+        } else {
+            pos
+        };
+        let Some(lower) = world
+            .get_block(lower_pos.x, lower_pos.y, lower_pos.z)
+            .filter(|lower| lower.id == block.id && lower.metadata & DOOR_TOP == 0)
+        else {
+            return true;
+        };
+
+        let panel = door_panel(lower);
+        let (dx, dz) = if panel.is_multiple_of(2) {
+            (1, 0)
+        } else {
+            (0, 1)
+        };
+        let paired = [lower_pos.offset(-dx, 0, -dz), lower_pos.offset(dx, 0, dz)]
+            .into_iter()
+            .find(|next| {
+                world
+                    .get_block(next.x, next.y, next.z)
+                    .is_some_and(|other| {
+                        other.id == lower.id
+                            && other.metadata & DOOR_TOP == 0
+                            && door_panel(other) == panel
+                    })
+            });
+
+        for lower_pos in std::iter::once(lower_pos).chain(paired) {
+            let Some(lower) = world.get_block(lower_pos.x, lower_pos.y, lower_pos.z) else {
+                continue;
+            };
+            let toggled = Block::with_metadata(lower.id, lower.metadata ^ OPEN);
+            world.set_block(lower_pos.x, lower_pos.y, lower_pos.z, toggled);
+            changes
+                .changed
+                .extend([lower_pos, lower_pos.offset(0, 1, 0)]);
+        }
+        changes.effects.push((lower_pos, door_sound(lower_pos)));
+
+        true
+    }
+}
+
+/// Opens and closes the single block, e.g. a trapdoor.
+struct ToggleHandler;
+
+impl BlockHandler for ToggleHandler {
+    fn interact(
+        &self,
+        world: &mut World,
+        pos: BlockPos,
+        block: Block,
+        changes: &mut BlockChanges,
+    ) -> bool {
+        let toggled = Block::with_metadata(block.id, block.metadata ^ OPEN);
+        world.set_block(pos.x, pos.y, pos.z, toggled);
+        changes.changed.push(pos);
+        changes.effects.push((pos, door_sound(pos)));
+
+        true
+    }
+}
+
+/// Switches the lever on and off, powering the redstone around it.
+struct LeverHandler;
+
+impl BlockHandler for LeverHandler {
+    fn interact(
+        &self,
+        world: &mut World,
+        pos: BlockPos,
+        block: Block,
+        changes: &mut BlockChanges,
+    ) -> bool {
+        let switched = Block::with_metadata(block.id, block.metadata ^ SWITCHED_ON);
+        world.set_block(pos.x, pos.y, pos.z, switched);
+        changes.changed.push(pos);
+        world::block_changed(world, pos, changes);
+
+        true
+    }
+}
+
+/// Presses the button, it powers the redstone around it until it's released
+/// a second later.
+struct ButtonHandler;
+
+impl BlockHandler for ButtonHandler {
+    fn interact(
+        &self,
+        world: &mut World,
+        pos: BlockPos,
+        block: Block,
+        changes: &mut BlockChanges,
+    ) -> bool {
+        if block.metadata & SWITCHED_ON != 0 {
+            return true;
+        }
+
+        let pressed = Block::with_metadata(block.id, block.metadata | SWITCHED_ON);
+        world.set_block(pos.x, pos.y, pos.z, pressed);
+        world.schedule_tick(pos, block.id, BUTTON_DELAY);
+        changes.changed.push(pos);
+        world::block_changed(world, pos, changes);
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::world::{
+        chunk::{ChunkColumn, ChunkPos},
+        redstone::Occupants,
+    };
+
+    use super::*;
+
+    #[test]
+    fn open_door_from_upper_half() {
+        let mut world = World::new();
+        world.insert_chunk(ChunkColumn::new(ChunkPos::new(0, 0)));
+        world.set_block(1, 64, 1, Block::with_metadata(64, 0x1));
+        world.set_block(1, 65, 1, Block::with_metadata(64, DOOR_TOP));
+
+        let handlers = BlockHandlers::with_builtins();
+        let door = handlers.get(Block::WOODEN_DOOR.id).unwrap();
+        let upper = world.get_block(1, 65, 1).unwrap();
+
+        let mut changes = BlockChanges::default();
+        assert!(door.interact(&mut world, BlockPos::new(1, 65, 1), upper, &mut changes));
+
+        assert_eq!(
+            changes.changed,
+            vec![BlockPos::new(1, 64, 1), BlockPos::new(1, 65, 1)]
+        );
+        assert_eq!(
+            changes.effects,
+            vec![(BlockPos::new(1, 64, 1), door_sound(BlockPos::new(1, 64, 1)))]
+        );
+        assert_eq!(
+            world.get_block(1, 64, 1),
+            Some(Block::with_metadata(64, 0x1 | OPEN))
+        );
+        assert_eq!(
+            world.get_block(1, 65, 1),
+            Some(Block::with_metadata(64, DOOR_TOP))
+        );
+    }
+
+    #[test]
+    fn paired_doors_swing_apart() {
+        let mut world = World::new();
+        world.insert_chunk(ChunkColumn::new(ChunkPos::new(0, 0)));
+        // The second door is placed mirrored, turned and open.
+        world.set_block(4, 64, 4, Block::with_metadata(64, 0));
+        world.set_block(4, 65, 4, Block::with_metadata(64, DOOR_TOP));
+        world.set_block(4, 64, 5, Block::with_metadata(64, 3 | OPEN));
+        world.set_block(4, 65, 5, Block::with_metadata(64, DOOR_TOP));
+        // Doors at a right angle aren't paired.
+        world.set_block(5, 64, 4, Block::with_metadata(64, 1));
+
+        let handlers = BlockHandlers::with_builtins();
+        let door = handlers.get(Block::WOODEN_DOOR.id).unwrap();
+        let lower = world.get_block(4, 64, 4).unwrap();
+
+        let mut changes = BlockChanges::default();
+        door.interact(&mut world, BlockPos::new(4, 64, 4), lower, &mut changes);
+
+        assert_eq!(changes.changed.len(), 4);
+        assert_eq!(
+            world.get_block(4, 64, 4),
+            Some(Block::with_metadata(64, OPEN))
+        );
+        assert_eq!(world.get_block(4, 64, 5), Some(Block::with_metadata(64, 3)));
+        assert_eq!(world.get_block(5, 64, 4), Some(Block::with_metadata(64, 1)));
+
+        // The iron doors ignore the clicks.
+        world.set_block(8, 64, 8, Block::IRON_DOOR);
+        let iron = handlers.get(Block::IRON_DOOR.id).unwrap();
+        let mut changes = BlockChanges::default();
+        assert!(iron.interact(
+            &mut world,
+            BlockPos::new(8, 64, 8),
+            Block::IRON_DOOR,
+            &mut changes
+        ));
+        assert!(changes.changed.is_empty());
+    }
+
+    #[test]
+    fn button_powers_the_door_until_released() {
+        let mut world = World::new();
+        world.insert_chunk(ChunkColumn::new(ChunkPos::new(0, 0)));
+        world.set_block(2, 63, 2, Block::STONE);
+        world.set_block(2, 64, 2, Block::STONE);
+        world.set_block(3, 64, 2, Block::with_metadata(77, 1));
+        world.set_block(2, 64, 3, Block::with_metadata(71, 0));
+        world.set_block(2, 65, 3, Block::with_metadata(71, DOOR_TOP));
+
+        let handlers = BlockHandlers::with_builtins();
+        let button = handlers.get(Block::STONE_BUTTON.id).unwrap();
+        let pos = BlockPos::new(3, 64, 2);
+
+        let mut changes = BlockChanges::default();
+        assert!(button.interact(&mut world, pos, Block::with_metadata(77, 1), &mut changes));
+        assert_eq!(
+            world.get_block(3, 64, 2),
+            Some(Block::with_metadata(77, 1 | SWITCHED_ON))
+        );
+        assert_eq!(
+            world.get_block(2, 64, 3),
+            Some(Block::with_metadata(71, OPEN))
+        );
+        assert_eq!(changes.effects.len(), 1);
+        assert!(world
+            .scheduled_ticks()
+            .is_scheduled(pos, Block::STONE_BUTTON.id));
+
+        let occupants = Occupants::new();
+        for _ in 0..BUTTON_DELAY {
+            world.tick_blocks(&occupants);
+        }
+        assert_eq!(world.get_block(3, 64, 2), Some(Block::with_metadata(77, 1)));
+        assert_eq!(world.get_block(2, 64, 3), Some(Block::with_metadata(71, 0)));
+    }
+}
@@ -0,0 +1,558 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::RwLock,
+};
+
+use protocol::{
+    chunk::{CHUNK_HEIGHT, CHUNK_WIDTH, SECTION_HEIGHT},
+    packet::{
+        BlockChangePayload, BlockChangeRecord, MultiBlockChangePayload, Packet,
+        MAX_BLOCK_CHANGE_RECORDS,
+    },
+    slot::ItemStack,
+};
+
+use crate::world::{
+    block::Block,
+    chunk::{ChunkColumn, ChunkPos},
+    dimension::Dimension,
+    fluid::Fluid,
+    note_block::NoteBlock,
+    redstone::Occupants,
+    scheduled::ScheduledTicks,
+    sign::Sign,
+    tile_entity::TileEntity,
+};
+
+/// Provides the storage of the columns in the Anvil format.
+pub mod anvil;
+/// Provides the blocks the world is made of.
+pub mod block;
+/// Provides the chunk columns and sections storing the blocks.
+pub mod chunk;
+/// Provides the time it takes to break the blocks and what they drop.
+pub mod digging;
+/// Provides the dimensions, the overworld, the nether and the end.
+pub mod dimension;
+/// Provides the water and the lava flowing and mixing.
+pub mod fluid;
+/// Provides the terrain generators of the chunk columns.
+pub mod generator;
+/// Provides the reactions of the blocks to the players clicking them.
+pub mod handler;
+/// Provides the noise the terrain is generated from.
+pub mod noise;
+/// Provides the note blocks and the instruments they play.
+pub mod note_block;
+/// Provides the nether portals, lighting them and finding or building their other ends.
+pub mod portal;
+/// Provides the redstone power, the dust carrying it and the blocks switching it.
+pub mod redstone;
+/// Provides the region files the columns are saved in.
+pub mod region;
+/// Provides the block ticks scheduled to happen later.
+pub mod scheduled;
+/// Provides the signs and the text written on them.
+pub mod sign;
+/// Provides the data of the blocks kept beside them, e.g. the text of the signs
+/// or the items in the chests.
+pub mod tile_entity;
+
+/// Absolute position of a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BlockPos {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl BlockPos {
+    pub fn new(x: i32, y: i32, z: i32) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn offset(&self, dx: i32, dy: i32, dz: i32) -> Self {
+        Self::new(self.x + dx, self.y + dy, self.z + dz)
+    }
+
+    /// Position of the neighbour on the face, `0` for -Y, `1` for +Y, `2` for -Z,
+    /// `3` for +Z, `4` for -X, `5` for +X, `None` for any other face.
+    pub fn neighbour(&self, face: i8) -> Option<Self> {
+        let (dx, dy, dz) = match face {
+            0 => (0, -1, 0),
+            1 => (0, 1, 0),
+            2 => (0, 0, -1),
+            3 => (0, 0, 1),
+            4 => (-1, 0, 0),
+            5 => (1, 0, 0),
+            _ => return None,
+        };
+
+        Some(self.offset(dx, dy, dz))
+    }
+
+    /// Column the block is in.
+    pub fn chunk(&self) -> ChunkPos {
+        ChunkPos::of_block(self.x, self.z)
+    }
+
+    /// Squared distance from the point to the center of the block.
+    pub fn distance_squared(&self, x: f64, y: f64, z: f64) -> f64 {
+        (self.x as f64 + 0.5 - x).powi(2)
+            + (self.y as f64 + 0.5 - y).powi(2)
+            + (self.z as f64 + 0.5 - z).powi(2)
+    }
+}
+
+/// The loaded chunk columns of a world.
+///
+/// Blocks are addressed with the absolute coordinates, reading the blocks
+/// of the columns which aren't loaded yields `None`.
+#[derive(Debug, Default)]
+pub struct World {
+    dimension: Dimension,
+    chunks: HashMap<ChunkPos, ChunkColumn>,
+
+    /// Time of the world in ticks, the clients take the time of the day
+    /// from it modulo `DAY_LENGTH`.
+    time: i64,
+
+    /// Block ticks waiting to happen, e.g. the fluids flowing on.
+    scheduled: ScheduledTicks,
+}
+
+/// Blocks changed by the world reacting to a change, e.g. the fluids flowing,
+/// the items of the blocks broken on the way and the effects played.
+#[derive(Debug, Default)]
+pub struct BlockChanges {
+    /// Positions of the changed blocks, to be sent to the players.
+    pub changed: Vec<BlockPos>,
+
+    /// Items dropped by the broken blocks, with the positions of the blocks.
+    pub drops: Vec<(BlockPos, ItemStack)>,
+
+    /// Packets of the effects played at the blocks, e.g. the notes of the note blocks.
+    pub effects: Vec<(BlockPos, Packet)>,
+}
+
+/// Number of ticks in a full day, 20 minutes.
+pub const DAY_LENGTH: i64 = 24000;
+
+/// Time of the day the night starts at, when the players may go to sleep.
+pub const NIGHT_START: i64 = 12541;
+
+/// Time of the day the night ends at, shortly before the sunrise.
+pub const NIGHT_END: i64 = 23458;
+
+/// Light level of the sky at the day.
+pub const DAY_SKY_LIGHT: u8 = 15;
+
+/// Light level of the sky at the night.
+pub const NIGHT_SKY_LIGHT: u8 = 4;
+
+impl World {
+    /// Creates the empty overworld.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates the empty world of the dimension.
+    pub fn for_dimension(dimension: Dimension) -> Self {
+        Self {
+            dimension,
+            ..Default::default()
+        }
+    }
+
+    pub fn dimension(&self) -> Dimension {
+        self.dimension
+    }
+
+    pub fn time(&self) -> i64 {
+        self.time
+    }
+
+    pub fn set_time(&mut self, time: i64) {
+        self.time = time;
+    }
+
+    /// Time of the day, from `0` at the sunrise to `DAY_LENGTH`.
+    pub fn time_of_day(&self) -> i64 {
+        self.time.rem_euclid(DAY_LENGTH)
+    }
+
+    pub fn is_night(&self) -> bool {
+        (NIGHT_START..NIGHT_END).contains(&self.time_of_day())
+    }
+
+    /// Light level of the sky at the current time, `0` in the dimensions without the sky.
+    pub fn sky_light(&self) -> u8 {
+        if !self.dimension.has_sky() {
+            0
+        } else if self.is_night() {
+            NIGHT_SKY_LIGHT
+        } else {
+            DAY_SKY_LIGHT
+        }
+    }
+
+    /// Whether nothing is above the block, `false` if its column isn't loaded
+    /// or the dimension has no sky.
+    pub fn sees_sky(&self, x: i32, y: i32, z: i32) -> bool {
+        if !self.dimension.has_sky() {
+            return false;
+        }
+
+        self.chunk(ChunkPos::of_block(x, z)).is_some_and(|chunk| {
+            chunk
+                .highest_block(local(x), local(z))
+                .is_none_or(|highest| y > highest as i32)
+        })
+    }
+
+    /// Light level at the block, the brighter of the sky and the block light,
+    /// `None` if its column isn't loaded or `y` is out of the world.
+    ///
+    /// The sky light isn't propagated, it lights only the blocks seeing the sky.
+    pub fn light(&self, x: i32, y: i32, z: i32) -> Option<u8> {
+        let column_y = column_y(y)?;
+        let chunk = self.chunk(ChunkPos::of_block(x, z))?;
+
+        let sky = if self.sees_sky(x, y, z) {
+            self.sky_light()
+        } else {
+            0
+        };
+        let block = chunk.section(column_y).map_or(0, |section| {
+            section.block_light(local(x), column_y % SECTION_HEIGHT, local(z))
+        });
+
+        Some(sky.max(block))
+    }
+
+    pub fn chunk(&self, pos: ChunkPos) -> Option<&ChunkColumn> {
+        self.chunks.get(&pos)
+    }
+
+    pub fn chunk_mut(&mut self, pos: ChunkPos) -> Option<&mut ChunkColumn> {
+        self.chunks.get_mut(&pos)
+    }
+
+    /// Stores the column, replacing the one at the same position.
+    pub fn insert_chunk(&mut self, chunk: ChunkColumn) -> Option<ChunkColumn> {
+        self.chunks.insert(chunk.pos, chunk)
+    }
+
+    pub fn remove_chunk(&mut self, pos: ChunkPos) -> Option<ChunkColumn> {
+        self.chunks.remove(&pos)
+    }
+
+    pub fn is_loaded(&self, pos: ChunkPos) -> bool {
+        self.chunks.contains_key(&pos)
+    }
+
+    /// Positions of the loaded columns.
+    pub fn loaded_chunks(&self) -> impl Iterator<Item = ChunkPos> + '_ {
+        self.chunks.keys().copied()
+    }
+
+    /// Returns the block, `None` if its column isn't loaded or `y` is out of the world.
+    pub fn get_block(&self, x: i32, y: i32, z: i32) -> Option<Block> {
+        let y = column_y(y)?;
+        let chunk = self.chunk(ChunkPos::of_block(x, z))?;
+
+        Some(chunk.get_block(local(x), y, local(z)))
+    }
+
+    /// Sets the block, returns the replaced one,
+    /// `None` if its column isn't loaded or `y` is out of the world.
+    pub fn set_block(&mut self, x: i32, y: i32, z: i32, block: Block) -> Option<Block> {
+        let y = column_y(y)?;
+        let chunk = self.chunk_mut(ChunkPos::of_block(x, z))?;
+
+        Some(chunk.set_block(local(x), y, local(z), block))
+    }
+
+    /// Returns the tile entity at the block, `None` if there is none or its column isn't loaded.
+    pub fn tile_entity(&self, x: i32, y: i32, z: i32) -> Option<&TileEntity> {
+        let y = column_y(y)?;
+        self.chunk(ChunkPos::of_block(x, z))?
+            .tile_entity(local(x), y, local(z))
+    }
+
+    /// Returns the tile entity at the block for changing, marking its column as changed,
+    /// creates the empty one if the block has none yet.
+    pub fn tile_entity_mut(&mut self, x: i32, y: i32, z: i32) -> Option<&mut TileEntity> {
+        let y = column_y(y)?;
+        self.chunk_mut(ChunkPos::of_block(x, z))?
+            .tile_entity_mut(local(x), y, local(z))
+    }
+
+    /// Stores the tile entity at the block, returns `false` if it doesn't belong
+    /// to the block or its column isn't loaded.
+    pub fn set_tile_entity(&mut self, x: i32, y: i32, z: i32, tile_entity: TileEntity) -> bool {
+        let Some(y) = column_y(y) else {
+            return false;
+        };
+
+        self.chunk_mut(ChunkPos::of_block(x, z))
+            .is_some_and(|chunk| chunk.set_tile_entity(local(x), y, local(z), tile_entity))
+    }
+
+    /// Returns the sign at the block, `None` if there is none or its column isn't loaded.
+    pub fn sign(&self, x: i32, y: i32, z: i32) -> Option<&Sign> {
+        match self.tile_entity(x, y, z)? {
+            TileEntity::Sign(sign) => Some(sign),
+            _ => None,
+        }
+    }
+
+    /// Returns the sign at the block for changing, marking its column as changed.
+    pub fn sign_mut(&mut self, x: i32, y: i32, z: i32) -> Option<&mut Sign> {
+        match self.tile_entity_mut(x, y, z)? {
+            TileEntity::Sign(sign) => Some(sign),
+            _ => None,
+        }
+    }
+
+    /// Returns the note block for changing, `None` if there is none or its column isn't loaded.
+    pub fn note_block_mut(&mut self, x: i32, y: i32, z: i32) -> Option<&mut NoteBlock> {
+        match self.tile_entity_mut(x, y, z)? {
+            TileEntity::NoteBlock(note_block) => Some(note_block),
+            _ => None,
+        }
+    }
+
+    /// Schedules the tick of the block in the number of ticks,
+    /// unless the block has one scheduled already.
+    pub fn schedule_tick(&mut self, pos: BlockPos, block_id: u16, delay: u64) {
+        self.scheduled.schedule(pos, block_id, delay);
+    }
+
+    pub fn scheduled_ticks(&self) -> &ScheduledTicks {
+        &self.scheduled
+    }
+
+    /// Presses the pressure plates under the entities, runs the block ticks due,
+    /// skipping the blocks which have changed since, and the ticks of the tile entities,
+    /// returns what they've changed.
+    pub fn tick_blocks(&mut self, occupants: &Occupants) -> BlockChanges {
+        let mut changes = BlockChanges::default();
+        redstone::press_plates(self, occupants, &mut changes);
+
+        for tick in self.scheduled.advance() {
+            let pos = tick.pos;
+            let Some(block) = self
+                .get_block(pos.x, pos.y, pos.z)
+                .filter(|block| block.id == tick.block_id)
+            else {
+                continue;
+            };
+
+            if Fluid::of(block).is_some() {
+                fluid::tick(self, pos, &mut changes);
+            } else {
+                redstone::tick(self, pos, occupants, &mut changes);
+            }
+        }
+
+        for chunk in self.chunks.values_mut() {
+            changes.changed.extend(chunk.tick_tile_entities());
+        }
+
+        changes
+    }
+}
+
+/// Worlds of all the dimensions, each behind a lock of its own.
+#[derive(Debug)]
+pub struct Worlds {
+    worlds: [RwLock<World>; Dimension::ALL.len()],
+}
+
+impl Default for Worlds {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Worlds {
+    /// Creates the empty worlds.
+    pub fn new() -> Self {
+        Self {
+            worlds: Dimension::ALL.map(|dimension| RwLock::new(World::for_dimension(dimension))),
+        }
+    }
+
+    pub fn get(&self, dimension: Dimension) -> &RwLock<World> {
+        &self.worlds[dimension.index()]
+    }
+
+    /// Worlds with their dimensions.
+    pub fn iter(&self) -> impl Iterator<Item = (Dimension, &RwLock<World>)> {
+        Dimension::ALL.into_iter().zip(&self.worlds)
+    }
+}
+
+/// Lets the blocks at the position and around it react to the block there changing,
+/// the fluids flow and the redstone around switches.
+pub fn block_changed(world: &mut World, pos: BlockPos, changes: &mut BlockChanges) {
+    fluid::block_changed(world, pos, changes);
+    redstone::block_changed(world, pos, changes);
+}
+
+/// Builds the packet telling the clients the block at the position.
+pub fn block_change(pos: BlockPos, block: Block) -> Packet {
+    Packet::BlockChange(BlockChangePayload {
+        x: pos.x,
+        y: pos.y as u8,
+        z: pos.z,
+        block_id: block.id as u8,
+        metadata: block.metadata,
+    })
+}
+
+/// Builds the packets telling the clients the blocks at the positions, Multi Block Changes
+/// for each column with more than one of them, returned with the positions of the columns.
+pub fn block_changes(world: &World, positions: &[BlockPos]) -> Vec<(ChunkPos, Packet)> {
+    let mut columns: BTreeMap<ChunkPos, BTreeMap<(i32, i32, i32), Block>> = BTreeMap::new();
+    for &pos in positions {
+        if let Some(block) = world.get_block(pos.x, pos.y, pos.z) {
+            columns
+                .entry(pos.chunk())
+                .or_default()
+                .insert((pos.y, pos.z, pos.x), block);
+        }
+    }
+
+    let mut packets = Vec::new();
+    for (chunk, blocks) in columns {
+        if blocks.len() == 1 {
+            let ((y, z, x), block) = blocks.into_iter().next().unwrap();
+            packets.push((chunk, block_change(BlockPos::new(x, y, z), block)));
+            continue;
+        }
+
+        let records: Vec<_> = blocks
+            .into_iter()
+            .map(|((y, z, x), block)| BlockChangeRecord {
+                x: local(x) as u8,
+                y: y as u8,
+                z: local(z) as u8,
+                block_id: block.id,
+                metadata: block.metadata,
+            })
+            .collect();
+        for records in records.chunks(MAX_BLOCK_CHANGE_RECORDS) {
+            let packet = Packet::MultiBlockChange(MultiBlockChangePayload {
+                chunk_x: chunk.x,
+                chunk_z: chunk.z,
+                records: records.to_vec(),
+            });
+            packets.push((chunk, packet));
+        }
+    }
+
+    packets
+}
+
+/// Coordinate within the column.
+fn local(coordinate: i32) -> usize {
+    coordinate.rem_euclid(CHUNK_WIDTH as i32) as usize
+}
+
+fn column_y(y: i32) -> Option<usize> {
+    usize::try_from(y).ok().filter(|&y| y < CHUNK_HEIGHT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_and_get_blocks() {
+        let mut world = World::new();
+        world.insert_chunk(ChunkColumn::new(ChunkPos::new(-1, 0)));
+
+        assert_eq!(world.get_block(-1, 64, 15), Some(Block::AIR));
+        assert_eq!(world.set_block(-1, 64, 15, Block::STONE), Some(Block::AIR));
+        assert_eq!(world.get_block(-1, 64, 15), Some(Block::STONE));
+
+        let chunk = world.chunk(ChunkPos::new(-1, 0)).unwrap();
+        assert_eq!(chunk.get_block(15, 64, 15), Block::STONE);
+        assert_eq!(chunk.highest_block(15, 15), Some(64));
+
+        // Unloaded column and out of the world.
+        assert_eq!(world.get_block(0, 64, 0), None);
+        assert_eq!(world.set_block(-1, 256, 15, Block::STONE), None);
+        assert_eq!(world.get_block(-1, -1, 15), None);
+    }
+
+    #[test]
+    fn empty_sections_are_dropped() {
+        let mut chunk = ChunkColumn::new(ChunkPos::new(0, 0));
+
+        chunk.set_block(1, 20, 1, Block::DIRT);
+        assert!(chunk.section(20).is_some());
+        assert_eq!(chunk.to_chunk_data().primary_bitmap, 0b10);
+
+        chunk.set_block(1, 20, 1, Block::AIR);
+        assert!(chunk.section(20).is_none());
+        assert_eq!(chunk.to_chunk_data().primary_bitmap, 0);
+    }
+
+    #[test]
+    fn light_from_sky_and_blocks() {
+        let mut world = World::new();
+        world.insert_chunk(ChunkColumn::new(ChunkPos::new(0, 0)));
+        world.set_block(1, 70, 1, Block::STONE);
+        world.set_block(1, 60, 1, Block::STONE);
+
+        assert_eq!(world.light(1, 71, 1), Some(DAY_SKY_LIGHT));
+        assert_eq!(world.light(1, 65, 1), Some(0));
+        assert!(!world.sees_sky(1, 65, 1));
+
+        let chunk = world.chunk_mut(ChunkPos::new(0, 0)).unwrap();
+        chunk
+            .section_mut(60)
+            .unwrap()
+            .set_block_light(1, 61 % 16, 1, 14);
+        assert_eq!(world.light(1, 61, 1), Some(14));
+
+        world.set_time(NIGHT_START);
+        assert_eq!(world.light(1, 71, 1), Some(NIGHT_SKY_LIGHT));
+        assert_eq!(world.light(5, 64, 5), Some(NIGHT_SKY_LIGHT));
+        assert_eq!(world.light(16, 64, 0), None);
+
+        let mut nether = World::for_dimension(Dimension::Nether);
+        nether.insert_chunk(ChunkColumn::new(ChunkPos::new(0, 0)));
+        assert!(!nether.sees_sky(5, 64, 5));
+        assert_eq!(nether.light(5, 64, 5), Some(0));
+    }
+
+    #[test]
+    fn columns_changed_while_saving_stay_dirty() {
+        let mut chunk = ChunkColumn::new(ChunkPos::new(0, 0));
+        chunk.set_block(1, 64, 1, Block::STONE);
+        let changes = chunk.changes();
+
+        // Changed while the copy is being written.
+        chunk.set_block(2, 64, 2, Block::DIRT);
+        chunk.mark_saved_at(changes);
+        assert!(chunk.is_dirty());
+
+        chunk.mark_saved_at(chunk.changes());
+        assert!(!chunk.is_dirty());
+    }
+
+    #[test]
+    fn block_neighbours() {
+        let pos = BlockPos::new(-1, 64, 15);
+
+        assert_eq!(pos.neighbour(0), Some(BlockPos::new(-1, 63, 15)));
+        assert_eq!(pos.neighbour(3), Some(BlockPos::new(-1, 64, 16)));
+        assert_eq!(pos.neighbour(4), Some(BlockPos::new(-2, 64, 15)));
+        assert_eq!(pos.neighbour(6), None);
+        assert_eq!(pos.chunk(), ChunkPos::new(-1, 0));
+    }
+}
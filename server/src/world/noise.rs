@@ -0,0 +1,158 @@
+/// Number of the gradient lattice points along an axis before the noise repeats.
+const PERIOD: usize = 256;
+
+/// Seeded 2D Perlin noise.
+#[derive(Debug, Clone)]
+pub struct PerlinNoise {
+    /// Shuffled lattice indices, doubled to skip the wrapping.
+    permutation: Box<[u8; PERIOD * 2]>,
+}
+
+impl PerlinNoise {
+    pub fn new(seed: u64) -> Self {
+        let mut random = SplitMix64(seed);
+        let mut shuffled: [u8; PERIOD] = std::array::from_fn(|i| i as u8);
+
+        // Fisher-Yates shuffle.
+        for i in (1..PERIOD).rev() {
+            let j = (random.next() % (i as u64 + 1)) as usize;
+            shuffled.swap(i, j);
+        }
+
+        Self {
+            permutation: Box::new(std::array::from_fn(|i| shuffled[i % PERIOD])),
+        }
+    }
+
+    /// Samples the noise, roughly in range `-1.0..=1.0`.
+    pub fn sample(&self, x: f64, z: f64) -> f64 {
+        let (x0, z0) = (x.floor(), z.floor());
+        let (xf, zf) = (x - x0, z - z0);
+        let xi = (x0 as i64).rem_euclid(PERIOD as i64) as usize;
+        let zi = (z0 as i64).rem_euclid(PERIOD as i64) as usize;
+
+        let p = &self.permutation;
+        let hash = |dx: usize, dz: usize| p[p[xi + dx] as usize + zi + dz];
+
+        let (u, v) = (fade(xf), fade(zf));
+
+        lerp(
+            v,
+            lerp(u, grad(hash(0, 0), xf, zf), grad(hash(1, 0), xf - 1.0, zf)),
+            lerp(
+                u,
+                grad(hash(0, 1), xf, zf - 1.0),
+                grad(hash(1, 1), xf - 1.0, zf - 1.0),
+            ),
+        )
+    }
+}
+
+/// Sum of the Perlin noises of increasing frequency and decreasing amplitude.
+#[derive(Debug, Clone)]
+pub struct OctaveNoise {
+    octaves: Vec<PerlinNoise>,
+
+    /// Amplitude of each octave relative to the previous one.
+    persistence: f64,
+}
+
+impl OctaveNoise {
+    pub fn new(seed: u64, octaves: usize, persistence: f64) -> Self {
+        let mut random = SplitMix64(seed);
+
+        Self {
+            octaves: (0..octaves)
+                .map(|_| PerlinNoise::new(random.next()))
+                .collect(),
+            persistence,
+        }
+    }
+
+    /// Samples the noise, roughly in range `-1.0..=1.0`.
+    pub fn sample(&self, x: f64, z: f64) -> f64 {
+        let mut total = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+        let mut max = 0.0;
+
+        for octave in &self.octaves {
+            total += octave.sample(x * frequency, z * frequency) * amplitude;
+            max += amplitude;
+            amplitude *= self.persistence;
+            frequency *= 2.0;
+        }
+
+        if max > 0.0 {
+            total / max
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Smoothstep easing the interpolation between the lattice points.
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f64, a: f64, b: f64) -> f64 {
+    a + t * (b - a)
+}
+
+/// Dot product of the offset and one of the eight gradients picked by the hash.
+fn grad(hash: u8, x: f64, z: f64) -> f64 {
+    match hash & 7 {
+        0 => x + z,
+        1 => -x + z,
+        2 => x - z,
+        3 => -x - z,
+        4 => x,
+        5 => -x,
+        6 => z,
+        _ => -z,
+    }
+}
+
+/// Small deterministic generator, keeps the worlds the same across the dependency versions.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn noise_is_seeded() {
+        let noise = OctaveNoise::new(42, 4, 0.5);
+        let same = OctaveNoise::new(42, 4, 0.5);
+        let other = OctaveNoise::new(43, 4, 0.5);
+
+        let samples = |noise: &OctaveNoise| -> Vec<f64> {
+            (0..64)
+                .map(|i| noise.sample(i as f64 * 0.37, i as f64 * -0.21))
+                .collect()
+        };
+
+        assert_eq!(samples(&noise), samples(&same));
+        assert_ne!(samples(&noise), samples(&other));
+        assert!(samples(&noise).iter().all(|n| (-1.0..=1.0).contains(n)));
+    }
+
+    #[test]
+    fn noise_is_zero_on_lattice_points() {
+        let noise = PerlinNoise::new(7);
+
+        assert_eq!(noise.sample(3.0, -12.0), 0.0);
+    }
+}
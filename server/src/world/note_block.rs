@@ -0,0 +1,63 @@
+use protocol::packet::{BlockActionPayload, Packet};
+
+use crate::world::{block::Block, BlockPos};
+
+/// Note of a note block and whether it's powered, it plays the note once it gets powered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NoteBlock {
+    /// Pitch of the note, from `0` up two octaves to `24`.
+    pub note: u8,
+
+    /// Whether the block was powered when last checked.
+    pub powered: bool,
+}
+
+impl NoteBlock {
+    /// Builds the packet playing the note on the instrument at the position.
+    pub fn play_packet(&self, pos: BlockPos, instrument: i8) -> Packet {
+        Packet::BlockAction(BlockActionPayload {
+            x: pos.x,
+            y: pos.y as i16,
+            z: pos.z,
+            data_0: instrument,
+            data_1: self.note as i8,
+        })
+    }
+}
+
+/// Instrument of the note block standing on the block, `0` for the harp.
+pub fn instrument(below: Block) -> i8 {
+    match below.id {
+        // Stone, ores, bricks, obsidian, netherrack and the like play the bass drum.
+        1
+        | 4
+        | 7
+        | 14..=16
+        | 21..=24
+        | 43..=45
+        | 48
+        | 49
+        | 52
+        | 56
+        | 61
+        | 62
+        | 67
+        | 70
+        | 73
+        | 74
+        | 87
+        | 98
+        | 108
+        | 109
+        | 112..=114
+        | 116
+        | 121 => 1,
+        // Sand, gravel and soul sand play the snare drum.
+        12 | 13 | 88 => 2,
+        // Glass and glowstone play the clicks.
+        20 | 89 | 102 => 3,
+        // Wood plays the bass.
+        5 | 17 | 25 | 47 | 53 | 54 | 58 | 63 | 64 | 68 | 72 | 84 | 85 | 96 | 107 => 4,
+        _ => 0,
+    }
+}
@@ -0,0 +1,356 @@
+use std::{collections::HashSet, time::Duration};
+
+use crate::world::{block::Block, dimension::Dimension, BlockPos, World};
+
+/// Item lighting the portals and setting the blocks on fire.
+pub const FLINT_AND_STEEL: i16 = 259;
+
+/// Number of the uses the flint and steel survives.
+pub const FLINT_AND_STEEL_DURABILITY: i16 = 64;
+
+/// Time the players in survival have to stand in a portal before they travel, 80 ticks.
+pub const TRAVEL_DELAY: Duration = Duration::from_secs(4);
+
+/// Distance from the destination the existing portals are searched within, horizontally.
+pub const SEARCH_RADIUS: i32 = 32;
+
+/// Width of the inside of a portal frame, along its axis.
+const PORTAL_WIDTH: i32 = 2;
+
+/// Height of the inside of a portal frame.
+const PORTAL_HEIGHT: i32 = 3;
+
+/// Y of the bottom of the inside of the portals built where there's no room for them.
+const FALLBACK_BUILD_Y: i32 = 70;
+
+/// Direction along which a portal frame spans.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Axis {
+    X,
+    Z,
+}
+
+impl Axis {
+    /// Offset of the next block along the axis.
+    fn step(self) -> (i32, i32) {
+        match self {
+            Axis::X => (1, 0),
+            Axis::Z => (0, 1),
+        }
+    }
+}
+
+/// Dimension the portals in the dimension lead to, `None` if they don't work in it.
+pub fn destination(dimension: Dimension) -> Option<Dimension> {
+    match dimension {
+        Dimension::Overworld => Some(Dimension::Nether),
+        Dimension::Nether => Some(Dimension::Overworld),
+        Dimension::End => None,
+    }
+}
+
+/// Horizontal position in the destination corresponding to the one in the source dimension,
+/// e.g. a block in the nether stands for eight blocks in the overworld.
+pub fn scaled(from: Dimension, to: Dimension, x: f64, z: f64) -> (f64, f64) {
+    let scale = from.scale() / to.scale();
+    (x * scale, z * scale)
+}
+
+/// Whether the entity at the position stands in a portal, with its feet or its head.
+pub fn stands_in(world: &World, x: f64, y: f64, z: f64) -> bool {
+    let (x, y, z) = (x.floor() as i32, y.floor() as i32, z.floor() as i32);
+
+    [y, y + 1].into_iter().any(|y| {
+        world
+            .get_block(x, y, z)
+            .is_some_and(|block| block.id == Block::PORTAL.id)
+    })
+}
+
+/// Lights the portal if the position is at the bottom of the inside of a complete
+/// obsidian frame, returns the positions of the portal blocks.
+///
+/// The frame is 4 blocks wide and 5 high, the corners may be missing,
+/// and its inside has to be empty.
+pub fn light(world: &mut World, pos: BlockPos) -> Option<Vec<BlockPos>> {
+    destination(world.dimension())?;
+
+    let obsidian = |dx, dz| {
+        world
+            .get_block(pos.x + dx, pos.y, pos.z + dz)
+            .is_some_and(|block| block.id == Block::OBSIDIAN.id)
+    };
+    let axis = match (
+        obsidian(-1, 0) || obsidian(1, 0),
+        obsidian(0, -1) || obsidian(0, 1),
+    ) {
+        (true, false) => Axis::X,
+        (false, true) => Axis::Z,
+        _ => return None,
+    };
+
+    // The position may be either of the two bottom blocks of the inside.
+    let (dx, dz) = axis.step();
+    let previous = pos.offset(-dx, 0, -dz);
+    let origin = if world
+        .get_block(previous.x, previous.y, previous.z)
+        .is_some_and(|block| block.is_air())
+    {
+        previous
+    } else {
+        pos
+    };
+
+    for (offset, height) in frame(true) {
+        let pos = origin.offset(dx * offset, height, dz * offset);
+        let block = world.get_block(pos.x, pos.y, pos.z)?;
+
+        let border =
+            offset == -1 || offset == PORTAL_WIDTH || height == -1 || height == PORTAL_HEIGHT;
+        let fits = if border {
+            block.id == Block::OBSIDIAN.id
+        } else {
+            block.is_air() || block.id == Block::FIRE.id
+        };
+        if !fits {
+            return None;
+        }
+    }
+
+    let inside: Vec<BlockPos> = frame(false)
+        .filter(|&(offset, height)| {
+            (0..PORTAL_WIDTH).contains(&offset) && (0..PORTAL_HEIGHT).contains(&height)
+        })
+        .map(|(offset, height)| origin.offset(dx * offset, height, dz * offset))
+        .collect();
+
+    for pos in &inside {
+        world.set_block(pos.x, pos.y, pos.z, Block::PORTAL);
+    }
+
+    Some(inside)
+}
+
+/// Offsets along the axis and heights of the blocks of a frame and its inside,
+/// from the bottom of the inside, with the corners unless `skip_corners` is set.
+fn frame(skip_corners: bool) -> impl Iterator<Item = (i32, i32)> {
+    (-1..=PORTAL_WIDTH)
+        .flat_map(|offset| (-1..=PORTAL_HEIGHT).map(move |height| (offset, height)))
+        .filter(move |&(offset, height)| {
+            let corner = (offset == -1 || offset == PORTAL_WIDTH)
+                && (height == -1 || height == PORTAL_HEIGHT);
+            !(skip_corners && corner)
+        })
+}
+
+/// Removes the portal blocks next to the position and the ones connected to them,
+/// as their frame gets broken, returns the positions of the removed blocks.
+pub fn collapse(world: &mut World, pos: BlockPos) -> Vec<BlockPos> {
+    let is_portal = |world: &World, pos: BlockPos| {
+        world
+            .get_block(pos.x, pos.y, pos.z)
+            .is_some_and(|block| block.id == Block::PORTAL.id)
+    };
+
+    let mut found = HashSet::new();
+    let mut pending: Vec<BlockPos> = (0..6).filter_map(|face| pos.neighbour(face)).collect();
+
+    while let Some(pos) = pending.pop() {
+        if !is_portal(world, pos) || !found.insert(pos) {
+            continue;
+        }
+
+        pending.extend((0..6).filter_map(|face| pos.neighbour(face)));
+    }
+
+    let removed: Vec<BlockPos> = found.into_iter().collect();
+    for pos in &removed {
+        world.set_block(pos.x, pos.y, pos.z, Block::AIR);
+    }
+
+    removed
+}
+
+/// Finds the portal block closest to the column within the search radius,
+/// the lowest one of its portal, `None` if there's none loaded.
+pub fn find(world: &World, x: i32, z: i32) -> Option<BlockPos> {
+    let mut nearest: Option<(i32, BlockPos)> = None;
+
+    for px in x - SEARCH_RADIUS..=x + SEARCH_RADIUS {
+        for pz in z - SEARCH_RADIUS..=z + SEARCH_RADIUS {
+            let distance = (px - x).pow(2) + (pz - z).pow(2);
+            if nearest.is_some_and(|(nearest, _)| nearest <= distance) {
+                continue;
+            }
+
+            let lowest = (0..world.dimension().height() as i32).find(|&y| {
+                world
+                    .get_block(px, y, pz)
+                    .is_some_and(|block| block.id == Block::PORTAL.id)
+            });
+
+            if let Some(y) = lowest {
+                nearest = Some((distance, BlockPos::new(px, y, pz)));
+            }
+        }
+    }
+
+    nearest.map(|(_, pos)| pos)
+}
+
+/// Builds a portal along the X axis at the column, on the highest floor with room for it,
+/// with a platform of obsidian on both of its sides.
+///
+/// Returns the bottom of the inside of the portal and the positions of the changed blocks.
+pub fn build(world: &mut World, x: i32, z: i32) -> (BlockPos, Vec<BlockPos>) {
+    // The frame has to fit below the top of the dimension, e.g. the bedrock of the nether.
+    let max_y = world.dimension().height() as i32 - PORTAL_HEIGHT - 2;
+    let y = (1..=max_y)
+        .rev()
+        .find(|&y| has_room(world, x, y, z))
+        .unwrap_or(FALLBACK_BUILD_Y.min(max_y));
+    let origin = BlockPos::new(x, y, z);
+
+    let mut changed = Vec::new();
+    let mut set = |world: &mut World, pos: BlockPos, block: Block| {
+        if world
+            .set_block(pos.x, pos.y, pos.z, block)
+            .is_some_and(|replaced| replaced != block)
+        {
+            changed.push(pos);
+        }
+    };
+
+    for (offset, height) in frame(false) {
+        let pos = origin.offset(offset, height, 0);
+        let inside = (0..PORTAL_WIDTH).contains(&offset) && (0..PORTAL_HEIGHT).contains(&height);
+
+        set(
+            world,
+            pos,
+            if inside {
+                Block::PORTAL
+            } else {
+                Block::OBSIDIAN
+            },
+        );
+    }
+
+    // Clears the way out of the portal and lays the floor in front of it.
+    for side in [-1, 1] {
+        for offset in 0..PORTAL_WIDTH {
+            let pos = origin.offset(offset, -1, side);
+            if world
+                .get_block(pos.x, pos.y, pos.z)
+                .is_some_and(|block| block.is_replaceable())
+            {
+                set(world, pos, Block::OBSIDIAN);
+            }
+
+            for height in 0..PORTAL_HEIGHT {
+                set(world, origin.offset(offset, height, side), Block::AIR);
+            }
+        }
+    }
+
+    (origin, changed)
+}
+
+/// Whether the portal with the bottom of its inside at the position stands on a solid floor
+/// with nothing but air in the way of its frame.
+fn has_room(world: &World, x: i32, y: i32, z: i32) -> bool {
+    let solid = |x, y, z| {
+        world
+            .get_block(x, y, z)
+            .is_some_and(|block: Block| !block.is_replaceable())
+    };
+    let air = |x, y, z| {
+        world
+            .get_block(x, y, z)
+            .is_some_and(|block: Block| block.is_air())
+    };
+
+    (0..PORTAL_WIDTH).all(|offset| solid(x + offset, y - 1, z))
+        && (-1..=PORTAL_WIDTH)
+            .all(|offset| (0..=PORTAL_HEIGHT).all(|height| air(x + offset, y + height, z)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::world::chunk::{ChunkColumn, ChunkPos};
+
+    use super::*;
+
+    /// Builds the frame along the Z axis with the bottom of its inside at the position.
+    fn frame_at(world: &mut World, origin: BlockPos) {
+        for (offset, height) in frame(true) {
+            let border =
+                offset == -1 || offset == PORTAL_WIDTH || height == -1 || height == PORTAL_HEIGHT;
+            if border {
+                let pos = origin.offset(0, height, offset);
+                world.set_block(pos.x, pos.y, pos.z, Block::OBSIDIAN);
+            }
+        }
+    }
+
+    #[test]
+    fn light_complete_frames() {
+        let mut world = World::new();
+        world.insert_chunk(ChunkColumn::new(ChunkPos::new(0, 0)));
+        let origin = BlockPos::new(4, 64, 4);
+        frame_at(&mut world, origin);
+
+        // Clicking the second bottom block lights the whole portal too.
+        let lit = light(&mut world, origin.offset(0, 0, 1)).unwrap();
+        assert_eq!(lit.len(), 6);
+        assert!(lit.contains(&origin) && lit.contains(&origin.offset(0, 2, 1)));
+        assert!(stands_in(&world, 4.5, 64.0, 5.5));
+        assert!(!stands_in(&world, 5.5, 64.0, 5.5));
+
+        let removed = collapse(&mut world, origin.offset(0, -1, 0));
+        assert_eq!(removed.len(), 6);
+        assert!(!stands_in(&world, 4.5, 64.0, 5.5));
+
+        // The frame is missing its top.
+        world.set_block(4, 67, 4, Block::AIR);
+        assert_eq!(light(&mut world, origin), None);
+
+        let mut end = World::for_dimension(Dimension::End);
+        end.insert_chunk(ChunkColumn::new(ChunkPos::new(0, 0)));
+        frame_at(&mut end, origin);
+        assert_eq!(light(&mut end, origin), None);
+    }
+
+    #[test]
+    fn build_and_find_portals() {
+        let mut world = World::for_dimension(Dimension::Nether);
+        for x in -3..=3 {
+            for z in -3..=3 {
+                world.insert_chunk(ChunkColumn::new(ChunkPos::new(x, z)));
+            }
+        }
+        for x in -2..6 {
+            for z in -2..6 {
+                world.set_block(x, 40, z, Block::NETHERRACK);
+            }
+        }
+
+        assert_eq!(find(&world, 10, 10), None);
+        let (origin, changed) = build(&mut world, 1, 1);
+        assert_eq!(origin, BlockPos::new(1, 41, 1));
+        assert!(changed.contains(&origin));
+        assert!(stands_in(&world, 2.0, 41.0, 1.5));
+        assert_eq!(world.get_block(1, 40, 0).unwrap(), Block::NETHERRACK);
+
+        assert_eq!(find(&world, 10, 10), Some(BlockPos::new(2, 41, 1)));
+        assert_eq!(find(&world, 1, 1), Some(origin));
+        assert_eq!(
+            scaled(Dimension::Nether, Dimension::Overworld, 2.0, -3.5),
+            (16.0, -28.0)
+        );
+        assert_eq!(
+            scaled(Dimension::Overworld, Dimension::Nether, 16.0, 8.0),
+            (2.0, 1.0)
+        );
+    }
+}
@@ -0,0 +1,724 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::world::{
+    self,
+    block::Block,
+    digging,
+    handler::{door_sound, DOOR_TOP, OPEN},
+    note_block, BlockChanges, BlockPos, World,
+};
+
+/// Item ID of the redstone dust.
+pub const REDSTONE: i16 = 331;
+
+/// Bit of the lever and button metadata set when they're switched on.
+pub const SWITCHED_ON: u8 = 0x8;
+
+/// Number of the ticks the buttons stay pressed for.
+pub const BUTTON_DELAY: u64 = 20;
+
+/// Number of the ticks the redstone torches take to switch.
+const TORCH_DELAY: u64 = 2;
+
+/// Number of the ticks between the checks whether anything still stands on a pressure plate.
+const PLATE_DELAY: u64 = 20;
+
+/// Power of the dust next to a power source, it loses one with each block further away.
+const MAX_POWER: u8 = 15;
+
+/// Faces of the horizontal neighbours.
+const HORIZONTAL_FACES: [i8; 4] = [2, 3, 4, 5];
+
+/// Blocks the entities are standing in, pressing the pressure plates there.
+#[derive(Debug, Default)]
+pub struct Occupants {
+    /// Blocks with any entities in them, pressing the wooden plates.
+    entities: HashSet<BlockPos>,
+
+    /// Blocks with the players or the mobs in them, pressing the stone plates too.
+    living: HashSet<BlockPos>,
+}
+
+impl Occupants {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the entity with its feet at the point.
+    ///
+    /// The entities landing on the plates as on the full blocks stand right above them,
+    /// so the block below counts too.
+    pub fn add(&mut self, x: f64, y: f64, z: f64, living: bool) {
+        let pos = BlockPos::new(x.floor() as i32, y.floor() as i32, z.floor() as i32);
+        let mut positions = vec![pos];
+        if y.fract() == 0.0 {
+            positions.push(pos.offset(0, -1, 0));
+        }
+
+        for pos in positions {
+            self.entities.insert(pos);
+            if living {
+                self.living.insert(pos);
+            }
+        }
+    }
+
+    /// Whether anything in the block presses the plate there.
+    fn presses(&self, pos: BlockPos, plate: Block) -> bool {
+        if plate.id == Block::STONE_PRESSURE_PLATE.id {
+            self.living.contains(&pos)
+        } else {
+            self.entities.contains(&pos)
+        }
+    }
+}
+
+/// Whether the item is placed as a redstone component, e.g. the dust or a lever.
+pub fn is_component_item(item: i16) -> bool {
+    matches!(item, 69 | 70 | 72 | 76 | 77 | REDSTONE)
+}
+
+/// Block placed by the redstone item used on the face, the torches, the levers
+/// and the buttons attached to the clicked block.
+///
+/// Returns `None` if the item can't be placed on the face, e.g. a button on top of a block.
+pub fn placed(item: i16, face: i8, yaw: f32) -> Option<Block> {
+    // The components on the walls face away from the block they're attached to.
+    let wall = match face {
+        2 => Some(4),
+        3 => Some(3),
+        4 => Some(2),
+        5 => Some(1),
+        _ => None,
+    };
+
+    let block = match (item, face) {
+        (REDSTONE, _) => Block::REDSTONE_WIRE,
+        (70 | 72, _) => Block::new(item as u16),
+        (76, 1) => Block::with_metadata(Block::REDSTONE_TORCH_ON.id, 5),
+        (76, _) => Block::with_metadata(Block::REDSTONE_TORCH_ON.id, wall?),
+        (69, 1) => {
+            // The levers on the floor lie along the direction the player looks in.
+            let along_x = ((yaw / 90.0).round() as i32).rem_euclid(2) == 1;
+            Block::with_metadata(Block::LEVER.id, if along_x { 6 } else { 5 })
+        }
+        (69, _) => Block::with_metadata(Block::LEVER.id, wall?),
+        (77, _) => Block::with_metadata(Block::STONE_BUTTON.id, wall?),
+        _ => return None,
+    };
+
+    Some(block)
+}
+
+/// Whether the block the component is attached to holds it, it breaks off otherwise.
+///
+/// Only the opaque cubes hold the components, the ones in the columns which
+/// aren't loaded are assumed to.
+pub fn is_supported(world: &World, pos: BlockPos, block: Block) -> bool {
+    let Some(face) = attached_face(block) else {
+        return true;
+    };
+
+    pos.neighbour(face)
+        .and_then(|next| world.get_block(next.x, next.y, next.z))
+        .is_none_or(|block| block.is_opaque_cube())
+}
+
+/// Whether the block is powered, by its neighbours or through the opaque cubes next to it.
+pub fn is_powered(world: &World, pos: BlockPos) -> bool {
+    receives_power(world, pos, true)
+}
+
+/// Lets the redstone around the changed block react to it, the dust recomputes its power,
+/// the torches switch, the components left without their blocks break off, and the doors
+/// and the note blocks follow their power.
+pub fn block_changed(world: &mut World, pos: BlockPos, changes: &mut BlockChanges) {
+    let mut updated = HashSet::new();
+
+    for next in around(pos) {
+        update_block(world, next, pos, &mut updated, changes);
+    }
+}
+
+/// Presses the pressure plates the entities stand on, they stay pressed
+/// until nothing stands on them.
+pub fn press_plates(world: &mut World, occupants: &Occupants, changes: &mut BlockChanges) {
+    for &pos in &occupants.entities {
+        let Some(plate) = world
+            .get_block(pos.x, pos.y, pos.z)
+            .filter(|block| is_plate(*block) && block.metadata == 0)
+        else {
+            continue;
+        };
+        if !occupants.presses(pos, plate) {
+            continue;
+        }
+
+        set_block(world, pos, Block::with_metadata(plate.id, 1), changes);
+        world.schedule_tick(pos, plate.id, PLATE_DELAY);
+    }
+}
+
+/// Runs the scheduled tick of the redstone component, switching the torch,
+/// or releasing the button or the pressure plate nothing stands on anymore.
+pub fn tick(world: &mut World, pos: BlockPos, occupants: &Occupants, changes: &mut BlockChanges) {
+    let Some(block) = world.get_block(pos.x, pos.y, pos.z) else {
+        return;
+    };
+
+    match block.id {
+        75 | 76 => {
+            let lit = block.id == Block::REDSTONE_TORCH_ON.id;
+            if lit != is_torch_powered(world, pos, block) {
+                return;
+            }
+
+            let id = if lit {
+                Block::REDSTONE_TORCH_OFF.id
+            } else {
+                Block::REDSTONE_TORCH_ON.id
+            };
+            set_block(
+                world,
+                pos,
+                Block::with_metadata(id, block.metadata),
+                changes,
+            );
+        }
+        77 if block.metadata & SWITCHED_ON != 0 => {
+            let released = Block::with_metadata(block.id, block.metadata & !SWITCHED_ON);
+            set_block(world, pos, released, changes);
+        }
+        70 | 72 if block.metadata != 0 => {
+            if occupants.presses(pos, block) {
+                world.schedule_tick(pos, block.id, PLATE_DELAY);
+            } else {
+                set_block(world, pos, Block::new(block.id), changes);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Whether the block provides the power or carries it, the dust connects to it.
+fn is_component(block: Block) -> bool {
+    matches!(block.id, 55 | 69 | 70 | 72 | 75..=77)
+}
+
+fn is_plate(block: Block) -> bool {
+    block.id == Block::STONE_PRESSURE_PLATE.id || block.id == Block::WOODEN_PRESSURE_PLATE.id
+}
+
+/// Face of the component the block holding it is on, `0` for the block below.
+fn attached_face(block: Block) -> Option<i8> {
+    match block.id {
+        55 | 70 | 72 => Some(0),
+        69 | 75..=77 => match block.metadata & 0x7 {
+            1 => Some(4),
+            2 => Some(5),
+            3 => Some(2),
+            4 => Some(3),
+            5 if block.id != Block::STONE_BUTTON.id => Some(0),
+            6 if block.id == Block::LEVER.id => Some(0),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The block, its neighbours and theirs, all the blocks a change of the power reaches.
+fn around(pos: BlockPos) -> Vec<BlockPos> {
+    let mut around = Vec::new();
+
+    for dx in -2i32..=2 {
+        for dy in -2i32..=2 {
+            for dz in -2i32..=2 {
+                if dx.abs() + dy.abs() + dz.abs() <= 2 {
+                    around.push(pos.offset(dx, dy, dz));
+                }
+            }
+        }
+    }
+
+    around
+}
+
+fn block_at(world: &World, pos: BlockPos) -> Option<Block> {
+    world.get_block(pos.x, pos.y, pos.z)
+}
+
+fn is_opaque_at(world: &World, pos: BlockPos) -> bool {
+    block_at(world, pos).is_some_and(|block| block.is_opaque_cube())
+}
+
+fn is_component_at(world: &World, pos: BlockPos) -> bool {
+    block_at(world, pos).is_some_and(is_component)
+}
+
+fn is_wire_at(world: &World, pos: BlockPos) -> bool {
+    block_at(world, pos).is_some_and(|block| block.id == Block::REDSTONE_WIRE.id)
+}
+
+/// Whether the block powers its neighbour seeing it on the `side`, e.g. the lit torch
+/// powers all its neighbours but the block it's attached to.
+///
+/// The dust counts only with `wires`, it doesn't power itself.
+fn weak_power(world: &World, pos: BlockPos, side: i8, wires: bool) -> bool {
+    let Some(block) = block_at(world, pos) else {
+        return false;
+    };
+
+    match block.id {
+        55 => wires && wire_powers(world, pos, block, side),
+        // The neighbour sees the torch on the `side`, in the opposite direction from it.
+        76 => attached_face(block) != Some(side ^ 1),
+        69 | 77 => block.metadata & SWITCHED_ON != 0,
+        70 | 72 => block.metadata != 0,
+        _ => false,
+    }
+}
+
+/// Whether the block powers its neighbour seeing it on the `side` strongly enough
+/// for an opaque cube to pass the power on, e.g. the lever the block it's attached to.
+fn strong_power(world: &World, pos: BlockPos, side: i8, wires: bool) -> bool {
+    let Some(block) = block_at(world, pos) else {
+        return false;
+    };
+
+    let strong = match block.id {
+        55 => true,
+        // The torch powers the block above it.
+        76 => side == 0,
+        69 | 70 | 72 | 77 => attached_face(block) == Some(side ^ 1),
+        _ => false,
+    };
+
+    strong && weak_power(world, pos, side, wires)
+}
+
+/// Whether the powered dust powers its neighbour seeing it on the `side`,
+/// the block below it and the one it points at, or all around if it connects nowhere.
+fn wire_powers(world: &World, pos: BlockPos, wire: Block, side: i8) -> bool {
+    if wire.metadata == 0 {
+        return false;
+    }
+    if side == 1 {
+        return true;
+    }
+    if !HORIZONTAL_FACES.contains(&side) {
+        return false;
+    }
+
+    let connected = HORIZONTAL_FACES.map(|face| connects(world, pos, face));
+    if !connected.contains(&true) {
+        return true;
+    }
+
+    // The dust points at the neighbour when it comes from the other side, straight.
+    let across = if side < 4 { [4, 5] } else { [2, 3] };
+    connected[side as usize - 2]
+        && across
+            .into_iter()
+            .all(|face: i8| !connected[face as usize - 2])
+}
+
+/// Whether the dust connects to its neighbour on the face, a component next to it,
+/// or a component a block up or down.
+fn connects(world: &World, pos: BlockPos, face: i8) -> bool {
+    let Some(next) = pos.neighbour(face) else {
+        return false;
+    };
+
+    if is_component_at(world, next) {
+        true
+    } else if is_opaque_at(world, next) {
+        !is_opaque_at(world, pos.offset(0, 1, 0)) && is_component_at(world, next.offset(0, 1, 0))
+    } else {
+        is_component_at(world, next.offset(0, -1, 0))
+    }
+}
+
+/// Whether any neighbour powers the block strongly, the opaque cubes powered so
+/// pass the power on to their neighbours.
+fn is_strongly_powered(world: &World, pos: BlockPos, wires: bool) -> bool {
+    (0i8..6).any(|face| {
+        pos.neighbour(face)
+            .is_some_and(|next| strong_power(world, next, face, wires))
+    })
+}
+
+/// Whether the block powers its neighbour seeing it on the `side`, the opaque cubes
+/// pass on the power they're strongly powered with.
+fn passes_power(world: &World, pos: BlockPos, side: i8, wires: bool) -> bool {
+    if is_opaque_at(world, pos) {
+        is_strongly_powered(world, pos, wires)
+    } else {
+        weak_power(world, pos, side, wires)
+    }
+}
+
+fn receives_power(world: &World, pos: BlockPos, wires: bool) -> bool {
+    is_strongly_powered(world, pos, wires)
+        || (0i8..6).any(|face| {
+            pos.neighbour(face)
+                .is_some_and(|next| passes_power(world, next, face, wires))
+        })
+}
+
+/// Whether the block the torch is attached to is powered, turning the torch off.
+fn is_torch_powered(world: &World, pos: BlockPos, torch: Block) -> bool {
+    attached_face(torch)
+        .and_then(|face| Some((pos.neighbour(face)?, face)))
+        .is_some_and(|(attached, face)| passes_power(world, attached, face, true))
+}
+
+/// Lets the redstone component or the block switched by the power react
+/// to the block at `from` changing.
+fn update_block(
+    world: &mut World,
+    pos: BlockPos,
+    from: BlockPos,
+    updated: &mut HashSet<BlockPos>,
+    changes: &mut BlockChanges,
+) {
+    let Some(block) = block_at(world, pos) else {
+        return;
+    };
+
+    if is_component(block) && !is_supported(world, pos, block) {
+        break_off(world, pos, block, changes);
+        return;
+    }
+
+    match block.id {
+        55 => update_wires(world, pos, updated, changes),
+        75 | 76 => world.schedule_tick(pos, block.id, TORCH_DELAY),
+        64 | 71 => update_door(world, pos, block, from, changes),
+        25 => update_note_block(world, pos, changes),
+        _ => {}
+    }
+}
+
+/// Dust connected to the dust at the position, next to it or a block up or down.
+fn wire_links(world: &World, pos: BlockPos) -> Vec<BlockPos> {
+    let covered = is_opaque_at(world, pos.offset(0, 1, 0));
+    let mut links = Vec::new();
+
+    for face in HORIZONTAL_FACES {
+        let Some(next) = pos.neighbour(face) else {
+            continue;
+        };
+
+        let linked = if is_wire_at(world, next) {
+            Some(next)
+        } else if is_opaque_at(world, next) {
+            Some(next.offset(0, 1, 0)).filter(|&up| !covered && is_wire_at(world, up))
+        } else {
+            Some(next.offset(0, -1, 0)).filter(|&down| is_wire_at(world, down))
+        };
+        links.extend(linked);
+    }
+
+    links
+}
+
+/// Recomputes the power of all the dust connected to the dust at the position,
+/// each losing one with every block further from the powered ones, and updates
+/// the blocks around the dust whose power has changed.
+///
+/// Skips the dust already recomputed in the `updated`.
+fn update_wires(
+    world: &mut World,
+    start: BlockPos,
+    updated: &mut HashSet<BlockPos>,
+    changes: &mut BlockChanges,
+) {
+    if updated.contains(&start) {
+        return;
+    }
+
+    let mut network = vec![start];
+    let mut links = HashMap::new();
+    updated.insert(start);
+    let mut i = 0;
+    while let Some(&pos) = network.get(i) {
+        i += 1;
+
+        let linked = wire_links(world, pos);
+        for &next in &linked {
+            if updated.insert(next) {
+                network.push(next);
+            }
+        }
+        links.insert(pos, linked);
+    }
+
+    // The power spreads from the strongest dust down, so each is first reached
+    // by its strongest neighbour.
+    let mut power: HashMap<BlockPos, u8> = HashMap::new();
+    let mut levels: Vec<Vec<BlockPos>> = vec![Vec::new(); usize::from(MAX_POWER) + 1];
+    for &pos in &network {
+        let source = receives_power(world, pos, false);
+        power.insert(pos, if source { MAX_POWER } else { 0 });
+        if source {
+            levels[usize::from(MAX_POWER)].push(pos);
+        }
+    }
+    for level in (2..=MAX_POWER).rev() {
+        for pos in std::mem::take(&mut levels[usize::from(level)]) {
+            for next in &links[&pos] {
+                if power[next] < level - 1 {
+                    power.insert(*next, level - 1);
+                    levels[usize::from(level - 1)].push(*next);
+                }
+            }
+        }
+    }
+
+    let mut switched = Vec::new();
+    for pos in network {
+        let level = power[&pos];
+        if block_at(world, pos).is_none_or(|wire| wire.metadata == level) {
+            continue;
+        }
+
+        world.set_block(pos.x, pos.y, pos.z, Block::with_metadata(55, level));
+        changes.changed.push(pos);
+        switched.push(pos);
+    }
+
+    // The dust is all set already, only the blocks around it are left.
+    for pos in switched {
+        for next in around(pos) {
+            if !is_wire_at(world, next) {
+                update_block(world, next, pos, updated, changes);
+            }
+        }
+    }
+}
+
+/// Opens the door while it's powered and closes it once it's not, the lower half
+/// holds the state.
+///
+/// Only the power changing moves the door, leaving the doors opened by hand alone.
+fn update_door(
+    world: &mut World,
+    pos: BlockPos,
+    door: Block,
+    from: BlockPos,
+    changes: &mut BlockChanges,
+) {
+    let power_changed =
+        block_at(world, from).is_some_and(|block| block.is_air() || is_component(block));
+    if !power_changed {
+        return;
+    }
+
+    let lower_pos = if door.metadata & DOOR_TOP != 0 {
+        pos.offset(0, -1, 0)
+    } else {
+        pos
+    };
+    let Some(lower) = block_at(world, lower_pos).filter(|lower| lower.id == door.id) else {
+        return;
+    };
+
+    let upper_pos = lower_pos.offset(0, 1, 0);
+    let powered = is_powered(world, lower_pos) || is_powered(world, upper_pos);
+    if powered == (lower.metadata & OPEN != 0) {
+        return;
+    }
+
+    let toggled = Block::with_metadata(lower.id, lower.metadata ^ OPEN);
+    world.set_block(lower_pos.x, lower_pos.y, lower_pos.z, toggled);
+    changes.changed.extend([lower_pos, upper_pos]);
+    changes.effects.push((lower_pos, door_sound(lower_pos)));
+}
+
+/// Plays the note of the note block once it gets powered, unless a block above muffles it.
+fn update_note_block(world: &mut World, pos: BlockPos, changes: &mut BlockChanges) {
+    let powered = is_powered(world, pos);
+    let muffled = block_at(world, pos.offset(0, 1, 0)).is_none_or(|above| !above.is_air());
+    let instrument = block_at(world, pos.offset(0, -1, 0)).map_or(0, note_block::instrument);
+
+    let Some(note_block) = world.note_block_mut(pos.x, pos.y, pos.z) else {
+        return;
+    };
+    if powered && !note_block.powered && !muffled {
+        changes
+            .effects
+            .push((pos, note_block.play_packet(pos, instrument)));
+    }
+    note_block.powered = powered;
+}
+
+/// Breaks off the component no longer held by its block, dropping its item.
+fn break_off(world: &mut World, pos: BlockPos, block: Block, changes: &mut BlockChanges) {
+    if let Some(item) = digging::drop(block, None) {
+        changes.drops.push((pos, item));
+    }
+
+    set_block(world, pos, Block::AIR, changes);
+}
+
+/// Sets the block and lets the blocks around it react.
+fn set_block(world: &mut World, pos: BlockPos, block: Block, changes: &mut BlockChanges) {
+    if world.set_block(pos.x, pos.y, pos.z, block).is_none() {
+        return;
+    }
+
+    changes.changed.push(pos);
+    world::block_changed(world, pos, changes);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::world::chunk::{ChunkColumn, ChunkPos};
+
+    use super::*;
+
+    /// Column with a stone floor at Y 63.
+    fn flat_world() -> World {
+        let mut world = World::new();
+        world.insert_chunk(ChunkColumn::new(ChunkPos::new(0, 0)));
+        for x in 0..16 {
+            for z in 0..16 {
+                world.set_block(x, 63, z, Block::STONE);
+            }
+        }
+        world
+    }
+
+    fn run_ticks(world: &mut World, occupants: &Occupants, ticks: usize) -> BlockChanges {
+        let mut changes = BlockChanges::default();
+        for _ in 0..ticks {
+            let tick = world.tick_blocks(occupants);
+            changes.changed.extend(tick.changed);
+            changes.effects.extend(tick.effects);
+        }
+        changes
+    }
+
+    fn power(world: &World, x: i32) -> u8 {
+        world.get_block(x, 64, 0).unwrap().metadata
+    }
+
+    #[test]
+    fn lever_powers_the_dust_line() {
+        let mut world = flat_world();
+        for x in 1..16 {
+            world.set_block(x, 64, 0, Block::REDSTONE_WIRE);
+        }
+        world.set_block(0, 64, 0, Block::with_metadata(69, 5 | SWITCHED_ON));
+
+        let mut changes = BlockChanges::default();
+        world::block_changed(&mut world, BlockPos::new(0, 64, 0), &mut changes);
+        assert_eq!((power(&world, 1), power(&world, 2)), (15, 14));
+        assert_eq!((power(&world, 14), power(&world, 15)), (2, 1));
+        assert_eq!(changes.changed.len(), 15);
+
+        world.set_block(0, 64, 0, Block::with_metadata(69, 5));
+        world::block_changed(&mut world, BlockPos::new(0, 64, 0), &mut changes);
+        assert!((1..16).all(|x| power(&world, x) == 0));
+
+        // The dust breaks off once the floor below it is gone.
+        let mut changes = BlockChanges::default();
+        world.set_block(3, 63, 0, Block::AIR);
+        world::block_changed(&mut world, BlockPos::new(3, 63, 0), &mut changes);
+        assert_eq!(world.get_block(3, 64, 0), Some(Block::AIR));
+        assert_eq!(changes.drops[0].1.id, REDSTONE);
+    }
+
+    #[test]
+    fn torch_switches_off_when_its_block_is_powered() {
+        let mut world = flat_world();
+        world.set_block(5, 64, 5, Block::STONE);
+        world.set_block(6, 64, 5, Block::with_metadata(76, 1));
+        world.set_block(7, 64, 5, Block::REDSTONE_WIRE);
+
+        let mut changes = BlockChanges::default();
+        world::block_changed(&mut world, BlockPos::new(6, 64, 5), &mut changes);
+        assert_eq!(
+            world.get_block(7, 64, 5),
+            Some(Block::with_metadata(55, 15))
+        );
+
+        // The lever powers the block it stands on, which the torch is attached to.
+        world.set_block(5, 65, 5, Block::with_metadata(69, 5 | SWITCHED_ON));
+        world::block_changed(&mut world, BlockPos::new(5, 65, 5), &mut changes);
+
+        let occupants = Occupants::new();
+        run_ticks(&mut world, &occupants, 1);
+        assert_eq!(world.get_block(6, 64, 5), Some(Block::with_metadata(76, 1)));
+        run_ticks(&mut world, &occupants, 1);
+        assert_eq!(world.get_block(6, 64, 5), Some(Block::with_metadata(75, 1)));
+        assert_eq!(world.get_block(7, 64, 5), Some(Block::REDSTONE_WIRE));
+    }
+
+    #[test]
+    fn pressure_plate_opens_the_door() {
+        let mut world = flat_world();
+        world.set_block(2, 64, 2, Block::WOODEN_PRESSURE_PLATE);
+        world.set_block(3, 64, 2, Block::with_metadata(64, 0));
+        world.set_block(3, 65, 2, Block::with_metadata(64, DOOR_TOP));
+        world.set_block(8, 64, 8, Block::STONE_PRESSURE_PLATE);
+
+        let mut occupants = Occupants::new();
+        occupants.add(2.5, 64.0625, 2.5, false);
+        occupants.add(8.5, 65.0, 8.5, false);
+        let changes = run_ticks(&mut world, &occupants, 1);
+        assert_eq!(world.get_block(2, 64, 2), Some(Block::with_metadata(72, 1)));
+        assert_eq!(
+            world.get_block(3, 64, 2),
+            Some(Block::with_metadata(64, OPEN))
+        );
+        assert!(changes.changed.contains(&BlockPos::new(3, 65, 2)));
+
+        // The items don't press the stone plates.
+        assert_eq!(world.get_block(8, 64, 8), Some(Block::STONE_PRESSURE_PLATE));
+
+        run_ticks(&mut world, &Occupants::new(), 20);
+        assert_eq!(
+            world.get_block(2, 64, 2),
+            Some(Block::WOODEN_PRESSURE_PLATE)
+        );
+        assert_eq!(world.get_block(3, 64, 2), Some(Block::with_metadata(64, 0)));
+    }
+
+    #[test]
+    fn button_plays_the_note_once_pressed() {
+        let mut world = flat_world();
+        world.set_block(8, 64, 8, Block::NOTE_BLOCK);
+        world.set_block(9, 64, 8, Block::with_metadata(77, 1));
+        let button = BlockPos::new(9, 64, 8);
+
+        let press = |world: &mut World| {
+            world.set_block(9, 64, 8, Block::with_metadata(77, 1 | SWITCHED_ON));
+            world.schedule_tick(button, Block::STONE_BUTTON.id, BUTTON_DELAY);
+
+            let mut changes = BlockChanges::default();
+            world::block_changed(world, button, &mut changes);
+            changes.effects
+        };
+
+        let effects = press(&mut world);
+        assert_eq!(effects.len(), 1);
+        assert_eq!(
+            effects[0].1,
+            world
+                .note_block_mut(8, 64, 8)
+                .unwrap()
+                .play_packet(BlockPos::new(8, 64, 8), 1)
+        );
+
+        let occupants = Occupants::new();
+        assert!(run_ticks(&mut world, &occupants, 19).changed.is_empty());
+        run_ticks(&mut world, &occupants, 1);
+        assert_eq!(world.get_block(9, 64, 8), Some(Block::with_metadata(77, 1)));
+        assert!(!world.note_block_mut(8, 64, 8).unwrap().powered);
+
+        // A block above muffles the note.
+        world.set_block(8, 65, 8, Block::DIRT);
+        assert!(press(&mut world).is_empty());
+    }
+}
@@ -0,0 +1,292 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use protocol::nbt::{Compression, Nbt};
+
+use crate::world::chunk::ChunkPos;
+
+/// Number of the columns along each side of a region.
+pub const REGION_WIDTH: i32 = 32;
+
+/// Number of the columns stored in a region file.
+const REGION_CHUNKS: usize = (REGION_WIDTH * REGION_WIDTH) as usize;
+
+/// Size of the sectors the file is allocated in, in bytes.
+const SECTOR_SIZE: usize = 4096;
+
+/// Number of the sectors taken by the header, the locations and the timestamps.
+const HEADER_SECTORS: usize = 2;
+
+/// Most sectors a single column may take, the count is stored in a byte.
+const MAX_CHUNK_SECTORS: usize = 255;
+
+/// Compression byte of the columns compressed with gzip.
+const GZIP_COMPRESSION: u8 = 1;
+
+/// Compression byte of the columns compressed with zlib.
+const ZLIB_COMPRESSION: u8 = 2;
+
+/// Position of the region containing the column, in regions.
+pub fn region_of(pos: ChunkPos) -> (i32, i32) {
+    (pos.x >> 5, pos.z >> 5)
+}
+
+/// Name of the file storing the region, e.g. `r.0.-1.mca`.
+pub fn region_file_name((x, z): (i32, i32)) -> String {
+    format!("r.{}.{}.mca", x, z)
+}
+
+/// Index of the column within its region's header.
+fn chunk_index(pos: ChunkPos) -> usize {
+    (pos.x.rem_euclid(REGION_WIDTH) + pos.z.rem_euclid(REGION_WIDTH) * REGION_WIDTH) as usize
+}
+
+fn invalid_data(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// A file storing the 32×32 columns of a region in the Anvil format.
+///
+/// The file is split into sectors of 4 KiB, the first one holds the sector offset
+/// and count of each column and the second one the time each column was saved at.
+/// Each column takes a run of sectors starting with its length and compression.
+#[derive(Debug)]
+pub struct RegionFile {
+    file: File,
+
+    /// Sector offset shifted by a byte with the sector count of each column, `0` if missing.
+    locations: [u32; REGION_CHUNKS],
+
+    /// Whether each sector of the file is taken by the header or a column.
+    used_sectors: Vec<bool>,
+}
+
+impl RegionFile {
+    /// Opens the region file, creating it with an empty header if it doesn't exist.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+
+        // Pads the file to whole sectors, the missing header is filled with zeroes.
+        let length = file.metadata()?.len() as usize;
+        let sectors = length.div_ceil(SECTOR_SIZE).max(HEADER_SECTORS);
+        if length != sectors * SECTOR_SIZE {
+            file.set_len((sectors * SECTOR_SIZE) as u64)?;
+        }
+
+        let mut header = [0u8; SECTOR_SIZE];
+        file.seek(SeekFrom::Start(0))?;
+        file.read_exact(&mut header)?;
+
+        let mut locations = [0u32; REGION_CHUNKS];
+        let mut used_sectors = vec![false; sectors];
+        used_sectors[..HEADER_SECTORS].fill(true);
+
+        for (i, location) in locations.iter_mut().enumerate() {
+            let entry = u32::from_be_bytes(header[i * 4..i * 4 + 4].try_into().unwrap());
+            let (offset, count) = split_location(entry);
+
+            // Columns pointing into the header or past the end are treated as missing.
+            if offset < HEADER_SECTORS || count == 0 || offset + count > sectors {
+                continue;
+            }
+
+            used_sectors[offset..offset + count].fill(true);
+            *location = entry;
+        }
+
+        Ok(Self {
+            file,
+            locations,
+            used_sectors,
+        })
+    }
+
+    /// Whether the column is stored in the file.
+    pub fn contains(&self, pos: ChunkPos) -> bool {
+        self.locations[chunk_index(pos)] != 0
+    }
+
+    /// Reads the NBT of the column, `None` if it isn't stored in the file.
+    pub fn read(&mut self, pos: ChunkPos) -> io::Result<Option<Nbt>> {
+        let (offset, count) = split_location(self.locations[chunk_index(pos)]);
+        if count == 0 {
+            return Ok(None);
+        }
+
+        let mut prefix = [0u8; 5];
+        self.file
+            .seek(SeekFrom::Start((offset * SECTOR_SIZE) as u64))?;
+        self.file.read_exact(&mut prefix)?;
+
+        // The length counts the compression byte too.
+        let length = u32::from_be_bytes(prefix[..4].try_into().unwrap()) as usize;
+        if length == 0 || length + 4 > count * SECTOR_SIZE {
+            return Err(invalid_data(format!(
+                "column at {}, {} has an invalid length {}",
+                pos.x, pos.z, length
+            )));
+        }
+
+        let compression = match prefix[4] {
+            GZIP_COMPRESSION => Compression::Gzip,
+            ZLIB_COMPRESSION => Compression::Zlib,
+            other => {
+                return Err(invalid_data(format!(
+                    "column at {}, {} has an unknown compression {}",
+                    pos.x, pos.z, other
+                )))
+            }
+        };
+
+        let mut data = vec![0u8; length - 1];
+        self.file.read_exact(&mut data)?;
+
+        Nbt::read(&data, compression)
+            .map(Some)
+            .map_err(invalid_data)
+    }
+
+    /// Writes the NBT of the column compressed with zlib, replacing the stored one.
+    ///
+    /// The column is kept in its sectors if it still fits in them, otherwise
+    /// it's moved to the first run of the free sectors large enough or appended.
+    pub fn write(&mut self, pos: ChunkPos, nbt: &Nbt) -> io::Result<()> {
+        let data = nbt.write(Compression::Zlib).map_err(invalid_data)?;
+
+        let mut bytes = Vec::with_capacity(data.len() + 5);
+        bytes.extend_from_slice(&(data.len() as u32 + 1).to_be_bytes());
+        bytes.push(ZLIB_COMPRESSION);
+        bytes.extend_from_slice(&data);
+
+        let count = bytes.len().div_ceil(SECTOR_SIZE);
+        if count > MAX_CHUNK_SECTORS {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("column at {}, {} is too large to store", pos.x, pos.z),
+            ));
+        }
+        bytes.resize(count * SECTOR_SIZE, 0);
+
+        let index = chunk_index(pos);
+        let (old_offset, old_count) = split_location(self.locations[index]);
+        self.used_sectors[old_offset..old_offset + old_count].fill(false);
+
+        let offset = if old_count != 0 && count <= old_count {
+            old_offset
+        } else {
+            self.allocate(count)
+        };
+
+        self.file
+            .seek(SeekFrom::Start((offset * SECTOR_SIZE) as u64))?;
+        self.file.write_all(&bytes)?;
+        self.used_sectors[offset..offset + count].fill(true);
+
+        self.locations[index] = (offset as u32) << 8 | count as u32;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs() as u32);
+
+        self.file.seek(SeekFrom::Start((index * 4) as u64))?;
+        self.file.write_all(&self.locations[index].to_be_bytes())?;
+        self.file
+            .seek(SeekFrom::Start((SECTOR_SIZE + index * 4) as u64))?;
+        self.file.write_all(&timestamp.to_be_bytes())?;
+
+        Ok(())
+    }
+
+    /// Finds the first run of the free sectors of the length, growing the file if none is.
+    fn allocate(&mut self, count: usize) -> usize {
+        let mut run = 0;
+
+        for (i, &used) in self.used_sectors.iter().enumerate() {
+            run = if used { 0 } else { run + 1 };
+
+            if run == count {
+                return i + 1 - count;
+            }
+        }
+
+        // Extends the trailing free sectors, if any, to the end of the file.
+        let offset = self.used_sectors.len() - run;
+        self.used_sectors.resize(offset + count, false);
+        offset
+    }
+}
+
+/// Splits the header entry into the sector offset and the sector count.
+fn split_location(entry: u32) -> (usize, usize) {
+    ((entry >> 8) as usize, (entry & 0xFF) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use protocol::nbt::{Compound, Tag};
+
+    use super::*;
+
+    fn column_nbt(x: i32, padding: usize) -> Nbt {
+        let mut root = Compound::new();
+        root.insert("xPos".to_string(), Tag::Int(x));
+        // Random bytes barely compress, making the column take more sectors.
+        root.insert(
+            "Padding".to_string(),
+            Tag::ByteArray((0..padding).map(|_| rand::random()).collect()),
+        );
+        Nbt::new("", root)
+    }
+
+    #[test]
+    fn store_columns_in_sectors() {
+        let path = std::env::temp_dir().join(format!("r.{}.0.mca", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let (small, large) = (column_nbt(1, 16), column_nbt(2, 3 * SECTOR_SIZE));
+        let mut region = RegionFile::open(&path).unwrap();
+        assert!(!region.contains(ChunkPos::new(1, 0)));
+        assert_eq!(region.read(ChunkPos::new(1, 0)).unwrap(), None);
+
+        region.write(ChunkPos::new(1, 0), &small).unwrap();
+        region.write(ChunkPos::new(-31, 1), &large).unwrap();
+        assert_eq!(split_location(region.locations[1]), (2, 1));
+        assert_eq!(split_location(region.locations[33]), (3, 4));
+
+        // The grown column no longer fits and moves past the other one.
+        region.write(ChunkPos::new(1, 0), &large).unwrap();
+        assert_eq!(split_location(region.locations[1]), (7, 4));
+        region.write(ChunkPos::new(2, 0), &small).unwrap();
+        assert_eq!(split_location(region.locations[2]), (2, 1));
+
+        let mut region = RegionFile::open(&path).unwrap();
+        assert_eq!(
+            region.read(ChunkPos::new(1, 0)).unwrap(),
+            Some(large.clone())
+        );
+        assert_eq!(region.read(ChunkPos::new(-31, 1)).unwrap(), Some(large));
+        assert_eq!(region.read(ChunkPos::new(2, 0)).unwrap(), Some(small));
+        assert_eq!(
+            std::fs::metadata(&path).unwrap().len(),
+            11 * SECTOR_SIZE as u64
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn regions_of_columns() {
+        assert_eq!(region_of(ChunkPos::new(31, 32)), (0, 1));
+        assert_eq!(region_of(ChunkPos::new(-1, -33)), (-1, -2));
+        assert_eq!(region_file_name((0, -1)), "r.0.-1.mca");
+        assert_eq!(chunk_index(ChunkPos::new(-1, 33)), 31 + 32);
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::{BTreeMap, HashSet};
+
+use crate::world::BlockPos;
+
+/// Most of the scheduled ticks run in a single tick, the rest wait for the next one.
+const MAX_TICKS_PER_TICK: usize = 1000;
+
+/// A tick of the block scheduled to happen later, e.g. the fluid flowing on.
+///
+/// The tick runs only if the block is still there when it's due.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScheduledTick {
+    pub pos: BlockPos,
+    pub block_id: u16,
+}
+
+/// Queue of the block ticks of a world, ordered by the tick they're due at.
+///
+/// The ticks are counted on their own, changing the world time doesn't affect them.
+#[derive(Debug, Default)]
+pub struct ScheduledTicks {
+    /// Number of the ticks run so far.
+    now: u64,
+
+    /// Ticks keyed by the tick they're due at and the order they were scheduled in.
+    queue: BTreeMap<(u64, u64), ScheduledTick>,
+
+    /// Ticks in the queue, a block has at most one scheduled at a time.
+    pending: HashSet<ScheduledTick>,
+
+    /// Number of the ticks scheduled so far, keeping the ones due together in order.
+    scheduled: u64,
+}
+
+impl ScheduledTicks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Schedules the tick of the block in the number of ticks,
+    /// unless the block has one scheduled already.
+    pub fn schedule(&mut self, pos: BlockPos, block_id: u16, delay: u64) {
+        let tick = ScheduledTick { pos, block_id };
+        if !self.pending.insert(tick) {
+            return;
+        }
+
+        self.queue.insert((self.now + delay, self.scheduled), tick);
+        self.scheduled += 1;
+    }
+
+    /// Whether the block has a tick scheduled.
+    pub fn is_scheduled(&self, pos: BlockPos, block_id: u16) -> bool {
+        self.pending.contains(&ScheduledTick { pos, block_id })
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Advances by a single tick, returns the ticks due by now in the order they were scheduled.
+    pub fn advance(&mut self) -> Vec<ScheduledTick> {
+        self.now += 1;
+
+        let mut due = Vec::new();
+        while due.len() < MAX_TICKS_PER_TICK {
+            let Some(entry) = self.queue.first_entry() else {
+                break;
+            };
+            if entry.key().0 > self.now {
+                break;
+            }
+
+            let tick = entry.remove();
+            self.pending.remove(&tick);
+            due.push(tick);
+        }
+
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticks_run_once_due() {
+        let mut ticks = ScheduledTicks::new();
+        let (a, b) = (BlockPos::new(0, 64, 0), BlockPos::new(1, 64, 0));
+
+        ticks.schedule(a, 8, 2);
+        ticks.schedule(b, 8, 1);
+        ticks.schedule(a, 8, 1);
+        assert_eq!(ticks.len(), 2);
+        assert!(ticks.is_scheduled(a, 8));
+        assert!(!ticks.is_scheduled(a, 10));
+
+        assert_eq!(
+            ticks.advance(),
+            vec![ScheduledTick {
+                pos: b,
+                block_id: 8
+            }]
+        );
+        assert_eq!(ticks.advance().len(), 1);
+        assert!(ticks.advance().is_empty());
+        assert!(ticks.is_empty());
+
+        // Once run, the block may be scheduled again.
+        ticks.schedule(a, 8, 0);
+        assert_eq!(ticks.advance().len(), 1);
+    }
+}
@@ -0,0 +1,119 @@
+use protocol::packet::{Packet, UpdateSignPayload};
+
+use crate::world::{block::Block, BlockPos};
+
+/// Item ID of the sign.
+pub const SIGN: i16 = 323;
+
+/// Number of the lines of text on a sign.
+pub const LINE_COUNT: usize = 4;
+
+/// Max length of a line, in UTF-16 code units.
+pub const MAX_LINE_LENGTH: usize = 15;
+
+/// Character starting the formatting codes.
+const FORMATTING_CODE: char = '§';
+
+/// Text of a sign post or a wall sign.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sign {
+    pub lines: [String; LINE_COUNT],
+
+    /// Whether the text may still be set, only the freshly placed signs are.
+    pub editable: bool,
+}
+
+impl Default for Sign {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sign {
+    /// Creates the blank sign waiting for the text of the player who placed it.
+    pub fn new() -> Self {
+        Self {
+            lines: Default::default(),
+            editable: true,
+        }
+    }
+
+    /// Creates the sign with the text, which can no longer be edited.
+    pub fn with_lines(lines: [String; LINE_COUNT]) -> Self {
+        Self {
+            lines,
+            editable: false,
+        }
+    }
+
+    /// Builds the packet showing the text of the sign at the position.
+    pub fn update_packet(&self, pos: BlockPos) -> Packet {
+        let [text1, text2, text3, text4] = self.lines.clone();
+
+        Packet::UpdateSign(UpdateSignPayload {
+            x: pos.x,
+            y: pos.y as i16,
+            z: pos.z,
+            text1,
+            text2,
+            text3,
+            text4,
+        })
+    }
+}
+
+/// Block placed by the sign item used on the face, a sign post facing
+/// the player on top of the block or a wall sign on its sides.
+///
+/// Returns `None` for the bottom face, the signs don't hang from the ceiling.
+pub fn placed(face: i8, yaw: f32) -> Option<Block> {
+    match face {
+        1 => {
+            // Sixteen directions, `0` facing the players looking north.
+            let rotation = ((yaw + 180.0) * 16.0 / 360.0 + 0.5).floor() as i32;
+            Some(Block::with_metadata(
+                Block::SIGN_POST.id,
+                (rotation & 0xF) as u8,
+            ))
+        }
+        2..=5 => Some(Block::with_metadata(Block::WALL_SIGN.id, face as u8)),
+        _ => None,
+    }
+}
+
+/// Sanitizes the line typed by the player.
+///
+/// Formatting codes are stripped and the line is cut to `MAX_LINE_LENGTH`.
+pub fn sanitize(line: &str) -> String {
+    let mut length = 0;
+
+    line.chars()
+        .filter(|c| *c != FORMATTING_CODE && !c.is_control())
+        .take_while(|c| {
+            length += c.len_utf16();
+            length <= MAX_LINE_LENGTH
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_face_the_player() {
+        assert_eq!(placed(1, 0.0), Some(Block::with_metadata(63, 8)));
+        assert_eq!(placed(1, -180.0), Some(Block::with_metadata(63, 0)));
+        assert_eq!(placed(1, 170.0), Some(Block::with_metadata(63, 0)));
+        assert_eq!(placed(1, 450.0), Some(Block::with_metadata(63, 12)));
+        assert_eq!(placed(4, 0.0), Some(Block::with_metadata(68, 4)));
+        assert_eq!(placed(0, 0.0), None);
+    }
+
+    #[test]
+    fn sanitize_lines() {
+        assert_eq!(sanitize("§cRed\u{7} sign"), "cRed sign");
+        assert_eq!(sanitize("a long line of the text"), "a long line of ");
+        assert_eq!(sanitize("😀😀😀😀😀😀😀😀"), "😀😀😀😀😀😀😀");
+    }
+}
@@ -0,0 +1,366 @@
+use std::io;
+
+use protocol::{
+    nbt::{Compound, Nbt, Tag},
+    slot::{ItemStack, Slot},
+};
+
+use crate::world::{
+    block::Block,
+    note_block::NoteBlock,
+    sign::{self, Sign},
+    BlockPos,
+};
+
+/// Number of the slots of a chest.
+pub const CHEST_SIZE: usize = 27;
+
+/// Number of the slots of a furnace, the input, the fuel and the output.
+pub const FURNACE_SIZE: usize = 3;
+
+/// Number of the slots of a dispenser.
+pub const DISPENSER_SIZE: usize = 9;
+
+/// Identifiers of the tile entities in the chunk saves.
+const SIGN_ID: &str = "Sign";
+const NOTE_BLOCK_ID: &str = "Music";
+const CHEST_ID: &str = "Chest";
+const FURNACE_ID: &str = "Furnace";
+const DISPENSER_ID: &str = "Trap";
+
+/// Slots of a block holding the items, e.g. a chest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Container {
+    pub slots: Vec<Slot>,
+}
+
+impl Container {
+    /// Creates the container with the number of the empty slots.
+    pub fn new(size: usize) -> Self {
+        Self {
+            slots: vec![Slot::EMPTY; size],
+        }
+    }
+}
+
+/// Slots of a furnace and the progress of its fire and of the item it cooks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Furnace {
+    pub container: Container,
+
+    /// Ticks the fuel keeps burning for.
+    pub burn_time: i16,
+
+    /// Ticks the input has been cooking for.
+    pub cook_time: i16,
+}
+
+impl Default for Furnace {
+    fn default() -> Self {
+        Self {
+            container: Container::new(FURNACE_SIZE),
+            burn_time: 0,
+            cook_time: 0,
+        }
+    }
+}
+
+/// Data of a block which doesn't fit in its metadata, kept by the column at the block.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TileEntity {
+    Sign(Sign),
+    NoteBlock(NoteBlock),
+    Chest(Container),
+    Furnace(Furnace),
+    Dispenser(Container),
+}
+
+impl TileEntity {
+    /// Creates the empty tile entity of the block, `None` if the block doesn't hold any.
+    ///
+    /// The signs created this way are blank and can't be edited,
+    /// the placed ones are stored with the text their players write.
+    pub fn new(block: Block) -> Option<Self> {
+        Some(match block.id {
+            id if id == Block::DISPENSER.id => Self::Dispenser(Container::new(DISPENSER_SIZE)),
+            id if id == Block::NOTE_BLOCK.id => Self::NoteBlock(NoteBlock::default()),
+            id if id == Block::CHEST.id => Self::Chest(Container::new(CHEST_SIZE)),
+            id if id == Block::FURNACE.id || id == Block::BURNING_FURNACE.id => {
+                Self::Furnace(Furnace::default())
+            }
+            _ if block.is_sign() => Self::Sign(Sign::with_lines(Default::default())),
+            _ => return None,
+        })
+    }
+
+    /// Whether the tile entity belongs to the block, kept when its block changes to it,
+    /// e.g. the furnace when it's lit.
+    pub fn accepts(&self, block: Block) -> bool {
+        TileEntity::new(block)
+            .is_some_and(|empty| std::mem::discriminant(&empty) == std::mem::discriminant(self))
+    }
+
+    /// Runs the tick of the tile entity, returns whether it has changed.
+    pub fn tick(&mut self) -> bool {
+        match self {
+            Self::Furnace(furnace) => {
+                if furnace.burn_time == 0 {
+                    return false;
+                }
+
+                furnace.burn_time -= 1;
+                if furnace.burn_time == 0 {
+                    furnace.cook_time = 0;
+                }
+                true
+            }
+            Self::Sign(_) | Self::NoteBlock(_) | Self::Chest(_) | Self::Dispenser(_) => false,
+        }
+    }
+
+    /// Block showing the state of the tile entity in place of the block,
+    /// the furnace is lit only while its fuel burns.
+    pub fn shown_block(&self, block: Block) -> Block {
+        match self {
+            Self::Furnace(furnace) => {
+                let id = if furnace.burn_time > 0 {
+                    Block::BURNING_FURNACE.id
+                } else {
+                    Block::FURNACE.id
+                };
+                Block::with_metadata(id, block.metadata)
+            }
+            _ => block,
+        }
+    }
+
+    /// Builds the compound the tile entity at the absolute position is saved as.
+    pub fn to_compound(&self, pos: BlockPos) -> Compound {
+        let mut compound = Compound::new();
+        let id = match self {
+            Self::Sign(_) => SIGN_ID,
+            Self::NoteBlock(_) => NOTE_BLOCK_ID,
+            Self::Chest(_) => CHEST_ID,
+            Self::Furnace(_) => FURNACE_ID,
+            Self::Dispenser(_) => DISPENSER_ID,
+        };
+        compound.insert("id".to_string(), Tag::String(id.to_string()));
+        compound.insert("x".to_string(), Tag::Int(pos.x));
+        compound.insert("y".to_string(), Tag::Int(pos.y));
+        compound.insert("z".to_string(), Tag::Int(pos.z));
+
+        match self {
+            Self::Sign(sign) => {
+                for (i, line) in sign.lines.iter().enumerate() {
+                    compound.insert(format!("Text{}", i + 1), Tag::String(line.clone()));
+                }
+            }
+            Self::NoteBlock(note_block) => {
+                compound.insert("note".to_string(), Tag::Byte(note_block.note as i8));
+            }
+            Self::Chest(container) | Self::Dispenser(container) => {
+                compound.insert("Items".to_string(), Tag::List(write_items(container)));
+            }
+            Self::Furnace(furnace) => {
+                compound.insert(
+                    "Items".to_string(),
+                    Tag::List(write_items(&furnace.container)),
+                );
+                compound.insert("BurnTime".to_string(), Tag::Short(furnace.burn_time));
+                compound.insert("CookTime".to_string(), Tag::Short(furnace.cook_time));
+            }
+        }
+
+        compound
+    }
+
+    /// Builds the tile entity from its compound, returns it with its absolute position,
+    /// `None` for the kinds of the tile entities which aren't kept.
+    ///
+    /// The lines of the signs are sanitized, the signs may have been written by other servers.
+    pub fn from_compound(compound: &Compound) -> io::Result<Option<(BlockPos, Self)>> {
+        let Some(id) = compound.get("id").and_then(Tag::as_str) else {
+            return Err(invalid_data("a tile entity has no id"));
+        };
+
+        let tile_entity = match id {
+            SIGN_ID => Self::Sign(Sign::with_lines(std::array::from_fn(|i| {
+                compound
+                    .get(&format!("Text{}", i + 1))
+                    .and_then(Tag::as_str)
+                    .map(sign::sanitize)
+                    .unwrap_or_default()
+            }))),
+            NOTE_BLOCK_ID => Self::NoteBlock(NoteBlock {
+                note: compound
+                    .get("note")
+                    .and_then(Tag::as_byte)
+                    .map_or(0, |note| (note as u8).min(24)),
+                powered: false,
+            }),
+            CHEST_ID => Self::Chest(read_items(compound, CHEST_SIZE)),
+            FURNACE_ID => {
+                let short = |name: &str| compound.get(name).and_then(Tag::as_short);
+                Self::Furnace(Furnace {
+                    container: read_items(compound, FURNACE_SIZE),
+                    burn_time: short("BurnTime").unwrap_or(0).max(0),
+                    cook_time: short("CookTime").unwrap_or(0).max(0),
+                })
+            }
+            DISPENSER_ID => Self::Dispenser(read_items(compound, DISPENSER_SIZE)),
+            _ => return Ok(None),
+        };
+
+        let coordinate = |name: &str| {
+            compound
+                .get(name)
+                .and_then(Tag::as_int)
+                .ok_or_else(|| invalid_data(format!("a tile entity has no {}", name)))
+        };
+        let pos = BlockPos::new(coordinate("x")?, coordinate("y")?, coordinate("z")?);
+
+        Ok(Some((pos, tile_entity)))
+    }
+}
+
+/// Builds the list of the stacks in the container, each with its slot.
+fn write_items(container: &Container) -> Vec<Tag> {
+    container
+        .slots
+        .iter()
+        .enumerate()
+        .filter_map(|(slot, item)| Some((slot, item.item()?)))
+        .map(|(slot, item)| {
+            let mut compound = Compound::new();
+            compound.insert("Slot".to_string(), Tag::Byte(slot as i8));
+            compound.insert("id".to_string(), Tag::Short(item.id));
+            compound.insert("Count".to_string(), Tag::Byte(item.count));
+            compound.insert("Damage".to_string(), Tag::Short(item.damage));
+            if let Some(nbt) = &item.nbt {
+                compound.insert("tag".to_string(), Tag::Compound(nbt.root.clone()));
+            }
+            Tag::Compound(compound)
+        })
+        .collect()
+}
+
+/// Builds the container of the size from the `Items` of the compound,
+/// skipping the stacks out of its slots.
+fn read_items(compound: &Compound, size: usize) -> Container {
+    let mut container = Container::new(size);
+    let items = compound
+        .get("Items")
+        .and_then(Tag::as_list)
+        .unwrap_or_default();
+
+    for compound in items.iter().filter_map(Tag::as_compound) {
+        let short = |name: &str| compound.get(name).and_then(Tag::as_short);
+        let (Some(slot), Some(id), Some(count)) = (
+            compound.get("Slot").and_then(Tag::as_byte),
+            short("id"),
+            compound.get("Count").and_then(Tag::as_byte),
+        ) else {
+            continue;
+        };
+        let Some(contents) = container.slots.get_mut(slot as u8 as usize) else {
+            continue;
+        };
+
+        let mut item = ItemStack::new(id, count, short("Damage").unwrap_or(0));
+        item.nbt = compound
+            .get("tag")
+            .and_then(Tag::as_compound)
+            .map(|tag| Nbt::new("tag", tag.clone()));
+        *contents = Slot::new(item);
+    }
+
+    container
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::{
+        chunk::{ChunkColumn, ChunkPos},
+        redstone::Occupants,
+        World,
+    };
+
+    #[test]
+    fn furnace_goes_out_with_its_fuel() {
+        let mut furnace = TileEntity::Furnace(Furnace {
+            burn_time: 2,
+            cook_time: 40,
+            ..Furnace::default()
+        });
+        let lit = Block::with_metadata(Block::BURNING_FURNACE.id, 3);
+
+        assert!(furnace.tick());
+        assert_eq!(furnace.shown_block(lit), lit);
+        assert!(furnace.tick());
+        assert_eq!(
+            furnace.shown_block(lit),
+            Block::with_metadata(Block::FURNACE.id, 3)
+        );
+        assert!(!furnace.tick());
+        assert!(furnace.accepts(Block::FURNACE));
+        assert!(!furnace.accepts(Block::CHEST));
+    }
+
+    #[test]
+    fn chest_round_trips_through_its_compound() {
+        let mut container = Container::new(CHEST_SIZE);
+        container.slots[4] = Slot::new(ItemStack::new(264, 3, 0));
+        container.slots[26] = Slot::new(ItemStack::new(35, 64, 14));
+        let chest = TileEntity::Chest(container);
+        let pos = BlockPos::new(-5, 40, 17);
+
+        let compound = chest.to_compound(pos);
+
+        assert_eq!(
+            TileEntity::from_compound(&compound).unwrap(),
+            Some((pos, chest))
+        );
+    }
+
+    #[test]
+    fn signs_break_with_their_blocks() {
+        let mut world = World::new();
+        world.insert_chunk(ChunkColumn::new(ChunkPos::new(0, -1)));
+
+        assert!(!world.set_tile_entity(3, 64, -2, TileEntity::Sign(Sign::new())));
+        world.set_block(3, 64, -2, Block::SIGN_POST);
+        assert!(world.set_tile_entity(3, 64, -2, TileEntity::Sign(Sign::new())));
+        world.sign_mut(3, 64, -2).unwrap().lines[0] = "Hello".to_string();
+        assert_eq!(world.sign(3, 64, -2).unwrap().lines[0], "Hello");
+
+        world.set_block(3, 64, -2, Block::AIR);
+        assert_eq!(world.sign(3, 64, -2), None);
+    }
+
+    #[test]
+    fn furnaces_keep_their_items_when_lit() {
+        let mut world = World::new();
+        world.insert_chunk(ChunkColumn::new(ChunkPos::new(0, 0)));
+        world.set_block(5, 64, 5, Block::FURNACE);
+
+        let Some(TileEntity::Furnace(furnace)) = world.tile_entity_mut(5, 64, 5) else {
+            panic!("the furnace has no tile entity");
+        };
+        furnace.container.slots[1] = Slot::new(ItemStack::new(263, 1, 0));
+        furnace.burn_time = 1;
+        world.set_block(5, 64, 5, Block::BURNING_FURNACE);
+
+        let changes = world.tick_blocks(&Occupants::new());
+        assert_eq!(changes.changed, vec![BlockPos::new(5, 64, 5)]);
+        assert_eq!(world.get_block(5, 64, 5), Some(Block::FURNACE));
+        let Some(TileEntity::Furnace(furnace)) = world.tile_entity(5, 64, 5) else {
+            panic!("the furnace lost its tile entity");
+        };
+        assert_eq!(furnace.container.slots[1].item().unwrap().id, 263);
+    }
+}